@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::asset_token::AssetToken;
+
+/// Maximum number of accounts that can hold roles
+pub const MAX_ROLE_MEMBERS: usize = 32;
+
+/// Role registry for a REAL token deployment
+#[account]
+pub struct Roles {
+    /// The REAL token these roles govern
+    pub real_token: Pubkey,
+    
+    /// Holder of the Admin role (changed only through the two-step handover)
+    pub admin: Pubkey,
+    
+    /// Accounts holding one or more non-admin roles
+    pub members: Vec<RoleMember>,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Roles held by a single account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RoleMember {
+    /// The account holding the roles
+    pub member: Pubkey,
+    
+    /// Bitmask of held roles
+    pub roles: u8,
+}
+
+/// Platform roles
+///
+/// Roles are held platform-wide. Asset management duties are additionally scoped
+/// to the asset: they need the AssetManager role and the asset's own authority key
+/// (see `Roles::manages_asset`). Verifier, Treasurer, KycProvider, Pauser and
+/// FeeManager are deliberately platform-wide so they stay independent of issuers.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Grants and revokes roles, configures the platform
+    Admin,
+    
+    /// Creates and manages asset tokens
+    AssetManager,
+    
+    /// Verifies assets and approves share issuance
+    Verifier,
+    
+    /// Triggers the emergency pause
+    Pauser,
+    
+    /// Updates the fee configuration
+    FeeManager,
+    
+    /// Distributes income
+    Treasurer,
+    
+    /// Writes investor KYC and accreditation records
+    KycProvider,
+}
+
+impl Role {
+    /// Bit used for this role in `RoleMember::roles`
+    pub fn mask(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+impl Roles {
+    /// Space required for a Roles account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // real_token
+        32 + // admin
+        4 + MAX_ROLE_MEMBERS * (32 + 1) + // members
+        1; // bump
+    
+    /// Whether an account holds a role
+    pub fn has_role(&self, account: Pubkey, role: Role) -> bool {
+        if role == Role::Admin {
+            return self.admin == account;
+        }
+        self.members
+            .iter()
+            .any(|m| m.member == account && m.roles & role.mask() != 0)
+    }
+    
+    /// Whether an account may manage an asset: an asset manager that is also the asset's authority
+    pub fn manages_asset(&self, account: Pubkey, asset_token: &AssetToken) -> bool {
+        asset_token.authority == account && self.has_role(account, Role::AssetManager)
+    }
+    
+    /// Grant a non-admin role
+    pub fn grant(&mut self, account: Pubkey, role: Role) -> Result<()> {
+        require!(role != Role::Admin, RealStackError::InvalidParameters);
+        
+        if let Some(member) = self.members.iter_mut().find(|m| m.member == account) {
+            member.roles |= role.mask();
+            return Ok(());
+        }
+        
+        require!(self.members.len() < MAX_ROLE_MEMBERS, RealStackError::RoleListFull);
+        self.members.push(RoleMember {
+            member: account,
+            roles: role.mask(),
+        });
+        Ok(())
+    }
+    
+    /// Revoke a non-admin role, dropping the member once it holds none
+    pub fn revoke(&mut self, account: Pubkey, role: Role) -> Result<()> {
+        require!(role != Role::Admin, RealStackError::InvalidParameters);
+        
+        let index = self.members
+            .iter()
+            .position(|m| m.member == account && m.roles & role.mask() != 0)
+            .ok_or(RealStackError::RoleNotHeld)?;
+        self.members[index].roles &= !role.mask();
+        if self.members[index].roles == 0 {
+            self.members.remove(index);
+        }
+        Ok(())
+    }
+}
+
+/// Context for granting or revoking roles
+#[derive(Accounts)]
+pub struct ManageRoles<'info> {
+    /// The admin
+    #[account(
+        constraint = roles.has_role(admin.key(), Role::Admin) @ RealStackError::Unauthorized
+    )]
+    pub admin: Signer<'info>,
+    
+    /// The role registry
+    #[account(
+        mut,
+        seeds = [b"roles".as_ref(), roles.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+/// Implementation of access control operations
+pub mod access_control_operations {
+    use super::*;
+    
+    /// Grant a role to an account
+    pub fn grant_role(
+        ctx: Context<ManageRoles>,
+        member: Pubkey,
+        role: Role,
+    ) -> Result<()> {
+        let roles = &mut ctx.accounts.roles;
+        
+        roles.grant(member, role)?;
+        
+        // Log the grant
+        msg!("Role {:?} granted to: {}", role, member);
+        
+        Ok(())
+    }
+    
+    /// Revoke a role from an account
+    pub fn revoke_role(
+        ctx: Context<ManageRoles>,
+        member: Pubkey,
+        role: Role,
+    ) -> Result<()> {
+        let roles = &mut ctx.accounts.roles;
+        
+        roles.revoke(member, role)?;
+        
+        // Log the revocation
+        msg!("Role {:?} revoked from: {}", role, member);
+        
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::token::{self, Burn, CloseAccount, Mint, MintTo, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::program_option::COption;
 use crate::errors::*;
+use crate::access_control::{Role, Roles};
+use crate::compliance::{debit_holder, ComplianceConfig, HolderRecord, InvestorRecord, ShareFreeze};
+use crate::tokenomics::{PauseFlag, RealToken};
 
 /// Asset Token data structure
 #[account]
@@ -11,6 +15,9 @@ pub struct AssetToken {
     /// Token mint address for this asset
     pub mint: Pubkey,
     
+    /// REAL token deployment this asset belongs to
+    pub real_token: Pubkey,
+    
     /// Asset name
     pub name: String,
     
@@ -73,6 +80,34 @@ pub struct AssetToken {
     
     /// Is the token burned/deactivated
     pub is_burned: bool,
+    
+    /// Lifecycle status of the asset
+    pub status: AssetStatus,
+    
+    /// Timestamp when the last share was redeemed
+    pub retired_at: i64,
+    
+    /// Who may hold the asset's shares
+    pub compliance: ComplianceConfig,
+    
+    /// Distinct wallets holding shares, as counted by holder records
+    pub holder_count: u32,
+    
+    /// Offerings created so far; the next offering's address is seeded with it
+    pub offering_count: u64,
+}
+
+/// Asset lifecycle states
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AssetStatus {
+    /// Live asset
+    Active,
+    
+    /// Underlying sold; holders are redeeming shares for proceeds
+    Redeeming,
+    
+    /// All shares redeemed
+    Retired,
 }
 
 /// Income distribution frequency options
@@ -90,6 +125,7 @@ impl AssetToken {
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
         32 + // mint
+        32 + // real_token
         100 + // name
         16 + // symbol
         32 + // category
@@ -110,7 +146,67 @@ impl AssetToken {
         8 + // last_income_distribution
         8 + // total_income_distributed
         1 + // can_mint_additional
-        1; // is_burned
+        1 + // is_burned
+        1 + // status
+        8 + // retired_at
+        ComplianceConfig::LEN + // compliance
+        4 + // holder_count
+        8; // offering_count
+    
+    /// PDA that signs asset operations approved by the asset's share holders
+    pub fn governance_signer(asset_token: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"asset_governance".as_ref(), asset_token.as_ref()], &crate::ID)
+    }
+    
+    /// PDA holding freeze authority over the asset's share mint
+    pub fn freeze_authority(asset_token: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"freeze_authority".as_ref(), asset_token.as_ref()], &crate::ID)
+    }
+}
+
+/// Sale proceeds backing the redemption of a wound-down asset
+#[account]
+pub struct Redemption {
+    /// The asset being wound down
+    pub asset_token: Pubkey,
+    
+    /// Mint the proceeds are paid in
+    pub proceeds_mint: Pubkey,
+    
+    /// Vault holding the remaining proceeds
+    pub proceeds_vault: Pubkey,
+    
+    /// Proceeds deposited at wind-down
+    pub total_proceeds: u64,
+    
+    /// Share supply outstanding at wind-down
+    pub initial_supply: u64,
+    
+    /// Shares redeemed so far
+    pub shares_redeemed: u64,
+    
+    /// Proceeds paid out so far
+    pub proceeds_paid: u64,
+    
+    /// Wind-down timestamp
+    pub started_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Redemption {
+    /// Space required for a Redemption account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // asset_token
+        32 + // proceeds_mint
+        32 + // proceeds_vault
+        8 + // total_proceeds
+        8 + // initial_supply
+        8 + // shares_redeemed
+        8 + // proceeds_paid
+        8 + // started_at
+        1; // bump
 }
 
 /// Context for creating a new asset token
@@ -123,13 +219,28 @@ impl AssetToken {
     uri: String,
     valuation: u64,
     total_shares: u64,
-    share_price: u64
+    share_price: u64,
+    can_mint_additional: bool,
+    compliance: ComplianceConfig
 )]
 pub struct CreateAssetToken<'info> {
-    /// The real token authority
-    #[account(mut)]
+    /// An asset manager
+    #[account(
+        mut,
+        constraint = roles.has_role(authority.key(), Role::AssetManager) @ RealStackError::Unauthorized
+    )]
     pub authority: Signer<'info>,
     
+    /// The REAL token account whose pause flags apply
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), real_token.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
     /// The asset token account to be created
     #[account(
         init,
@@ -138,9 +249,36 @@ pub struct CreateAssetToken<'info> {
     )]
     pub asset_token: Account<'info, AssetToken>,
     
-    /// The mint associated with this asset token
+    /// The mint associated with this asset token, fully issued and handed to the program
+    #[account(
+        mint::authority = mint_authority,
+        mint::freeze_authority = freeze_authority,
+        constraint = mint.supply == total_shares @ RealStackError::InvalidParameters
+    )]
     pub mint: Account<'info, Mint>,
     
+    /// CHECK: PDA that must hold mint authority over the share mint
+    #[account(
+        seeds = [b"mint_authority".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA that must hold freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// The account holding the whole issued supply, frozen so it only moves through the program
+    #[account(
+        mut,
+        constraint = issuer_share_account.mint == mint.key() @ RealStackError::InvalidTokenMint,
+        constraint = issuer_share_account.amount == total_shares @ RealStackError::InvalidParameters
+    )]
+    pub issuer_share_account: Account<'info, TokenAccount>,
+    
     /// System program
     pub system_program: Program<'info, System>,
     
@@ -154,35 +292,53 @@ pub struct CreateAssetToken<'info> {
 /// Context for updating an asset token's valuation
 #[derive(Accounts)]
 pub struct UpdateAssetValuation<'info> {
-    /// The authority that can update the asset
+    /// The asset's managing authority, or its governance signer
     #[account(
-        constraint = asset_token.authority == authority.key() @ RealStackError::Unauthorized
+        constraint = roles.manages_asset(authority.key(), &asset_token)
+            || authority.key() == AssetToken::governance_signer(&asset_token.key()).0 @ RealStackError::Unauthorized
     )]
     pub authority: Signer<'info>,
     
     /// The asset token account to update
     #[account(mut)]
     pub asset_token: Account<'info, AssetToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
 /// Context for verifying an asset token
 #[derive(Accounts)]
 pub struct VerifyAsset<'info> {
     /// The verifier (must have verifier role)
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = roles.has_role(verifier.key(), Role::Verifier) @ RealStackError::Unauthorized
+    )]
     pub verifier: Signer<'info>,
     
     /// The asset token account to verify
     #[account(mut)]
     pub asset_token: Account<'info, AssetToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
 /// Context for distributing income to token holders
 #[derive(Accounts)]
 pub struct DistributeIncome<'info> {
-    /// The authority that can distribute income
+    /// A treasurer
     #[account(
-        constraint = asset_token.authority == authority.key() @ RealStackError::Unauthorized
+        constraint = roles.has_role(authority.key(), Role::Treasurer) @ RealStackError::Unauthorized
     )]
     pub authority: Signer<'info>,
     
@@ -190,6 +346,19 @@ pub struct DistributeIncome<'info> {
     #[account(mut)]
     pub asset_token: Account<'info, AssetToken>,
     
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = asset_token.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
     /// The income source account
     #[account(mut)]
     pub income_source: Account<'info, TokenAccount>,
@@ -204,29 +373,386 @@ pub struct DistributeIncome<'info> {
 /// Context for toggling tradability of an asset
 #[derive(Accounts)]
 pub struct ToggleTradability<'info> {
-    /// The authority that can update the asset
+    /// The asset's managing authority, or its governance signer
     #[account(
-        constraint = asset_token.authority == authority.key() @ RealStackError::Unauthorized
+        constraint = roles.manages_asset(authority.key(), &asset_token)
+            || authority.key() == AssetToken::governance_signer(&asset_token.key()).0 @ RealStackError::Unauthorized
     )]
     pub authority: Signer<'info>,
     
     /// The asset token account to update
     #[account(mut)]
     pub asset_token: Account<'info, AssetToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
-/// Context for burning (deactivating) an asset token
+/// Context for transferring shares between holders
+#[derive(Accounts)]
+pub struct TransferShares<'info> {
+    /// The holder sending shares
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    
+    /// The asset whose shares move
+    #[account(mut)]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = asset_token.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The sender's share account
+    #[account(
+        mut,
+        constraint = sender_share_account.owner == sender.key() @ RealStackError::InvalidTokenAccount,
+        constraint = sender_share_account.mint == asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub sender_share_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: The sender's holder record, debited when it exists
+    #[account(
+        mut,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), sender.key().as_ref()],
+        bump
+    )]
+    pub sender_holder_record: UncheckedAccount<'info>,
+    
+    /// The recipient's share account
+    #[account(
+        mut,
+        constraint = recipient_share_account.mint == asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub recipient_share_account: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// The recipient's investor record, required by restricted assets
+    pub recipient_investor_record: Option<Account<'info, InvestorRecord>>,
+    
+    /// The recipient's holder record, created on first acquisition
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = HolderRecord::LEN,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), recipient_share_account.owner.as_ref()],
+        bump
+    )]
+    pub recipient_holder_record: Account<'info, HolderRecord>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for burning (winding down) an asset token
 #[derive(Accounts)]
 pub struct BurnAssetToken<'info> {
-    /// The authority that can burn the asset
+    /// The asset's managing authority, or its governance signer
     #[account(
-        constraint = asset_token.authority == authority.key() @ RealStackError::Unauthorized
+        mut,
+        constraint = roles.manages_asset(authority.key(), &asset_token)
+            || authority.key() == AssetToken::governance_signer(&asset_token.key()).0 @ RealStackError::Unauthorized
     )]
     pub authority: Signer<'info>,
     
     /// The asset token account to burn
     #[account(mut)]
     pub asset_token: Account<'info, AssetToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// Redemption record to create
+    #[account(
+        init,
+        payer = authority,
+        space = Redemption::LEN,
+        seeds = [b"redemption".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub redemption: Account<'info, Redemption>,
+    
+    /// Mint the sale proceeds are paid in
+    pub proceeds_mint: Account<'info, Mint>,
+    
+    /// Vault for the sale proceeds
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"redemption_vault".as_ref(), redemption.key().as_ref()],
+        bump,
+        token::mint = proceeds_mint,
+        token::authority = redemption
+    )]
+    pub proceeds_vault: Account<'info, TokenAccount>,
+    
+    /// Account the sale proceeds are deposited from
+    #[account(
+        mut,
+        constraint = proceeds_source.mint == proceeds_mint.key() @ RealStackError::InvalidTokenMint
+    )]
+    pub proceeds_source: Account<'info, TokenAccount>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Context for redeeming shares of a wound-down asset
+#[derive(Accounts)]
+pub struct RedeemShares<'info> {
+    /// The share holder
+    pub holder: Signer<'info>,
+    
+    /// The asset token account
+    #[account(mut)]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The asset's redemption record
+    #[account(
+        mut,
+        seeds = [b"redemption".as_ref(), asset_token.key().as_ref()],
+        bump = redemption.bump
+    )]
+    pub redemption: Account<'info, Redemption>,
+    
+    /// Share mint of the asset
+    #[account(
+        mut,
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// The holder's share account
+    #[account(
+        mut,
+        constraint = holder_share_account.owner == holder.key() @ RealStackError::InvalidTokenAccount,
+        constraint = holder_share_account.mint == asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub holder_share_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: The holder's holder record, debited when it exists
+    #[account(
+        mut,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), holder.key().as_ref()],
+        bump
+    )]
+    pub holder_record: UncheckedAccount<'info>,
+    
+    /// Vault for the sale proceeds
+    #[account(
+        mut,
+        address = redemption.proceeds_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub proceeds_vault: Account<'info, TokenAccount>,
+    
+    /// The holder's proceeds account
+    #[account(
+        mut,
+        constraint = holder_proceeds_account.mint == redemption.proceeds_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub holder_proceeds_account: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for closing a retired asset token
+#[derive(Accounts)]
+pub struct CloseAssetToken<'info> {
+    /// The asset's managing authority, receiving the rent
+    #[account(
+        mut,
+        constraint = roles.manages_asset(authority.key(), &asset_token) @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// The asset token account to close
+    #[account(
+        mut,
+        close = authority
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// The asset's redemption record
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"redemption".as_ref(), asset_token.key().as_ref()],
+        bump = redemption.bump
+    )]
+    pub redemption: Account<'info, Redemption>,
+    
+    /// Vault for the sale proceeds
+    #[account(
+        mut,
+        address = redemption.proceeds_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub proceeds_vault: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for issuing additional shares of an asset
+#[derive(Accounts)]
+pub struct MintAdditionalShares<'info> {
+    /// The asset's managing authority requesting the issuance, or its governance signer, paying for a new holder record
+    #[account(
+        mut,
+        constraint = roles.manages_asset(authority.key(), &asset_token)
+            || authority.key() == AssetToken::governance_signer(&asset_token.key()).0 @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// The asset's governance signer, or a verifier other than the requester, approving the issuance
+    #[account(
+        constraint = approver.key() == AssetToken::governance_signer(&asset_token.key()).0
+            || (roles.has_role(approver.key(), Role::Verifier) && approver.key() != authority.key()) @ RealStackError::Unauthorized
+    )]
+    pub approver: Signer<'info>,
+    
+    /// The asset token account
+    #[account(mut)]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = asset_token.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// Share mint of the asset, whose mint authority must be the program PDA
+    #[account(
+        mut,
+        address = asset_token.mint @ RealStackError::InvalidTokenMint,
+        constraint = share_mint.mint_authority == COption::Some(mint_authority.key()) @ RealStackError::CannotMintAdditional
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding mint authority over the share mint
+    #[account(
+        seeds = [b"mint_authority".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// Account receiving the new shares
+    #[account(
+        mut,
+        constraint = destination.mint == asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    
+    /// The receiving wallet's investor record, required by restricted assets
+    pub destination_investor_record: Option<Account<'info, InvestorRecord>>,
+    
+    /// The receiving wallet's holder record, created on first acquisition
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = HolderRecord::LEN,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), destination.owner.as_ref()],
+        bump
+    )]
+    pub destination_holder_record: Account<'info, HolderRecord>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for permanently locking an asset's share supply
+#[derive(Accounts)]
+pub struct LockSupply<'info> {
+    /// The asset's managing authority
+    #[account(
+        constraint = roles.manages_asset(authority.key(), &asset_token) @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// The asset token account to update
+    #[account(mut)]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
 /// Implementation of asset token operations
@@ -244,14 +770,23 @@ pub mod asset_token_operations {
         valuation: u64,
         total_shares: u64,
         share_price: u64,
+        can_mint_additional: bool,
+        compliance: ComplianceConfig,
     ) -> Result<()> {
         let asset_token = &mut ctx.accounts.asset_token;
         let authority = &ctx.accounts.authority;
         let current_timestamp = Clock::get()?.unix_timestamp;
         
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::AssetCreation, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        compliance.validate()?;
+        
         // Initialize the asset token data
         asset_token.authority = authority.key();
         asset_token.mint = ctx.accounts.mint.key();
+        asset_token.real_token = ctx.accounts.real_token.key();
         asset_token.name = name;
         asset_token.symbol = symbol;
         asset_token.category = category;
@@ -271,8 +806,25 @@ pub mod asset_token_operations {
         asset_token.income_distribution_frequency = IncomeDistributionFrequency::Monthly;
         asset_token.last_income_distribution = 0;
         asset_token.total_income_distributed = 0;
-        asset_token.can_mint_additional = false;
+        asset_token.can_mint_additional = can_mint_additional;
         asset_token.is_burned = false;
+        asset_token.status = AssetStatus::Active;
+        asset_token.retired_at = 0;
+        asset_token.compliance = compliance;
+        asset_token.holder_count = 0;
+        asset_token.offering_count = 0;
+        
+        // Freeze the issued supply so shares only move through the program
+        if !ctx.accounts.issuer_share_account.is_frozen() {
+            let freeze = ShareFreeze {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                share_mint: ctx.accounts.mint.to_account_info(),
+                freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+                asset_token: asset_token.key(),
+                bump: *ctx.bumps.get("freeze_authority").unwrap(),
+            };
+            freeze.freeze(&ctx.accounts.issuer_share_account)?;
+        }
         
         // Log the creation
         msg!("Asset token created: {}", asset_token.name);
@@ -332,6 +884,9 @@ pub mod asset_token_operations {
         let asset_token = &mut ctx.accounts.asset_token;
         let current_timestamp = Clock::get()?.unix_timestamp;
         
+        // A deactivated asset cannot be relisted
+        require!(!is_tradable || !asset_token.is_burned, RealStackError::AssetBurned);
+        
         // Update tradability status
         asset_token.is_tradable = is_tradable;
         asset_token.updated_at = current_timestamp;
@@ -343,27 +898,226 @@ pub mod asset_token_operations {
         Ok(())
     }
     
-    /// Burn (deactivate) an asset token
+    /// Transfer shares to another holder
+    pub fn transfer_shares(
+        ctx: Context<TransferShares>,
+        amount: u64,
+    ) -> Result<()> {
+        let asset_token = &mut ctx.accounts.asset_token;
+        let recipient = ctx.accounts.recipient_share_account.owner;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate trading status
+        require!(!asset_token.is_burned, RealStackError::AssetBurned);
+        require!(asset_token.is_tradable, RealStackError::AssetNotTradable);
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Trading, current_timestamp),
+            RealStackError::TransfersPaused
+        );
+        require!(amount > 0, RealStackError::InvalidParameters);
+        
+        // The recipient must meet the asset's investor requirements
+        asset_token.compliance.check_investor(
+            asset_token.real_token,
+            recipient,
+            ctx.accounts.recipient_investor_record.as_deref(),
+            current_timestamp,
+        )?;
+        
+        // Move the tracked balance, counting a first-time recipient and keeping them under the holding cap;
+        // a wallet moving shares between its own accounts keeps its balance
+        let credited = if recipient == ctx.accounts.sender.key() {
+            0
+        } else {
+            debit_holder(&ctx.accounts.sender_holder_record, amount)?;
+            amount
+        };
+        let bump = *ctx.bumps.get("recipient_holder_record").unwrap();
+        ctx.accounts.recipient_holder_record.acquire(asset_token, recipient, bump, credited, current_timestamp)?;
+        
+        // Share accounts stay frozen outside the program, so thaw both sides around the transfer
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: asset_token.key(),
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        let same_account = ctx.accounts.sender_share_account.key() == ctx.accounts.recipient_share_account.key();
+        freeze.thaw(&ctx.accounts.sender_share_account)?;
+        if !same_account {
+            freeze.thaw(&ctx.accounts.recipient_share_account)?;
+        }
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.sender_share_account.to_account_info(),
+                    to: ctx.accounts.recipient_share_account.to_account_info(),
+                    authority: ctx.accounts.sender.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        freeze.freeze(&ctx.accounts.sender_share_account)?;
+        if !same_account {
+            freeze.freeze(&ctx.accounts.recipient_share_account)?;
+        }
+        
+        // Log the transfer
+        msg!("Shares transferred: {} of {} to {}", amount, asset_token.name, recipient);
+        
+        Ok(())
+    }
+    
+    /// Burn (wind down) an asset token, depositing the sale proceeds for redemption
     pub fn burn_asset_token(
         ctx: Context<BurnAssetToken>,
+        proceeds_amount: u64,
     ) -> Result<()> {
         let asset_token = &mut ctx.accounts.asset_token;
+        let redemption = &mut ctx.accounts.redemption;
         let current_timestamp = Clock::get()?.unix_timestamp;
         
         // Verify the asset is not already burned
         require!(!asset_token.is_burned, RealStackError::AssetAlreadyBurned);
         
-        // Mark the asset as burned
+        let initial_supply = ctx.accounts.share_mint.supply;
+        require!(initial_supply > 0, RealStackError::InvalidParameters);
+        require!(proceeds_amount > 0, RealStackError::InvalidParameters);
+        
+        // Deposit the sale proceeds
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.proceeds_source.to_account_info(),
+                    to: ctx.accounts.proceeds_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            proceeds_amount,
+        )?;
+        
+        // Set up the redemption
+        redemption.asset_token = asset_token.key();
+        redemption.proceeds_mint = ctx.accounts.proceeds_mint.key();
+        redemption.proceeds_vault = ctx.accounts.proceeds_vault.key();
+        redemption.total_proceeds = proceeds_amount;
+        redemption.initial_supply = initial_supply;
+        redemption.shares_redeemed = 0;
+        redemption.proceeds_paid = 0;
+        redemption.started_at = current_timestamp;
+        redemption.bump = *ctx.bumps.get("redemption").unwrap();
+        
+        // Mark the asset as burned and open redemptions
         asset_token.is_burned = true;
         asset_token.is_tradable = false;
+        asset_token.status = AssetStatus::Redeeming;
         asset_token.updated_at = current_timestamp;
         
         // Log the burn
         msg!("Asset token burned: {}", asset_token.name);
+        msg!("Proceeds: {}, Shares outstanding: {}", proceeds_amount, initial_supply);
+        
+        Ok(())
+    }
+    
+    /// Burn shares of a wound-down asset for a pro-rata part of the sale proceeds
+    pub fn redeem_shares(
+        ctx: Context<RedeemShares>,
+        amount: u64,
+    ) -> Result<()> {
+        let asset_token = &mut ctx.accounts.asset_token;
+        let redemption = &mut ctx.accounts.redemption;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(
+            asset_token.status == AssetStatus::Redeeming,
+            RealStackError::AssetNotRedeeming
+        );
+        require!(amount > 0, RealStackError::InvalidParameters);
+        
+        // Pay out of what is left, so rounding dust goes to the last holder
+        let supply = ctx.accounts.share_mint.supply;
+        let payout = redemption_payout(ctx.accounts.proceeds_vault.amount, amount, supply)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        // Retire the holder's shares, thawing the account around the burn
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: asset_token.key(),
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        freeze.thaw(&ctx.accounts.holder_share_account)?;
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    from: ctx.accounts.holder_share_account.to_account_info(),
+                    authority: ctx.accounts.holder.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        freeze.freeze(&ctx.accounts.holder_share_account)?;
+        debit_holder(&ctx.accounts.holder_record, amount)?;
+        
+        // Pay the holder
+        if payout > 0 {
+            let asset_key = asset_token.key();
+            let seeds = &[b"redemption".as_ref(), asset_key.as_ref(), &[redemption.bump]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.proceeds_vault.to_account_info(),
+                        to: ctx.accounts.holder_proceeds_account.to_account_info(),
+                        authority: redemption.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                payout,
+            )?;
+        }
+        
+        // Update redemption totals
+        redemption.shares_redeemed = redemption.shares_redeemed
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        redemption.proceeds_paid = redemption.proceeds_paid
+            .checked_add(payout)
+            .ok_or(RealStackError::MathOverflow)?;
+        asset_token.updated_at = current_timestamp;
+        
+        // Retire the asset once the last share is gone
+        ctx.accounts.share_mint.reload()?;
+        if ctx.accounts.share_mint.supply == 0 {
+            asset_token.status = AssetStatus::Retired;
+            asset_token.retired_at = current_timestamp;
+            msg!("Asset fully retired: {}", asset_token.name);
+        }
+        
+        // Log the redemption
+        msg!("Redeemed {} shares for {} by {}", amount, payout, ctx.accounts.holder.key());
         
         Ok(())
     }
     
+    /// Proceeds owed for burning `amount` of the `supply` shares still outstanding
+    pub fn redemption_payout(remaining_proceeds: u64, amount: u64, supply: u64) -> Option<u64> {
+        if amount > supply {
+            return None;
+        }
+        let payout = (remaining_proceeds as u128)
+            .checked_mul(amount as u128)?
+            .checked_div(supply as u128)?;
+        u64::try_from(payout).ok()
+    }
+    
     /// Distribute income to token holders
     pub fn distribute_income(
         ctx: Context<DistributeIncome>,
@@ -374,6 +1128,10 @@ pub mod asset_token_operations {
         
         // Verify the asset is active
         require!(!asset_token.is_burned, RealStackError::AssetBurned);
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::IncomeDistribution, current_timestamp),
+            RealStackError::OperationPaused
+        );
         
         // Update income distribution data
         asset_token.last_income_distribution = current_timestamp;
@@ -392,4 +1150,136 @@ pub mod asset_token_operations {
         
         Ok(())
     }
-} 
\ No newline at end of file
+    
+    /// Issue additional shares, diluting the current share price
+    pub fn mint_additional_shares(
+        ctx: Context<MintAdditionalShares>,
+        amount: u64,
+    ) -> Result<()> {
+        let asset_token = &mut ctx.accounts.asset_token;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Verify issuance is allowed
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::AssetCreation, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(!asset_token.is_burned, RealStackError::AssetBurned);
+        require!(asset_token.can_mint_additional, RealStackError::CannotMintAdditional);
+        require!(amount > 0, RealStackError::InvalidParameters);
+        
+        let total_shares = asset_token.total_shares
+            .checked_add(amount)
+            .ok_or(RealStackError::TotalSharesExceedsMaximum)?;
+        
+        // The recipient must meet the asset's investor requirements
+        let recipient = ctx.accounts.destination.owner;
+        asset_token.compliance.check_investor(
+            asset_token.real_token,
+            recipient,
+            ctx.accounts.destination_investor_record.as_deref(),
+            current_timestamp,
+        )?;
+        
+        // Count a first-time recipient and keep them under the holding cap of the enlarged supply
+        asset_token.total_shares = total_shares;
+        let bump = *ctx.bumps.get("destination_holder_record").unwrap();
+        ctx.accounts.destination_holder_record.acquire(asset_token, recipient, bump, amount, current_timestamp)?;
+        
+        // Mint the new shares to the designated account, thawing it around the mint
+        let asset_key = asset_token.key();
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: asset_key,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        freeze.thaw(&ctx.accounts.destination)?;
+        let seeds = &[
+            b"mint_authority".as_ref(),
+            asset_key.as_ref(),
+            &[*ctx.bumps.get("mint_authority").unwrap()],
+        ];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+        freeze.freeze(&ctx.accounts.destination)?;
+        
+        // Spread the unchanged valuation over the enlarged share count
+        asset_token.current_share_price = asset_token.valuation / total_shares;
+        asset_token.updated_at = current_timestamp;
+        
+        // Log the issuance
+        msg!("Additional shares minted for: {}", asset_token.name);
+        msg!("Amount: {}, Total shares: {}, New share price: {}",
+            amount, total_shares, asset_token.current_share_price);
+        msg!("Approved by: {}", ctx.accounts.approver.key());
+        
+        Ok(())
+    }
+    
+    /// Permanently disable additional share issuance
+    pub fn lock_supply(
+        ctx: Context<LockSupply>,
+    ) -> Result<()> {
+        let asset_token = &mut ctx.accounts.asset_token;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Verify the supply is not already locked
+        require!(asset_token.can_mint_additional, RealStackError::CannotMintAdditional);
+        
+        // Clear the flag; nothing sets it again after creation
+        asset_token.can_mint_additional = false;
+        asset_token.updated_at = current_timestamp;
+        
+        // Log the lock
+        msg!("Share supply locked for: {}", asset_token.name);
+        msg!("Total shares: {}", asset_token.total_shares);
+        
+        Ok(())
+    }
+    
+    /// Close a fully retired asset and its redemption accounts, returning the rent
+    pub fn close_asset_token(
+        ctx: Context<CloseAssetToken>,
+    ) -> Result<()> {
+        let asset_token = &ctx.accounts.asset_token;
+        let redemption = &ctx.accounts.redemption;
+        
+        // Only fully retired assets can be closed
+        require!(
+            asset_token.status == AssetStatus::Retired,
+            RealStackError::AssetNotRetired
+        );
+        require!(ctx.accounts.share_mint.supply == 0, RealStackError::AssetNotRetired);
+        require!(ctx.accounts.proceeds_vault.amount == 0, RealStackError::AssetNotRetired);
+        
+        // Close the emptied proceeds vault
+        let asset_key = asset_token.key();
+        let seeds = &[b"redemption".as_ref(), asset_key.as_ref(), &[redemption.bump]];
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.proceeds_vault.to_account_info(),
+                destination: ctx.accounts.authority.to_account_info(),
+                authority: redemption.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+        
+        // Log the close
+        msg!("Asset token closed: {}", asset_token.name);
+        
+        Ok(())
+    }
+}
\ No newline at end of file
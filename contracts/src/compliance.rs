@@ -0,0 +1,551 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, FreezeAccount, ThawAccount, TokenAccount};
+use crate::errors::*;
+use crate::access_control::{Role, Roles};
+use crate::tokenomics::RealToken;
+use crate::asset_token::AssetToken;
+
+/// Maximum number of jurisdictions an asset can admit
+pub const MAX_ALLOWED_JURISDICTIONS: usize = 16;
+
+/// An investor's identity checks, written by a KYC provider
+#[account]
+pub struct InvestorRecord {
+    /// The investor's wallet
+    pub wallet: Pubkey,
+    
+    /// REAL token deployment whose KYC providers vouch for the investor
+    pub real_token: Pubkey,
+    
+    /// Outcome of the identity checks
+    pub kyc_status: KycStatus,
+    
+    /// ISO 3166-1 alpha-2 code of the investor's jurisdiction
+    pub jurisdiction: [u8; 2],
+    
+    /// Whether the investor qualifies as accredited
+    pub accredited: bool,
+    
+    /// When the verification lapses
+    pub expires_at: i64,
+    
+    /// KYC provider that last wrote the record
+    pub provider: Pubkey,
+    
+    /// Last update timestamp
+    pub updated_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Outcome of an investor's identity checks
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KycStatus {
+    /// Checks in progress
+    Pending,
+    
+    /// Identity verified
+    Verified,
+    
+    /// Checks failed
+    Rejected,
+    
+    /// Verification withdrawn by the provider
+    Revoked,
+}
+
+impl InvestorRecord {
+    /// Space required for an InvestorRecord account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        32 + // real_token
+        1 + // kyc_status
+        2 + // jurisdiction
+        1 + // accredited
+        8 + // expires_at
+        32 + // provider
+        8 + // updated_at
+        1; // bump
+    
+    /// PDA holding a wallet's record under a REAL token deployment
+    pub fn address(real_token: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"investor".as_ref(), real_token.as_ref(), wallet.as_ref()], &crate::ID)
+    }
+    
+    /// Read the record at a wallet's record PDA, which may not exist
+    pub fn load(investor_record: &AccountInfo) -> Result<Option<Self>> {
+        if investor_record.data_is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::try_deserialize(&mut &investor_record.try_borrow_data()?[..])?))
+    }
+}
+
+/// A wallet counted among an asset's distinct holders
+#[account]
+pub struct HolderRecord {
+    /// The asset held
+    pub asset_token: Pubkey,
+    
+    /// The holder's wallet
+    pub wallet: Pubkey,
+    
+    /// When the wallet first acquired shares
+    pub registered_at: i64,
+    
+    /// Shares the wallet acquired through the program, net of those it moved out through it
+    pub balance: u64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl HolderRecord {
+    /// Space required for a HolderRecord account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // asset_token
+        32 + // wallet
+        8 + // registered_at
+        8 + // balance
+        1; // bump
+    
+    /// PDA counting a wallet as a holder of an asset
+    pub fn address(asset_token: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"holder".as_ref(), asset_token.as_ref(), wallet.as_ref()], &crate::ID)
+    }
+    
+    /// Whether the record has counted its wallet yet
+    pub fn is_registered(&self) -> bool {
+        self.wallet != Pubkey::default()
+    }
+    
+    /// Count `wallet` as a holder of the asset the first time it acquires shares
+    pub fn register(
+        &mut self,
+        asset_token: &mut Account<AssetToken>,
+        wallet: Pubkey,
+        bump: u8,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        if self.is_registered() {
+            return Ok(());
+        }
+        let holder_count = asset_token.holder_count
+            .checked_add(1)
+            .ok_or(RealStackError::MathOverflow)?;
+        require!(
+            asset_token.compliance.max_holders == 0 || holder_count <= asset_token.compliance.max_holders,
+            RealStackError::HolderLimitReached
+        );
+        asset_token.holder_count = holder_count;
+        self.open(asset_token.key(), wallet, bump, current_timestamp);
+        Ok(())
+    }
+    
+    /// Start counting `wallet` as a holder of `asset_token`
+    fn open(&mut self, asset_token: Pubkey, wallet: Pubkey, bump: u8, current_timestamp: i64) {
+        self.asset_token = asset_token;
+        self.wallet = wallet;
+        self.registered_at = current_timestamp;
+        self.balance = 0;
+        self.bump = bump;
+    }
+    
+    /// Count `wallet` as a holder and credit `amount` acquired shares, keeping it under the holding cap
+    pub fn acquire(
+        &mut self,
+        asset_token: &mut Account<AssetToken>,
+        wallet: Pubkey,
+        bump: u8,
+        amount: u64,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        // Check before counting the holder, so a refused acquisition leaves the record untouched
+        let balance = self.balance
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        asset_token.compliance.check_holding(asset_token.total_shares, balance)?;
+        self.register(asset_token, wallet, bump, current_timestamp)?;
+        self.balance = balance;
+        Ok(())
+    }
+    
+    /// Credit `amount` shares a wallet takes back out of a program vault it put them in,
+    /// skipping the holding cap and holder limit so an existing position can always be exited
+    pub fn restore(
+        &mut self,
+        asset_token: &mut Account<AssetToken>,
+        wallet: Pubkey,
+        bump: u8,
+        amount: u64,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        if !self.is_registered() {
+            asset_token.holder_count = asset_token.holder_count
+                .checked_add(1)
+                .ok_or(RealStackError::MathOverflow)?;
+            self.open(asset_token.key(), wallet, bump, current_timestamp);
+        }
+        self.balance = self.balance
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        Ok(())
+    }
+    
+    /// Take `amount` shares moved out through the program off the tracked balance
+    pub fn debit(&mut self, amount: u64) {
+        // Shares that reached the wallet outside the program, such as the issuer's supply, were never credited
+        self.balance = self.balance.saturating_sub(amount);
+    }
+}
+
+/// Debit shares a wallet moved out through the program, given its holder record PDA, which may not exist
+pub fn debit_holder(holder_record: &AccountInfo, amount: u64) -> Result<()> {
+    if holder_record.data_is_empty() {
+        return Ok(());
+    }
+    let mut record = Account::<HolderRecord>::try_from(holder_record)?;
+    record.debit(amount);
+    record.exit(&crate::ID)
+}
+
+/// Thaws and refreezes an asset's wallet share accounts, which stay frozen outside program instructions
+/// so shares only move where the program enforces the asset's investor requirements
+pub struct ShareFreeze<'info> {
+    /// Token program
+    pub token_program: AccountInfo<'info>,
+    
+    /// Share mint of the asset
+    pub share_mint: AccountInfo<'info>,
+    
+    /// The asset's freeze authority PDA
+    pub freeze_authority: AccountInfo<'info>,
+    
+    /// The asset the share mint belongs to
+    pub asset_token: Pubkey,
+    
+    /// Bump of the freeze authority PDA
+    pub bump: u8,
+}
+
+impl<'info> ShareFreeze<'info> {
+    /// Thaw a share account so the program can move its shares; a new account is not frozen yet
+    pub fn thaw(&self, account: &Account<'info, TokenAccount>) -> Result<()> {
+        if !account.is_frozen() {
+            return Ok(());
+        }
+        let bump = [self.bump];
+        let seeds = &[b"freeze_authority".as_ref(), self.asset_token.as_ref(), &bump];
+        token::thaw_account(CpiContext::new_with_signer(
+            self.token_program.clone(),
+            ThawAccount {
+                account: account.to_account_info(),
+                mint: self.share_mint.clone(),
+                authority: self.freeze_authority.clone(),
+            },
+            &[&seeds[..]],
+        ))
+    }
+    
+    /// Freeze a share account again once the program has moved its shares
+    pub fn freeze(&self, account: &Account<'info, TokenAccount>) -> Result<()> {
+        let bump = [self.bump];
+        let seeds = &[b"freeze_authority".as_ref(), self.asset_token.as_ref(), &bump];
+        token::freeze_account(CpiContext::new_with_signer(
+            self.token_program.clone(),
+            FreezeAccount {
+                account: account.to_account_info(),
+                mint: self.share_mint.clone(),
+                authority: self.freeze_authority.clone(),
+            },
+            &[&seeds[..]],
+        ))
+    }
+}
+
+/// Shares a wallet bought in an offering, kept in the offering's vault until they unlock
+#[account]
+pub struct ShareLock {
+    /// The asset whose shares are locked
+    pub asset_token: Pubkey,
+    
+    /// The locked wallet
+    pub wallet: Pubkey,
+    
+    /// Shares held in escrow until `unlocks_at`
+    pub locked_amount: u64,
+    
+    /// When the locked shares may be claimed
+    pub unlocks_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ShareLock {
+    /// Space required for a ShareLock account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // asset_token
+        32 + // wallet
+        8 + // locked_amount
+        8 + // unlocks_at
+        1; // bump
+    
+    /// PDA holding a wallet's lock on an asset's shares
+    pub fn address(asset_token: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"share_lock".as_ref(), asset_token.as_ref(), wallet.as_ref()], &crate::ID)
+    }
+    
+    /// Shares still locked at `current_timestamp`
+    pub fn locked_at(&self, current_timestamp: i64) -> u64 {
+        if current_timestamp < self.unlocks_at {
+            self.locked_amount
+        } else {
+            0
+        }
+    }
+    
+    /// Lock newly bought shares for `lockup_duration`; shares still locked are held back until the new unlock time.
+    /// One lock covers every purchase, so a later purchase delays the earlier ones by at most the offering window.
+    pub fn lock(&mut self, amount: u64, lockup_duration: i64, current_timestamp: i64) -> Result<()> {
+        self.locked_amount = self.locked_at(current_timestamp)
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        self.unlocks_at = current_timestamp
+            .checked_add(lockup_duration)
+            .ok_or(RealStackError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// Who may hold an asset's shares, fixed by the issuer at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ComplianceConfig {
+    /// Whether holders need a current KYC verification
+    pub kyc_required: bool,
+    
+    /// Jurisdictions holders may reside in; empty admits any
+    pub allowed_jurisdictions: Vec<[u8; 2]>,
+    
+    /// Whether holders must be accredited investors
+    pub accredited_only: bool,
+    
+    /// Largest part of `total_shares` one wallet may hold, in basis points; 0 for no cap
+    pub max_holding_bps: u16,
+    
+    /// Most distinct holders the asset may have; 0 for no limit
+    pub max_holders: u32,
+    
+    /// Seconds shares bought in an offering stay in escrow after purchase; 0 for no lock-up
+    pub lockup_duration: i64,
+}
+
+impl ComplianceConfig {
+    /// Space required for a ComplianceConfig
+    pub const LEN: usize = 1 + // kyc_required
+        4 + MAX_ALLOWED_JURISDICTIONS * 2 + // allowed_jurisdictions
+        1 + // accredited_only
+        2 + // max_holding_bps
+        4 + // max_holders
+        8; // lockup_duration
+    
+    /// Check the configuration an issuer supplied
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.allowed_jurisdictions.len() <= MAX_ALLOWED_JURISDICTIONS,
+            RealStackError::InvalidParameters
+        );
+        require!(
+            self.allowed_jurisdictions.iter().all(is_jurisdiction_code),
+            RealStackError::InvalidParameters
+        );
+        require!(self.max_holding_bps <= 10_000, RealStackError::InvalidParameters);
+        require!(self.lockup_duration >= 0, RealStackError::InvalidParameters);
+        Ok(())
+    }
+    
+    /// Whether acquiring shares needs an investor record at all
+    pub fn requires_investor_record(&self) -> bool {
+        self.kyc_required || self.accredited_only || !self.allowed_jurisdictions.is_empty()
+    }
+    
+    /// Check that `wallet` may acquire shares, given its investor record if supplied
+    pub fn check_investor(
+        &self,
+        real_token: Pubkey,
+        wallet: Pubkey,
+        investor_record: Option<&InvestorRecord>,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        if !self.requires_investor_record() {
+            return Ok(());
+        }
+        let record = investor_record.ok_or(RealStackError::InvestorNotVerified)?;
+        require!(
+            record.wallet == wallet && record.real_token == real_token,
+            RealStackError::InvalidInvestorRecord
+        );
+        require!(
+            record.kyc_status == KycStatus::Verified && current_timestamp < record.expires_at,
+            RealStackError::InvestorNotVerified
+        );
+        require!(
+            self.allowed_jurisdictions.is_empty() || self.allowed_jurisdictions.contains(&record.jurisdiction),
+            RealStackError::JurisdictionNotAllowed
+        );
+        require!(
+            !self.accredited_only || record.accredited,
+            RealStackError::AccreditationRequired
+        );
+        Ok(())
+    }
+    
+    /// Check a wallet's share balance after an acquisition against the per-wallet cap
+    pub fn check_holding(&self, total_shares: u64, balance: u64) -> Result<()> {
+        if self.max_holding_bps == 0 {
+            return Ok(());
+        }
+        let cap = (total_shares as u128)
+            .checked_mul(self.max_holding_bps as u128)
+            .ok_or(RealStackError::MathOverflow)?
+            / 10_000;
+        require!(balance as u128 <= cap, RealStackError::HoldingCapExceeded);
+        Ok(())
+    }
+}
+
+/// Whether a code has the shape of an ISO 3166-1 alpha-2 code
+fn is_jurisdiction_code(code: &[u8; 2]) -> bool {
+    code.iter().all(u8::is_ascii_uppercase)
+}
+
+/// Context for writing an investor record
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct SetInvestorRecord<'info> {
+    /// A KYC provider
+    #[account(
+        mut,
+        constraint = roles.has_role(provider.key(), Role::KycProvider) @ RealStackError::Unauthorized
+    )]
+    pub provider: Signer<'info>,
+    
+    /// The REAL token deployment
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), real_token.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
+    /// The investor's record, created on first write
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = InvestorRecord::LEN,
+        seeds = [b"investor".as_ref(), real_token.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub investor_record: Account<'info, InvestorRecord>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for closing the holder record of a wallet that sold out
+#[derive(Accounts)]
+pub struct CloseHolderRecord<'info> {
+    /// The former holder, refunded the record's rent
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    
+    /// The asset no longer held
+    #[account(mut)]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The wallet's holder record, whose tracked balance must be zero
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub holder_record: Account<'info, HolderRecord>,
+    
+    /// The wallet's share account, which must be empty
+    #[account(
+        constraint = share_account.owner == wallet.key() @ RealStackError::InvalidTokenAccount,
+        constraint = share_account.mint == asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_account: Account<'info, TokenAccount>,
+}
+
+/// Implementation of compliance operations
+pub mod compliance_operations {
+    use super::*;
+    
+    /// Record the outcome of a wallet's KYC and accreditation checks
+    pub fn set_investor_record(
+        ctx: Context<SetInvestorRecord>,
+        wallet: Pubkey,
+        kyc_status: KycStatus,
+        jurisdiction: [u8; 2],
+        accredited: bool,
+        expires_at: i64,
+    ) -> Result<()> {
+        let investor_record = &mut ctx.accounts.investor_record;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate the record
+        require!(is_jurisdiction_code(&jurisdiction), RealStackError::InvalidParameters);
+        require!(
+            kyc_status != KycStatus::Verified || expires_at > current_timestamp,
+            RealStackError::InvalidParameters
+        );
+        
+        // Write the record
+        investor_record.wallet = wallet;
+        investor_record.real_token = ctx.accounts.real_token.key();
+        investor_record.kyc_status = kyc_status;
+        investor_record.jurisdiction = jurisdiction;
+        investor_record.accredited = accredited;
+        investor_record.expires_at = expires_at;
+        investor_record.provider = ctx.accounts.provider.key();
+        investor_record.updated_at = current_timestamp;
+        investor_record.bump = *ctx.bumps.get("investor_record").unwrap();
+        
+        // Log the update
+        msg!("Investor record updated for: {}", wallet);
+        msg!("Status: {:?}, Jurisdiction: {}, Accredited: {}, Expires at: {}",
+            kyc_status, String::from_utf8_lossy(&jurisdiction), accredited, expires_at);
+        
+        Ok(())
+    }
+    
+    /// Stop counting a wallet that no longer holds shares among an asset's holders
+    pub fn close_holder_record(ctx: Context<CloseHolderRecord>) -> Result<()> {
+        let asset_token = &mut ctx.accounts.asset_token;
+        
+        // Every share the wallet acquired through the program, in any of its accounts, must be gone
+        require!(ctx.accounts.holder_record.balance == 0, RealStackError::SharesStillHeld);
+        
+        // Nor may the wallet still hold shares the program did not track
+        require!(ctx.accounts.share_account.amount == 0, RealStackError::SharesStillHeld);
+        
+        // Records opened by a sale never counted their wallet
+        if ctx.accounts.holder_record.is_registered() {
+            asset_token.holder_count = asset_token.holder_count
+                .checked_sub(1)
+                .ok_or(RealStackError::MathUnderflow)?;
+        }
+        
+        // Log the closure
+        msg!("Holder record closed for: {}", ctx.accounts.wallet.key());
+        msg!("Holders remaining: {}", asset_token.holder_count);
+        
+        Ok(())
+    }
+}
@@ -122,4 +122,268 @@ pub enum RealStackError {
     /// Liquidity pool not found
     #[msg("Liquidity pool not found for this asset")]
     LiquidityPoolNotFound,
-} 
\ No newline at end of file
+    
+    /// Stake amount exceeds staked balance
+    #[msg("Insufficient staked balance")]
+    InsufficientStake,
+    
+    /// Nothing waiting to be withdrawn
+    #[msg("No pending unstake to withdraw")]
+    NoPendingUnstake,
+    
+    /// Unstake cooldown still running
+    #[msg("Unstake cooldown has not elapsed")]
+    StakeCooldownActive,
+    
+    /// No rewards accrued
+    #[msg("No staking rewards to claim")]
+    NoRewardsToClaim,
+    
+    /// Nothing is staked
+    #[msg("No tokens are staked in the pool")]
+    NoStakedTokens,
+    
+    /// Vote weight exceeds the voter's power
+    #[msg("Vote weight exceeds voting power")]
+    InsufficientVotingPower,
+    
+    /// Offering not active
+    #[msg("Offering is not active")]
+    OfferingNotActive,
+    
+    /// Offering not started
+    #[msg("Offering has not started")]
+    OfferingNotStarted,
+    
+    /// Offering ended
+    #[msg("Offering has ended")]
+    OfferingEnded,
+    
+    /// Offering still running
+    #[msg("Offering has not ended")]
+    OfferingNotEnded,
+    
+    /// Offering already finalized
+    #[msg("Offering has already been finalized")]
+    OfferingAlreadyFinalized,
+    
+    /// Offering sold out
+    #[msg("Not enough shares left in the offering")]
+    OfferingSoldOut,
+    
+    /// Purchase below minimum
+    #[msg("Purchase is below the minimum size")]
+    PurchaseBelowMinimum,
+    
+    /// Per-wallet limit exceeded
+    #[msg("Purchase exceeds the per-wallet limit")]
+    WalletLimitExceeded,
+    
+    /// Offering not successful
+    #[msg("Offering did not succeed")]
+    OfferingNotSucceeded,
+    
+    /// Offering not failed
+    #[msg("Offering did not fail")]
+    OfferingNotFailed,
+    
+    /// Purchase already settled
+    #[msg("Purchase has already been settled")]
+    PurchaseAlreadySettled,
+    
+    /// Order book full
+    #[msg("Order book side is full")]
+    OrderBookFull,
+    
+    /// Order not found
+    #[msg("Order not found")]
+    OrderNotFound,
+    
+    /// Best orders do not cross
+    #[msg("Best bid and ask do not cross")]
+    OrdersNotCrossing,
+    
+    /// Transfers paused
+    #[msg("Token transfers are paused")]
+    TransfersPaused,
+    
+    /// Slippage limit exceeded
+    #[msg("Slippage limit exceeded")]
+    SlippageExceeded,
+    
+    /// Insufficient pool liquidity
+    #[msg("Insufficient liquidity in the pool")]
+    InsufficientLiquidity,
+    
+    /// Asset not redeeming
+    #[msg("Asset is not in redemption")]
+    AssetNotRedeeming,
+    
+    /// Proposal not finalized
+    #[msg("Proposal has not been finalized")]
+    ProposalNotFinalized,
+    
+    /// Retention period active
+    #[msg("Retention period has not elapsed")]
+    RetentionPeriodActive,
+    
+    /// Asset not retired
+    #[msg("Asset has not been fully retired")]
+    AssetNotRetired,
+    
+    /// Pause cooldown active
+    #[msg("Pause cooldown has not elapsed")]
+    PauseCooldownActive,
+    
+    /// Operation paused
+    #[msg("This operation is paused")]
+    OperationPaused,
+    
+    /// Role list full
+    #[msg("Role member list is full")]
+    RoleListFull,
+    
+    /// Role not held
+    #[msg("Account does not hold this role")]
+    RoleNotHeld,
+    
+    /// Unsupported admin action
+    #[msg("Instruction cannot be wrapped in an admin action")]
+    UnsupportedAdminAction,
+    
+    /// Action already approved
+    #[msg("Admin action already approved by this owner")]
+    ActionAlreadyApproved,
+    
+    /// Action already executed
+    #[msg("Admin action has already been executed")]
+    ActionAlreadyExecuted,
+    
+    /// Action expired
+    #[msg("Admin action approvals have expired")]
+    ActionExpired,
+    
+    /// Insufficient approvals
+    #[msg("Not enough approvals to execute the admin action")]
+    InsufficientApprovals,
+    
+    /// Already voted
+    #[msg("Voter has already voted on this proposal")]
+    AlreadyVoted,
+    
+    /// Delegation does not apply
+    #[msg("Delegation does not cover this proposal")]
+    DelegationNotApplicable,
+    
+    /// Invalid ballot
+    #[msg("Ballot does not match the proposal's options")]
+    InvalidBallot,
+    
+    /// Insufficient proposal balance
+    #[msg("Proposer does not hold enough REAL to create a proposal")]
+    InsufficientProposalBalance,
+    
+    /// Too many active proposals
+    #[msg("Proposer has too many proposals with unsettled deposits")]
+    TooManyActiveProposals,
+    
+    /// Deposit already settled
+    #[msg("Proposal deposit has already been settled")]
+    DepositAlreadySettled,
+    
+    /// Deposit not settled
+    #[msg("Proposal deposit must be settled first")]
+    DepositNotSettled,
+    
+    /// Proposal scope mismatch
+    #[msg("Proposal is not voted on with this token")]
+    ProposalScopeMismatch,
+    
+    /// Stake locked by vote
+    #[msg("Stake is locked by a conviction vote")]
+    StakeLockedByVote,
+    
+    /// Wrong execution instruction
+    #[msg("Proposal must be executed with its dedicated instruction")]
+    WrongExecutionInstruction,
+    
+    /// Spending cap exceeded
+    #[msg("Treasury spending cap for this period exceeded")]
+    SpendingCapExceeded,
+    
+    /// Insufficient treasury funds
+    #[msg("Treasury vault has insufficient unreserved funds")]
+    InsufficientTreasuryFunds,
+    
+    /// Stream cancelled
+    #[msg("Stream has already been cancelled")]
+    StreamCancelled,
+    
+    /// Nothing to claim
+    #[msg("Nothing has accrued to claim")]
+    NothingToClaim,
+    
+    /// Timelock not expired
+    #[msg("Proposal timelock has not expired")]
+    TimelockNotExpired,
+    
+    /// Invalid upgrade buffer
+    #[msg("Buffer is not the loader buffer named by the proposal")]
+    InvalidUpgradeBuffer,
+    
+    /// Buffer hash mismatch
+    #[msg("Buffer contents do not match the approved hash")]
+    BufferHashMismatch,
+    
+    /// Delegation after snapshot
+    #[msg("Delegation was set after the proposal's snapshot")]
+    DelegationAfterSnapshot,
+    
+    /// Optimistic proposal
+    #[msg("Optimistic proposals take objections until escalated to a vote")]
+    OptimisticProposal,
+    
+    /// Not an optimistic proposal
+    #[msg("Proposal is not in an optimistic challenge window")]
+    NotOptimisticProposal,
+    
+    /// Investor not verified
+    #[msg("Investor has no current KYC verification")]
+    InvestorNotVerified,
+    
+    /// Invalid investor record
+    #[msg("Investor record belongs to another wallet or deployment")]
+    InvalidInvestorRecord,
+    
+    /// Jurisdiction not allowed
+    #[msg("Investor's jurisdiction is not eligible for this asset")]
+    JurisdictionNotAllowed,
+    
+    /// Accreditation required
+    #[msg("Asset is restricted to accredited investors")]
+    AccreditationRequired,
+    
+    /// Acquisition would exceed the per-wallet holding cap
+    #[msg("Acquisition would exceed the per-wallet holding cap")]
+    HoldingCapExceeded,
+    
+    /// Asset has reached its maximum number of holders
+    #[msg("Asset has reached its maximum number of holders")]
+    HolderLimitReached,
+    
+    /// Share account still holds shares
+    #[msg("Share account still holds shares")]
+    SharesStillHeld,
+    
+    /// Shares are still locked
+    #[msg("Shares are still in their lock-up period")]
+    SharesLocked,
+    
+    /// Owner has too many resting orders
+    #[msg("Owner has too many resting orders on this side of the book")]
+    OwnerOrderLimitReached,
+    
+    /// Order below minimum notional
+    #[msg("Order is below the book's minimum notional")]
+    OrderBelowMinimum,
+}
\ No newline at end of file
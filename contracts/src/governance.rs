@@ -1,6 +1,47 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
 use crate::errors::*;
+use crate::access_control::{Role, Roles};
+use crate::asset_token::AssetToken;
+use crate::compliance::ShareFreeze;
+use crate::liquidity_pool::liquidity_pool_operations::integer_sqrt;
+use crate::multisig::{AdminActionKind, MAX_ACTION_ACCOUNTS, MAX_ACTION_DATA};
+use crate::staking::{StakeAccount, StakePool, VotingPowerCheckpoints, MAX_SNAPSHOT_AGE};
+use crate::tokenomics::{token_operations::calculate_percentage, FeeConfig, PauseFlag, RealToken, MAX_TRANSACTION_FEE_BPS};
+use crate::treasury::TreasuryAction;
+use crate::upgrade::UpgradeAction;
+
+/// How long a finished proposal is kept before its rent can be reclaimed (30 days)
+pub const PROPOSAL_RETENTION_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+/// Maximum number of options on a multi-option proposal
+pub const MAX_PROPOSAL_OPTIONS: usize = 4;
+
+/// Maximum length of an option label
+pub const MAX_OPTION_LABEL_LEN: usize = 32;
+
+/// Maximum length of an option's execution data
+pub const MAX_OPTION_DATA_LEN: usize = 64;
+
+/// Share of an asset's supply that must vote for an asset proposal to reach quorum
+pub const ASSET_QUORUM_PERCENTAGE: u8 = 20;
+
+/// Share of an asset's supply a holder needs to propose an asset action
+pub const ASSET_PROPOSAL_THRESHOLD_PERCENTAGE: u8 = 1;
+
+/// Lock duration that earns a conviction vote one more multiple of its tokens (7 days)
+pub const CONVICTION_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+/// Highest weight multiplier a conviction vote can earn
+pub const MAX_CONVICTION_MULTIPLIER: u64 = 6;
+
+/// Longest lock a conviction vote can choose
+pub const MAX_CONVICTION_LOCK: i64 = (MAX_CONVICTION_MULTIPLIER as i64 - 1) * CONVICTION_PERIOD;
+
+/// Share of staked REAL whose objections send an optimistic proposal to a full vote
+pub const OPTIMISTIC_OBJECTION_PERCENTAGE: u8 = 5;
 
 /// Governance proposal data structure
 #[account]
@@ -29,6 +70,9 @@ pub struct Proposal {
     /// Total no votes
     pub no_votes: u64,
     
+    /// Total abstain votes (count toward quorum only)
+    pub abstain_votes: u64,
+    
     /// Whether the proposal has been executed
     pub executed: bool,
     
@@ -55,10 +99,52 @@ pub struct Proposal {
     
     /// The account that executed the proposal
     pub executor: Option<Pubkey>,
+    
+    /// Governance configuration the proposal was created under
+    pub governance_config: Pubkey,
+    
+    /// Labeled options; empty for a yes/no proposal
+    pub options: Vec<ProposalOption>,
+    
+    /// How the winning option is chosen
+    pub resolution: ResolutionMethod,
+    
+    /// Ranked-choice ballots, aggregated by identical ranking
+    pub ranked_ballots: Vec<RankedBallot>,
+    
+    /// Winning option index once a multi-option proposal is executed
+    pub winning_option: Option<u8>,
+    
+    /// REAL locked in the deposit escrow by the proposer
+    pub deposit: u64,
+    
+    /// Whether the deposit has been refunded or slashed
+    pub deposit_settled: bool,
+    
+    /// Asset whose share holders vote, or None for REAL-weighted governance
+    pub asset_token: Option<Pubkey>,
+    
+    /// How votes are weighted, fixed when the proposal is created
+    pub voting_mode: VotingMode,
+    
+    /// Creation slot; votes count the power held before it
+    pub snapshot_slot: u64,
+    
+    /// Whether the proposal passes unless objections escalate it to a full vote
+    pub optimistic: bool,
+    
+    /// Staked REAL objecting to an optimistic proposal
+    pub objection_votes: u64,
+    
+    /// Objections above which an optimistic proposal escalates to a full vote
+    pub objection_threshold: u64,
+    
+    /// Sequence number under the governance config, seeding the proposal's address
+    pub proposal_id: u64,
 }
 
 /// Types of governance proposals
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ProposalType {
     /// General text proposal with no on-chain execution
     Text,
@@ -85,6 +171,167 @@ pub enum ProposalType {
     CommunityFunding,
 }
 
+/// What an approved parameter or fee proposal changes, stored in its execution data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParameterAction {
+    /// Replace the governance voting parameters
+    Voting {
+        min_voting_period: i64,
+        max_voting_period: i64,
+        min_quorum_votes: u64,
+        approval_threshold: u8,
+    },
+    
+    /// Replace the REAL token's fee configuration
+    Fees {
+        transaction_fee_bps: u16,
+        fee_recipient: Pubkey,
+        fees_enabled: bool,
+    },
+}
+
+impl ParameterAction {
+    /// The proposal type the action is voted on as
+    pub fn proposal_type(&self) -> ProposalType {
+        match self {
+            ParameterAction::Voting { .. } => ProposalType::PlatformParameters,
+            ParameterAction::Fees { .. } => ProposalType::UpdateFees,
+        }
+    }
+    
+    /// Reject actions that could never execute
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            ParameterAction::Voting { min_voting_period, max_voting_period, approval_threshold, .. } => {
+                require!(min_voting_period > 0, RealStackError::InvalidVotingPeriod);
+                require!(max_voting_period >= min_voting_period, RealStackError::InvalidVotingPeriod);
+                require!(max_voting_period <= MAX_SNAPSHOT_AGE, RealStackError::InvalidVotingPeriod);
+                require!(
+                    approval_threshold > 0 && approval_threshold <= 100,
+                    RealStackError::InvalidParameters
+                );
+            },
+            ParameterAction::Fees { transaction_fee_bps, .. } => {
+                require!(transaction_fee_bps <= MAX_TRANSACTION_FEE_BPS, RealStackError::InvalidParameters);
+            },
+        }
+        Ok(())
+    }
+}
+
+/// A voter's choice on a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VoteChoice {
+    /// In favour
+    Yes,
+    
+    /// Against
+    No,
+    
+    /// Counts toward quorum but not the approval ratio
+    Abstain,
+}
+
+/// A labeled option on a multi-option proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ProposalOption {
+    /// Short label shown to voters
+    pub label: String,
+    
+    /// Execution data applied if this option wins
+    pub execution_data: Vec<u8>,
+    
+    /// First-choice votes for this option
+    pub votes: u64,
+}
+
+impl ProposalOption {
+    /// Space required for one option
+    pub const LEN: usize = 4 + MAX_OPTION_LABEL_LEN + // label
+        4 + MAX_OPTION_DATA_LEN + // execution_data
+        8; // votes
+}
+
+/// An option as supplied when creating a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ProposalOptionInput {
+    /// Short label shown to voters
+    pub label: String,
+    
+    /// Execution data applied if this option wins
+    pub execution_data: Vec<u8>,
+}
+
+/// How a multi-option proposal picks its winner
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ResolutionMethod {
+    /// The option with the most votes wins
+    Plurality,
+    
+    /// Instant runoff over voters' rankings
+    RankedChoice,
+}
+
+/// How tokens turn into voting weight
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VotingMode {
+    /// One vote per token
+    Linear,
+    
+    /// The integer square root of the tokens voted
+    Quadratic,
+    
+    /// Tokens multiplied by how long the voter locks their stake after voting ends
+    Conviction,
+}
+
+/// Combined weight of every ballot with the same ranking
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RankedBallot {
+    /// Option indices, most preferred first
+    pub ranking: Vec<u8>,
+    
+    /// Total vote weight behind this ranking
+    pub weight: u64,
+}
+
+impl RankedBallot {
+    /// Space required for one aggregated ballot
+    pub const LEN: usize = 4 + MAX_PROPOSAL_OPTIONS + // ranking
+        8; // weight
+    
+    /// Number of distinct rankings (of any length) over `option_count` options
+    pub fn max_distinct(option_count: usize) -> usize {
+        let mut total = 0;
+        let mut permutations = 1;
+        for length in 0..option_count {
+            permutations *= option_count - length;
+            total += permutations;
+        }
+        total
+    }
+}
+
+/// What a vote record voted for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum Ballot {
+    /// A choice on a yes/no proposal
+    Choice(VoteChoice),
+    
+    /// Option indices on a multi-option proposal, most preferred first
+    Options(Vec<u8>),
+}
+
+impl Ballot {
+    /// The yes/no choice, if this is a yes/no ballot
+    pub fn choice(&self) -> Option<VoteChoice> {
+        match self {
+            Ballot::Choice(choice) => Some(*choice),
+            Ballot::Options(_) => None,
+        }
+    }
+}
+
 impl Proposal {
     /// Space required for a Proposal account
     pub const LEN: usize = 8 + // discriminator
@@ -96,6 +343,7 @@ impl Proposal {
         8 + // voting_ends_at
         8 + // yes_votes
         8 + // no_votes
+        8 + // abstain_votes
         1 + // executed
         1 + // proposal_type
         64 + // target_accounts (assuming up to 2 accounts)
@@ -104,7 +352,56 @@ impl Proposal {
         8 + // quorum_votes
         1 + // approval_threshold_percentage
         8 + // executed_at
-        33; // executor (Option<Pubkey>)
+        33 + // executor (Option<Pubkey>)
+        32 + // governance_config
+        4 + // options (empty)
+        1 + // resolution
+        4 + // ranked_ballots (empty)
+        2 + // winning_option (Option<u8>)
+        8 + // deposit
+        1 + // deposit_settled
+        33 + // asset_token (Option<Pubkey>)
+        1 + // voting_mode
+        8 + // snapshot_slot
+        1 + // optimistic
+        8 + // objection_votes
+        8 + // objection_threshold
+        8; // proposal_id
+    
+    /// Space required for a proposal with `option_count` options
+    pub fn space_with_options(option_count: usize, resolution: ResolutionMethod) -> usize {
+        // Oversized requests are rejected by the handler; keep the arithmetic bounded
+        let option_count = option_count.min(MAX_PROPOSAL_OPTIONS);
+        let ballot_space = match resolution {
+            ResolutionMethod::Plurality => 0,
+            ResolutionMethod::RankedChoice => RankedBallot::max_distinct(option_count) * RankedBallot::LEN,
+        };
+        Self::LEN + option_count * ProposalOption::LEN + ballot_space
+    }
+    
+    /// Space required for a proposal wrapping an instruction with `account_count` accounts
+    pub fn space_with_action(account_count: usize, data_len: usize) -> usize {
+        Self::LEN + account_count.min(MAX_ACTION_ACCOUNTS) * 32 + data_len.min(MAX_ACTION_DATA)
+    }
+    
+    /// Whether the proposal is voted on by option rather than yes/no
+    pub fn has_options(&self) -> bool {
+        !self.options.is_empty()
+    }
+    
+    /// The tally a choice is counted in
+    pub fn tally_mut(&mut self, choice: VoteChoice) -> &mut u64 {
+        match choice {
+            VoteChoice::Yes => &mut self.yes_votes,
+            VoteChoice::No => &mut self.no_votes,
+            VoteChoice::Abstain => &mut self.abstain_votes,
+        }
+    }
+    
+    /// Whether voting on the proposal is over
+    pub fn is_finalized(&self, current_timestamp: i64) -> bool {
+        self.executed || !self.is_active || current_timestamp >= self.voting_ends_at
+    }
 }
 
 /// Vote record to track individual votes
@@ -116,14 +413,23 @@ pub struct VoteRecord {
     /// The voter
     pub voter: Pubkey,
     
-    /// Whether the vote was yes
-    pub is_yes_vote: bool,
+    /// What the voter voted for
+    pub ballot: Ballot,
     
     /// Vote weight
     pub vote_weight: u64,
     
     /// When the vote was cast
     pub timestamp: i64,
+    
+    /// Delegate that cast the vote on the voter's behalf, if any
+    pub delegate: Option<Pubkey>,
+    
+    /// Account that paid the record's rent and reclaims it on close
+    pub payer: Pubkey,
+    
+    /// How long past the vote the voter's stake is locked (conviction votes)
+    pub lock_duration: i64,
 }
 
 impl VoteRecord {
@@ -131,17 +437,93 @@ impl VoteRecord {
     pub const LEN: usize = 8 + // discriminator
         32 + // proposal
         32 + // voter
-        1 + // is_yes_vote
+        1 + 4 + MAX_PROPOSAL_OPTIONS + // ballot
         8 + // vote_weight
+        8 + // timestamp
+        33 + // delegate (Option<Pubkey>)
+        32 + // payer
+        8; // lock_duration
+}
+
+/// A holder's objection to an optimistic proposal
+#[account]
+pub struct ObjectionRecord {
+    /// The proposal objected to
+    pub proposal: Pubkey,
+    
+    /// The objecting holder
+    pub objector: Pubkey,
+    
+    /// Staked REAL counted against the proposal
+    pub weight: u64,
+    
+    /// When the objection was raised
+    pub timestamp: i64,
+}
+
+impl ObjectionRecord {
+    /// Space required for an ObjectionRecord account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // proposal
+        32 + // objector
+        8 + // weight
         8; // timestamp
 }
 
+/// A holder's voting power lent to another wallet
+///
+/// No checkpoint is written on delegate or undelegate: delegated votes are weighed from the
+/// delegator's own checkpoints, and `updated_slot` keeps the delegation out of older snapshots
+#[account]
+pub struct Delegation {
+    /// Governance config the delegation applies under
+    pub governance_config: Pubkey,
+    
+    /// The holder lending their voting power
+    pub delegator: Pubkey,
+    
+    /// The wallet voting on the holder's behalf
+    pub delegate: Pubkey,
+    
+    /// Proposal type covered, or None for every type
+    pub proposal_type: Option<ProposalType>,
+    
+    /// When the delegation was last set
+    pub updated_at: i64,
+    
+    /// Slot the delegation was last set in; proposals snapshotted earlier ignore it
+    pub updated_slot: u64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Delegation {
+    /// Space required for a Delegation account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance_config
+        32 + // delegator
+        32 + // delegate
+        2 + // proposal_type (Option<ProposalType>)
+        8 + // updated_at
+        8 + // updated_slot
+        1; // bump
+    
+    /// PDA seed for a delegation scope: 0 for global, 1 + the type otherwise
+    pub fn scope_seed(proposal_type: Option<ProposalType>) -> u8 {
+        proposal_type.map_or(0, |proposal_type| proposal_type as u8 + 1)
+    }
+}
+
 /// Governance parameters
 #[account]
 pub struct GovernanceConfig {
     /// Authority that can update governance parameters
     pub authority: Pubkey,
     
+    /// The REAL token whose holders govern the platform
+    pub real_token: Pubkey,
+    
     /// Minimum period for voting (in seconds)
     pub min_voting_period: i64,
     
@@ -162,19 +544,109 @@ pub struct GovernanceConfig {
     
     /// Whether governance is active
     pub governance_active: bool,
+    
+    /// REAL a proposer locks in escrow for each proposal
+    pub proposal_deposit: u64,
+    
+    /// Most proposals a proposer can have with unsettled deposits
+    pub max_active_proposals: u8,
+    
+    /// REAL token account that receives slashed deposits
+    pub treasury: Pubkey,
+    
+    /// How votes are weighted on new proposals
+    pub voting_mode: VotingMode,
+    
+    /// Proposals created so far; the next proposal's address is seeded with it
+    pub proposal_count: u64,
+    
+    /// PDA bump (the config signs for deposit escrows)
+    pub bump: u8,
 }
 
 impl GovernanceConfig {
     /// Space required for a GovernanceConfig account
     pub const LEN: usize = 8 + // discriminator
         32 + // authority
+        32 + // real_token
         8 + // min_voting_period
         8 + // max_voting_period
         8 + // min_quorum_votes
         1 + // approval_threshold
         8 + // min_proposal_balance
         8 + // min_vote_balance
-        1; // governance_active
+        1 + // governance_active
+        8 + // proposal_deposit
+        1 + // max_active_proposals
+        32 + // treasury
+        1 + // voting_mode
+        8 + // proposal_count
+        1; // bump
+}
+
+/// Per-proposer bookkeeping for the active proposal limit
+#[account]
+pub struct ProposerState {
+    /// Governance config the proposer created proposals under
+    pub governance_config: Pubkey,
+    
+    /// The proposer
+    pub proposer: Pubkey,
+    
+    /// Proposals whose deposit has not been settled yet
+    pub active_proposals: u8,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ProposerState {
+    /// Space required for a ProposerState account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance_config
+        32 + // proposer
+        1 + // active_proposals
+        1; // bump
+}
+
+/// Context for initializing the governance configuration
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    /// The admin
+    #[account(
+        mut,
+        constraint = roles.has_role(authority.key(), Role::Admin) @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// The REAL token account
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), real_token.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
+    /// REAL token account that receives slashed proposal deposits
+    #[account(
+        constraint = treasury.mint == real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    /// Governance config to create
+    #[account(
+        init,
+        payer = authority,
+        space = GovernanceConfig::LEN,
+        seeds = [b"governance_config".as_ref(), real_token.key().as_ref()],
+        bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
 }
 
 /// Context for creating a proposal
@@ -184,184 +656,2267 @@ pub struct CreateProposal<'info> {
     #[account(mut)]
     pub proposer: Signer<'info>,
     
+    /// Governance config
+    #[account(mut)]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
     /// The proposal account to create
     #[account(
         init,
         payer = proposer,
-        space = Proposal::LEN
+        space = Proposal::LEN,
+        seeds = [
+            b"proposal".as_ref(),
+            governance_config.key().as_ref(),
+            &governance_config.proposal_count.to_le_bytes()
+        ],
+        bump
     )]
     pub proposal: Account<'info, Proposal>,
     
-    /// Governance config
-    pub governance_config: Account<'info, GovernanceConfig>,
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The REAL mint
+    #[account(
+        address = real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub real_mint: Account<'info, Mint>,
+    
+    /// The proposer's REAL token account, which funds the deposit
+    #[account(
+        mut,
+        constraint = proposer_token_account.owner == proposer.key() @ RealStackError::InvalidTokenAccount,
+        constraint = proposer_token_account.mint == real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub proposer_token_account: Account<'info, TokenAccount>,
+    
+    /// Escrow holding the proposal deposit until it is settled
+    #[account(
+        init,
+        payer = proposer,
+        seeds = [b"proposal_deposit".as_ref(), proposal.key().as_ref()],
+        bump,
+        token::mint = real_mint,
+        token::authority = governance_config
+    )]
+    pub deposit_escrow: Account<'info, TokenAccount>,
+    
+    /// The proposer's active proposal count
+    #[account(
+        init_if_needed,
+        payer = proposer,
+        space = ProposerState::LEN,
+        seeds = [
+            b"proposer_state".as_ref(),
+            governance_config.key().as_ref(),
+            proposer.key().as_ref()
+        ],
+        bump
+    )]
+    pub proposer_state: Account<'info, ProposerState>,
+    
+    /// The REAL stake pool; optimistic proposals set their objection threshold from its stake
+    #[account(
+        constraint = stake_pool.real_token == governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub stake_pool: Option<Account<'info, StakePool>>,
     
     /// System program
     pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
 }
 
-/// Context for voting on a proposal
+/// Context for creating a multi-option proposal
 #[derive(Accounts)]
-pub struct VoteOnProposal<'info> {
-    /// The voter
+#[instruction(
+    title: String,
+    description: String,
+    voting_ends_at: i64,
+    options: Vec<ProposalOptionInput>,
+    resolution: ResolutionMethod
+)]
+pub struct CreateOptionProposal<'info> {
+    /// The proposer
     #[account(mut)]
-    pub voter: Signer<'info>,
+    pub proposer: Signer<'info>,
     
-    /// The proposal to vote on
+    /// Governance config
     #[account(mut)]
-    pub proposal: Account<'info, Proposal>,
+    pub governance_config: Account<'info, GovernanceConfig>,
     
-    /// Vote record to create
+    /// The proposal account to create, sized for its options
     #[account(
         init,
-        payer = voter,
-        space = VoteRecord::LEN,
+        payer = proposer,
+        space = Proposal::space_with_options(options.len(), resolution),
         seeds = [
-            b"vote_record".as_ref(),
-            proposal.key().as_ref(),
-            voter.key().as_ref()
+            b"proposal".as_ref(),
+            governance_config.key().as_ref(),
+            &governance_config.proposal_count.to_le_bytes()
         ],
         bump
     )]
-    pub vote_record: Account<'info, VoteRecord>,
+    pub proposal: Account<'info, Proposal>,
     
-    /// Governance config
-    pub governance_config: Account<'info, GovernanceConfig>,
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The REAL mint
+    #[account(
+        address = real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub real_mint: Account<'info, Mint>,
+    
+    /// The proposer's REAL token account, which funds the deposit
+    #[account(
+        mut,
+        constraint = proposer_token_account.owner == proposer.key() @ RealStackError::InvalidTokenAccount,
+        constraint = proposer_token_account.mint == real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub proposer_token_account: Account<'info, TokenAccount>,
+    
+    /// Escrow holding the proposal deposit until it is settled
+    #[account(
+        init,
+        payer = proposer,
+        seeds = [b"proposal_deposit".as_ref(), proposal.key().as_ref()],
+        bump,
+        token::mint = real_mint,
+        token::authority = governance_config
+    )]
+    pub deposit_escrow: Account<'info, TokenAccount>,
+    
+    /// The proposer's active proposal count
+    #[account(
+        init_if_needed,
+        payer = proposer,
+        space = ProposerState::LEN,
+        seeds = [
+            b"proposer_state".as_ref(),
+            governance_config.key().as_ref(),
+            proposer.key().as_ref()
+        ],
+        bump
+    )]
+    pub proposer_state: Account<'info, ProposerState>,
     
     /// System program
     pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
 }
 
-/// Context for executing a proposal
+/// Context for creating a proposal voted on by an asset's share holders
 #[derive(Accounts)]
-pub struct ExecuteProposal<'info> {
-    /// The executor
+#[instruction(
+    title: String,
+    description: String,
+    voting_ends_at: i64,
+    accounts: Vec<Pubkey>,
+    data: Vec<u8>
+)]
+pub struct CreateAssetProposal<'info> {
+    /// The proposer
     #[account(mut)]
-    pub executor: Signer<'info>,
+    pub proposer: Signer<'info>,
     
-    /// The proposal to execute
+    /// Governance config
     #[account(mut)]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The proposal account to create, sized for the wrapped instruction
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::space_with_action(accounts.len(), data.len()),
+        seeds = [
+            b"proposal".as_ref(),
+            governance_config.key().as_ref(),
+            &governance_config.proposal_count.to_le_bytes()
+        ],
+        bump
+    )]
     pub proposal: Account<'info, Proposal>,
-}
-
-/// Implementation of governance operations
-pub mod governance_operations {
-    use super::*;
     
-    /// Create a new proposal
-    pub fn create_proposal(
-        ctx: Context<CreateProposal>,
-        title: String,
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The asset the proposal acts on
+    #[account(
+        constraint = asset_token.real_token == governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// The proposer's share account
+    #[account(
+        constraint = proposer_share_account.owner == proposer.key() @ RealStackError::InvalidTokenAccount,
+        constraint = proposer_share_account.mint == asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub proposer_share_account: Account<'info, TokenAccount>,
+    
+    /// The REAL mint
+    #[account(
+        address = real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub real_mint: Account<'info, Mint>,
+    
+    /// Empty deposit escrow, closed when the proposal is settled so the proposer's slot is freed
+    #[account(
+        init,
+        payer = proposer,
+        seeds = [b"proposal_deposit".as_ref(), proposal.key().as_ref()],
+        bump,
+        token::mint = real_mint,
+        token::authority = governance_config
+    )]
+    pub deposit_escrow: Account<'info, TokenAccount>,
+    
+    /// The proposer's active proposal count
+    #[account(
+        init_if_needed,
+        payer = proposer,
+        space = ProposerState::LEN,
+        seeds = [
+            b"proposer_state".as_ref(),
+            governance_config.key().as_ref(),
+            proposer.key().as_ref()
+        ],
+        bump
+    )]
+    pub proposer_state: Account<'info, ProposerState>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Context for voting on a proposal
+#[derive(Accounts)]
+pub struct VoteOnProposal<'info> {
+    /// The voter
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    /// The proposal to vote on
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Vote record to create, or to take over from the voter's delegate
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [
+            b"vote_record".as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    /// Governance config
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The REAL stake pool
+    #[account(
+        constraint = stake_pool.real_token == governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The voter's stake position, locked by conviction votes
+    #[account(
+        mut,
+        constraint = stake_account.owner == voter.key() @ RealStackError::Unauthorized,
+        constraint = stake_account.stake_pool == stake_pool.key() @ RealStackError::InvalidParameters
+    )]
+    pub stake_account: Option<Account<'info, StakeAccount>>,
+    
+    /// The voter's staked voting power history, if they have ever staked
+    #[account(
+        seeds = [b"voting_power".as_ref(), stake_pool.key().as_ref(), voter.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Option<Account<'info, VotingPowerCheckpoints>>,
+    
+    /// The REAL mint; needed only to open the vote escrow
+    #[account(
+        address = real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub real_mint: Option<Account<'info, Mint>>,
+    
+    /// The voter's REAL account, which covers whatever part of the vote their stake does not
+    #[account(
+        mut,
+        constraint = voter_real_account.owner == voter.key() @ RealStackError::InvalidTokenAccount,
+        constraint = voter_real_account.mint == real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub voter_real_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Escrow holding the voter's liquid REAL until the proposal is finalized, so it cannot vote twice
+    #[account(
+        init_if_needed,
+        payer = voter,
+        seeds = [b"vote_escrow".as_ref(), proposal.key().as_ref(), voter.key().as_ref()],
+        bump,
+        token::mint = real_mint,
+        token::authority = governance_config
+    )]
+    pub vote_escrow: Option<Account<'info, TokenAccount>>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for voting on a multi-option proposal
+#[derive(Accounts)]
+pub struct VoteOnOptions<'info> {
+    /// The voter
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    /// The proposal to vote on
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Vote record to create
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [
+            b"vote_record".as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    /// Governance config
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The REAL stake pool
+    #[account(
+        constraint = stake_pool.real_token == governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The voter's stake position, locked by conviction votes
+    #[account(
+        mut,
+        constraint = stake_account.owner == voter.key() @ RealStackError::Unauthorized,
+        constraint = stake_account.stake_pool == stake_pool.key() @ RealStackError::InvalidParameters
+    )]
+    pub stake_account: Option<Account<'info, StakeAccount>>,
+    
+    /// The voter's staked voting power history, if they have ever staked
+    #[account(
+        seeds = [b"voting_power".as_ref(), stake_pool.key().as_ref(), voter.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Option<Account<'info, VotingPowerCheckpoints>>,
+    
+    /// The REAL mint; needed only to open the vote escrow
+    #[account(
+        address = real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub real_mint: Option<Account<'info, Mint>>,
+    
+    /// The voter's REAL account, which covers whatever part of the vote their stake does not
+    #[account(
+        mut,
+        constraint = voter_real_account.owner == voter.key() @ RealStackError::InvalidTokenAccount,
+        constraint = voter_real_account.mint == real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub voter_real_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Escrow holding the voter's liquid REAL until the proposal is finalized, so it cannot vote twice
+    #[account(
+        init_if_needed,
+        payer = voter,
+        seeds = [b"vote_escrow".as_ref(), proposal.key().as_ref(), voter.key().as_ref()],
+        bump,
+        token::mint = real_mint,
+        token::authority = governance_config
+    )]
+    pub vote_escrow: Option<Account<'info, TokenAccount>>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for voting on an asset proposal with asset shares
+#[derive(Accounts)]
+pub struct VoteOnAssetProposal<'info> {
+    /// The voter
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    /// The proposal to vote on
+    #[account(
+        mut,
+        constraint = proposal.asset_token == Some(asset_token.key()) @ RealStackError::ProposalScopeMismatch
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Vote record to create
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [
+            b"vote_record".as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    /// Governance config
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The asset the proposal acts on
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// The voter's share account (voting power)
+    #[account(
+        mut,
+        constraint = voter_share_account.owner == voter.key() @ RealStackError::InvalidTokenAccount,
+        constraint = voter_share_account.mint == asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub voter_share_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// Escrow holding the voted shares until the proposal is finalized, so they cannot vote twice
+    #[account(
+        init,
+        payer = voter,
+        seeds = [b"vote_escrow".as_ref(), proposal.key().as_ref(), voter.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = governance_config
+    )]
+    pub vote_escrow: Account<'info, TokenAccount>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Context for returning shares or REAL escrowed by a vote
+#[derive(Accounts)]
+pub struct ReclaimVoteShares<'info> {
+    /// The voter receiving the escrowed tokens and the escrow's rent
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    /// CHECK: The voted proposal; may already be closed
+    pub proposal: UncheckedAccount<'info>,
+    
+    /// Governance config, which signs for the escrow
+    #[account(
+        address = vote_escrow.owner @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token, telling a REAL escrow from a share escrow
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// Escrow holding the voted shares or REAL
+    #[account(
+        mut,
+        seeds = [b"vote_escrow".as_ref(), proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_escrow: Account<'info, TokenAccount>,
+    
+    /// The voter's account for the escrowed mint
+    #[account(
+        mut,
+        constraint = voter_share_account.owner == voter.key() @ RealStackError::InvalidTokenAccount,
+        constraint = voter_share_account.mint == vote_escrow.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub voter_share_account: Account<'info, TokenAccount>,
+    
+    /// The asset whose shares are escrowed, required to reclaim shares
+    #[account(
+        constraint = asset_token.mint == vote_escrow.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub asset_token: Option<Account<'info, AssetToken>>,
+    
+    /// Share mint of the asset, required to reclaim shares
+    #[account(
+        address = vote_escrow.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Option<Account<'info, Mint>>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint, required to reclaim shares
+    pub freeze_authority: Option<UncheckedAccount<'info>>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for changing an existing vote
+#[derive(Accounts)]
+pub struct ChangeVote<'info> {
+    /// The voter
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    /// The proposal voted on
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// The voter's existing vote record
+    #[account(
+        mut,
+        seeds = [
+            b"vote_record".as_ref(),
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    /// Governance config
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The REAL stake pool
+    #[account(
+        constraint = stake_pool.real_token == governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The voter's stake position, locked by conviction votes
+    #[account(
+        mut,
+        constraint = stake_account.owner == voter.key() @ RealStackError::Unauthorized,
+        constraint = stake_account.stake_pool == stake_pool.key() @ RealStackError::InvalidParameters
+    )]
+    pub stake_account: Option<Account<'info, StakeAccount>>,
+    
+    /// The voter's staked voting power history, if they have ever staked
+    #[account(
+        seeds = [b"voting_power".as_ref(), stake_pool.key().as_ref(), voter.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Option<Account<'info, VotingPowerCheckpoints>>,
+    
+    /// The REAL mint; needed only to open the vote escrow
+    #[account(
+        address = real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub real_mint: Option<Account<'info, Mint>>,
+    
+    /// The voter's REAL account, which covers whatever part of the vote their stake does not
+    #[account(
+        mut,
+        constraint = voter_real_account.owner == voter.key() @ RealStackError::InvalidTokenAccount,
+        constraint = voter_real_account.mint == real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub voter_real_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Escrow holding the voter's liquid REAL until the proposal is finalized, so it cannot vote twice
+    #[account(
+        init_if_needed,
+        payer = voter,
+        seeds = [b"vote_escrow".as_ref(), proposal.key().as_ref(), voter.key().as_ref()],
+        bump,
+        token::mint = real_mint,
+        token::authority = governance_config
+    )]
+    pub vote_escrow: Option<Account<'info, TokenAccount>>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for objecting to an optimistic proposal
+#[derive(Accounts)]
+pub struct ObjectToProposal<'info> {
+    /// The objecting holder
+    #[account(mut)]
+    pub objector: Signer<'info>,
+    
+    /// The optimistic proposal
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Objection record; its existence prevents objecting twice
+    #[account(
+        init,
+        payer = objector,
+        space = ObjectionRecord::LEN,
+        seeds = [
+            b"objection".as_ref(),
+            proposal.key().as_ref(),
+            objector.key().as_ref()
+        ],
+        bump
+    )]
+    pub objection_record: Account<'info, ObjectionRecord>,
+    
+    /// Governance config
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The REAL stake pool
+    #[account(
+        constraint = stake_pool.real_token == governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The objector's staked voting power history
+    #[account(
+        seeds = [b"voting_power".as_ref(), stake_pool.key().as_ref(), objector.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for a delegate voting with a delegator's power
+#[derive(Accounts)]
+pub struct CastDelegatedVote<'info> {
+    /// The delegate
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+    
+    /// The proposal to vote on
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// The delegation being exercised
+    #[account(
+        constraint = delegation.delegate == delegate.key() @ RealStackError::Unauthorized,
+        constraint = delegation.governance_config == proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub delegation: Account<'info, Delegation>,
+    
+    /// CHECK: The delegator's delegation for this proposal's type, which overrides a global one
+    #[account(
+        seeds = [
+            b"delegation".as_ref(),
+            proposal.governance_config.as_ref(),
+            delegation.delegator.as_ref(),
+            &[Delegation::scope_seed(Some(proposal.proposal_type))]
+        ],
+        bump
+    )]
+    pub type_delegation: UncheckedAccount<'info>,
+    
+    /// The delegator's vote record, paid for by the delegate
+    #[account(
+        init,
+        payer = delegate,
+        space = VoteRecord::LEN,
+        seeds = [
+            b"vote_record".as_ref(),
+            proposal.key().as_ref(),
+            delegation.delegator.as_ref()
+        ],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    /// Governance config
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The REAL stake pool
+    #[account(
+        constraint = stake_pool.real_token == governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The delegator's staked voting power history
+    #[account(
+        seeds = [b"voting_power".as_ref(), stake_pool.key().as_ref(), delegation.delegator.as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for executing a proposal
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// The executor
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    
+    /// The proposal to execute
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Governance config
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply, and whose fees a fee proposal sets
+    #[account(
+        mut,
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+}
+
+/// Context for executing an asset proposal's wrapped instruction
+#[derive(Accounts)]
+pub struct ExecuteAssetProposal<'info> {
+    /// The executor
+    pub executor: Signer<'info>,
+    
+    /// The proposal to execute
+    #[account(
+        mut,
+        constraint = proposal.asset_token.is_some() @ RealStackError::ProposalScopeMismatch
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Governance config
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// CHECK: PDA that signs the wrapped instruction as the asset's authority
+    #[account(
+        mut,
+        seeds = [b"asset_governance".as_ref(), proposal.asset_token.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub asset_governance_signer: UncheckedAccount<'info>,
+    
+    /// CHECK: This program, invoked with the wrapped instruction
+    #[account(
+        address = crate::ID @ RealStackError::InvalidParameters
+    )]
+    pub realstack_program: UncheckedAccount<'info>,
+}
+
+/// Context for refunding or slashing a proposal deposit
+#[derive(Accounts)]
+pub struct SettleProposalDeposit<'info> {
+    /// Anyone may settle once voting has ended
+    pub settler: Signer<'info>,
+    
+    /// The proposal whose deposit is settled
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// CHECK: The proposer, who receives the escrow's rent
+    #[account(
+        mut,
+        address = proposal.proposer @ RealStackError::InvalidParameters
+    )]
+    pub proposer: UncheckedAccount<'info>,
+    
+    /// Governance config, which signs for the escrow
+    #[account(
+        address = proposal.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The proposer's active proposal count
+    #[account(
+        mut,
+        seeds = [
+            b"proposer_state".as_ref(),
+            governance_config.key().as_ref(),
+            proposal.proposer.as_ref()
+        ],
+        bump = proposer_state.bump
+    )]
+    pub proposer_state: Account<'info, ProposerState>,
+    
+    /// Escrow holding the deposit
+    #[account(
+        mut,
+        seeds = [b"proposal_deposit".as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub deposit_escrow: Account<'info, TokenAccount>,
+    
+    /// The proposer's REAL token account, required only when the deposit is refunded
+    #[account(
+        mut,
+        constraint = proposer_token_account.owner == proposal.proposer @ RealStackError::InvalidTokenAccount,
+        constraint = proposer_token_account.mint == real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub proposer_token_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Treasury, credited if quorum was missed
+    #[account(
+        mut,
+        address = governance_config.treasury @ RealStackError::InvalidTokenAccount
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for reclaiming rent from a vote record
+#[derive(Accounts)]
+pub struct CloseVoteRecord<'info> {
+    /// The voter receiving the rent
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    /// CHECK: The voted proposal; may already be closed
+    #[account(
+        address = vote_record.proposal @ RealStackError::InvalidParameters
+    )]
+    pub proposal: UncheckedAccount<'info>,
+    
+    /// The vote record to close
+    #[account(
+        mut,
+        close = voter,
+        constraint = vote_record.payer == voter.key() @ RealStackError::Unauthorized
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+}
+
+/// Context for delegating voting power
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey, proposal_type: Option<ProposalType>)]
+pub struct Delegate<'info> {
+    /// The holder delegating their voting power
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+    
+    /// Governance config the delegation applies under
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The delegation, created or re-pointed
+    #[account(
+        init_if_needed,
+        payer = delegator,
+        space = Delegation::LEN,
+        seeds = [
+            b"delegation".as_ref(),
+            governance_config.key().as_ref(),
+            delegator.key().as_ref(),
+            &[Delegation::scope_seed(proposal_type)]
+        ],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for revoking a delegation
+#[derive(Accounts)]
+pub struct Undelegate<'info> {
+    /// The delegator receiving the rent
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+    
+    /// The delegation to revoke
+    #[account(
+        mut,
+        close = delegator,
+        constraint = delegation.delegator == delegator.key() @ RealStackError::Unauthorized
+    )]
+    pub delegation: Account<'info, Delegation>,
+}
+
+/// Context for reclaiming rent from a proposal
+#[derive(Accounts)]
+pub struct CloseProposal<'info> {
+    /// The proposer receiving the rent
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    /// The proposal to close
+    #[account(
+        mut,
+        close = proposer,
+        constraint = proposal.proposer == proposer.key() @ RealStackError::Unauthorized
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// Implementation of governance operations
+pub mod governance_operations {
+    use super::*;
+    
+    /// Initialize the governance configuration
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        min_voting_period: i64,
+        max_voting_period: i64,
+        min_quorum_votes: u64,
+        approval_threshold: u8,
+        min_proposal_balance: u64,
+        min_vote_balance: u64,
+        proposal_deposit: u64,
+        max_active_proposals: u8,
+        voting_mode: VotingMode,
+    ) -> Result<()> {
+        let governance_config = &mut ctx.accounts.governance_config;
+        
+        // Validate parameters
+        require!(min_voting_period > 0, RealStackError::InvalidVotingPeriod);
+        require!(
+            max_voting_period >= min_voting_period,
+            RealStackError::InvalidVotingPeriod
+        );
+        // Voting power checkpoints are only kept long enough to answer snapshots this old
+        require!(
+            max_voting_period <= MAX_SNAPSHOT_AGE,
+            RealStackError::InvalidVotingPeriod
+        );
+        require!(
+            approval_threshold > 0 && approval_threshold <= 100,
+            RealStackError::InvalidParameters
+        );
+        require!(max_active_proposals > 0, RealStackError::InvalidParameters);
+        
+        // Initialize the config
+        governance_config.authority = ctx.accounts.authority.key();
+        governance_config.real_token = ctx.accounts.real_token.key();
+        governance_config.min_voting_period = min_voting_period;
+        governance_config.max_voting_period = max_voting_period;
+        governance_config.min_quorum_votes = min_quorum_votes;
+        governance_config.approval_threshold = approval_threshold;
+        governance_config.min_proposal_balance = min_proposal_balance;
+        governance_config.min_vote_balance = min_vote_balance;
+        governance_config.governance_active = true;
+        governance_config.proposal_deposit = proposal_deposit;
+        governance_config.max_active_proposals = max_active_proposals;
+        governance_config.treasury = ctx.accounts.treasury.key();
+        governance_config.voting_mode = voting_mode;
+        governance_config.proposal_count = 0;
+        governance_config.bump = *ctx.bumps.get("governance_config").unwrap();
+        
+        // Log the initialization
+        msg!("Governance initialized for: {}", governance_config.real_token);
+        msg!("Voting period: {}-{}s, Quorum: {}, Threshold: {}%",
+            min_voting_period, max_voting_period, min_quorum_votes, approval_threshold);
+        msg!("Proposal deposit: {}, Max active proposals: {}", proposal_deposit, max_active_proposals);
+        msg!("Voting mode: {:?}", voting_mode);
+        
+        Ok(())
+    }
+    
+    /// Create a new proposal
+    pub fn create_proposal(
+        mut ctx: Context<CreateProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+    ) -> Result<()> {
+        open_proposal(&mut ctx, title, description, voting_ends_at)?;
+        let proposal = &ctx.accounts.proposal;
+        
+        // Log the creation
+        msg!("Governance proposal created: {}", proposal.title);
+        msg!("Voting ends at: {}", proposal.voting_ends_at);
+        
+        Ok(())
+    }
+    
+    /// Create a proposal that pays out of or streams from the governance treasury
+    pub fn create_treasury_proposal(
+        mut ctx: Context<CreateProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+        action: TreasuryAction,
+    ) -> Result<()> {
+        action.validate()?;
+        open_proposal(&mut ctx, title, description, voting_ends_at)?;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.proposal_type = action.proposal_type();
+        proposal.execution_data = action.try_to_vec()?;
+        
+        // Log the creation
+        msg!("Treasury proposal created: {}", proposal.title);
+        msg!("Action: {:?}", action);
+        msg!("Voting ends at: {}", proposal.voting_ends_at);
+        
+        Ok(())
+    }
+    
+    /// Create a proposal that upgrades the program from a loader buffer
+    pub fn create_upgrade_proposal(
+        mut ctx: Context<CreateProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+        action: UpgradeAction,
+    ) -> Result<()> {
+        action.validate()?;
+        open_proposal(&mut ctx, title, description, voting_ends_at)?;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.proposal_type = ProposalType::ProgramUpgrade;
+        proposal.target_accounts = vec![action.buffer];
+        proposal.execution_data = action.try_to_vec()?;
+        
+        // Log the creation
+        msg!("Upgrade proposal created: {}", proposal.title);
+        msg!("Buffer: {}", action.buffer);
+        msg!("Voting ends at: {}", proposal.voting_ends_at);
+        
+        Ok(())
+    }
+    
+    /// Create a routine proposal that passes unless enough stake objects before `challenge_ends_at`
+    pub fn create_optimistic_proposal(
+        mut ctx: Context<CreateProposal>,
+        title: String,
+        description: String,
+        challenge_ends_at: i64,
+        action: ParameterAction,
+    ) -> Result<()> {
+        action.validate()?;
+        let stake_pool = ctx.accounts.stake_pool.as_deref().ok_or(RealStackError::InvalidParameters)?;
+        // Objections are weighed by stake at the snapshot, so the threshold is a share of the stake
+        // then; nothing staked in this slot counts yet, since it has not been read by any snapshot
+        let objection_threshold = calculate_percentage(stake_pool.total_staked, OPTIMISTIC_OBJECTION_PERCENTAGE);
+        open_proposal(&mut ctx, title, description, challenge_ends_at)?;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.proposal_type = action.proposal_type();
+        proposal.execution_data = action.try_to_vec()?;
+        proposal.optimistic = true;
+        proposal.objection_threshold = objection_threshold;
+        
+        // Log the creation
+        msg!("Optimistic proposal created: {}", proposal.title);
+        msg!("Action: {:?}, Objection threshold: {}", action, objection_threshold);
+        msg!("Challenge window ends at: {}", proposal.voting_ends_at);
+        
+        Ok(())
+    }
+    
+    /// Initialize a proposal and escrow the proposer's deposit
+    fn open_proposal(
+        ctx: &mut Context<CreateProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        
+        init_proposal(
+            proposal,
+            ctx.accounts.proposer.key(),
+            &mut ctx.accounts.governance_config,
+            &ctx.accounts.real_token,
+            title,
+            description,
+            voting_ends_at,
+        )?;
+        ctx.accounts.proposer_state.bump = *ctx.bumps.get("proposer_state").unwrap();
+        lock_deposit(
+            proposal,
+            &mut ctx.accounts.proposer_state,
+            &ctx.accounts.proposer,
+            &ctx.accounts.governance_config,
+            &ctx.accounts.proposer_token_account,
+            &ctx.accounts.deposit_escrow,
+            &ctx.accounts.token_program,
+        )
+    }
+    
+    /// Create a proposal that voters decide between labeled options
+    pub fn create_option_proposal(
+        ctx: Context<CreateOptionProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+        options: Vec<ProposalOptionInput>,
+        resolution: ResolutionMethod,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        
+        // Validate the options
+        require!(
+            options.len() >= 2 && options.len() <= MAX_PROPOSAL_OPTIONS,
+            RealStackError::InvalidParameters
+        );
+        for option in &options {
+            require!(
+                !option.label.is_empty() && option.label.len() <= MAX_OPTION_LABEL_LEN,
+                RealStackError::InvalidParameters
+            );
+            require!(
+                option.execution_data.len() <= MAX_OPTION_DATA_LEN,
+                RealStackError::InvalidParameters
+            );
+        }
+        
+        init_proposal(
+            proposal,
+            ctx.accounts.proposer.key(),
+            &mut ctx.accounts.governance_config,
+            &ctx.accounts.real_token,
+            title,
+            description,
+            voting_ends_at,
+        )?;
+        ctx.accounts.proposer_state.bump = *ctx.bumps.get("proposer_state").unwrap();
+        lock_deposit(
+            proposal,
+            &mut ctx.accounts.proposer_state,
+            &ctx.accounts.proposer,
+            &ctx.accounts.governance_config,
+            &ctx.accounts.proposer_token_account,
+            &ctx.accounts.deposit_escrow,
+            &ctx.accounts.token_program,
+        )?;
+        proposal.options = options
+            .into_iter()
+            .map(|option| ProposalOption {
+                label: option.label,
+                execution_data: option.execution_data,
+                votes: 0,
+            })
+            .collect();
+        proposal.resolution = resolution;
+        
+        // Log the creation
+        msg!("Governance proposal created: {}", proposal.title);
+        msg!("Options: {}, Resolution: {:?}", proposal.options.len(), resolution);
+        msg!("Voting ends at: {}", proposal.voting_ends_at);
+        
+        Ok(())
+    }
+    
+    /// Create a proposal that an asset's share holders vote on, wrapping an asset instruction
+    pub fn create_asset_proposal(
+        ctx: Context<CreateAssetProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+        accounts: Vec<Pubkey>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let asset_token = &ctx.accounts.asset_token;
+        let share_supply = ctx.accounts.share_mint.supply;
+        
+        // Validate the wrapped instruction
+        require!(accounts.len() <= MAX_ACTION_ACCOUNTS, RealStackError::InvalidParameters);
+        require!(data.len() <= MAX_ACTION_DATA, RealStackError::InvalidParameters);
+        let kind = AdminActionKind::from_instruction_data(&data)
+            .filter(AdminActionKind::is_asset_action)
+            .ok_or(RealStackError::UnsupportedAdminAction)?;
+        
+        // Asset instructions take their authority first and the asset second
+        let (signer, _) = AssetToken::governance_signer(&asset_token.key());
+        require!(
+            accounts.len() >= 2 && accounts[0] == signer && accounts[1] == asset_token.key(),
+            RealStackError::InvalidParameters
+        );
+        
+        // Only a meaningful holder of the asset can propose
+        require!(
+            ctx.accounts.proposer_share_account.amount > 0
+                && ctx.accounts.proposer_share_account.amount
+                    >= calculate_percentage(share_supply, ASSET_PROPOSAL_THRESHOLD_PERCENTAGE),
+            RealStackError::InsufficientProposalBalance
+        );
+        
+        init_proposal(
+            proposal,
+            ctx.accounts.proposer.key(),
+            &mut ctx.accounts.governance_config,
+            &ctx.accounts.real_token,
+            title,
+            description,
+            voting_ends_at,
+        )?;
+        proposal.proposal_type = ProposalType::AssetAction;
+        proposal.asset_token = Some(asset_token.key());
+        proposal.target_accounts = accounts;
+        proposal.execution_data = data;
+        proposal.quorum_votes = calculate_percentage(share_supply, ASSET_QUORUM_PERCENTAGE);
+        
+        // Shares are economic ownership of the asset and always count one for one
+        proposal.voting_mode = VotingMode::Linear;
+        
+        // Share holders do not lock a REAL deposit, but still count toward the active proposal limit
+        let proposer_state = &mut ctx.accounts.proposer_state;
+        require!(
+            proposer_state.active_proposals < ctx.accounts.governance_config.max_active_proposals,
+            RealStackError::TooManyActiveProposals
+        );
+        proposer_state.governance_config = ctx.accounts.governance_config.key();
+        proposer_state.proposer = ctx.accounts.proposer.key();
+        proposer_state.active_proposals = proposer_state.active_proposals
+            .checked_add(1)
+            .ok_or(RealStackError::MathOverflow)?;
+        proposer_state.bump = *ctx.bumps.get("proposer_state").unwrap();
+        proposal.deposit = 0;
+        proposal.deposit_settled = false;
+        
+        // Log the creation
+        msg!("Asset proposal created: {}", proposal.title);
+        msg!("Asset: {}, Action: {:?}, Quorum: {}", asset_token.key(), kind, proposal.quorum_votes);
+        msg!("Voting ends at: {}", proposal.voting_ends_at);
+        
+        Ok(())
+    }
+    
+    /// Check the proposer's balance and active proposal limit, then escrow the deposit
+    fn lock_deposit<'info>(
+        proposal: &mut Proposal,
+        proposer_state: &mut Account<'info, ProposerState>,
+        proposer: &Signer<'info>,
+        governance_config: &Account<'info, GovernanceConfig>,
+        proposer_token_account: &Account<'info, TokenAccount>,
+        deposit_escrow: &Account<'info, TokenAccount>,
+        token_program: &Program<'info, Token>,
+    ) -> Result<()> {
+        let deposit = governance_config.proposal_deposit;
+        
+        require!(
+            proposer_token_account.amount >= governance_config.min_proposal_balance
+                && proposer_token_account.amount >= deposit,
+            RealStackError::InsufficientProposalBalance
+        );
+        require!(
+            proposer_state.active_proposals < governance_config.max_active_proposals,
+            RealStackError::TooManyActiveProposals
+        );
+        
+        // Lock the deposit until the proposal is settled
+        if deposit > 0 {
+            token::transfer(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    Transfer {
+                        from: proposer_token_account.to_account_info(),
+                        to: deposit_escrow.to_account_info(),
+                        authority: proposer.to_account_info(),
+                    },
+                ),
+                deposit,
+            )?;
+        }
+        
+        proposer_state.governance_config = governance_config.key();
+        proposer_state.proposer = proposer.key();
+        proposer_state.active_proposals = proposer_state.active_proposals
+            .checked_add(1)
+            .ok_or(RealStackError::MathOverflow)?;
+        proposal.deposit = deposit;
+        proposal.deposit_settled = false;
+        
+        Ok(())
+    }
+    
+    /// Validate a new proposal's parameters and initialize its common fields
+    fn init_proposal(
+        proposal: &mut Proposal,
+        proposer: Pubkey,
+        governance_config: &mut Account<GovernanceConfig>,
+        real_token: &RealToken,
+        title: String,
         description: String,
         voting_ends_at: i64,
+    ) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate proposal parameters
+        require!(
+            !real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(!title.is_empty(), RealStackError::InvalidParameters);
+        require!(!description.is_empty(), RealStackError::InvalidParameters);
+        
+        // Validate voting period
+        let min_end_time = current_timestamp.checked_add(governance_config.min_voting_period)
+            .ok_or(RealStackError::MathOverflow)?;
+        let max_end_time = current_timestamp.checked_add(governance_config.max_voting_period)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        require!(
+            voting_ends_at >= min_end_time,
+            RealStackError::InvalidVotingPeriod
+        );
+        require!(
+            voting_ends_at <= max_end_time,
+            RealStackError::InvalidVotingPeriod
+        );
+        
+        // Initialize the proposal
+        proposal.title = title;
+        proposal.description = description;
+        proposal.proposer = proposer;
+        proposal.is_active = true;
+        proposal.creation_timestamp = current_timestamp;
+        proposal.voting_ends_at = voting_ends_at;
+        proposal.yes_votes = 0;
+        proposal.no_votes = 0;
+        proposal.abstain_votes = 0;
+        proposal.executed = false;
+        proposal.proposal_type = ProposalType::Text; // Default type
+        proposal.target_accounts = vec![];
+        proposal.execution_data = vec![];
+        proposal.min_voting_period = governance_config.min_voting_period;
+        // Quorum is configured in REAL and weighed like a single vote of that size, so tallies and
+        // quorum share a unit. Under quadratic voting that is sqrt(min_quorum_votes): one holder
+        // with the configured amount just meets it, while n holders splitting it meet it n^0.5 times
+        proposal.quorum_votes = voting_weight(governance_config.voting_mode, governance_config.min_quorum_votes, 0)?;
+        proposal.approval_threshold_percentage = governance_config.approval_threshold;
+        proposal.executed_at = 0;
+        proposal.executor = None;
+        proposal.governance_config = governance_config.key();
+        proposal.options = vec![];
+        proposal.resolution = ResolutionMethod::Plurality;
+        proposal.ranked_ballots = vec![];
+        proposal.winning_option = None;
+        proposal.asset_token = None;
+        proposal.voting_mode = governance_config.voting_mode;
+        proposal.snapshot_slot = Clock::get()?.slot;
+        proposal.optimistic = false;
+        proposal.objection_votes = 0;
+        proposal.objection_threshold = 0;
+        
+        // Number the proposal; its address is never reused, so old vote records cannot carry over
+        proposal.proposal_id = governance_config.proposal_count;
+        governance_config.proposal_count = governance_config.proposal_count
+            .checked_add(1)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        Ok(())
+    }
+    
+    /// Vote on a proposal
+    pub fn vote_on_proposal(
+        ctx: Context<VoteOnProposal>,
+        choice: VoteChoice,
+        vote_weight: u64,
+        lock_duration: i64,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let voter = &ctx.accounts.voter;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let governance_config = &ctx.accounts.governance_config;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate voting requirements
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(proposal.is_active, RealStackError::ProposalInactive);
+        require!(
+            current_timestamp < proposal.voting_ends_at,
+            RealStackError::VotingPeriodEnded
+        );
+        require!(!proposal.has_options(), RealStackError::InvalidBallot);
+        require!(proposal.asset_token.is_none(), RealStackError::ProposalScopeMismatch);
+        require!(!proposal.optimistic, RealStackError::OptimisticProposal);
+        require!(
+            vote_weight >= governance_config.min_vote_balance,
+            RealStackError::InvalidParameters
+        );
+        
+        escrow_vote_weight(
+            proposal,
+            vote_weight,
+            ctx.accounts.checkpoints.as_deref(),
+            &ctx.accounts.voter,
+            ctx.accounts.voter_real_account.as_ref(),
+            ctx.accounts.vote_escrow.as_ref(),
+            &ctx.accounts.token_program,
+        )?;
+        let tally_weight = weigh_vote(proposal, ctx.accounts.stake_account.as_mut(), vote_weight, lock_duration)?;
+        
+        // A direct vote overrides one the voter's delegate already cast
+        if vote_record.voter == Pubkey::default() {
+            vote_record.payer = voter.key();
+        } else {
+            let delegate = vote_record.delegate.ok_or(RealStackError::AlreadyVoted)?;
+            let previous_choice = vote_record.ballot.choice().ok_or(RealStackError::InvalidBallot)?;
+            untally_vote(proposal, previous_choice, vote_record.vote_weight)?;
+            msg!("Overriding vote cast by delegate: {}", delegate);
+        }
+        
+        // Set up vote record
+        vote_record.proposal = proposal.key();
+        vote_record.voter = voter.key();
+        vote_record.ballot = Ballot::Choice(choice);
+        vote_record.vote_weight = tally_weight;
+        vote_record.timestamp = current_timestamp;
+        vote_record.delegate = None;
+        vote_record.lock_duration = lock_duration;
+        
+        // Update vote counts
+        tally_vote(proposal, choice, tally_weight)?;
+        
+        // Log the vote
+        msg!("Vote recorded for proposal: {}", proposal.title);
+        msg!("Voter: {}, Vote: {:?}, Tokens: {}, Weight: {}", voter.key(), choice, vote_weight, tally_weight);
+        
+        Ok(())
+    }
+    
+    /// Change an existing vote while voting is open
+    pub fn change_vote(
+        ctx: Context<ChangeVote>,
+        choice: VoteChoice,
+        vote_weight: u64,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let voter = &ctx.accounts.voter;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let governance_config = &ctx.accounts.governance_config;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate voting requirements
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(proposal.is_active, RealStackError::ProposalInactive);
+        require!(
+            current_timestamp < proposal.voting_ends_at,
+            RealStackError::VotingPeriodEnded
+        );
+        require!(!proposal.has_options(), RealStackError::InvalidBallot);
+        require!(proposal.asset_token.is_none(), RealStackError::ProposalScopeMismatch);
+        require!(!proposal.optimistic, RealStackError::OptimisticProposal);
+        require!(
+            vote_weight >= governance_config.min_vote_balance,
+            RealStackError::InvalidParameters
+        );
+        
+        escrow_vote_weight(
+            proposal,
+            vote_weight,
+            ctx.accounts.checkpoints.as_deref(),
+            &ctx.accounts.voter,
+            ctx.accounts.voter_real_account.as_ref(),
+            ctx.accounts.vote_escrow.as_ref(),
+            &ctx.accounts.token_program,
+        )?;
+        
+        // A conviction vote keeps the lock it was cast with
+        let tally_weight = weigh_vote(
+            proposal,
+            ctx.accounts.stake_account.as_mut(),
+            vote_weight,
+            vote_record.lock_duration,
+        )?;
+        
+        // Move the recorded weight to the new choice
+        let previous_choice = vote_record.ballot.choice().ok_or(RealStackError::InvalidBallot)?;
+        untally_vote(proposal, previous_choice, vote_record.vote_weight)?;
+        tally_vote(proposal, choice, tally_weight)?;
+        
+        // A changed vote is the voter's own, even if a delegate cast the original
+        vote_record.ballot = Ballot::Choice(choice);
+        vote_record.vote_weight = tally_weight;
+        vote_record.timestamp = current_timestamp;
+        vote_record.delegate = None;
+        
+        // Log the change
+        msg!("Vote changed on proposal: {}", proposal.title);
+        msg!("Voter: {}, Vote: {:?} -> {:?}, Weight: {}",
+            voter.key(), previous_choice, choice, tally_weight);
+        
+        Ok(())
+    }
+    
+    /// Vote on an asset proposal, weighted by the voter's shares of the asset
+    pub fn vote_on_asset_proposal(
+        ctx: Context<VoteOnAssetProposal>,
+        choice: VoteChoice,
+        vote_weight: u64,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let voter = &ctx.accounts.voter;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate voting requirements
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(proposal.is_active, RealStackError::ProposalInactive);
+        require!(
+            current_timestamp < proposal.voting_ends_at,
+            RealStackError::VotingPeriodEnded
+        );
+        require!(vote_weight > 0, RealStackError::InvalidParameters);
+        require!(
+            vote_weight <= ctx.accounts.voter_share_account.amount,
+            RealStackError::InsufficientVotingPower
+        );
+        
+        // Set up vote record
+        vote_record.proposal = proposal.key();
+        vote_record.voter = voter.key();
+        vote_record.ballot = Ballot::Choice(choice);
+        vote_record.vote_weight = vote_weight;
+        vote_record.timestamp = current_timestamp;
+        vote_record.delegate = None;
+        vote_record.payer = voter.key();
+        vote_record.lock_duration = 0;
+        
+        // Escrow the voted shares so they cannot be moved to another wallet and voted again,
+        // thawing the voter's account around the transfer
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: ctx.accounts.asset_token.key(),
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        freeze.thaw(&ctx.accounts.voter_share_account)?;
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.voter_share_account.to_account_info(),
+                    to: ctx.accounts.vote_escrow.to_account_info(),
+                    authority: voter.to_account_info(),
+                },
+            ),
+            vote_weight,
+        )?;
+        freeze.freeze(&ctx.accounts.voter_share_account)?;
+        
+        // Update vote counts
+        tally_vote(proposal, choice, vote_weight)?;
+        
+        // Log the vote
+        msg!("Asset vote recorded for proposal: {}", proposal.title);
+        msg!("Voter: {}, Vote: {:?}, Shares: {}", voter.key(), choice, vote_weight);
+        
+        Ok(())
+    }
+    
+    /// Vote on a multi-option proposal with a single choice or a ranking
+    pub fn vote_on_options(
+        ctx: Context<VoteOnOptions>,
+        ranking: Vec<u8>,
+        vote_weight: u64,
+        lock_duration: i64,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let voter = &ctx.accounts.voter;
+        let vote_record = &mut ctx.accounts.vote_record;
+        let governance_config = &ctx.accounts.governance_config;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate voting requirements
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(proposal.is_active, RealStackError::ProposalInactive);
+        require!(
+            current_timestamp < proposal.voting_ends_at,
+            RealStackError::VotingPeriodEnded
+        );
+        require!(
+            is_valid_ranking(&ranking, proposal.options.len(), proposal.resolution),
+            RealStackError::InvalidBallot
+        );
+        require!(
+            vote_weight >= governance_config.min_vote_balance,
+            RealStackError::InvalidParameters
+        );
+        
+        escrow_vote_weight(
+            proposal,
+            vote_weight,
+            ctx.accounts.checkpoints.as_deref(),
+            &ctx.accounts.voter,
+            ctx.accounts.voter_real_account.as_ref(),
+            ctx.accounts.vote_escrow.as_ref(),
+            &ctx.accounts.token_program,
+        )?;
+        let tally_weight = weigh_vote(proposal, ctx.accounts.stake_account.as_mut(), vote_weight, lock_duration)?;
+        
+        // First choices are tallied per option; rankings are kept for the runoff
+        let first_choice = &mut proposal.options[ranking[0] as usize];
+        first_choice.votes = add_votes(first_choice.votes, tally_weight)?;
+        if proposal.resolution == ResolutionMethod::RankedChoice {
+            match proposal.ranked_ballots.iter_mut().find(|ballot| ballot.ranking == ranking) {
+                Some(ballot) => ballot.weight = add_votes(ballot.weight, tally_weight)?,
+                None => proposal.ranked_ballots.push(RankedBallot {
+                    ranking: ranking.clone(),
+                    weight: tally_weight,
+                }),
+            }
+        }
+        
+        // Set up vote record
+        vote_record.proposal = proposal.key();
+        vote_record.voter = voter.key();
+        vote_record.ballot = Ballot::Options(ranking.clone());
+        vote_record.vote_weight = tally_weight;
+        vote_record.timestamp = current_timestamp;
+        vote_record.delegate = None;
+        vote_record.payer = voter.key();
+        vote_record.lock_duration = lock_duration;
+        
+        // Log the vote
+        msg!("Vote recorded for proposal: {}", proposal.title);
+        msg!("Voter: {}, Ranking: {:?}, Tokens: {}, Weight: {}", voter.key(), ranking, vote_weight, tally_weight);
+        
+        Ok(())
+    }
+    
+    /// Execute a proposal
+    pub fn execute_proposal(
+        ctx: Context<ExecuteProposal>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let executor = &ctx.accounts.executor;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate execution requirements
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(proposal.is_active, RealStackError::ProposalInactive);
+        require!(
+            current_timestamp >= proposal.voting_ends_at,
+            RealStackError::VotingPeriodNotEnded
+        );
+        require!(!proposal.executed, RealStackError::ProposalAlreadyExecuted);
+        require!(proposal.asset_token.is_none(), RealStackError::ProposalScopeMismatch);
+        require!(
+            !matches!(
+                proposal.proposal_type,
+                ProposalType::TreasuryTransfer | ProposalType::CommunityFunding | ProposalType::ProgramUpgrade
+            ),
+            RealStackError::WrongExecutionInstruction
+        );
+        
+        if proposal.has_options() {
+            return execute_option_proposal(proposal, executor.key(), current_timestamp);
+        }
+        
+        // An optimistic proposal still on the fast path was never challenged enough to vote on
+        let approved = if proposal.optimistic {
+            true
+        } else {
+            // Check if quorum was reached; abstentions count toward it
+            let total_votes = total_votes(proposal)?;
+            require!(
+                total_votes >= proposal.quorum_votes,
+                RealStackError::InvalidParameters
+            );
+            
+            // Check if approval threshold was met
+            is_approved(
+                proposal.yes_votes,
+                proposal.no_votes,
+                proposal.approval_threshold_percentage,
+            )
+        };
+        let yes_percentage = yes_percentage(proposal.yes_votes, proposal.no_votes);
+        
+        // Update proposal state
+        proposal.executed = true;
+        proposal.is_active = false;
+        proposal.executed_at = current_timestamp;
+        proposal.executor = Some(executor.key());
+        
+        // Log the execution result
+        if approved {
+            msg!("Proposal passed and executed: {}", proposal.title);
+            if proposal.optimistic {
+                msg!("Objections: {}, Escalation threshold: {}",
+                    proposal.objection_votes, proposal.objection_threshold);
+            } else {
+                msg!("Yes votes: {} ({}%), Required: {}%", 
+                    proposal.yes_votes, yes_percentage, proposal.approval_threshold_percentage);
+            }
+            
+            // Execute based on proposal type
+            match proposal.proposal_type {
+                ProposalType::Text => {
+                    msg!("Text proposal execution: No on-chain action needed");
+                },
+                ProposalType::PlatformParameters | ProposalType::UpdateFees => {
+                    let action = ParameterAction::try_from_slice(&proposal.execution_data)?;
+                    apply_parameter_action(
+                        action,
+                        &mut ctx.accounts.governance_config,
+                        &mut ctx.accounts.real_token,
+                        current_timestamp,
+                    );
+                },
+                _ => {
+                    // Other proposal types would have specific execution logic
+                    msg!("Executing proposal of type: {:?}", proposal.proposal_type);
+                }
+            }
+        } else {
+            msg!("Proposal failed: {}", proposal.title);
+            msg!("Yes votes: {} ({}%), Required: {}%", 
+                proposal.yes_votes, yes_percentage, proposal.approval_threshold_percentage);
+        }
+        
+        Ok(())
+    }
+    
+    /// Write an approved parameter or fee change to the account it governs
+    fn apply_parameter_action(
+        action: ParameterAction,
+        governance_config: &mut GovernanceConfig,
+        real_token: &mut RealToken,
+        current_timestamp: i64,
+    ) {
+        match action {
+            ParameterAction::Voting { min_voting_period, max_voting_period, min_quorum_votes, approval_threshold } => {
+                governance_config.min_voting_period = min_voting_period;
+                governance_config.max_voting_period = max_voting_period;
+                governance_config.min_quorum_votes = min_quorum_votes;
+                governance_config.approval_threshold = approval_threshold;
+            },
+            ParameterAction::Fees { transaction_fee_bps, fee_recipient, fees_enabled } => {
+                real_token.fee_config = FeeConfig {
+                    transaction_fee_bps,
+                    fee_recipient,
+                    fees_enabled,
+                };
+                real_token.last_update_timestamp = current_timestamp;
+            },
+        }
+        
+        // Log the change
+        msg!("Applied: {:?}", action);
+    }
+    
+    /// Object to an optimistic proposal; enough objections escalate it to a full vote
+    pub fn object_to_proposal(
+        ctx: Context<ObjectToProposal>,
     ) -> Result<()> {
         let proposal = &mut ctx.accounts.proposal;
-        let proposer = &ctx.accounts.proposer;
-        let governance_config = &ctx.accounts.governance_config;
+        let objector = &ctx.accounts.objector;
         let current_timestamp = Clock::get()?.unix_timestamp;
         
-        // Validate proposal parameters
-        require!(!title.is_empty(), RealStackError::InvalidParameters);
-        require!(!description.is_empty(), RealStackError::InvalidParameters);
-        
-        // Validate voting period
-        let min_end_time = current_timestamp.checked_add(governance_config.min_voting_period)
-            .ok_or(RealStackError::MathOverflow)?;
-        let max_end_time = current_timestamp.checked_add(governance_config.max_voting_period)
-            .ok_or(RealStackError::MathOverflow)?;
-        
+        // Validate objection requirements
         require!(
-            voting_ends_at >= min_end_time,
-            RealStackError::InvalidVotingPeriod
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
         );
+        require!(proposal.is_active, RealStackError::ProposalInactive);
         require!(
-            voting_ends_at <= max_end_time,
-            RealStackError::InvalidVotingPeriod
+            current_timestamp < proposal.voting_ends_at,
+            RealStackError::VotingPeriodEnded
         );
+        require!(proposal.optimistic, RealStackError::NotOptimisticProposal);
         
-        // Initialize the proposal
-        proposal.title = title;
-        proposal.description = description;
-        proposal.proposer = proposer.key();
-        proposal.is_active = true;
-        proposal.creation_timestamp = current_timestamp;
-        proposal.voting_ends_at = voting_ends_at;
-        proposal.yes_votes = 0;
-        proposal.no_votes = 0;
-        proposal.executed = false;
-        proposal.proposal_type = ProposalType::Text; // Default type
-        proposal.target_accounts = vec![];
-        proposal.execution_data = vec![];
-        proposal.min_voting_period = governance_config.min_voting_period;
-        proposal.quorum_votes = governance_config.min_quorum_votes;
-        proposal.approval_threshold_percentage = governance_config.approval_threshold;
-        proposal.executed_at = 0;
-        proposal.executor = None;
+        let weight = ctx.accounts.checkpoints.power_before(proposal.snapshot_slot);
+        require!(weight > 0, RealStackError::InsufficientVotingPower);
         
-        // Log the creation
-        msg!("Governance proposal created: {}", proposal.title);
-        msg!("Voting ends at: {}", proposal.voting_ends_at);
+        // Record the objection
+        let objection_record = &mut ctx.accounts.objection_record;
+        objection_record.proposal = proposal.key();
+        objection_record.objector = objector.key();
+        objection_record.weight = weight;
+        objection_record.timestamp = current_timestamp;
+        proposal.objection_votes = add_votes(proposal.objection_votes, weight)?;
+        
+        // Log the objection
+        msg!("Objection recorded for proposal: {}", proposal.title);
+        msg!("Objector: {}, Weight: {}, Total: {}", objector.key(), weight, proposal.objection_votes);
+        
+        // Too much opposition for the fast path: hold a full vote of at least the minimum period
+        if proposal.objection_votes > proposal.objection_threshold {
+            let full_vote_ends_at = current_timestamp
+                .checked_add(proposal.min_voting_period)
+                .ok_or(RealStackError::MathOverflow)?;
+            proposal.optimistic = false;
+            proposal.voting_ends_at = proposal.voting_ends_at.max(full_vote_ends_at);
+            msg!("Proposal escalated to a full vote ending at: {}", proposal.voting_ends_at);
+        }
         
         Ok(())
     }
     
-    /// Vote on a proposal
-    pub fn vote_on_proposal(
-        ctx: Context<VoteOnProposal>,
-        vote_yes: bool,
-        vote_weight: u64,
+    /// Vote with the full voting power of a holder who delegated to the signer
+    pub fn cast_delegated_vote(
+        ctx: Context<CastDelegatedVote>,
+        choice: VoteChoice,
     ) -> Result<()> {
         let proposal = &mut ctx.accounts.proposal;
-        let voter = &ctx.accounts.voter;
+        let delegation = &ctx.accounts.delegation;
         let vote_record = &mut ctx.accounts.vote_record;
         let governance_config = &ctx.accounts.governance_config;
         let current_timestamp = Clock::get()?.unix_timestamp;
         
         // Validate voting requirements
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
         require!(proposal.is_active, RealStackError::ProposalInactive);
         require!(
             current_timestamp < proposal.voting_ends_at,
             RealStackError::VotingPeriodEnded
         );
+        require!(!proposal.has_options(), RealStackError::InvalidBallot);
+        require!(proposal.asset_token.is_none(), RealStackError::ProposalScopeMismatch);
+        require!(!proposal.optimistic, RealStackError::OptimisticProposal);
+        
+        // A type-specific delegation takes precedence over a global one
+        match delegation.proposal_type {
+            Some(proposal_type) => require!(
+                proposal_type == proposal.proposal_type,
+                RealStackError::DelegationNotApplicable
+            ),
+            None => require!(
+                ctx.accounts.type_delegation.data_is_empty(),
+                RealStackError::DelegationNotApplicable
+            ),
+        }
+        
+        require!(
+            delegation.updated_slot < proposal.snapshot_slot,
+            RealStackError::DelegationAfterSnapshot
+        );
+        
+        // The delegate votes the delegator's whole voting power at the snapshot
+        let vote_weight = ctx.accounts.checkpoints.power_before(proposal.snapshot_slot);
         require!(
-            vote_weight >= governance_config.min_vote_balance,
-            RealStackError::InvalidParameters
+            vote_weight > 0 && vote_weight >= governance_config.min_vote_balance,
+            RealStackError::InsufficientVotingPower
         );
         
-        // Set up vote record
+        // Delegates cannot lock a delegator's stake, so conviction votes earn no multiplier
+        let tally_weight = voting_weight(proposal.voting_mode, vote_weight, 0)?;
+        
+        // Record the vote under the delegator
         vote_record.proposal = proposal.key();
-        vote_record.voter = voter.key();
-        vote_record.is_yes_vote = vote_yes;
-        vote_record.vote_weight = vote_weight;
+        vote_record.voter = delegation.delegator;
+        vote_record.ballot = Ballot::Choice(choice);
+        vote_record.vote_weight = tally_weight;
         vote_record.timestamp = current_timestamp;
+        vote_record.delegate = Some(delegation.delegate);
+        vote_record.payer = delegation.delegate;
+        vote_record.lock_duration = 0;
         
         // Update vote counts
-        if vote_yes {
-            proposal.yes_votes = proposal.yes_votes
-                .checked_add(vote_weight)
-                .ok_or(RealStackError::MathOverflow)?;
+        tally_vote(proposal, choice, tally_weight)?;
+        
+        // Log the vote
+        msg!("Delegated vote recorded for proposal: {}", proposal.title);
+        msg!("Delegate: {}, Delegator: {}, Vote: {:?}, Weight: {}",
+            delegation.delegate, delegation.delegator, choice, tally_weight);
+        
+        Ok(())
+    }
+    
+    /// Delegate voting power globally or for one proposal type
+    pub fn delegate(
+        ctx: Context<Delegate>,
+        delegate: Pubkey,
+        proposal_type: Option<ProposalType>,
+    ) -> Result<()> {
+        let delegation = &mut ctx.accounts.delegation;
+        let delegator = &ctx.accounts.delegator;
+        
+        require!(delegate != delegator.key(), RealStackError::InvalidParameters);
+        
+        delegation.governance_config = ctx.accounts.governance_config.key();
+        delegation.delegator = delegator.key();
+        delegation.delegate = delegate;
+        delegation.proposal_type = proposal_type;
+        let clock = Clock::get()?;
+        delegation.updated_at = clock.unix_timestamp;
+        delegation.updated_slot = clock.slot;
+        delegation.bump = *ctx.bumps.get("delegation").unwrap();
+        
+        // Log the delegation
+        msg!("Voting power of {} delegated to {}", delegator.key(), delegate);
+        msg!("Scope: {:?}", proposal_type);
+        
+        Ok(())
+    }
+    
+    /// Revoke a delegation; votes the delegate already cast stand
+    pub fn undelegate(
+        ctx: Context<Undelegate>,
+    ) -> Result<()> {
+        let delegation = &ctx.accounts.delegation;
+        
+        // Log the revocation
+        msg!("Delegation from {} to {} revoked", delegation.delegator, delegation.delegate);
+        
+        Ok(())
+    }
+    
+    /// Refund a proposal's deposit if it reached quorum, or slash it to the treasury
+    pub fn settle_proposal_deposit(
+        ctx: Context<SettleProposalDeposit>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let governance_config = &ctx.accounts.governance_config;
+        let deposit_escrow = &ctx.accounts.deposit_escrow;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate settlement requirements
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(
+            current_timestamp >= proposal.voting_ends_at,
+            RealStackError::VotingPeriodNotEnded
+        );
+        require!(!proposal.deposit_settled, RealStackError::DepositAlreadySettled);
+        
+        // An unchallenged optimistic proposal never needed votes
+        let refunded = proposal.optimistic || total_votes(proposal)? >= proposal.quorum_votes;
+        
+        // Pay out the escrow and return its rent to the proposer
+        let real_token_key = governance_config.real_token;
+        let seeds = &[b"governance_config".as_ref(), real_token_key.as_ref(), &[governance_config.bump]];
+        let escrowed = deposit_escrow.amount;
+        if escrowed > 0 {
+            let destination = if refunded {
+                ctx.accounts.proposer_token_account
+                    .as_ref()
+                    .ok_or(RealStackError::InvalidTokenAccount)?
+                    .to_account_info()
+            } else {
+                ctx.accounts.treasury.to_account_info()
+            };
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: deposit_escrow.to_account_info(),
+                        to: destination,
+                        authority: governance_config.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                escrowed,
+            )?;
+        }
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: deposit_escrow.to_account_info(),
+                destination: ctx.accounts.proposer.to_account_info(),
+                authority: governance_config.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+        
+        // Free the proposer's active proposal slot
+        let proposer_state = &mut ctx.accounts.proposer_state;
+        proposer_state.active_proposals = proposer_state.active_proposals
+            .checked_sub(1)
+            .ok_or(RealStackError::MathUnderflow)?;
+        proposal.deposit_settled = true;
+        
+        // Log the settlement
+        if refunded {
+            msg!("Deposit of {} refunded for proposal: {}", escrowed, proposal.title);
         } else {
-            proposal.no_votes = proposal.no_votes
-                .checked_add(vote_weight)
-                .ok_or(RealStackError::MathOverflow)?;
+            msg!("Deposit of {} slashed to treasury for proposal: {}", escrowed, proposal.title);
         }
         
-        // Log the vote
-        msg!("Vote recorded for proposal: {}", proposal.title);
-        msg!("Voter: {}, Vote: {}, Weight: {}", 
-            voter.key(), if vote_yes { "Yes" } else { "No" }, vote_weight);
+        Ok(())
+    }
+    
+    /// Reclaim a vote record's rent once voting on its proposal is over
+    pub fn close_vote_record(
+        ctx: Context<CloseVoteRecord>,
+    ) -> Result<()> {
+        let proposal_info = &ctx.accounts.proposal;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // A proposal that was already closed is finalized by definition
+        if !proposal_info.data_is_empty() {
+            let proposal = Account::<Proposal>::try_from(&proposal_info.to_account_info())?;
+            require!(
+                proposal.is_finalized(current_timestamp),
+                RealStackError::ProposalNotFinalized
+            );
+        }
+        
+        // Log the close
+        msg!("Vote record closed for voter: {}", ctx.accounts.voter.key());
         
         Ok(())
     }
     
-    /// Execute a proposal
-    pub fn execute_proposal(
-        ctx: Context<ExecuteProposal>,
+    /// Return the shares or REAL escrowed by a vote once its proposal is finalized
+    pub fn reclaim_vote_shares(
+        ctx: Context<ReclaimVoteShares>,
+    ) -> Result<()> {
+        let proposal_info = &ctx.accounts.proposal;
+        let governance_config = &ctx.accounts.governance_config;
+        let vote_escrow = &ctx.accounts.vote_escrow;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // A proposal that was already closed is finalized by definition
+        if !proposal_info.data_is_empty() {
+            let proposal = Account::<Proposal>::try_from(&proposal_info.to_account_info())?;
+            require!(
+                proposal.is_finalized(current_timestamp),
+                RealStackError::ProposalNotFinalized
+            );
+        }
+        
+        // Escrowed shares go back to a frozen account, so the asset's freeze authority thaws both sides
+        let freeze = if vote_escrow.mint == ctx.accounts.real_token.mint {
+            None
+        } else {
+            let (asset_token, share_mint, freeze_authority) = match (
+                ctx.accounts.asset_token.as_ref(),
+                ctx.accounts.share_mint.as_ref(),
+                ctx.accounts.freeze_authority.as_ref(),
+            ) {
+                (Some(asset_token), Some(share_mint), Some(freeze_authority)) => (asset_token, share_mint, freeze_authority),
+                _ => return err!(RealStackError::InvalidTokenAccount),
+            };
+            let (expected_authority, bump) = AssetToken::freeze_authority(&asset_token.key());
+            require!(
+                freeze_authority.key() == expected_authority,
+                RealStackError::InvalidParameters
+            );
+            let freeze = ShareFreeze {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                share_mint: share_mint.to_account_info(),
+                freeze_authority: freeze_authority.to_account_info(),
+                asset_token: asset_token.key(),
+                bump,
+            };
+            freeze.thaw(vote_escrow)?;
+            freeze.thaw(&ctx.accounts.voter_share_account)?;
+            Some(freeze)
+        };
+        
+        // Return the escrowed tokens and the escrow's rent to the voter
+        let real_token_key = governance_config.real_token;
+        let seeds = &[b"governance_config".as_ref(), real_token_key.as_ref(), &[governance_config.bump]];
+        let amount = vote_escrow.amount;
+        if amount > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: vote_escrow.to_account_info(),
+                        to: ctx.accounts.voter_share_account.to_account_info(),
+                        authority: governance_config.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                amount,
+            )?;
+        }
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: vote_escrow.to_account_info(),
+                destination: ctx.accounts.voter.to_account_info(),
+                authority: governance_config.to_account_info(),
+            },
+            &[&seeds[..]],
+        ))?;
+        if let Some(freeze) = freeze {
+            freeze.freeze(&ctx.accounts.voter_share_account)?;
+        }
+        
+        // Log the reclaim
+        msg!("Vote shares reclaimed: {} by {}", amount, ctx.accounts.voter.key());
+        
+        Ok(())
+    }
+    
+    /// Reclaim a proposal's rent after execution or expiry plus the retention period
+    pub fn close_proposal(
+        ctx: Context<CloseProposal>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(
+            proposal.is_finalized(current_timestamp),
+            RealStackError::ProposalNotFinalized
+        );
+        require!(proposal.deposit_settled, RealStackError::DepositNotSettled);
+        
+        // Keep finished proposals around for the retention period
+        let finished_at = if proposal.executed {
+            proposal.executed_at
+        } else {
+            proposal.voting_ends_at
+        };
+        let closable_at = finished_at
+            .checked_add(PROPOSAL_RETENTION_PERIOD)
+            .ok_or(RealStackError::MathOverflow)?;
+        require!(
+            current_timestamp >= closable_at,
+            RealStackError::RetentionPeriodActive
+        );
+        
+        // Log the close
+        msg!("Proposal closed: {}", proposal.title);
+        
+        Ok(())
+    }
+    
+    /// Execute an asset proposal, invoking its wrapped instruction if it passed
+    pub fn execute_asset_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteAssetProposal<'info>>,
     ) -> Result<()> {
         let proposal = &mut ctx.accounts.proposal;
         let executor = &ctx.accounts.executor;
         let current_timestamp = Clock::get()?.unix_timestamp;
         
         // Validate execution requirements
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
         require!(proposal.is_active, RealStackError::ProposalInactive);
         require!(
             current_timestamp >= proposal.voting_ends_at,
@@ -369,52 +2924,325 @@ pub mod governance_operations {
         );
         require!(!proposal.executed, RealStackError::ProposalAlreadyExecuted);
         
-        // Check if quorum was reached
-        let total_votes = proposal.yes_votes
-            .checked_add(proposal.no_votes)
-            .ok_or(RealStackError::MathOverflow)?;
+        // Check if quorum was reached; abstentions count toward it
+        let total_votes = total_votes(proposal)?;
         require!(
             total_votes >= proposal.quorum_votes,
             RealStackError::InvalidParameters
         );
         
-        // Check if approval threshold was met
-        let yes_percentage = if total_votes > 0 {
-            (proposal.yes_votes as u128 * 100) / total_votes as u128
-        } else {
-            0
+        let approved = is_approved(
+            proposal.yes_votes,
+            proposal.no_votes,
+            proposal.approval_threshold_percentage,
+        );
+        
+        // Update proposal state before invoking the wrapped instruction
+        proposal.executed = true;
+        proposal.is_active = false;
+        proposal.executed_at = current_timestamp;
+        proposal.executor = Some(executor.key());
+        
+        if !approved {
+            msg!("Asset proposal failed: {}", proposal.title);
+            msg!("Yes votes: {} ({}%), Required: {}%",
+                proposal.yes_votes,
+                yes_percentage(proposal.yes_votes, proposal.no_votes),
+                proposal.approval_threshold_percentage);
+            return Ok(());
+        }
+        
+        // The remaining accounts must be exactly the proposed ones
+        require!(
+            ctx.remaining_accounts.len() == proposal.target_accounts.len(),
+            RealStackError::InvalidParameters
+        );
+        let signer_key = ctx.accounts.asset_governance_signer.key();
+        let mut metas = Vec::with_capacity(proposal.target_accounts.len());
+        for (pubkey, info) in proposal.target_accounts.iter().zip(ctx.remaining_accounts.iter()) {
+            require!(*pubkey == info.key(), RealStackError::InvalidParameters);
+            let is_signer = *pubkey == signer_key;
+            metas.push(if info.is_writable {
+                AccountMeta::new(*pubkey, is_signer)
+            } else {
+                AccountMeta::new_readonly(*pubkey, is_signer)
+            });
+        }
+        
+        let instruction = Instruction {
+            program_id: crate::ID,
+            accounts: metas,
+            data: proposal.execution_data.clone(),
         };
         
-        let approved = yes_percentage >= proposal.approval_threshold_percentage as u128;
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.asset_governance_signer.to_account_info());
+        account_infos.push(ctx.accounts.realstack_program.to_account_info());
+        
+        let asset_key = proposal.asset_token.unwrap_or_default();
+        let seeds = &[
+            b"asset_governance".as_ref(),
+            asset_key.as_ref(),
+            &[*ctx.bumps.get("asset_governance_signer").unwrap()],
+        ];
+        invoke_signed(&instruction, &account_infos, &[&seeds[..]])?;
+        
+        // Log the execution
+        msg!("Asset proposal passed and executed: {}", proposal.title);
+        msg!("Yes votes: {}, No votes: {}", proposal.yes_votes, proposal.no_votes);
+        
+        Ok(())
+    }
+    
+    /// Resolve a multi-option proposal and record its winning option
+    fn execute_option_proposal(
+        proposal: &mut Proposal,
+        executor: Pubkey,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        // Check if quorum was reached on first choices
+        let total_votes = total_votes(proposal)?;
+        require!(
+            total_votes >= proposal.quorum_votes,
+            RealStackError::InvalidParameters
+        );
+        
+        let winning_option = match proposal.resolution {
+            ResolutionMethod::Plurality => {
+                let tallies: Vec<u64> = proposal.options.iter().map(|option| option.votes).collect();
+                resolve_plurality(&tallies)
+            },
+            ResolutionMethod::RankedChoice => {
+                resolve_ranked_choice(proposal.options.len(), &proposal.ranked_ballots)
+            },
+        };
         
         // Update proposal state
         proposal.executed = true;
         proposal.is_active = false;
         proposal.executed_at = current_timestamp;
-        proposal.executor = Some(executor.key());
+        proposal.executor = Some(executor);
+        proposal.winning_option = winning_option;
         
         // Log the execution result
-        if approved {
-            msg!("Proposal passed and executed: {}", proposal.title);
-            msg!("Yes votes: {} ({}%), Required: {}%", 
-                proposal.yes_votes, yes_percentage, proposal.approval_threshold_percentage);
-            
-            // Execute based on proposal type (implementation would vary by type)
-            match proposal.proposal_type {
-                ProposalType::Text => {
-                    msg!("Text proposal execution: No on-chain action needed");
-                },
-                _ => {
-                    // Other proposal types would have specific execution logic
-                    msg!("Executing proposal of type: {:?}", proposal.proposal_type);
-                }
+        match winning_option {
+            Some(index) => {
+                let option = &proposal.options[index as usize];
+                msg!("Proposal resolved and executed: {}", proposal.title);
+                msg!("Winning option {}: {} ({} first-choice votes)", index, option.label, option.votes);
+                msg!("Execution data: {:?}", option.execution_data);
+            },
+            None => {
+                msg!("Proposal failed: {}", proposal.title);
+                msg!("No option won under {:?} resolution", proposal.resolution);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Votes counted toward quorum: every yes, no and abstain vote, or first choices on options
+    pub(crate) fn total_votes(proposal: &Proposal) -> Result<u64> {
+        if proposal.has_options() {
+            let mut total_votes = 0;
+            for option in &proposal.options {
+                total_votes = add_votes(total_votes, option.votes)?;
             }
+            Ok(total_votes)
         } else {
-            msg!("Proposal failed: {}", proposal.title);
-            msg!("Yes votes: {} ({}%), Required: {}%", 
-                proposal.yes_votes, yes_percentage, proposal.approval_threshold_percentage);
+            add_votes(
+                add_votes(proposal.yes_votes, proposal.no_votes)?,
+                proposal.abstain_votes,
+            )
+        }
+    }
+    
+    /// Whether a ranking is a valid ballot for a proposal's options
+    fn is_valid_ranking(ranking: &[u8], option_count: usize, resolution: ResolutionMethod) -> bool {
+        let max_len = match resolution {
+            ResolutionMethod::Plurality => 1,
+            ResolutionMethod::RankedChoice => option_count,
+        };
+        if ranking.is_empty() || ranking.len() > max_len {
+            return false;
+        }
+        ranking.iter().enumerate().all(|(position, &option)| {
+            (option as usize) < option_count && !ranking[..position].contains(&option)
+        })
+    }
+    
+    /// Weight a vote by the proposal's voting mode, locking the stake behind a conviction vote
+    fn weigh_vote(
+        proposal: &Proposal,
+        stake_account: Option<&mut Account<StakeAccount>>,
+        vote_weight: u64,
+        lock_duration: i64,
+    ) -> Result<u64> {
+        let tally_weight = voting_weight(proposal.voting_mode, vote_weight, lock_duration)?;
+        if lock_duration > 0 {
+            // Only staked REAL can be locked, so it must cover the whole vote
+            require!(
+                proposal.voting_mode == VotingMode::Conviction,
+                RealStackError::InvalidParameters
+            );
+            let stake_account = stake_account.ok_or(RealStackError::InsufficientVotingPower)?;
+            require!(
+                vote_weight <= stake_account.voting_power(),
+                RealStackError::InsufficientVotingPower
+            );
+            let locked_until = proposal.voting_ends_at
+                .checked_add(lock_duration)
+                .ok_or(RealStackError::MathOverflow)?;
+            stake_account.vote_locked_until = stake_account.vote_locked_until.max(locked_until);
+        }
+        Ok(tally_weight)
+    }
+    
+    /// Check a vote against the voter's stake at the snapshot plus their REAL balance. Liquid REAL
+    /// has no history to snapshot, so the part the stake does not cover is escrowed until the
+    /// proposal is finalized; REAL escrowed by an earlier vote on the proposal counts again
+    fn escrow_vote_weight<'info>(
+        proposal: &Proposal,
+        vote_weight: u64,
+        checkpoints: Option<&VotingPowerCheckpoints>,
+        voter: &Signer<'info>,
+        voter_real_account: Option<&Account<'info, TokenAccount>>,
+        vote_escrow: Option<&Account<'info, TokenAccount>>,
+        token_program: &Program<'info, Token>,
+    ) -> Result<()> {
+        let staked_power = checkpoints.map_or(0, |checkpoints| checkpoints.power_before(proposal.snapshot_slot));
+        let escrowed = vote_escrow.map_or(0, |vote_escrow| vote_escrow.amount);
+        let shortfall = vote_weight.saturating_sub(staked_power.saturating_add(escrowed));
+        if shortfall == 0 {
+            return Ok(());
         }
         
+        let (voter_real_account, vote_escrow) = match (voter_real_account, vote_escrow) {
+            (Some(voter_real_account), Some(vote_escrow)) => (voter_real_account, vote_escrow),
+            _ => return err!(RealStackError::InsufficientVotingPower),
+        };
+        require!(
+            shortfall <= voter_real_account.amount,
+            RealStackError::InsufficientVotingPower
+        );
+        token::transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                Transfer {
+                    from: voter_real_account.to_account_info(),
+                    to: vote_escrow.to_account_info(),
+                    authority: voter.to_account_info(),
+                },
+            ),
+            shortfall,
+        )
+    }
+    
+    /// Add a vote to the proposal's tally
+    fn tally_vote(proposal: &mut Proposal, choice: VoteChoice, vote_weight: u64) -> Result<()> {
+        let tally = proposal.tally_mut(choice);
+        *tally = add_votes(*tally, vote_weight)?;
+        Ok(())
+    }
+    
+    /// Remove a previously tallied vote
+    fn untally_vote(proposal: &mut Proposal, choice: VoteChoice, vote_weight: u64) -> Result<()> {
+        let tally = proposal.tally_mut(choice);
+        *tally = tally
+            .checked_sub(vote_weight)
+            .ok_or(RealStackError::MathUnderflow)?;
         Ok(())
     }
-} 
\ No newline at end of file
+    
+    /// Add vote weight to a tally, failing instead of wrapping
+    pub fn add_votes(tally: u64, weight: u64) -> Result<u64> {
+        Ok(tally.checked_add(weight).ok_or(RealStackError::MathOverflow)?)
+    }
+    
+    /// Tally weight of `tokens` under a voting mode
+    pub fn voting_weight(mode: VotingMode, tokens: u64, lock_duration: i64) -> Result<u64> {
+        require!(
+            (0..=MAX_CONVICTION_LOCK).contains(&lock_duration),
+            RealStackError::InvalidParameters
+        );
+        match mode {
+            VotingMode::Linear => Ok(tokens),
+            VotingMode::Quadratic => Ok(integer_sqrt(tokens as u128) as u64),
+            VotingMode::Conviction => Ok(tokens
+                .checked_mul(conviction_multiplier(lock_duration))
+                .ok_or(RealStackError::MathOverflow)?),
+        }
+    }
+    
+    /// Multiplier a conviction vote earns: one, plus one per full conviction period locked
+    pub fn conviction_multiplier(lock_duration: i64) -> u64 {
+        let periods = (lock_duration.max(0) / CONVICTION_PERIOD) as u64;
+        (1 + periods).min(MAX_CONVICTION_MULTIPLIER)
+    }
+    
+    /// Share of yes votes in whole percent, rounded down (0 with no votes)
+    pub fn yes_percentage(yes_votes: u64, no_votes: u64) -> u64 {
+        let total_votes = yes_votes as u128 + no_votes as u128;
+        if total_votes == 0 {
+            return 0;
+        }
+        (yes_votes as u128 * 100 / total_votes) as u64
+    }
+    
+    /// Whether a tally meets the approval threshold
+    pub fn is_approved(yes_votes: u64, no_votes: u64, approval_threshold_percentage: u8) -> bool {
+        yes_percentage(yes_votes, no_votes) >= approval_threshold_percentage as u64
+    }
+    
+    /// Index of the option with strictly the most votes (None on a tie or no votes)
+    pub fn resolve_plurality(tallies: &[u64]) -> Option<u8> {
+        let max_votes = tallies.iter().copied().max().filter(|&votes| votes > 0)?;
+        let mut leaders = tallies.iter().enumerate().filter(|(_, &votes)| votes == max_votes);
+        let (winner, _) = leaders.next()?;
+        match leaders.next() {
+            Some(_) => None,
+            None => Some(winner as u8),
+        }
+    }
+    
+    /// Instant-runoff winner over aggregated rankings
+    ///
+    /// Each round counts every ballot for its highest-ranked option still in
+    /// the race. An option with more than half of those votes wins; otherwise
+    /// every option tied for the fewest votes is eliminated. Returns None when
+    /// no ballots remain or the remaining options are all tied.
+    pub fn resolve_ranked_choice(option_count: usize, ballots: &[RankedBallot]) -> Option<u8> {
+        let mut eliminated = vec![false; option_count];
+        loop {
+            let mut counts = vec![0u128; option_count];
+            for ballot in ballots {
+                let current_choice = ballot.ranking.iter()
+                    .map(|&option| option as usize)
+                    .find(|&option| option < option_count && !eliminated[option]);
+                if let Some(option) = current_choice {
+                    counts[option] += ballot.weight as u128;
+                }
+            }
+            
+            let active_votes: u128 = counts.iter().sum();
+            if active_votes == 0 {
+                return None;
+            }
+            if let Some(winner) = (0..option_count).find(|&option| counts[option] * 2 > active_votes) {
+                return Some(winner as u8);
+            }
+            
+            let remaining: Vec<usize> = (0..option_count).filter(|&option| !eliminated[option]).collect();
+            let fewest = remaining.iter().map(|&option| counts[option]).min()?;
+            let most = remaining.iter().map(|&option| counts[option]).max()?;
+            if fewest == most {
+                return None;
+            }
+            for option in remaining {
+                if counts[option] == fewest {
+                    eliminated[option] = true;
+                }
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -10,12 +10,30 @@ pub mod errors;
 pub mod asset_token;
 pub mod governance;
 pub mod tokenomics;
+pub mod staking;
+pub mod offering;
+pub mod marketplace;
+pub mod liquidity_pool;
+pub mod access_control;
+pub mod multisig;
+pub mod treasury;
+pub mod upgrade;
+pub mod compliance;
 
 // Re-export key components
 pub use errors::*;
 pub use asset_token::*;
 pub use governance::*;
 pub use tokenomics::*;
+pub use staking::*;
+pub use offering::*;
+pub use marketplace::*;
+pub use liquidity_pool::*;
+pub use access_control::*;
+pub use multisig::*;
+pub use treasury::*;
+pub use upgrade::*;
+pub use compliance::*;
 
 declare_id!("REALstaXZRGVWvZ8xpHCxJVBGMtp7RKWMeJhmvXwXcL");
 
@@ -35,6 +53,65 @@ pub mod realstack {
         tokenomics::token_operations::initialize(ctx, name, symbol, uri, total_supply)
     }
     
+    /// Start the two-step admin handover
+    pub fn transfer_authority(
+        ctx: Context<TransferAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        tokenomics::token_operations::transfer_authority(ctx, new_authority)
+    }
+    
+    /// Accept the admin handover
+    pub fn accept_authority(
+        ctx: Context<AcceptAuthority>,
+    ) -> Result<()> {
+        tokenomics::token_operations::accept_authority(ctx)
+    }
+    
+    /// Grant a role to an account
+    pub fn grant_role(
+        ctx: Context<ManageRoles>,
+        member: Pubkey,
+        role: Role,
+    ) -> Result<()> {
+        access_control::access_control_operations::grant_role(ctx, member, role)
+    }
+    
+    /// Revoke a role from an account
+    pub fn revoke_role(
+        ctx: Context<ManageRoles>,
+        member: Pubkey,
+        role: Role,
+    ) -> Result<()> {
+        access_control::access_control_operations::revoke_role(ctx, member, role)
+    }
+    
+    /// Update the fee configuration
+    pub fn update_fee_config(
+        ctx: Context<UpdateFeeConfig>,
+        transaction_fee_bps: u16,
+        fee_recipient: Pubkey,
+        fees_enabled: bool,
+    ) -> Result<()> {
+        tokenomics::token_operations::update_fee_config(ctx, transaction_fee_bps, fee_recipient, fees_enabled)
+    }
+    
+    /// Pause or unpause trading (emergency function)
+    pub fn set_transfer_pause(
+        ctx: Context<SetTransferPause>,
+        paused: bool,
+    ) -> Result<()> {
+        tokenomics::token_operations::set_transfer_pause(ctx, paused)
+    }
+    
+    /// Set the emergency pause flags
+    pub fn set_pause_flags(
+        ctx: Context<SetTransferPause>,
+        flags: PauseFlags,
+    ) -> Result<()> {
+        tokenomics::token_operations::set_pause_flags(ctx, flags)
+    }
+    
     /// Create a new asset token
     pub fn create_asset_token(
         ctx: Context<CreateAssetToken>,
@@ -46,9 +123,12 @@ pub mod realstack {
         valuation: u64,
         total_shares: u64,
         share_price: u64,
+        can_mint_additional: bool,
+        compliance: ComplianceConfig,
     ) -> Result<()> {
         asset_token::asset_token_operations::create_asset_token(
-            ctx, name, symbol, category, description, uri, valuation, total_shares, share_price
+            ctx, name, symbol, category, description, uri, valuation, total_shares, share_price,
+            can_mint_additional, compliance
         )
     }
     
@@ -76,11 +156,42 @@ pub mod realstack {
         asset_token::asset_token_operations::toggle_tradability(ctx, is_tradable)
     }
     
-    /// Burn (deactivate) an asset token
+    /// Burn (wind down) an asset token
     pub fn burn_asset_token(
         ctx: Context<BurnAssetToken>,
+        proceeds_amount: u64,
+    ) -> Result<()> {
+        asset_token::asset_token_operations::burn_asset_token(ctx, proceeds_amount)
+    }
+    
+    /// Redeem shares of a wound-down asset for sale proceeds
+    pub fn redeem_shares(
+        ctx: Context<RedeemShares>,
+        amount: u64,
+    ) -> Result<()> {
+        asset_token::asset_token_operations::redeem_shares(ctx, amount)
+    }
+    
+    /// Issue additional shares of an asset
+    pub fn mint_additional_shares(
+        ctx: Context<MintAdditionalShares>,
+        amount: u64,
+    ) -> Result<()> {
+        asset_token::asset_token_operations::mint_additional_shares(ctx, amount)
+    }
+    
+    /// Permanently lock an asset's share supply
+    pub fn lock_supply(
+        ctx: Context<LockSupply>,
+    ) -> Result<()> {
+        asset_token::asset_token_operations::lock_supply(ctx)
+    }
+    
+    /// Close a retired asset token
+    pub fn close_asset_token(
+        ctx: Context<CloseAssetToken>,
     ) -> Result<()> {
-        asset_token::asset_token_operations::burn_asset_token(ctx)
+        asset_token::asset_token_operations::close_asset_token(ctx)
     }
     
     /// Distribute income to token holders
@@ -91,6 +202,26 @@ pub mod realstack {
         asset_token::asset_token_operations::distribute_income(ctx, amount)
     }
     
+    /// Initialize the governance configuration
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        min_voting_period: i64,
+        max_voting_period: i64,
+        min_quorum_votes: u64,
+        approval_threshold: u8,
+        min_proposal_balance: u64,
+        min_vote_balance: u64,
+        proposal_deposit: u64,
+        max_active_proposals: u8,
+        voting_mode: VotingMode,
+    ) -> Result<()> {
+        governance::governance_operations::initialize_governance(
+            ctx, min_voting_period, max_voting_period, min_quorum_votes,
+            approval_threshold, min_proposal_balance, min_vote_balance,
+            proposal_deposit, max_active_proposals, voting_mode
+        )
+    }
+    
     /// Create a governance proposal
     pub fn create_proposal(
         ctx: Context<CreateProposal>,
@@ -101,13 +232,116 @@ pub mod realstack {
         governance::governance_operations::create_proposal(ctx, title, description, voting_ends_at)
     }
     
+    /// Create a governance proposal with labeled options
+    pub fn create_option_proposal(
+        ctx: Context<CreateOptionProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+        options: Vec<ProposalOptionInput>,
+        resolution: ResolutionMethod,
+    ) -> Result<()> {
+        governance::governance_operations::create_option_proposal(
+            ctx, title, description, voting_ends_at, options, resolution
+        )
+    }
+    
+    /// Create a proposal voted on by an asset's share holders
+    pub fn create_asset_proposal(
+        ctx: Context<CreateAssetProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+        accounts: Vec<Pubkey>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        governance::governance_operations::create_asset_proposal(
+            ctx, title, description, voting_ends_at, accounts, data
+        )
+    }
+    
+    /// Create a proposal that spends from the governance treasury
+    pub fn create_treasury_proposal(
+        ctx: Context<CreateProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+        action: TreasuryAction,
+    ) -> Result<()> {
+        governance::governance_operations::create_treasury_proposal(
+            ctx, title, description, voting_ends_at, action
+        )
+    }
+    
+    /// Create a proposal that upgrades the program from a loader buffer
+    pub fn create_upgrade_proposal(
+        ctx: Context<CreateProposal>,
+        title: String,
+        description: String,
+        voting_ends_at: i64,
+        action: UpgradeAction,
+    ) -> Result<()> {
+        governance::governance_operations::create_upgrade_proposal(
+            ctx, title, description, voting_ends_at, action
+        )
+    }
+    
+    /// Create a routine proposal that passes unless enough stake objects to it
+    pub fn create_optimistic_proposal(
+        ctx: Context<CreateProposal>,
+        title: String,
+        description: String,
+        challenge_ends_at: i64,
+        action: ParameterAction,
+    ) -> Result<()> {
+        governance::governance_operations::create_optimistic_proposal(
+            ctx, title, description, challenge_ends_at, action
+        )
+    }
+    
     /// Vote on a governance proposal
     pub fn vote_on_proposal(
         ctx: Context<VoteOnProposal>,
-        vote_yes: bool,
+        choice: VoteChoice,
+        vote_weight: u64,
+        lock_duration: i64,
+    ) -> Result<()> {
+        governance::governance_operations::vote_on_proposal(ctx, choice, vote_weight, lock_duration)
+    }
+    
+    /// Change an existing vote before voting ends
+    pub fn change_vote(
+        ctx: Context<ChangeVote>,
+        choice: VoteChoice,
+        vote_weight: u64,
+    ) -> Result<()> {
+        governance::governance_operations::change_vote(ctx, choice, vote_weight)
+    }
+    
+    /// Object to an optimistic proposal with staked REAL
+    pub fn object_to_proposal(
+        ctx: Context<ObjectToProposal>,
+    ) -> Result<()> {
+        governance::governance_operations::object_to_proposal(ctx)
+    }
+    
+    /// Vote on a multi-option proposal, ranking options for ranked choice
+    pub fn vote_on_options(
+        ctx: Context<VoteOnOptions>,
+        ranking: Vec<u8>,
+        vote_weight: u64,
+        lock_duration: i64,
+    ) -> Result<()> {
+        governance::governance_operations::vote_on_options(ctx, ranking, vote_weight, lock_duration)
+    }
+    
+    /// Vote on an asset proposal with asset shares
+    pub fn vote_on_asset_proposal(
+        ctx: Context<VoteOnAssetProposal>,
+        choice: VoteChoice,
         vote_weight: u64,
     ) -> Result<()> {
-        governance::governance_operations::vote_on_proposal(ctx, vote_yes, vote_weight)
+        governance::governance_operations::vote_on_asset_proposal(ctx, choice, vote_weight)
     }
     
     /// Execute a governance proposal
@@ -116,6 +350,353 @@ pub mod realstack {
     ) -> Result<()> {
         governance::governance_operations::execute_proposal(ctx)
     }
+    
+    /// Execute an asset proposal's wrapped instruction
+    pub fn execute_asset_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteAssetProposal<'info>>,
+    ) -> Result<()> {
+        governance::governance_operations::execute_asset_proposal(ctx)
+    }
+    
+    /// Vote on a proposal with a delegator's voting power
+    pub fn cast_delegated_vote(
+        ctx: Context<CastDelegatedVote>,
+        choice: VoteChoice,
+    ) -> Result<()> {
+        governance::governance_operations::cast_delegated_vote(ctx, choice)
+    }
+    
+    /// Delegate voting power, globally or for one proposal type
+    pub fn delegate(
+        ctx: Context<Delegate>,
+        delegate: Pubkey,
+        proposal_type: Option<ProposalType>,
+    ) -> Result<()> {
+        governance::governance_operations::delegate(ctx, delegate, proposal_type)
+    }
+    
+    /// Revoke a delegation
+    pub fn undelegate(
+        ctx: Context<Undelegate>,
+    ) -> Result<()> {
+        governance::governance_operations::undelegate(ctx)
+    }
+    
+    /// Refund or slash a proposal deposit once voting ends
+    pub fn settle_proposal_deposit(
+        ctx: Context<SettleProposalDeposit>,
+    ) -> Result<()> {
+        governance::governance_operations::settle_proposal_deposit(ctx)
+    }
+    
+    /// Close a vote record once its proposal is finalized
+    pub fn close_vote_record(
+        ctx: Context<CloseVoteRecord>,
+    ) -> Result<()> {
+        governance::governance_operations::close_vote_record(ctx)
+    }
+    
+    /// Return the shares or REAL escrowed by a vote
+    pub fn reclaim_vote_shares(
+        ctx: Context<ReclaimVoteShares>,
+    ) -> Result<()> {
+        governance::governance_operations::reclaim_vote_shares(ctx)
+    }
+    
+    /// Close a finished proposal after the retention period
+    pub fn close_proposal(
+        ctx: Context<CloseProposal>,
+    ) -> Result<()> {
+        governance::governance_operations::close_proposal(ctx)
+    }
+    
+    /// Create the REAL stake pool
+    pub fn initialize_stake_pool(
+        ctx: Context<InitializeStakePool>,
+        fee_share_bps: u16,
+        unstake_cooldown: i64,
+    ) -> Result<()> {
+        staking::staking_operations::initialize_stake_pool(ctx, fee_share_bps, unstake_cooldown)
+    }
+    
+    /// Stake REAL
+    pub fn stake(
+        ctx: Context<Stake>,
+        amount: u64,
+    ) -> Result<()> {
+        staking::staking_operations::stake(ctx, amount)
+    }
+    
+    /// Unstake REAL (starts the cooldown)
+    pub fn unstake(
+        ctx: Context<Unstake>,
+        amount: u64,
+    ) -> Result<()> {
+        staking::staking_operations::unstake(ctx, amount)
+    }
+    
+    /// Withdraw unstaked REAL after the cooldown
+    pub fn withdraw_unstaked(
+        ctx: Context<WithdrawUnstaked>,
+    ) -> Result<()> {
+        staking::staking_operations::withdraw_unstaked(ctx)
+    }
+    
+    /// Claim staking rewards
+    pub fn claim_rewards(
+        ctx: Context<ClaimRewards>,
+    ) -> Result<()> {
+        staking::staking_operations::claim_rewards(ctx)
+    }
+    
+    /// Route the stakers' share of collected fees to the stake pool
+    pub fn distribute_staking_rewards(
+        ctx: Context<DistributeStakingRewards>,
+        collected_fees: u64,
+    ) -> Result<()> {
+        staking::staking_operations::distribute_staking_rewards(ctx, collected_fees)
+    }
+    
+    /// Create a fixed-price primary offering for an asset
+    pub fn create_offering(
+        ctx: Context<CreateOffering>,
+        start_time: i64,
+        end_time: i64,
+        min_raise: u64,
+        max_raise: u64,
+        min_purchase_shares: u64,
+        max_shares_per_wallet: u64,
+    ) -> Result<()> {
+        offering::offering_operations::create_offering(
+            ctx, start_time, end_time, min_raise, max_raise, min_purchase_shares, max_shares_per_wallet
+        )
+    }
+    
+    /// Buy shares in a primary offering
+    pub fn buy_shares(
+        ctx: Context<BuyShares>,
+        shares: u64,
+    ) -> Result<()> {
+        offering::offering_operations::buy_shares(ctx, shares)
+    }
+    
+    /// Finalize a primary offering
+    pub fn finalize_offering(
+        ctx: Context<FinalizeOffering>,
+    ) -> Result<()> {
+        offering::offering_operations::finalize_offering(ctx)
+    }
+    
+    /// Claim shares from a successful offering
+    pub fn claim_offering_shares(
+        ctx: Context<ClaimOfferingShares>,
+    ) -> Result<()> {
+        offering::offering_operations::claim_offering_shares(ctx)
+    }
+    
+    /// Refund a purchase from a failed offering
+    pub fn refund_purchase(
+        ctx: Context<RefundPurchase>,
+    ) -> Result<()> {
+        offering::offering_operations::refund_purchase(ctx)
+    }
+    
+    /// Create the order book for an asset
+    pub fn create_order_book(
+        ctx: Context<CreateOrderBook>,
+        min_order_notional: u64,
+    ) -> Result<()> {
+        marketplace::marketplace_operations::create_order_book(ctx, min_order_notional)
+    }
+    
+    /// Place a limit order
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        side: OrderSide,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        marketplace::marketplace_operations::place_order(ctx, side, price, quantity)
+    }
+    
+    /// Cancel a limit order
+    pub fn cancel_order(
+        ctx: Context<CancelOrder>,
+        side: OrderSide,
+        order_id: u64,
+    ) -> Result<()> {
+        marketplace::marketplace_operations::cancel_order(ctx, side, order_id)
+    }
+    
+    /// Refund a resting order of a wound-down asset to its owner
+    pub fn unwind_order(
+        ctx: Context<UnwindOrder>,
+        side: OrderSide,
+        order_id: u64,
+    ) -> Result<()> {
+        marketplace::marketplace_operations::unwind_order(ctx, side, order_id)
+    }
+    
+    /// Match the best bid against the best ask
+    pub fn match_orders(
+        ctx: Context<MatchOrders>,
+    ) -> Result<()> {
+        marketplace::marketplace_operations::match_orders(ctx)
+    }
+    
+    /// Create the liquidity pool for an asset
+    pub fn create_pool(
+        ctx: Context<CreatePool>,
+    ) -> Result<()> {
+        liquidity_pool::liquidity_pool_operations::create_pool(ctx)
+    }
+    
+    /// Add liquidity to an asset pool
+    pub fn add_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        max_share_amount: u64,
+        max_quote_amount: u64,
+        min_lp_amount: u64,
+    ) -> Result<()> {
+        liquidity_pool::liquidity_pool_operations::add_liquidity(
+            ctx, max_share_amount, max_quote_amount, min_lp_amount
+        )
+    }
+    
+    /// Remove liquidity from an asset pool
+    pub fn remove_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        lp_amount: u64,
+        min_share_amount: u64,
+        min_quote_amount: u64,
+    ) -> Result<()> {
+        liquidity_pool::liquidity_pool_operations::remove_liquidity(
+            ctx, lp_amount, min_share_amount, min_quote_amount
+        )
+    }
+    
+    /// Swap against an asset pool
+    pub fn swap(
+        ctx: Context<Swap>,
+        direction: SwapDirection,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        liquidity_pool::liquidity_pool_operations::swap(ctx, direction, amount_in, min_amount_out)
+    }
+    
+    /// Create an M-of-N multisig
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+        action_lifetime: i64,
+    ) -> Result<()> {
+        multisig::multisig_operations::create_multisig(ctx, owners, threshold, action_lifetime)
+    }
+    
+    /// Propose an admin instruction for multisig approval
+    pub fn propose_admin_action(
+        ctx: Context<ProposeAdminAction>,
+        accounts: Vec<ActionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        multisig::multisig_operations::propose_admin_action(ctx, accounts, data)
+    }
+    
+    /// Approve a pending admin action
+    pub fn approve_admin_action(
+        ctx: Context<ApproveAdminAction>,
+    ) -> Result<()> {
+        multisig::multisig_operations::approve_admin_action(ctx)
+    }
+    
+    /// Execute an approved admin action
+    pub fn execute_admin_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteAdminAction<'info>>,
+    ) -> Result<()> {
+        multisig::multisig_operations::execute_admin_action(ctx)
+    }
+    
+    /// Create the governance treasury
+    pub fn initialize_treasury(
+        ctx: Context<InitializeTreasury>,
+        spending_period: i64,
+    ) -> Result<()> {
+        treasury::treasury_operations::initialize_treasury(ctx, spending_period)
+    }
+    
+    /// Add a token vault to the treasury
+    pub fn create_treasury_vault(
+        ctx: Context<CreateTreasuryVault>,
+        spending_cap: u64,
+    ) -> Result<()> {
+        treasury::treasury_operations::create_treasury_vault(ctx, spending_cap)
+    }
+    
+    /// Pay out an approved treasury transfer
+    pub fn execute_treasury_transfer(
+        ctx: Context<ExecuteTreasuryTransfer>,
+    ) -> Result<()> {
+        treasury::treasury_operations::execute_treasury_transfer(ctx)
+    }
+    
+    /// Start an approved treasury stream
+    pub fn execute_treasury_stream(
+        ctx: Context<ExecuteTreasuryStream>,
+    ) -> Result<()> {
+        treasury::treasury_operations::execute_treasury_stream(ctx)
+    }
+    
+    /// Cancel a treasury stream through an approved proposal
+    pub fn execute_stream_cancellation(
+        ctx: Context<ExecuteStreamCancellation>,
+    ) -> Result<()> {
+        treasury::treasury_operations::execute_stream_cancellation(ctx)
+    }
+    
+    /// Pay a stream's recipient what has accrued
+    pub fn claim_stream(
+        ctx: Context<ClaimStream>,
+    ) -> Result<()> {
+        treasury::treasury_operations::claim_stream(ctx)
+    }
+    
+    /// Upgrade the program through an approved proposal after its timelock
+    pub fn execute_program_upgrade(
+        ctx: Context<ExecuteProgramUpgrade>,
+    ) -> Result<()> {
+        upgrade::upgrade_operations::execute_program_upgrade(ctx)
+    }
+    
+    /// Record a wallet's KYC and accreditation status
+    pub fn set_investor_record(
+        ctx: Context<SetInvestorRecord>,
+        wallet: Pubkey,
+        kyc_status: KycStatus,
+        jurisdiction: [u8; 2],
+        accredited: bool,
+        expires_at: i64,
+    ) -> Result<()> {
+        compliance::compliance_operations::set_investor_record(
+            ctx, wallet, kyc_status, jurisdiction, accredited, expires_at
+        )
+    }
+    
+    /// Transfer shares to another holder, enforcing the asset's investor requirements
+    pub fn transfer_shares(
+        ctx: Context<TransferShares>,
+        amount: u64,
+    ) -> Result<()> {
+        asset_token::asset_token_operations::transfer_shares(ctx, amount)
+    }
+    
+    /// Stop counting a wallet that sold out among an asset's holders
+    pub fn close_holder_record(
+        ctx: Context<CloseHolderRecord>,
+    ) -> Result<()> {
+        compliance::compliance_operations::close_holder_record(ctx)
+    }
 }
 
 /// Context for initializing the REAL token
@@ -125,6 +706,16 @@ pub struct Initialize<'info> {
     #[account(init, payer = authority, space = RealToken::LEN)]
     pub real_token: Account<'info, RealToken>,
     
+    /// Role registry, with the authority as admin
+    #[account(
+        init,
+        payer = authority,
+        space = Roles::LEN,
+        seeds = [b"roles".as_ref(), real_token.key().as_ref()],
+        bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
     /// Token mint
     pub mint: Account<'info, Mint>,
     
@@ -141,152 +732,3 @@ pub struct Initialize<'info> {
     /// Rent sysvar
     pub rent: Sysvar<'info, Rent>,
 }
-
-#[derive(Accounts)]
-pub struct CreateAsset<'info> {
-    #[account(mut)]
-    pub real_token: Account<'info, RealToken>,
-    #[account(init, payer = authority, space = Asset::LEN)]
-    pub asset: Account<'info, Asset>,
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
-}
-
-#[derive(Accounts)]
-pub struct UpdateAssetValue<'info> {
-    #[account(mut)]
-    pub asset: Account<'info, Asset>,
-    pub authority: Signer<'info>,
-}
-
-#[derive(Accounts)]
-pub struct CreateGovernanceProposal<'info> {
-    #[account(init, payer = proposer, space = GovernanceProposal::LEN)]
-    pub proposal: Account<'info, GovernanceProposal>,
-    #[account(mut)]
-    pub proposer: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
-
-#[derive(Accounts)]
-pub struct VoteOnProposal<'info> {
-    #[account(mut)]
-    pub proposal: Account<'info, GovernanceProposal>,
-    pub voter: Signer<'info>,
-}
-
-#[derive(Accounts)]
-pub struct ExecuteProposal<'info> {
-    #[account(mut)]
-    pub proposal: Account<'info, GovernanceProposal>,
-    pub executor: Signer<'info>,
-}
-
-#[account]
-pub struct RealToken {
-    pub name: String,
-    pub symbol: String,
-    pub uri: String,
-    pub total_supply: u64,
-    pub authority: Pubkey,
-    pub is_initialized: bool,
-    pub last_update_timestamp: i64,
-    pub mint: Pubkey,
-}
-
-impl RealToken {
-    pub const LEN: usize = 8 + // discriminator
-        32 + // name string
-        8 + // symbol string
-        128 + // uri string
-        8 + // total_supply
-        32 + // authority
-        1 + // is_initialized
-        8 + // last_update_timestamp
-        32; // mint
-}
-
-#[account]
-pub struct Asset {
-    pub name: String,
-    pub symbol: String,
-    pub uri: String,
-    pub asset_value: u64,
-    pub total_shares: u64,
-    pub authority: Pubkey,
-    pub is_initialized: bool,
-    pub creation_timestamp: i64,
-    pub last_update_timestamp: i64,
-    pub mint: Pubkey,
-    pub real_token: Pubkey,
-}
-
-impl Asset {
-    pub const LEN: usize = 8 + // discriminator
-        64 + // name string
-        16 + // symbol string
-        128 + // uri string
-        8 + // asset_value
-        8 + // total_shares
-        32 + // authority
-        1 + // is_initialized
-        8 + // creation_timestamp
-        8 + // last_update_timestamp
-        32 + // mint
-        32; // real_token
-}
-
-#[account]
-pub struct GovernanceProposal {
-    pub title: String,
-    pub description: String,
-    pub proposer: Pubkey,
-    pub is_active: bool,
-    pub creation_timestamp: i64,
-    pub voting_ends_at: i64,
-    pub yes_votes: u64,
-    pub no_votes: u64,
-    pub executed: bool,
-}
-
-impl GovernanceProposal {
-    pub const LEN: usize = 8 + // discriminator
-        64 + // title string
-        256 + // description string
-        32 + // proposer
-        1 + // is_active
-        8 + // creation_timestamp
-        8 + // voting_ends_at
-        8 + // yes_votes
-        8 + // no_votes
-        1; // executed
-}
-
-#[error_code]
-pub enum RealStackError {
-    #[msg("Unauthorized access")]
-    Unauthorized,
-    
-    #[msg("Invalid voting period")]
-    InvalidVotingPeriod,
-    
-    #[msg("Proposal is inactive")]
-    ProposalInactive,
-    
-    #[msg("Voting period has ended")]
-    VotingPeriodEnded,
-    
-    #[msg("Voting period has not ended yet")]
-    VotingPeriodNotEnded,
-    
-    #[msg("Proposal has already been executed")]
-    ProposalAlreadyExecuted,
-    
-    #[msg("Math overflow")]
-    MathOverflow,
-} 
\ No newline at end of file
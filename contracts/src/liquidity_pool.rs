@@ -0,0 +1,850 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use crate::errors::*;
+use crate::access_control::Roles;
+use crate::asset_token::AssetToken;
+use crate::compliance::{HolderRecord, InvestorRecord, ShareFreeze};
+use crate::staking::accrue_staker_fees;
+use crate::tokenomics::{PauseFlag, RealToken};
+
+/// LP tokens locked in the pool on the first deposit so its reserves can never be drained to zero
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// Constant-product (x * y = k) pool between an asset's shares and a quote mint
+#[account]
+pub struct LiquidityPool {
+    /// The asset whose shares are pooled
+    pub asset_token: Pubkey,
+    
+    /// The REAL token whose fee configuration applies
+    pub real_token: Pubkey,
+    
+    /// Share mint of the asset
+    pub share_mint: Pubkey,
+    
+    /// Quote mint (e.g. USDC)
+    pub quote_mint: Pubkey,
+    
+    /// Vault holding the share reserve
+    pub share_vault: Pubkey,
+    
+    /// Vault holding the quote reserve
+    pub quote_vault: Pubkey,
+    
+    /// Mint of the LP tokens
+    pub lp_mint: Pubkey,
+    
+    /// Vault holding the locked minimum liquidity
+    pub locked_lp_vault: Pubkey,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl LiquidityPool {
+    /// Space required for a LiquidityPool account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // asset_token
+        32 + // real_token
+        32 + // share_mint
+        32 + // quote_mint
+        32 + // share_vault
+        32 + // quote_vault
+        32 + // lp_mint
+        32 + // locked_lp_vault
+        8 + // created_at
+        1; // bump
+}
+
+/// Swap direction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SwapDirection {
+    /// Sell shares for the quote token
+    SharesToQuote,
+    
+    /// Buy shares with the quote token
+    QuoteToShares,
+}
+
+/// Context for creating a liquidity pool
+#[derive(Accounts)]
+pub struct CreatePool<'info> {
+    /// The asset's managing authority
+    #[account(
+        mut,
+        constraint = roles.manages_asset(authority.key(), &asset_token) @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// The asset to pool
+    #[account(
+        mut,
+        constraint = asset_token.liquidity_pool.is_none() @ RealStackError::LiquidityPoolExists
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The REAL token whose fee configuration applies
+    #[account(
+        address = asset_token.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
+    /// The pool to create
+    #[account(
+        init,
+        payer = authority,
+        space = LiquidityPool::LEN,
+        seeds = [b"liquidity_pool".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// Quote mint
+    pub quote_mint: Account<'info, Mint>,
+    
+    /// Vault holding the share reserve
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_shares".as_ref(), liquidity_pool.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = liquidity_pool
+    )]
+    pub share_vault: Account<'info, TokenAccount>,
+    
+    /// Vault holding the quote reserve
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_quote".as_ref(), liquidity_pool.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = liquidity_pool
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// LP token mint
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"lp_mint".as_ref(), liquidity_pool.key().as_ref()],
+        bump,
+        mint::decimals = quote_mint.decimals,
+        mint::authority = liquidity_pool
+    )]
+    pub lp_mint: Account<'info, Mint>,
+    
+    /// Vault holding the locked minimum liquidity, never paid out
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"pool_locked_lp".as_ref(), liquidity_pool.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = liquidity_pool
+    )]
+    pub locked_lp_vault: Account<'info, TokenAccount>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Context for adding or removing liquidity
+#[derive(Accounts)]
+pub struct ModifyLiquidity<'info> {
+    /// The liquidity provider
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    
+    /// The pooled asset
+    #[account(
+        mut,
+        address = liquidity_pool.asset_token @ RealStackError::AssetNotFound
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The REAL token whose pause flags apply
+    #[account(
+        address = liquidity_pool.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The pool
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+    
+    /// Vault holding the share reserve
+    #[account(
+        mut,
+        address = liquidity_pool.share_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub share_vault: Account<'info, TokenAccount>,
+    
+    /// Vault holding the quote reserve
+    #[account(
+        mut,
+        address = liquidity_pool.quote_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = liquidity_pool.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), liquidity_pool.asset_token.as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// LP token mint
+    #[account(
+        mut,
+        address = liquidity_pool.lp_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub lp_mint: Account<'info, Mint>,
+    
+    /// Vault holding the locked minimum liquidity
+    #[account(
+        mut,
+        address = liquidity_pool.locked_lp_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub locked_lp_vault: Account<'info, TokenAccount>,
+    
+    /// Provider's share account
+    #[account(
+        mut,
+        constraint = provider_share_account.owner == provider.key() @ RealStackError::InvalidTokenAccount,
+        constraint = provider_share_account.mint == liquidity_pool.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub provider_share_account: Account<'info, TokenAccount>,
+    
+    /// Provider's quote account
+    #[account(
+        mut,
+        constraint = provider_quote_account.owner == provider.key() @ RealStackError::InvalidTokenAccount,
+        constraint = provider_quote_account.mint == liquidity_pool.quote_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub provider_quote_account: Account<'info, TokenAccount>,
+    
+    /// Provider's LP token account
+    #[account(
+        mut,
+        constraint = provider_lp_account.owner == provider.key() @ RealStackError::InvalidTokenAccount,
+        constraint = provider_lp_account.mint == liquidity_pool.lp_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub provider_lp_account: Account<'info, TokenAccount>,
+    
+    /// The provider's holder record, tracking the shares they move in and out of the pool
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = HolderRecord::LEN,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), provider.key().as_ref()],
+        bump
+    )]
+    pub holder_record: Account<'info, HolderRecord>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for swapping against a pool
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    /// The trader
+    #[account(mut)]
+    pub trader: Signer<'info>,
+    
+    /// The pooled asset
+    #[account(
+        mut,
+        constraint = asset_token.liquidity_pool == Some(liquidity_pool.key()) @ RealStackError::LiquidityPoolNotFound
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The REAL token account
+    #[account(
+        address = liquidity_pool.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The pool
+    pub liquidity_pool: Account<'info, LiquidityPool>,
+    
+    /// Vault holding the share reserve
+    #[account(
+        mut,
+        address = liquidity_pool.share_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub share_vault: Account<'info, TokenAccount>,
+    
+    /// Vault holding the quote reserve
+    #[account(
+        mut,
+        address = liquidity_pool.quote_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = liquidity_pool.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), liquidity_pool.asset_token.as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// Trader's share account
+    #[account(
+        mut,
+        constraint = trader_share_account.owner == trader.key() @ RealStackError::InvalidTokenAccount,
+        constraint = trader_share_account.mint == liquidity_pool.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub trader_share_account: Account<'info, TokenAccount>,
+    
+    /// Trader's quote account
+    #[account(
+        mut,
+        constraint = trader_quote_account.owner == trader.key() @ RealStackError::InvalidTokenAccount,
+        constraint = trader_quote_account.mint == liquidity_pool.quote_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub trader_quote_account: Account<'info, TokenAccount>,
+    
+    /// Fee recipient's account in the quote mint
+    #[account(
+        mut,
+        constraint = fee_account.owner == real_token.fee_config.fee_recipient @ RealStackError::InvalidTokenAccount,
+        constraint = fee_account.mint == liquidity_pool.quote_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub fee_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: The REAL stake pool, credited with the stakers' share of fees once it exists
+    #[account(
+        mut,
+        seeds = [b"stake_pool".as_ref(), real_token.key().as_ref()],
+        bump
+    )]
+    pub stake_pool: UncheckedAccount<'info>,
+    
+    /// CHECK: The stake pool's reward vault, receiving the stakers' share of fees
+    #[account(
+        mut,
+        seeds = [b"reward_vault".as_ref(), stake_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: UncheckedAccount<'info>,
+    
+    /// The trader's investor record, required to buy shares of restricted assets
+    pub investor_record: Option<Account<'info, InvestorRecord>>,
+    
+    /// The trader's holder record, created on first acquisition
+    #[account(
+        init_if_needed,
+        payer = trader,
+        space = HolderRecord::LEN,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), trader.key().as_ref()],
+        bump
+    )]
+    pub holder_record: Account<'info, HolderRecord>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Implementation of liquidity pool operations
+pub mod liquidity_pool_operations {
+    use super::*;
+    
+    /// Create the pool for an asset and record it on the asset
+    pub fn create_pool(
+        ctx: Context<CreatePool>,
+    ) -> Result<()> {
+        let asset_token = &mut ctx.accounts.asset_token;
+        let liquidity_pool = &mut ctx.accounts.liquidity_pool;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(!asset_token.is_burned, RealStackError::AssetBurned);
+        
+        // Initialize the pool
+        liquidity_pool.asset_token = asset_token.key();
+        liquidity_pool.real_token = ctx.accounts.real_token.key();
+        liquidity_pool.share_mint = ctx.accounts.share_mint.key();
+        liquidity_pool.quote_mint = ctx.accounts.quote_mint.key();
+        liquidity_pool.share_vault = ctx.accounts.share_vault.key();
+        liquidity_pool.quote_vault = ctx.accounts.quote_vault.key();
+        liquidity_pool.lp_mint = ctx.accounts.lp_mint.key();
+        liquidity_pool.locked_lp_vault = ctx.accounts.locked_lp_vault.key();
+        liquidity_pool.created_at = current_timestamp;
+        liquidity_pool.bump = *ctx.bumps.get("liquidity_pool").unwrap();
+        
+        // Link the pool to the asset
+        asset_token.liquidity_pool = Some(liquidity_pool.key());
+        asset_token.updated_at = current_timestamp;
+        
+        // Log the creation
+        msg!("Liquidity pool created for asset: {}", asset_token.name);
+        msg!("Quote mint: {}", liquidity_pool.quote_mint);
+        
+        Ok(())
+    }
+    
+    /// Deposit shares and quote tokens at the current ratio in exchange for LP tokens
+    pub fn add_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        max_share_amount: u64,
+        max_quote_amount: u64,
+        min_lp_amount: u64,
+    ) -> Result<()> {
+        let liquidity_pool = &ctx.accounts.liquidity_pool;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(!ctx.accounts.asset_token.is_burned, RealStackError::AssetBurned);
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Trading, current_timestamp),
+            RealStackError::TransfersPaused
+        );
+        require!(
+            max_share_amount > 0 && max_quote_amount > 0,
+            RealStackError::InvalidParameters
+        );
+        
+        let (lp_amount, share_amount, quote_amount) = deposit_amounts(
+            ctx.accounts.share_vault.amount,
+            ctx.accounts.quote_vault.amount,
+            ctx.accounts.lp_mint.supply,
+            max_share_amount,
+            max_quote_amount,
+        )
+        .ok_or(RealStackError::MathOverflow)?;
+        
+        // The first deposit leaves MINIMUM_LIQUIDITY locked in the pool
+        let locked_amount = if ctx.accounts.lp_mint.supply == 0 { MINIMUM_LIQUIDITY } else { 0 };
+        let provider_lp_amount = lp_amount
+            .checked_sub(locked_amount)
+            .ok_or(RealStackError::InsufficientLiquidity)?;
+        require!(provider_lp_amount > 0, RealStackError::InsufficientLiquidity);
+        require!(provider_lp_amount >= min_lp_amount, RealStackError::SlippageExceeded);
+        
+        // Pull both sides of the deposit, thawing the share accounts around the transfer
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: liquidity_pool.asset_token,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        freeze.thaw(&ctx.accounts.share_vault)?;
+        freeze.thaw(&ctx.accounts.provider_share_account)?;
+        for (from, to, amount) in [
+            (&ctx.accounts.provider_share_account, &ctx.accounts.share_vault, share_amount),
+            (&ctx.accounts.provider_quote_account, &ctx.accounts.quote_vault, quote_amount),
+        ] {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.provider.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+        freeze.freeze(&ctx.accounts.provider_share_account)?;
+        
+        // Mint LP tokens to the locked vault and the provider
+        let asset_key = liquidity_pool.asset_token;
+        let seeds = &[b"liquidity_pool".as_ref(), asset_key.as_ref(), &[liquidity_pool.bump]];
+        for (to, amount) in [
+            (&ctx.accounts.locked_lp_vault, locked_amount),
+            (&ctx.accounts.provider_lp_account, provider_lp_amount),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.lp_mint.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: liquidity_pool.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                amount,
+            )?;
+        }
+        
+        // Shares in the pool no longer count toward the provider's holding
+        ctx.accounts.holder_record.debit(share_amount);
+        
+        // Log the deposit
+        msg!("Liquidity added: {} shares, {} quote", share_amount, quote_amount);
+        msg!("LP tokens minted: {} ({} locked)", lp_amount, locked_amount);
+        
+        Ok(())
+    }
+    
+    /// Burn LP tokens and withdraw the proportional share of both reserves
+    pub fn remove_liquidity(
+        ctx: Context<ModifyLiquidity>,
+        lp_amount: u64,
+        min_share_amount: u64,
+        min_quote_amount: u64,
+    ) -> Result<()> {
+        let liquidity_pool = &ctx.accounts.liquidity_pool;
+        let lp_supply = ctx.accounts.lp_mint.supply;
+        
+        require!(lp_amount > 0, RealStackError::InvalidParameters);
+        require!(lp_amount <= lp_supply, RealStackError::InsufficientLiquidity);
+        
+        // Once the asset is wound down, the last provider out also takes the locked minimum,
+        // so no shares are left in the pool unredeemed
+        let locked_amount = ctx.accounts.locked_lp_vault.amount;
+        let releases_locked = ctx.accounts.asset_token.is_burned
+            && locked_amount > 0
+            && lp_supply.checked_sub(locked_amount) == Some(lp_amount);
+        let redeemed_lp = if releases_locked { lp_supply } else { lp_amount };
+        
+        let share_amount = proportional(ctx.accounts.share_vault.amount, redeemed_lp, lp_supply)
+            .ok_or(RealStackError::MathOverflow)?;
+        let quote_amount = proportional(ctx.accounts.quote_vault.amount, redeemed_lp, lp_supply)
+            .ok_or(RealStackError::MathOverflow)?;
+        require!(share_amount >= min_share_amount, RealStackError::SlippageExceeded);
+        require!(quote_amount >= min_quote_amount, RealStackError::SlippageExceeded);
+        
+        // Burn the provider's LP tokens
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.provider_lp_account.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            lp_amount,
+        )?;
+        
+        let asset_key = liquidity_pool.asset_token;
+        let seeds = &[b"liquidity_pool".as_ref(), asset_key.as_ref(), &[liquidity_pool.bump]];
+        if releases_locked {
+            token::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.lp_mint.to_account_info(),
+                        from: ctx.accounts.locked_lp_vault.to_account_info(),
+                        authority: liquidity_pool.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                locked_amount,
+            )?;
+        }
+        
+        // Pay out both reserves, thawing the share accounts around the transfer
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: liquidity_pool.asset_token,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        freeze.thaw(&ctx.accounts.share_vault)?;
+        freeze.thaw(&ctx.accounts.provider_share_account)?;
+        for (from, to, amount) in [
+            (&ctx.accounts.share_vault, &ctx.accounts.provider_share_account, share_amount),
+            (&ctx.accounts.quote_vault, &ctx.accounts.provider_quote_account, quote_amount),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: liquidity_pool.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                amount,
+            )?;
+        }
+        freeze.freeze(&ctx.accounts.provider_share_account)?;
+        
+        // Withdrawn shares count toward the provider's holding again, unless the asset is being wound down.
+        // Exiting is never refused: eligibility and the holding cap apply where shares are bought
+        if !ctx.accounts.asset_token.is_burned {
+            let current_timestamp = Clock::get()?.unix_timestamp;
+            let bump = *ctx.bumps.get("holder_record").unwrap();
+            ctx.accounts.holder_record.restore(
+                &mut ctx.accounts.asset_token,
+                ctx.accounts.provider.key(),
+                bump,
+                share_amount,
+                current_timestamp,
+            )?;
+        }
+        
+        // Log the withdrawal
+        msg!("Liquidity removed: {} shares, {} quote", share_amount, quote_amount);
+        msg!("LP tokens burned: {}", redeemed_lp);
+        
+        Ok(())
+    }
+    
+    /// Swap against the pool, charging the FeeConfig fee in the quote mint
+    pub fn swap(
+        ctx: Context<Swap>,
+        direction: SwapDirection,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        let liquidity_pool = &ctx.accounts.liquidity_pool;
+        let real_token = &ctx.accounts.real_token;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate trading status
+        require!(!ctx.accounts.asset_token.is_burned, RealStackError::AssetBurned);
+        require!(ctx.accounts.asset_token.is_tradable, RealStackError::AssetNotTradable);
+        require!(
+            !real_token.is_paused(PauseFlag::Trading, current_timestamp),
+            RealStackError::TransfersPaused
+        );
+        require!(amount_in > 0, RealStackError::InvalidParameters);
+        
+        // Buyers must meet the asset's investor requirements
+        if direction == SwapDirection::QuoteToShares {
+            let asset_token = &ctx.accounts.asset_token;
+            asset_token.compliance.check_investor(
+                asset_token.real_token,
+                ctx.accounts.trader.key(),
+                ctx.accounts.investor_record.as_deref(),
+                current_timestamp,
+            )?;
+        }
+        
+        let (trader_in, trader_out, vault_in, vault_out) = match direction {
+            SwapDirection::SharesToQuote => (
+                &ctx.accounts.trader_share_account,
+                &ctx.accounts.trader_quote_account,
+                &ctx.accounts.share_vault,
+                &ctx.accounts.quote_vault,
+            ),
+            SwapDirection::QuoteToShares => (
+                &ctx.accounts.trader_quote_account,
+                &ctx.accounts.trader_share_account,
+                &ctx.accounts.quote_vault,
+                &ctx.accounts.share_vault,
+            ),
+        };
+        
+        // Buyers pay the fee off the quote input, sellers off the quote output
+        let fee_of = |amount: u64| if real_token.fee_config.fees_enabled {
+            (amount as u128 * real_token.fee_config.transaction_fee_bps as u128 / 10_000) as u64
+        } else {
+            0
+        };
+        let (net_in, fee, amount_out) = match direction {
+            SwapDirection::QuoteToShares => {
+                let fee = fee_of(amount_in);
+                let net_in = amount_in
+                    .checked_sub(fee)
+                    .ok_or(RealStackError::MathUnderflow)?;
+                let amount_out = swap_output(vault_in.amount, vault_out.amount, net_in)
+                    .ok_or(RealStackError::MathOverflow)?;
+                (net_in, fee, amount_out)
+            }
+            SwapDirection::SharesToQuote => {
+                let gross_out = swap_output(vault_in.amount, vault_out.amount, amount_in)
+                    .ok_or(RealStackError::MathOverflow)?;
+                let fee = fee_of(gross_out);
+                let amount_out = gross_out
+                    .checked_sub(fee)
+                    .ok_or(RealStackError::MathUnderflow)?;
+                (amount_in, fee, amount_out)
+            }
+        };
+        require!(amount_out > 0, RealStackError::InsufficientLiquidity);
+        require!(amount_out >= min_amount_out, RealStackError::SlippageExceeded);
+        
+        // Track the trader's holding, counting a first-time buyer and keeping them under the cap
+        if direction == SwapDirection::QuoteToShares {
+            let asset_token = &mut ctx.accounts.asset_token;
+            let bump = *ctx.bumps.get("holder_record").unwrap();
+            ctx.accounts.holder_record.acquire(asset_token, ctx.accounts.trader.key(), bump, amount_out, current_timestamp)?;
+        } else {
+            ctx.accounts.holder_record.debit(amount_in);
+        }
+        
+        // The stakers' share of the fee goes to the reward vault
+        let staker_fee = accrue_staker_fees(
+            &ctx.accounts.stake_pool,
+            fee,
+            liquidity_pool.quote_mint,
+            current_timestamp,
+        )?;
+        let recipient_fee = fee
+            .checked_sub(staker_fee)
+            .ok_or(RealStackError::MathUnderflow)?;
+        
+        // Trader pays the input, the pool pays the output, and whoever holds the quote side pays the fee;
+        // the share accounts are thawed around the transfers
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: liquidity_pool.asset_token,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        freeze.thaw(&ctx.accounts.share_vault)?;
+        freeze.thaw(&ctx.accounts.trader_share_account)?;
+        let asset_key = liquidity_pool.asset_token;
+        let seeds = &[b"liquidity_pool".as_ref(), asset_key.as_ref(), &[liquidity_pool.bump]];
+        let pool_signer = [&seeds[..]];
+        let pool_pays_fee = direction == SwapDirection::SharesToQuote;
+        let fee_payer = if pool_pays_fee { vault_out } else { trader_in };
+        for (from_pool, from, to, amount) in [
+            (false, trader_in, vault_in.to_account_info(), net_in),
+            (true, vault_out, trader_out.to_account_info(), amount_out),
+            (pool_pays_fee, fee_payer, ctx.accounts.fee_account.to_account_info(), recipient_fee),
+            (pool_pays_fee, fee_payer, ctx.accounts.reward_vault.to_account_info(), staker_fee),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            let (authority, signer) = if from_pool {
+                (liquidity_pool.to_account_info(), &pool_signer[..])
+            } else {
+                (ctx.accounts.trader.to_account_info(), &[][..])
+            };
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to,
+                        authority,
+                    },
+                    signer,
+                ),
+                amount,
+            )?;
+        }
+        freeze.freeze(&ctx.accounts.trader_share_account)?;
+        
+        // Log the swap
+        msg!("Swap: {} in ({} fee), {} out", amount_in, fee, amount_out);
+        
+        Ok(())
+    }
+    
+    /// Output of a constant-product swap: reserve_out * amount_in / (reserve_in + amount_in)
+    pub fn swap_output(reserve_in: u64, reserve_out: u64, amount_in: u64) -> Option<u64> {
+        let numerator = (reserve_out as u128).checked_mul(amount_in as u128)?;
+        let denominator = (reserve_in as u128).checked_add(amount_in as u128)?;
+        if denominator == 0 {
+            return Some(0);
+        }
+        u64::try_from(numerator / denominator).ok()
+    }
+    
+    /// LP tokens minted and amounts pulled for a deposit capped at the given maxima
+    pub fn deposit_amounts(
+        share_reserve: u64,
+        quote_reserve: u64,
+        lp_supply: u64,
+        max_share_amount: u64,
+        max_quote_amount: u64,
+    ) -> Option<(u64, u64, u64)> {
+        // First deposit sets the price; LP supply starts at the geometric mean
+        if lp_supply == 0 || share_reserve == 0 || quote_reserve == 0 {
+            let lp_amount = integer_sqrt((max_share_amount as u128).checked_mul(max_quote_amount as u128)?);
+            return Some((u64::try_from(lp_amount).ok()?, max_share_amount, max_quote_amount));
+        }
+        
+        // Later deposits mint against the scarcer side and round the pull up
+        let lp_amount = proportional(lp_supply, max_share_amount, share_reserve)?
+            .min(proportional(lp_supply, max_quote_amount, quote_reserve)?);
+        let share_amount = proportional_ceil(share_reserve, lp_amount, lp_supply)?;
+        let quote_amount = proportional_ceil(quote_reserve, lp_amount, lp_supply)?;
+        
+        Some((lp_amount, share_amount, quote_amount))
+    }
+    
+    /// value * numerator / denominator, rounded down
+    pub fn proportional(value: u64, numerator: u64, denominator: u64) -> Option<u64> {
+        let result = (value as u128)
+            .checked_mul(numerator as u128)?
+            .checked_div(denominator as u128)?;
+        u64::try_from(result).ok()
+    }
+    
+    /// value * numerator / denominator, rounded up
+    pub fn proportional_ceil(value: u64, numerator: u64, denominator: u64) -> Option<u64> {
+        let product = (value as u128).checked_mul(numerator as u128)?;
+        let denominator = denominator as u128;
+        if denominator == 0 {
+            return None;
+        }
+        let rounded = product / denominator + u128::from(product % denominator != 0);
+        u64::try_from(rounded).ok()
+    }
+    
+    /// Integer square root (floor)
+    pub fn integer_sqrt(value: u128) -> u128 {
+        if value < 2 {
+            return value;
+        }
+        
+        // Newton's method from an overestimate
+        let mut x = value;
+        let mut y = x / 2 + (x & 1);
+        while y < x {
+            x = y;
+            y = (x + value / x) / 2;
+        }
+        x
+    }
+}
@@ -0,0 +1,1051 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::errors::*;
+use crate::access_control::Roles;
+use crate::asset_token::AssetToken;
+use crate::compliance::{debit_holder, HolderRecord, InvestorRecord, ShareFreeze};
+use crate::staking::accrue_staker_fees;
+use crate::tokenomics::{PauseFlag, RealToken};
+
+/// Maximum resting orders per side of a book
+pub const MAX_ORDERS_PER_SIDE: usize = 32;
+
+/// Maximum resting orders one owner may have per side of a book
+pub const MAX_ORDERS_PER_OWNER: usize = 4;
+
+/// Limit order book for secondary trading of an asset's shares
+#[account]
+pub struct OrderBook {
+    /// The asset traded on this book
+    pub asset_token: Pubkey,
+    
+    /// The REAL token whose fee configuration applies
+    pub real_token: Pubkey,
+    
+    /// Share mint of the asset
+    pub share_mint: Pubkey,
+    
+    /// Mint orders are priced in
+    pub payment_mint: Pubkey,
+    
+    /// Vault escrowing shares for asks
+    pub base_vault: Pubkey,
+    
+    /// Vault escrowing payment for bids
+    pub quote_vault: Pubkey,
+    
+    /// Id assigned to the next order
+    pub next_order_id: u64,
+    
+    /// Smallest price times quantity an order may be placed with, in payment mint units
+    pub min_order_notional: u64,
+    
+    /// Resting bids, best (highest) price first
+    pub bids: Vec<Order>,
+    
+    /// Resting asks, best (lowest) price first
+    pub asks: Vec<Order>,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// A resting limit order
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Order {
+    /// Unique id within the book (also gives time priority)
+    pub order_id: u64,
+    
+    /// Owner of the order
+    pub owner: Pubkey,
+    
+    /// Limit price per share in payment mint units
+    pub price: u64,
+    
+    /// Remaining unfilled shares
+    pub quantity: u64,
+    
+    /// When the order was placed
+    pub created_at: i64,
+}
+
+/// Order side
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    /// Buy shares
+    Bid,
+    
+    /// Sell shares
+    Ask,
+}
+
+impl Order {
+    /// Serialized size of an Order
+    pub const LEN: usize = 8 + // order_id
+        32 + // owner
+        8 + // price
+        8 + // quantity
+        8; // created_at
+}
+
+impl OrderBook {
+    /// Space required for an OrderBook account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // asset_token
+        32 + // real_token
+        32 + // share_mint
+        32 + // payment_mint
+        32 + // base_vault
+        32 + // quote_vault
+        8 + // next_order_id
+        8 + // min_order_notional
+        4 + MAX_ORDERS_PER_SIDE * Order::LEN + // bids
+        4 + MAX_ORDERS_PER_SIDE * Order::LEN + // asks
+        1; // bump
+    
+    /// Insert an order keeping price-time priority, returning the worst resting order if a full side evicted it
+    pub fn insert_order(&mut self, side: OrderSide, order: Order) -> Result<Option<Order>> {
+        let orders = match side {
+            OrderSide::Bid => &mut self.bids,
+            OrderSide::Ask => &mut self.asks,
+        };
+        require!(
+            orders.iter().filter(|resting| resting.owner == order.owner).count() < MAX_ORDERS_PER_OWNER,
+            RealStackError::OwnerOrderLimitReached
+        );
+        
+        // A full side only takes an order priced better than its worst, so stale orders cannot hold it
+        let evicted = match orders.last() {
+            Some(worst) if orders.len() >= MAX_ORDERS_PER_SIDE => {
+                let beats_worst = match side {
+                    OrderSide::Bid => order.price > worst.price,
+                    OrderSide::Ask => order.price < worst.price,
+                };
+                require!(beats_worst, RealStackError::OrderBookFull);
+                orders.pop()
+            }
+            _ => None,
+        };
+        
+        // Orders at the same price keep arrival order
+        let position = orders
+            .iter()
+            .position(|resting| match side {
+                OrderSide::Bid => order.price > resting.price,
+                OrderSide::Ask => order.price < resting.price,
+            })
+            .unwrap_or(orders.len());
+        orders.insert(position, order);
+        
+        Ok(evicted)
+    }
+    
+    /// Remove an order by id
+    pub fn remove_order(&mut self, side: OrderSide, order_id: u64) -> Result<Order> {
+        let orders = match side {
+            OrderSide::Bid => &mut self.bids,
+            OrderSide::Ask => &mut self.asks,
+        };
+        let position = orders
+            .iter()
+            .position(|order| order.order_id == order_id)
+            .ok_or(RealStackError::OrderNotFound)?;
+        
+        Ok(orders.remove(position))
+    }
+    
+    /// Compute the next fill between the best bid and best ask, if they cross
+    pub fn next_fill(&self) -> Option<Fill> {
+        let bid = self.bids.first()?;
+        let ask = self.asks.first()?;
+        if bid.price < ask.price {
+            return None;
+        }
+        
+        // The earlier (resting) order sets the execution price
+        let price = if bid.order_id < ask.order_id { bid.price } else { ask.price };
+        
+        Some(Fill {
+            bid_order_id: bid.order_id,
+            ask_order_id: ask.order_id,
+            buyer: bid.owner,
+            seller: ask.owner,
+            bid_price: bid.price,
+            price,
+            quantity: bid.quantity.min(ask.quantity),
+        })
+    }
+    
+    /// Reduce the best orders on both sides by a fill, removing filled orders
+    pub fn apply_fill(&mut self, fill: &Fill) -> Result<()> {
+        for side in [OrderSide::Bid, OrderSide::Ask] {
+            let orders = match side {
+                OrderSide::Bid => &mut self.bids,
+                OrderSide::Ask => &mut self.asks,
+            };
+            let best = orders.first_mut().ok_or(RealStackError::OrderNotFound)?;
+            best.quantity = best.quantity
+                .checked_sub(fill.quantity)
+                .ok_or(RealStackError::MathUnderflow)?;
+            if best.quantity == 0 {
+                orders.remove(0);
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// A match between the best bid and best ask
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Fill {
+    /// The bid being filled
+    pub bid_order_id: u64,
+    
+    /// The ask being filled
+    pub ask_order_id: u64,
+    
+    /// Owner of the bid
+    pub buyer: Pubkey,
+    
+    /// Owner of the ask
+    pub seller: Pubkey,
+    
+    /// Price the bid escrowed at
+    pub bid_price: u64,
+    
+    /// Execution price
+    pub price: u64,
+    
+    /// Shares exchanged
+    pub quantity: u64,
+}
+
+/// Context for creating an order book for an asset
+#[derive(Accounts)]
+pub struct CreateOrderBook<'info> {
+    /// The asset's managing authority
+    #[account(
+        mut,
+        constraint = roles.manages_asset(authority.key(), &asset_token) @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// The asset to trade
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The REAL token whose fee configuration applies
+    #[account(
+        address = asset_token.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
+    /// The order book to create
+    #[account(
+        init,
+        payer = authority,
+        space = OrderBook::LEN,
+        seeds = [b"order_book".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// Payment mint
+    pub payment_mint: Account<'info, Mint>,
+    
+    /// Vault escrowing shares for asks
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"order_book_base".as_ref(), order_book.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = order_book
+    )]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    /// Vault escrowing payment for bids
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"order_book_quote".as_ref(), order_book.key().as_ref()],
+        bump,
+        token::mint = payment_mint,
+        token::authority = order_book
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Context for placing a limit order
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    /// The trader
+    pub owner: Signer<'info>,
+    
+    /// The asset traded on the book
+    #[account(
+        address = order_book.asset_token @ RealStackError::AssetNotFound
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The REAL token account
+    #[account(
+        address = order_book.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The order book
+    #[account(mut)]
+    pub order_book: Account<'info, OrderBook>,
+    
+    /// The trader's share account
+    #[account(
+        mut,
+        constraint = owner_share_account.owner == owner.key() @ RealStackError::InvalidTokenAccount,
+        constraint = owner_share_account.mint == order_book.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub owner_share_account: Account<'info, TokenAccount>,
+    
+    /// The trader's payment account
+    #[account(
+        mut,
+        constraint = owner_payment_account.owner == owner.key() @ RealStackError::InvalidTokenAccount,
+        constraint = owner_payment_account.mint == order_book.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub owner_payment_account: Account<'info, TokenAccount>,
+    
+    /// Vault escrowing shares for asks
+    #[account(
+        mut,
+        address = order_book.base_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    /// Vault escrowing payment for bids
+    #[account(
+        mut,
+        address = order_book.quote_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = order_book.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), order_book.asset_token.as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// The trader's investor record, required to bid on restricted assets
+    pub investor_record: Option<Account<'info, InvestorRecord>>,
+    
+    /// Account refunding the owner of the worst order on a full side, which a better-priced order evicts
+    #[account(mut)]
+    pub evicted_owner_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for cancelling a limit order
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    /// The trader
+    pub owner: Signer<'info>,
+    
+    /// The order book
+    #[account(mut)]
+    pub order_book: Account<'info, OrderBook>,
+    
+    /// The trader's share account
+    #[account(
+        mut,
+        constraint = owner_share_account.owner == owner.key() @ RealStackError::InvalidTokenAccount,
+        constraint = owner_share_account.mint == order_book.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub owner_share_account: Account<'info, TokenAccount>,
+    
+    /// The trader's payment account
+    #[account(
+        mut,
+        constraint = owner_payment_account.owner == owner.key() @ RealStackError::InvalidTokenAccount,
+        constraint = owner_payment_account.mint == order_book.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub owner_payment_account: Account<'info, TokenAccount>,
+    
+    /// Vault escrowing shares for asks
+    #[account(
+        mut,
+        address = order_book.base_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    /// Vault escrowing payment for bids
+    #[account(
+        mut,
+        address = order_book.quote_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = order_book.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), order_book.asset_token.as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for refunding a resting order on the book of a wound-down asset
+#[derive(Accounts)]
+pub struct UnwindOrder<'info> {
+    /// Anyone may unwind orders once the asset is burned
+    pub cranker: Signer<'info>,
+    
+    /// The asset traded on the book
+    #[account(
+        address = order_book.asset_token @ RealStackError::AssetNotFound
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The order book
+    #[account(mut)]
+    pub order_book: Account<'info, OrderBook>,
+    
+    /// The order owner's share account
+    #[account(
+        mut,
+        constraint = owner_share_account.mint == order_book.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub owner_share_account: Account<'info, TokenAccount>,
+    
+    /// The order owner's payment account
+    #[account(
+        mut,
+        constraint = owner_payment_account.mint == order_book.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub owner_payment_account: Account<'info, TokenAccount>,
+    
+    /// Vault escrowing shares for asks
+    #[account(
+        mut,
+        address = order_book.base_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    /// Vault escrowing payment for bids
+    #[account(
+        mut,
+        address = order_book.quote_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = order_book.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), order_book.asset_token.as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for matching the best bid against the best ask
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    /// Anyone may crank the book, paying for a first-time buyer's holder record
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    
+    /// The asset traded on the book
+    #[account(
+        mut,
+        address = order_book.asset_token @ RealStackError::AssetNotFound
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The REAL token account
+    #[account(
+        address = order_book.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The order book
+    #[account(mut)]
+    pub order_book: Account<'info, OrderBook>,
+    
+    /// Vault escrowing shares for asks
+    #[account(
+        mut,
+        address = order_book.base_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    /// Vault escrowing payment for bids
+    #[account(
+        mut,
+        address = order_book.quote_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = order_book.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), order_book.asset_token.as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// Buyer's share account receiving the shares
+    #[account(
+        mut,
+        constraint = buyer_share_account.mint == order_book.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub buyer_share_account: Account<'info, TokenAccount>,
+    
+    /// Buyer's payment account receiving any price improvement
+    #[account(
+        mut,
+        constraint = buyer_payment_account.mint == order_book.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    
+    /// Seller's payment account receiving the proceeds
+    #[account(
+        mut,
+        constraint = seller_payment_account.mint == order_book.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub seller_payment_account: Account<'info, TokenAccount>,
+    
+    /// Fee recipient's payment account
+    #[account(
+        mut,
+        constraint = fee_account.owner == real_token.fee_config.fee_recipient @ RealStackError::InvalidTokenAccount,
+        constraint = fee_account.mint == order_book.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub fee_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: The REAL stake pool, credited with the stakers' share of fees once it exists
+    #[account(
+        mut,
+        seeds = [b"stake_pool".as_ref(), real_token.key().as_ref()],
+        bump
+    )]
+    pub stake_pool: UncheckedAccount<'info>,
+    
+    /// CHECK: The stake pool's reward vault, receiving the stakers' share of fees
+    #[account(
+        mut,
+        seeds = [b"reward_vault".as_ref(), stake_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: UncheckedAccount<'info>,
+    
+    /// CHECK: Buyer's investor record, which may not exist; restricted assets cancel the bid of a buyer without a valid one
+    #[account(
+        seeds = [b"investor".as_ref(), real_token.key().as_ref(), buyer_share_account.owner.as_ref()],
+        bump
+    )]
+    pub buyer_investor_record: UncheckedAccount<'info>,
+    
+    /// Buyer's holder record, created on first acquisition
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = HolderRecord::LEN,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), buyer_share_account.owner.as_ref()],
+        bump
+    )]
+    pub buyer_holder_record: Account<'info, HolderRecord>,
+    
+    /// CHECK: Seller's holder record, debited with the shares sold if it exists
+    #[account(
+        mut,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), seller_payment_account.owner.as_ref()],
+        bump
+    )]
+    pub seller_holder_record: UncheckedAccount<'info>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Implementation of marketplace operations
+pub mod marketplace_operations {
+    use super::*;
+    
+    /// Create the order book for an asset
+    pub fn create_order_book(
+        ctx: Context<CreateOrderBook>,
+        min_order_notional: u64,
+    ) -> Result<()> {
+        let order_book = &mut ctx.accounts.order_book;
+        
+        require!(!ctx.accounts.asset_token.is_burned, RealStackError::AssetBurned);
+        require!(min_order_notional > 0, RealStackError::InvalidParameters);
+        
+        order_book.asset_token = ctx.accounts.asset_token.key();
+        order_book.real_token = ctx.accounts.real_token.key();
+        order_book.share_mint = ctx.accounts.share_mint.key();
+        order_book.payment_mint = ctx.accounts.payment_mint.key();
+        order_book.base_vault = ctx.accounts.base_vault.key();
+        order_book.quote_vault = ctx.accounts.quote_vault.key();
+        order_book.next_order_id = 0;
+        order_book.min_order_notional = min_order_notional;
+        order_book.bids = vec![];
+        order_book.asks = vec![];
+        order_book.bump = *ctx.bumps.get("order_book").unwrap();
+        
+        // Log the creation
+        msg!("Order book created for asset: {}", ctx.accounts.asset_token.name);
+        msg!("Payment mint: {}, Minimum order notional: {}", order_book.payment_mint, min_order_notional);
+        
+        Ok(())
+    }
+    
+    /// Place a resting limit order, escrowing shares (ask) or payment (bid)
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        side: OrderSide,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        let order_book = &mut ctx.accounts.order_book;
+        let owner = &ctx.accounts.owner;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate trading status
+        require!(!ctx.accounts.asset_token.is_burned, RealStackError::AssetBurned);
+        require!(ctx.accounts.asset_token.is_tradable, RealStackError::AssetNotTradable);
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Trading, current_timestamp),
+            RealStackError::TransfersPaused
+        );
+        
+        // Validate the order
+        require!(price > 0, RealStackError::InvalidParameters);
+        require!(quantity > 0, RealStackError::InvalidParameters);
+        let notional = price.checked_mul(quantity).ok_or(RealStackError::MathOverflow)?;
+        require!(
+            notional >= order_book.min_order_notional,
+            RealStackError::OrderBelowMinimum
+        );
+        
+        // Bidders must be able to hold the shares they bid for
+        if side == OrderSide::Bid {
+            let asset_token = &ctx.accounts.asset_token;
+            asset_token.compliance.check_investor(
+                asset_token.real_token,
+                owner.key(),
+                ctx.accounts.investor_record.as_deref(),
+                current_timestamp,
+            )?;
+        }
+        
+        // Escrow what the order may need to deliver, thawing the share accounts around an ask
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: order_book.asset_token,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        if side == OrderSide::Ask {
+            freeze.thaw(&ctx.accounts.base_vault)?;
+            freeze.thaw(&ctx.accounts.owner_share_account)?;
+        }
+        let (from, to, amount) = match side {
+            OrderSide::Bid => (
+                ctx.accounts.owner_payment_account.to_account_info(),
+                ctx.accounts.quote_vault.to_account_info(),
+                notional,
+            ),
+            OrderSide::Ask => (
+                ctx.accounts.owner_share_account.to_account_info(),
+                ctx.accounts.base_vault.to_account_info(),
+                quantity,
+            ),
+        };
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from,
+                    to,
+                    authority: owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        if side == OrderSide::Ask {
+            freeze.freeze(&ctx.accounts.owner_share_account)?;
+        }
+        
+        // Rest the order on the book
+        let order_id = order_book.next_order_id;
+        order_book.next_order_id = order_id
+            .checked_add(1)
+            .ok_or(RealStackError::MathOverflow)?;
+        let evicted = order_book.insert_order(side, Order {
+            order_id,
+            owner: owner.key(),
+            price,
+            quantity,
+            created_at: current_timestamp,
+        })?;
+        
+        // Refund an evicted order to its owner
+        if let Some(evicted) = evicted {
+            let account = ctx.accounts.evicted_owner_account
+                .as_ref()
+                .ok_or(RealStackError::InvalidTokenAccount)?;
+            require!(account.owner == evicted.owner, RealStackError::InvalidTokenAccount);
+            let expected_mint = match side {
+                OrderSide::Bid => order_book.payment_mint,
+                OrderSide::Ask => order_book.share_mint,
+            };
+            require!(account.mint == expected_mint, RealStackError::InvalidTokenMint);
+            let amount = refund_order(
+                order_book,
+                side,
+                &evicted,
+                &ctx.accounts.base_vault,
+                &ctx.accounts.quote_vault,
+                account,
+                account,
+                &ctx.accounts.token_program,
+                &freeze,
+            )?;
+            msg!("Order {} of {} evicted, refunded: {}", evicted.order_id, evicted.owner, amount);
+        }
+        
+        // Log the order
+        msg!("Order {} placed by {}", order_id, owner.key());
+        msg!("Side: {}, Price: {}, Quantity: {}",
+            if side == OrderSide::Bid { "Bid" } else { "Ask" }, price, quantity);
+        
+        Ok(())
+    }
+    
+    /// Cancel a resting order and refund its remaining escrow
+    pub fn cancel_order(
+        ctx: Context<CancelOrder>,
+        side: OrderSide,
+        order_id: u64,
+    ) -> Result<()> {
+        let order_book = &mut ctx.accounts.order_book;
+        let owner = &ctx.accounts.owner;
+        
+        // Only the owner may cancel
+        let order = order_book.remove_order(side, order_id)?;
+        require!(order.owner == owner.key(), RealStackError::Unauthorized);
+        
+        // Refund the unfilled remainder
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: order_book.asset_token,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        let amount = refund_order(
+            order_book,
+            side,
+            &order,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.owner_share_account,
+            &ctx.accounts.owner_payment_account,
+            &ctx.accounts.token_program,
+            &freeze,
+        )?;
+        
+        // Log the cancellation
+        msg!("Order {} cancelled by {}", order_id, owner.key());
+        msg!("Refunded: {}", amount);
+        
+        Ok(())
+    }
+    
+    /// Refund a resting order of a wound-down asset to its owner, so its shares can be redeemed
+    pub fn unwind_order(
+        ctx: Context<UnwindOrder>,
+        side: OrderSide,
+        order_id: u64,
+    ) -> Result<()> {
+        let order_book = &mut ctx.accounts.order_book;
+        
+        // Orders only unwind once the asset is burned
+        require!(ctx.accounts.asset_token.is_burned, RealStackError::AssetNotRedeeming);
+        
+        // The escrow goes back to the order's owner
+        let order = order_book.remove_order(side, order_id)?;
+        require!(
+            ctx.accounts.owner_share_account.owner == order.owner
+                && ctx.accounts.owner_payment_account.owner == order.owner,
+            RealStackError::InvalidTokenAccount
+        );
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: order_book.asset_token,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        let amount = refund_order(
+            order_book,
+            side,
+            &order,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.owner_share_account,
+            &ctx.accounts.owner_payment_account,
+            &ctx.accounts.token_program,
+            &freeze,
+        )?;
+        
+        // Log the unwind
+        msg!("Order {} of {} unwound by {}", order_id, order.owner, ctx.accounts.cranker.key());
+        msg!("Refunded: {}", amount);
+        
+        Ok(())
+    }
+    
+    /// Return an order's unfilled escrow to the owner's accounts, thawing the share accounts around a refunded ask
+    #[allow(clippy::too_many_arguments)]
+    fn refund_order<'info>(
+        order_book: &Account<'info, OrderBook>,
+        side: OrderSide,
+        order: &Order,
+        base_vault: &Account<'info, TokenAccount>,
+        quote_vault: &Account<'info, TokenAccount>,
+        owner_share_account: &Account<'info, TokenAccount>,
+        owner_payment_account: &Account<'info, TokenAccount>,
+        token_program: &Program<'info, Token>,
+        freeze: &ShareFreeze<'info>,
+    ) -> Result<u64> {
+        if side == OrderSide::Ask {
+            freeze.thaw(base_vault)?;
+            freeze.thaw(owner_share_account)?;
+        }
+        let (from, to, amount) = match side {
+            OrderSide::Bid => (
+                quote_vault.to_account_info(),
+                owner_payment_account.to_account_info(),
+                order.price.checked_mul(order.quantity).ok_or(RealStackError::MathOverflow)?,
+            ),
+            OrderSide::Ask => (
+                base_vault.to_account_info(),
+                owner_share_account.to_account_info(),
+                order.quantity,
+            ),
+        };
+        let asset_key = order_book.asset_token;
+        let seeds = &[b"order_book".as_ref(), asset_key.as_ref(), &[order_book.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from,
+                    to,
+                    authority: order_book.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+        if side == OrderSide::Ask {
+            freeze.freeze(owner_share_account)?;
+        }
+        Ok(amount)
+    }
+    
+    /// Match the best bid against the best ask, filling partially if sizes differ
+    pub fn match_orders(
+        ctx: Context<MatchOrders>,
+    ) -> Result<()> {
+        let order_book = &mut ctx.accounts.order_book;
+        let real_token = &ctx.accounts.real_token;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate trading status
+        require!(!ctx.accounts.asset_token.is_burned, RealStackError::AssetBurned);
+        require!(ctx.accounts.asset_token.is_tradable, RealStackError::AssetNotTradable);
+        require!(
+            !real_token.is_paused(PauseFlag::Trading, current_timestamp),
+            RealStackError::TransfersPaused
+        );
+        
+        let fill = order_book.next_fill().ok_or(RealStackError::OrdersNotCrossing)?;
+        
+        // Settlement accounts must belong to the matched traders
+        require!(
+            ctx.accounts.buyer_share_account.owner == fill.buyer,
+            RealStackError::InvalidTokenAccount
+        );
+        require!(
+            ctx.accounts.buyer_payment_account.owner == fill.buyer,
+            RealStackError::InvalidTokenAccount
+        );
+        require!(
+            ctx.accounts.seller_payment_account.owner == fill.seller,
+            RealStackError::InvalidTokenAccount
+        );
+        
+        // Credit the buyer's holding, counting a first-time buyer and keeping them under the cap;
+        // a trader filling against their own ask keeps their balance
+        let asset_token = &mut ctx.accounts.asset_token;
+        let investor_record = InvestorRecord::load(&ctx.accounts.buyer_investor_record)?;
+        let credited = if fill.buyer == fill.seller { 0 } else { fill.quantity };
+        let bump = *ctx.bumps.get("buyer_holder_record").unwrap();
+        let eligible = asset_token.compliance
+            .check_investor(asset_token.real_token, fill.buyer, investor_record.as_ref(), current_timestamp)
+            .and_then(|_| {
+                ctx.accounts.buyer_holder_record.acquire(asset_token, fill.buyer, bump, credited, current_timestamp)
+            })
+            .is_ok();
+        
+        // Share accounts stay frozen outside the program, so refunds and deliveries thaw them
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: order_book.asset_token,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        
+        // A buyer who can no longer take the shares, say because their KYC lapsed, loses the bid
+        // instead of blocking the book
+        if !eligible {
+            let order = order_book.remove_order(OrderSide::Bid, fill.bid_order_id)?;
+            let amount = refund_order(
+                order_book,
+                OrderSide::Bid,
+                &order,
+                &ctx.accounts.base_vault,
+                &ctx.accounts.quote_vault,
+                &ctx.accounts.buyer_share_account,
+                &ctx.accounts.buyer_payment_account,
+                &ctx.accounts.token_program,
+                &freeze,
+            )?;
+            msg!("Bid {} of {} cancelled: buyer not eligible", fill.bid_order_id, fill.buyer);
+            msg!("Refunded: {}", amount);
+            return Ok(());
+        }
+        if credited > 0 {
+            debit_holder(&ctx.accounts.seller_holder_record, credited)?;
+        }
+        
+        // Work out the payment legs
+        let quote_amount = fill.quantity
+            .checked_mul(fill.price)
+            .ok_or(RealStackError::MathOverflow)?;
+        let fee = if real_token.fee_config.fees_enabled {
+            (quote_amount as u128 * real_token.fee_config.transaction_fee_bps as u128 / 10_000) as u64
+        } else {
+            0
+        };
+        let seller_proceeds = quote_amount
+            .checked_sub(fee)
+            .ok_or(RealStackError::MathUnderflow)?;
+        let staker_fee = accrue_staker_fees(&ctx.accounts.stake_pool, fee, order_book.payment_mint, current_timestamp)?;
+        let recipient_fee = fee
+            .checked_sub(staker_fee)
+            .ok_or(RealStackError::MathUnderflow)?;
+        let price_improvement = fill.bid_price
+            .checked_sub(fill.price)
+            .ok_or(RealStackError::MathUnderflow)?
+            .checked_mul(fill.quantity)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        order_book.apply_fill(&fill)?;
+        
+        // Deliver the shares and pay out, thawing the share accounts around the delivery
+        freeze.thaw(&ctx.accounts.base_vault)?;
+        freeze.thaw(&ctx.accounts.buyer_share_account)?;
+        let asset_key = order_book.asset_token;
+        let seeds = &[b"order_book".as_ref(), asset_key.as_ref(), &[order_book.bump]];
+        let transfers = [
+            (&ctx.accounts.base_vault, ctx.accounts.buyer_share_account.to_account_info(), fill.quantity),
+            (&ctx.accounts.quote_vault, ctx.accounts.seller_payment_account.to_account_info(), seller_proceeds),
+            (&ctx.accounts.quote_vault, ctx.accounts.fee_account.to_account_info(), recipient_fee),
+            (&ctx.accounts.quote_vault, ctx.accounts.reward_vault.to_account_info(), staker_fee),
+            (&ctx.accounts.quote_vault, ctx.accounts.buyer_payment_account.to_account_info(), price_improvement),
+        ];
+        for (from, to, amount) in transfers {
+            if amount == 0 {
+                continue;
+            }
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: from.to_account_info(),
+                        to,
+                        authority: order_book.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                amount,
+            )?;
+        }
+        freeze.freeze(&ctx.accounts.buyer_share_account)?;
+        
+        // Log the fill
+        msg!("Matched bid {} with ask {}", fill.bid_order_id, fill.ask_order_id);
+        msg!("Quantity: {}, Price: {}, Fee: {} ({} to stakers)", fill.quantity, fill.price, fee, staker_fee);
+        
+        Ok(())
+    }
+}
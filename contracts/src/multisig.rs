@@ -0,0 +1,446 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::Discriminator;
+use crate::errors::*;
+
+/// Maximum number of multisig owners
+pub const MAX_MULTISIG_OWNERS: usize = 10;
+
+/// Maximum number of accounts a wrapped instruction can take
+pub const MAX_ACTION_ACCOUNTS: usize = 16;
+
+/// Maximum size of a wrapped instruction's data
+pub const MAX_ACTION_DATA: usize = 256;
+
+/// Longest an admin action can wait for approvals (30 days)
+pub const MAX_ACTION_LIFETIME: i64 = 30 * 24 * 60 * 60;
+
+/// M-of-N multisig whose signer PDA can hold any authority or role
+#[account]
+pub struct Multisig {
+    /// Accounts allowed to propose and approve actions
+    pub owners: Vec<Pubkey>,
+    
+    /// Approvals required to execute an action
+    pub threshold: u8,
+    
+    /// How long an action stays approvable (in seconds)
+    pub action_lifetime: i64,
+    
+    /// Number of actions proposed so far
+    pub action_count: u64,
+    
+    /// Bump of the signer PDA
+    pub signer_bump: u8,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+}
+
+impl Multisig {
+    /// Space required for a Multisig account
+    pub const LEN: usize = 8 + // discriminator
+        4 + MAX_MULTISIG_OWNERS * 32 + // owners
+        1 + // threshold
+        8 + // action_lifetime
+        8 + // action_count
+        1 + // signer_bump
+        8; // created_at
+    
+    /// Whether an account is one of the owners
+    pub fn is_owner(&self, account: Pubkey) -> bool {
+        self.owners.contains(&account)
+    }
+}
+
+/// A wrapped admin instruction awaiting approvals
+#[account]
+pub struct AdminAction {
+    /// The multisig this action belongs to
+    pub multisig: Pubkey,
+    
+    /// Owner that proposed the action
+    pub proposer: Pubkey,
+    
+    /// The wrapped instruction
+    pub kind: AdminActionKind,
+    
+    /// Accounts passed to the wrapped instruction, in order
+    pub accounts: Vec<ActionAccount>,
+    
+    /// Instruction data of the wrapped instruction
+    pub data: Vec<u8>,
+    
+    /// Owners that approved the action
+    pub approvals: Vec<Pubkey>,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// When the approvals go stale
+    pub expires_at: i64,
+    
+    /// Whether the action has been executed
+    pub executed: bool,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Account used by a wrapped instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ActionAccount {
+    /// Account address
+    pub pubkey: Pubkey,
+    
+    /// Whether the account is writable
+    pub is_writable: bool,
+}
+
+/// Admin instructions a multisig can execute
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AdminActionKind {
+    UpdateAssetValuation,
+    UpdateFeeConfig,
+    SetTransferPause,
+    TransferAuthority,
+    AcceptAuthority,
+    BurnAssetToken,
+    ToggleTradability,
+}
+
+impl AdminActionKind {
+    /// Identify the wrapped instruction from its discriminator
+    pub fn from_instruction_data(data: &[u8]) -> Option<Self> {
+        let discriminator: [u8; 8] = data.get(..8)?.try_into().ok()?;
+        if discriminator == crate::instruction::UpdateAssetValuation::DISCRIMINATOR {
+            Some(AdminActionKind::UpdateAssetValuation)
+        } else if discriminator == crate::instruction::UpdateFeeConfig::DISCRIMINATOR {
+            Some(AdminActionKind::UpdateFeeConfig)
+        } else if discriminator == crate::instruction::SetTransferPause::DISCRIMINATOR {
+            Some(AdminActionKind::SetTransferPause)
+        } else if discriminator == crate::instruction::TransferAuthority::DISCRIMINATOR {
+            Some(AdminActionKind::TransferAuthority)
+        } else if discriminator == crate::instruction::AcceptAuthority::DISCRIMINATOR {
+            Some(AdminActionKind::AcceptAuthority)
+        } else if discriminator == crate::instruction::BurnAssetToken::DISCRIMINATOR {
+            Some(AdminActionKind::BurnAssetToken)
+        } else if discriminator == crate::instruction::ToggleTradability::DISCRIMINATOR {
+            Some(AdminActionKind::ToggleTradability)
+        } else {
+            None
+        }
+    }
+    
+    /// Whether the instruction operates on a single asset
+    pub fn is_asset_action(&self) -> bool {
+        matches!(
+            self,
+            AdminActionKind::UpdateAssetValuation
+                | AdminActionKind::BurnAssetToken
+                | AdminActionKind::ToggleTradability
+        )
+    }
+}
+
+impl AdminAction {
+    /// Space required for an AdminAction account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // multisig
+        32 + // proposer
+        1 + // kind
+        4 + MAX_ACTION_ACCOUNTS * (32 + 1) + // accounts
+        4 + MAX_ACTION_DATA + // data
+        4 + MAX_MULTISIG_OWNERS * 32 + // approvals
+        8 + // created_at
+        8 + // expires_at
+        1 + // executed
+        1; // bump
+}
+
+/// Context for creating a multisig
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    /// Pays for the multisig account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// The multisig account to create
+    #[account(init, payer = payer, space = Multisig::LEN)]
+    pub multisig: Account<'info, Multisig>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for proposing an admin action
+#[derive(Accounts)]
+pub struct ProposeAdminAction<'info> {
+    /// The proposing owner
+    #[account(
+        mut,
+        constraint = multisig.is_owner(proposer.key()) @ RealStackError::Unauthorized
+    )]
+    pub proposer: Signer<'info>,
+    
+    /// The multisig
+    #[account(mut)]
+    pub multisig: Account<'info, Multisig>,
+    
+    /// The action to create
+    #[account(
+        init,
+        payer = proposer,
+        space = AdminAction::LEN,
+        seeds = [
+            b"admin_action".as_ref(),
+            multisig.key().as_ref(),
+            &multisig.action_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for approving an admin action
+#[derive(Accounts)]
+pub struct ApproveAdminAction<'info> {
+    /// The approving owner
+    #[account(
+        constraint = multisig.is_owner(owner.key()) @ RealStackError::Unauthorized
+    )]
+    pub owner: Signer<'info>,
+    
+    /// The multisig
+    pub multisig: Account<'info, Multisig>,
+    
+    /// The action to approve
+    #[account(
+        mut,
+        constraint = admin_action.multisig == multisig.key() @ RealStackError::InvalidParameters
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+}
+
+/// Context for executing an approved admin action
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    /// The executing owner
+    #[account(
+        constraint = multisig.is_owner(executor.key()) @ RealStackError::Unauthorized
+    )]
+    pub executor: Signer<'info>,
+    
+    /// The multisig
+    pub multisig: Account<'info, Multisig>,
+    
+    /// The action to execute
+    #[account(
+        mut,
+        constraint = admin_action.multisig == multisig.key() @ RealStackError::InvalidParameters
+    )]
+    pub admin_action: Account<'info, AdminAction>,
+    
+    /// CHECK: PDA that signs the wrapped instruction as its authority
+    #[account(
+        mut,
+        seeds = [b"multisig_signer".as_ref(), multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+    
+    /// CHECK: This program, invoked with the wrapped instruction
+    #[account(
+        address = crate::ID @ RealStackError::InvalidParameters
+    )]
+    pub realstack_program: UncheckedAccount<'info>,
+}
+
+/// Implementation of multisig operations
+pub mod multisig_operations {
+    use super::*;
+    
+    /// Create an M-of-N multisig
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+        action_lifetime: i64,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate the owner set
+        require!(
+            !owners.is_empty() && owners.len() <= MAX_MULTISIG_OWNERS,
+            RealStackError::InvalidParameters
+        );
+        for (i, owner) in owners.iter().enumerate() {
+            require!(!owners[..i].contains(owner), RealStackError::InvalidParameters);
+        }
+        require!(
+            threshold > 0 && threshold as usize <= owners.len(),
+            RealStackError::InvalidParameters
+        );
+        require!(
+            action_lifetime > 0 && action_lifetime <= MAX_ACTION_LIFETIME,
+            RealStackError::InvalidParameters
+        );
+        
+        let (_, signer_bump) = Pubkey::find_program_address(
+            &[b"multisig_signer".as_ref(), multisig.key().as_ref()],
+            ctx.program_id,
+        );
+        
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.action_lifetime = action_lifetime;
+        multisig.action_count = 0;
+        multisig.signer_bump = signer_bump;
+        multisig.created_at = current_timestamp;
+        
+        // Log the creation
+        msg!("Multisig created: {}-of-{}", threshold, multisig.owners.len());
+        
+        Ok(())
+    }
+    
+    /// Propose a wrapped admin instruction (counts as the proposer's approval)
+    pub fn propose_admin_action(
+        ctx: Context<ProposeAdminAction>,
+        accounts: Vec<ActionAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let admin_action = &mut ctx.accounts.admin_action;
+        let proposer = &ctx.accounts.proposer;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(accounts.len() <= MAX_ACTION_ACCOUNTS, RealStackError::InvalidParameters);
+        require!(data.len() <= MAX_ACTION_DATA, RealStackError::InvalidParameters);
+        let kind = AdminActionKind::from_instruction_data(&data)
+            .ok_or(RealStackError::UnsupportedAdminAction)?;
+        
+        admin_action.multisig = multisig.key();
+        admin_action.proposer = proposer.key();
+        admin_action.kind = kind;
+        admin_action.accounts = accounts;
+        admin_action.data = data;
+        admin_action.approvals = vec![proposer.key()];
+        admin_action.created_at = current_timestamp;
+        admin_action.expires_at = current_timestamp
+            .checked_add(multisig.action_lifetime)
+            .ok_or(RealStackError::MathOverflow)?;
+        admin_action.executed = false;
+        admin_action.bump = *ctx.bumps.get("admin_action").unwrap();
+        
+        multisig.action_count = multisig.action_count
+            .checked_add(1)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        // Log the proposal
+        msg!("Admin action proposed: {:?}", kind);
+        msg!("Approvals expire at: {}", admin_action.expires_at);
+        
+        Ok(())
+    }
+    
+    /// Approve a pending admin action
+    pub fn approve_admin_action(
+        ctx: Context<ApproveAdminAction>,
+    ) -> Result<()> {
+        let admin_action = &mut ctx.accounts.admin_action;
+        let owner = &ctx.accounts.owner;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(!admin_action.executed, RealStackError::ActionAlreadyExecuted);
+        require!(
+            current_timestamp < admin_action.expires_at,
+            RealStackError::ActionExpired
+        );
+        require!(
+            !admin_action.approvals.contains(&owner.key()),
+            RealStackError::ActionAlreadyApproved
+        );
+        
+        admin_action.approvals.push(owner.key());
+        
+        // Log the approval
+        msg!("Admin action approved by: {}", owner.key());
+        msg!("Approvals: {}", admin_action.approvals.len());
+        
+        Ok(())
+    }
+    
+    /// Execute an admin action once enough owners approved it
+    pub fn execute_admin_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteAdminAction<'info>>,
+    ) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let admin_action = &mut ctx.accounts.admin_action;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(!admin_action.executed, RealStackError::ActionAlreadyExecuted);
+        require!(
+            current_timestamp < admin_action.expires_at,
+            RealStackError::ActionExpired
+        );
+        
+        // Only approvals from current owners count
+        let approvals = admin_action.approvals
+            .iter()
+            .filter(|approver| multisig.is_owner(**approver))
+            .count();
+        require!(
+            approvals >= multisig.threshold as usize,
+            RealStackError::InsufficientApprovals
+        );
+        
+        // The remaining accounts must be exactly the proposed ones
+        require!(
+            ctx.remaining_accounts.len() == admin_action.accounts.len(),
+            RealStackError::InvalidParameters
+        );
+        let signer_key = ctx.accounts.multisig_signer.key();
+        let mut metas = Vec::with_capacity(admin_action.accounts.len());
+        for (account, info) in admin_action.accounts.iter().zip(ctx.remaining_accounts.iter()) {
+            require!(account.pubkey == info.key(), RealStackError::InvalidParameters);
+            let is_signer = account.pubkey == signer_key;
+            metas.push(if account.is_writable {
+                AccountMeta::new(account.pubkey, is_signer)
+            } else {
+                AccountMeta::new_readonly(account.pubkey, is_signer)
+            });
+        }
+        
+        let instruction = Instruction {
+            program_id: crate::ID,
+            accounts: metas,
+            data: admin_action.data.clone(),
+        };
+        
+        // Mark executed before invoking the wrapped instruction
+        admin_action.executed = true;
+        
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.multisig_signer.to_account_info());
+        account_infos.push(ctx.accounts.realstack_program.to_account_info());
+        
+        let multisig_key = multisig.key();
+        let seeds = &[
+            b"multisig_signer".as_ref(),
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ];
+        invoke_signed(&instruction, &account_infos, &[&seeds[..]])?;
+        
+        // Log the execution
+        msg!("Admin action executed: {:?}", admin_action.kind);
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,898 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::errors::*;
+use crate::access_control::Roles;
+use crate::asset_token::AssetToken;
+use crate::compliance::{HolderRecord, InvestorRecord, ShareFreeze, ShareLock};
+use crate::tokenomics::{PauseFlag, RealToken};
+
+/// Fixed-price primary offering of an asset's shares
+#[account]
+pub struct Offering {
+    /// The asset being offered
+    pub asset_token: Pubkey,
+    
+    /// Issuer receiving the proceeds
+    pub authority: Pubkey,
+    
+    /// Share mint of the asset
+    pub share_mint: Pubkey,
+    
+    /// Mint investors pay with (e.g. USDC)
+    pub payment_mint: Pubkey,
+    
+    /// Vault holding the shares for sale
+    pub share_vault: Pubkey,
+    
+    /// Escrow vault holding investor payments
+    pub payment_vault: Pubkey,
+    
+    /// Price per share in payment mint units
+    pub share_price: u64,
+    
+    /// When the offering opens
+    pub start_time: i64,
+    
+    /// When the offering closes
+    pub end_time: i64,
+    
+    /// Soft cap: minimum raise for the offering to succeed
+    pub min_raise: u64,
+    
+    /// Hard cap: maximum raise
+    pub max_raise: u64,
+    
+    /// Minimum shares per purchase
+    pub min_purchase_shares: u64,
+    
+    /// Maximum shares a single wallet may buy
+    pub max_shares_per_wallet: u64,
+    
+    /// Shares deposited for sale
+    pub shares_offered: u64,
+    
+    /// Shares sold so far
+    pub shares_sold: u64,
+    
+    /// Payment collected so far
+    pub total_raised: u64,
+    
+    /// Current status
+    pub status: OfferingStatus,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Finalization timestamp
+    pub finalized_at: i64,
+    
+    /// Sequence number under the asset, seeding the offering's address
+    pub offering_id: u64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+/// Offering lifecycle states
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OfferingStatus {
+    /// Accepting purchases
+    Active,
+    
+    /// Soft cap met; shares claimable and proceeds released
+    Succeeded,
+    
+    /// Soft cap missed; payments refundable
+    Failed,
+}
+
+impl Offering {
+    /// Space required for an Offering account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // asset_token
+        32 + // authority
+        32 + // share_mint
+        32 + // payment_mint
+        32 + // share_vault
+        32 + // payment_vault
+        8 + // share_price
+        8 + // start_time
+        8 + // end_time
+        8 + // min_raise
+        8 + // max_raise
+        8 + // min_purchase_shares
+        8 + // max_shares_per_wallet
+        8 + // shares_offered
+        8 + // shares_sold
+        8 + // total_raised
+        1 + // status
+        8 + // created_at
+        8 + // finalized_at
+        8 + // offering_id
+        1; // bump
+}
+
+/// A single investor's participation in an offering
+#[account]
+pub struct OfferingPurchase {
+    /// The offering
+    pub offering: Pubkey,
+    
+    /// The investor
+    pub buyer: Pubkey,
+    
+    /// Shares bought
+    pub shares_purchased: u64,
+    
+    /// Payment escrowed
+    pub amount_paid: u64,
+    
+    /// Whether shares or refund have been claimed
+    pub settled: bool,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl OfferingPurchase {
+    /// Space required for an OfferingPurchase account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // offering
+        32 + // buyer
+        8 + // shares_purchased
+        8 + // amount_paid
+        1 + // settled
+        1; // bump
+}
+
+/// Context for creating an offering
+#[derive(Accounts)]
+pub struct CreateOffering<'info> {
+    /// The asset's managing authority (issuer)
+    #[account(
+        mut,
+        constraint = roles.manages_asset(authority.key(), &asset_token) @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// The asset being offered
+    #[account(mut)]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), asset_token.real_token.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = asset_token.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The offering account to create, one per raise so an asset can raise again after an offering closes
+    #[account(
+        init,
+        payer = authority,
+        space = Offering::LEN,
+        seeds = [
+            b"offering".as_ref(),
+            asset_token.key().as_ref(),
+            &asset_token.offering_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub offering: Account<'info, Offering>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = asset_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), asset_token.key().as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// Payment mint
+    pub payment_mint: Account<'info, Mint>,
+    
+    /// Issuer's share account funding the offering
+    #[account(
+        mut,
+        constraint = issuer_share_account.owner == authority.key() @ RealStackError::InvalidTokenAccount,
+        constraint = issuer_share_account.mint == share_mint.key() @ RealStackError::InvalidTokenMint
+    )]
+    pub issuer_share_account: Account<'info, TokenAccount>,
+    
+    /// Vault for the shares on sale
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"offering_shares".as_ref(), offering.key().as_ref()],
+        bump,
+        token::mint = share_mint,
+        token::authority = offering
+    )]
+    pub share_vault: Account<'info, TokenAccount>,
+    
+    /// Escrow for investor payments
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"offering_payments".as_ref(), offering.key().as_ref()],
+        bump,
+        token::mint = payment_mint,
+        token::authority = offering
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Context for buying shares in an offering
+#[derive(Accounts)]
+pub struct BuyShares<'info> {
+    /// The investor
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    /// The offering
+    #[account(mut)]
+    pub offering: Account<'info, Offering>,
+    
+    /// The offered asset
+    #[account(
+        mut,
+        address = offering.asset_token @ RealStackError::AssetNotFound
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = asset_token.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The investor's purchase record, created on first purchase
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = OfferingPurchase::LEN,
+        seeds = [b"offering_purchase".as_ref(), offering.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase: Account<'info, OfferingPurchase>,
+    
+    /// The investor's payment account
+    #[account(
+        mut,
+        constraint = buyer_payment_account.owner == buyer.key() @ RealStackError::InvalidTokenAccount,
+        constraint = buyer_payment_account.mint == offering.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    
+    /// Escrow for investor payments
+    #[account(
+        mut,
+        address = offering.payment_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+    
+    /// The investor's record, required by restricted assets
+    pub investor_record: Option<Account<'info, InvestorRecord>>,
+    
+    /// The buyer's holder record, created on first acquisition
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = HolderRecord::LEN,
+        seeds = [b"holder".as_ref(), asset_token.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub holder_record: Account<'info, HolderRecord>,
+    
+    /// The buyer's share lock, holding bought shares for the asset's lock-up period
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = ShareLock::LEN,
+        seeds = [b"share_lock".as_ref(), asset_token.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub share_lock: Account<'info, ShareLock>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for finalizing an offering
+#[derive(Accounts)]
+pub struct FinalizeOffering<'info> {
+    /// Anyone may finalize once the offering has closed
+    pub payer: Signer<'info>,
+    
+    /// The offering
+    #[account(mut)]
+    pub offering: Account<'info, Offering>,
+    
+    /// Vault for the shares on sale
+    #[account(
+        mut,
+        address = offering.share_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub share_vault: Account<'info, TokenAccount>,
+    
+    /// Escrow for investor payments
+    #[account(
+        mut,
+        address = offering.payment_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+    
+    /// Issuer account receiving unsold shares
+    #[account(
+        mut,
+        constraint = issuer_share_account.owner == offering.authority @ RealStackError::InvalidTokenAccount,
+        constraint = issuer_share_account.mint == offering.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub issuer_share_account: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = offering.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), offering.asset_token.as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// Issuer account receiving the proceeds
+    #[account(
+        mut,
+        constraint = issuer_payment_account.owner == offering.authority @ RealStackError::InvalidTokenAccount,
+        constraint = issuer_payment_account.mint == offering.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub issuer_payment_account: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for claiming purchased shares from a successful offering
+#[derive(Accounts)]
+pub struct ClaimOfferingShares<'info> {
+    /// The investor
+    pub buyer: Signer<'info>,
+    
+    /// The offering
+    pub offering: Account<'info, Offering>,
+    
+    /// The offered asset
+    #[account(
+        address = offering.asset_token @ RealStackError::AssetNotFound
+    )]
+    pub asset_token: Account<'info, AssetToken>,
+    
+    /// The investor's purchase record
+    #[account(
+        mut,
+        seeds = [b"offering_purchase".as_ref(), offering.key().as_ref(), buyer.key().as_ref()],
+        bump = purchase.bump
+    )]
+    pub purchase: Account<'info, OfferingPurchase>,
+    
+    /// Vault for the shares on sale
+    #[account(
+        mut,
+        address = offering.share_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub share_vault: Account<'info, TokenAccount>,
+    
+    /// The investor's share account
+    #[account(
+        mut,
+        constraint = buyer_share_account.owner == buyer.key() @ RealStackError::InvalidTokenAccount,
+        constraint = buyer_share_account.mint == offering.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub buyer_share_account: Account<'info, TokenAccount>,
+    
+    /// Share mint of the asset
+    #[account(
+        address = offering.share_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub share_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA holding freeze authority over the share mint
+    #[account(
+        seeds = [b"freeze_authority".as_ref(), offering.asset_token.as_ref()],
+        bump
+    )]
+    pub freeze_authority: UncheckedAccount<'info>,
+    
+    /// The investor's share lock, which must have expired
+    #[account(
+        seeds = [b"share_lock".as_ref(), offering.asset_token.as_ref(), buyer.key().as_ref()],
+        bump = share_lock.bump
+    )]
+    pub share_lock: Account<'info, ShareLock>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for reclaiming payment from a failed offering
+#[derive(Accounts)]
+pub struct RefundPurchase<'info> {
+    /// The investor
+    pub buyer: Signer<'info>,
+    
+    /// The offering
+    pub offering: Account<'info, Offering>,
+    
+    /// The investor's purchase record
+    #[account(
+        mut,
+        seeds = [b"offering_purchase".as_ref(), offering.key().as_ref(), buyer.key().as_ref()],
+        bump = purchase.bump
+    )]
+    pub purchase: Account<'info, OfferingPurchase>,
+    
+    /// Escrow for investor payments
+    #[account(
+        mut,
+        address = offering.payment_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub payment_vault: Account<'info, TokenAccount>,
+    
+    /// The investor's payment account
+    #[account(
+        mut,
+        constraint = buyer_payment_account.owner == buyer.key() @ RealStackError::InvalidTokenAccount,
+        constraint = buyer_payment_account.mint == offering.payment_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    
+    /// The investor's share lock, released for refunded shares
+    #[account(
+        mut,
+        seeds = [b"share_lock".as_ref(), offering.asset_token.as_ref(), buyer.key().as_ref()],
+        bump = share_lock.bump
+    )]
+    pub share_lock: Account<'info, ShareLock>,
+    
+    /// The investor's holder record, credited with the purchase
+    #[account(
+        mut,
+        seeds = [b"holder".as_ref(), offering.asset_token.as_ref(), buyer.key().as_ref()],
+        bump = holder_record.bump
+    )]
+    pub holder_record: Account<'info, HolderRecord>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Implementation of primary offering operations
+pub mod offering_operations {
+    use super::*;
+    
+    /// Create a fixed-price offering at the asset's initial share price
+    pub fn create_offering(
+        ctx: Context<CreateOffering>,
+        start_time: i64,
+        end_time: i64,
+        min_raise: u64,
+        max_raise: u64,
+        min_purchase_shares: u64,
+        max_shares_per_wallet: u64,
+    ) -> Result<()> {
+        let asset_token = &mut ctx.accounts.asset_token;
+        let offering = &mut ctx.accounts.offering;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate the asset
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::AssetCreation, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(!asset_token.is_burned, RealStackError::AssetBurned);
+        require!(asset_token.is_verified, RealStackError::AssetNotVerified);
+        require!(asset_token.initial_share_price > 0, RealStackError::SharePriceTooLow);
+        
+        // Validate offering parameters
+        require!(end_time > start_time, RealStackError::InvalidParameters);
+        require!(end_time > current_timestamp, RealStackError::InvalidParameters);
+        require!(
+            min_raise > 0 && min_raise <= max_raise,
+            RealStackError::InvalidParameters
+        );
+        require!(
+            min_purchase_shares > 0 && min_purchase_shares <= max_shares_per_wallet,
+            RealStackError::InvalidParameters
+        );
+        
+        // Shares needed to fill the hard cap
+        let shares_offered = max_raise / asset_token.initial_share_price;
+        require!(shares_offered > 0, RealStackError::InvalidParameters);
+        require!(
+            shares_offered <= asset_token.total_shares,
+            RealStackError::TotalSharesExceedsMaximum
+        );
+        
+        // Escrow the shares for sale, thawing the issuer's account around the transfer
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: asset_token.key(),
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        freeze.thaw(&ctx.accounts.issuer_share_account)?;
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.issuer_share_account.to_account_info(),
+                    to: ctx.accounts.share_vault.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            shares_offered,
+        )?;
+        freeze.freeze(&ctx.accounts.issuer_share_account)?;
+        
+        // Initialize the offering
+        offering.asset_token = asset_token.key();
+        offering.authority = ctx.accounts.authority.key();
+        offering.share_mint = ctx.accounts.share_mint.key();
+        offering.payment_mint = ctx.accounts.payment_mint.key();
+        offering.share_vault = ctx.accounts.share_vault.key();
+        offering.payment_vault = ctx.accounts.payment_vault.key();
+        offering.share_price = asset_token.initial_share_price;
+        offering.start_time = start_time;
+        offering.end_time = end_time;
+        offering.min_raise = min_raise;
+        offering.max_raise = max_raise;
+        offering.min_purchase_shares = min_purchase_shares;
+        offering.max_shares_per_wallet = max_shares_per_wallet;
+        offering.shares_offered = shares_offered;
+        offering.shares_sold = 0;
+        offering.total_raised = 0;
+        offering.status = OfferingStatus::Active;
+        offering.created_at = current_timestamp;
+        offering.finalized_at = 0;
+        offering.offering_id = asset_token.offering_count;
+        offering.bump = *ctx.bumps.get("offering").unwrap();
+        asset_token.offering_count = asset_token.offering_count
+            .checked_add(1)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        // Log the creation
+        msg!("Offering created for asset: {}", asset_token.name);
+        msg!("Shares offered: {}, Price: {}, Soft cap: {}, Hard cap: {}",
+            shares_offered, offering.share_price, min_raise, max_raise);
+        
+        Ok(())
+    }
+    
+    /// Buy shares, escrowing the payment until the offering is finalized
+    pub fn buy_shares(
+        ctx: Context<BuyShares>,
+        shares: u64,
+    ) -> Result<()> {
+        let offering = &mut ctx.accounts.offering;
+        let purchase = &mut ctx.accounts.purchase;
+        let buyer = &ctx.accounts.buyer;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate the offering window
+        require!(!ctx.accounts.asset_token.is_burned, RealStackError::AssetBurned);
+        require!(
+            offering.status == OfferingStatus::Active,
+            RealStackError::OfferingNotActive
+        );
+        require!(
+            current_timestamp >= offering.start_time,
+            RealStackError::OfferingNotStarted
+        );
+        require!(
+            current_timestamp < offering.end_time,
+            RealStackError::OfferingEnded
+        );
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Trading, current_timestamp),
+            RealStackError::TransfersPaused
+        );
+        
+        // The investor must meet the asset's requirements
+        let asset_token = &mut ctx.accounts.asset_token;
+        asset_token.compliance.check_investor(
+            asset_token.real_token,
+            buyer.key(),
+            ctx.accounts.investor_record.as_deref(),
+            current_timestamp,
+        )?;
+        
+        // Validate the purchase size
+        require!(
+            shares >= offering.min_purchase_shares,
+            RealStackError::PurchaseBelowMinimum
+        );
+        let wallet_total = purchase.shares_purchased
+            .checked_add(shares)
+            .ok_or(RealStackError::MathOverflow)?;
+        require!(
+            wallet_total <= offering.max_shares_per_wallet,
+            RealStackError::WalletLimitExceeded
+        );
+        let shares_sold = offering.shares_sold
+            .checked_add(shares)
+            .ok_or(RealStackError::MathOverflow)?;
+        require!(
+            shares_sold <= offering.shares_offered,
+            RealStackError::OfferingSoldOut
+        );
+        
+        // Credit the purchase to the buyer's holding, counting a first-time buyer and keeping them under the cap
+        let bump = *ctx.bumps.get("holder_record").unwrap();
+        ctx.accounts.holder_record.acquire(asset_token, buyer.key(), bump, shares, current_timestamp)?;
+        
+        // Newly issued shares stay in the offering's vault for the asset's lock-up period
+        let share_lock = &mut ctx.accounts.share_lock;
+        if share_lock.wallet == Pubkey::default() {
+            share_lock.asset_token = asset_token.key();
+            share_lock.wallet = buyer.key();
+            share_lock.bump = *ctx.bumps.get("share_lock").unwrap();
+        }
+        share_lock.lock(shares, asset_token.compliance.lockup_duration, current_timestamp)?;
+        
+        let cost = shares
+            .checked_mul(offering.share_price)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        // Escrow the payment
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.buyer_payment_account.to_account_info(),
+                    to: ctx.accounts.payment_vault.to_account_info(),
+                    authority: buyer.to_account_info(),
+                },
+            ),
+            cost,
+        )?;
+        
+        // Set up a freshly created purchase record
+        if purchase.buyer == Pubkey::default() {
+            purchase.offering = offering.key();
+            purchase.buyer = buyer.key();
+            purchase.settled = false;
+            purchase.bump = *ctx.bumps.get("purchase").unwrap();
+        }
+        
+        // Update totals
+        purchase.shares_purchased = wallet_total;
+        purchase.amount_paid = purchase.amount_paid
+            .checked_add(cost)
+            .ok_or(RealStackError::MathOverflow)?;
+        offering.shares_sold = shares_sold;
+        offering.total_raised = offering.total_raised
+            .checked_add(cost)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        // Log the purchase
+        msg!("Offering purchase: {} shares for {} by {}", shares, cost, buyer.key());
+        msg!("Total raised: {}", offering.total_raised);
+        
+        Ok(())
+    }
+    
+    /// Close the offering, releasing proceeds on success or enabling refunds on failure
+    pub fn finalize_offering(
+        ctx: Context<FinalizeOffering>,
+    ) -> Result<()> {
+        let offering = &mut ctx.accounts.offering;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(
+            offering.status == OfferingStatus::Active,
+            RealStackError::OfferingAlreadyFinalized
+        );
+        require!(
+            current_timestamp >= offering.end_time || offering.shares_sold == offering.shares_offered,
+            RealStackError::OfferingNotEnded
+        );
+        
+        let succeeded = offering.total_raised >= offering.min_raise;
+        
+        // Shares not owed to investors go back to the issuer
+        let unsold_shares = if succeeded {
+            offering.shares_offered
+                .checked_sub(offering.shares_sold)
+                .ok_or(RealStackError::MathUnderflow)?
+        } else {
+            offering.shares_offered
+        };
+        
+        let asset_key = offering.asset_token;
+        let offering_id = offering.offering_id.to_le_bytes();
+        let seeds = &[b"offering".as_ref(), asset_key.as_ref(), offering_id.as_ref(), &[offering.bump]];
+        
+        if unsold_shares > 0 {
+            // The vault may have been frozen by a transfer into it; the issuer's account is frozen again afterwards
+            let freeze = ShareFreeze {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                share_mint: ctx.accounts.share_mint.to_account_info(),
+                freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+                asset_token: asset_key,
+                bump: *ctx.bumps.get("freeze_authority").unwrap(),
+            };
+            freeze.thaw(&ctx.accounts.share_vault)?;
+            freeze.thaw(&ctx.accounts.issuer_share_account)?;
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.share_vault.to_account_info(),
+                        to: ctx.accounts.issuer_share_account.to_account_info(),
+                        authority: offering.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                unsold_shares,
+            )?;
+            freeze.freeze(&ctx.accounts.issuer_share_account)?;
+        }
+        
+        if succeeded {
+            // Release the proceeds to the issuer
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.payment_vault.to_account_info(),
+                        to: ctx.accounts.issuer_payment_account.to_account_info(),
+                        authority: offering.to_account_info(),
+                    },
+                    &[&seeds[..]],
+                ),
+                offering.total_raised,
+            )?;
+            offering.status = OfferingStatus::Succeeded;
+        } else {
+            offering.status = OfferingStatus::Failed;
+        }
+        offering.finalized_at = current_timestamp;
+        
+        // Log the result
+        if succeeded {
+            msg!("Offering succeeded: raised {} for {} shares", offering.total_raised, offering.shares_sold);
+        } else {
+            msg!("Offering failed: raised {} of {} soft cap, refunds enabled",
+                offering.total_raised, offering.min_raise);
+        }
+        
+        Ok(())
+    }
+    
+    /// Claim shares bought in a successful offering
+    pub fn claim_offering_shares(
+        ctx: Context<ClaimOfferingShares>,
+    ) -> Result<()> {
+        let offering = &ctx.accounts.offering;
+        let purchase = &mut ctx.accounts.purchase;
+        
+        require!(
+            offering.status == OfferingStatus::Succeeded,
+            RealStackError::OfferingNotSucceeded
+        );
+        require!(!purchase.settled, RealStackError::PurchaseAlreadySettled);
+        
+        // Shares stay in escrow through the lock-up, unless the asset is wound down and they are only good for redemption
+        let asset_token = &ctx.accounts.asset_token;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.share_lock.locked_at(current_timestamp) == 0 || asset_token.is_burned,
+            RealStackError::SharesLocked
+        );
+        
+        // Eligibility was checked at purchase, and the payment is already with the issuer
+        purchase.settled = true;
+        
+        // Release the shares to the investor, thawing both accounts in case a transfer into the vault froze it
+        let asset_key = offering.asset_token;
+        let offering_id = offering.offering_id.to_le_bytes();
+        let seeds = &[b"offering".as_ref(), asset_key.as_ref(), offering_id.as_ref(), &[offering.bump]];
+        let freeze = ShareFreeze {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            share_mint: ctx.accounts.share_mint.to_account_info(),
+            freeze_authority: ctx.accounts.freeze_authority.to_account_info(),
+            asset_token: asset_key,
+            bump: *ctx.bumps.get("freeze_authority").unwrap(),
+        };
+        freeze.thaw(&ctx.accounts.share_vault)?;
+        freeze.thaw(&ctx.accounts.buyer_share_account)?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.share_vault.to_account_info(),
+                    to: ctx.accounts.buyer_share_account.to_account_info(),
+                    authority: offering.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            purchase.shares_purchased,
+        )?;
+        freeze.freeze(&ctx.accounts.buyer_share_account)?;
+        
+        // Log the claim
+        msg!("Offering shares claimed: {} by {}", purchase.shares_purchased, purchase.buyer);
+        
+        Ok(())
+    }
+    
+    /// Reclaim payment from a failed offering
+    pub fn refund_purchase(
+        ctx: Context<RefundPurchase>,
+    ) -> Result<()> {
+        let offering = &ctx.accounts.offering;
+        let purchase = &mut ctx.accounts.purchase;
+        
+        require!(
+            offering.status == OfferingStatus::Failed,
+            RealStackError::OfferingNotFailed
+        );
+        require!(!purchase.settled, RealStackError::PurchaseAlreadySettled);
+        
+        purchase.settled = true;
+        
+        // Refunded shares were never delivered, so none of them stay locked or count toward the holding
+        let share_lock = &mut ctx.accounts.share_lock;
+        share_lock.locked_amount = share_lock.locked_amount.saturating_sub(purchase.shares_purchased);
+        ctx.accounts.holder_record.debit(purchase.shares_purchased);
+        
+        // Return the escrowed payment
+        let asset_key = offering.asset_token;
+        let offering_id = offering.offering_id.to_le_bytes();
+        let seeds = &[b"offering".as_ref(), asset_key.as_ref(), offering_id.as_ref(), &[offering.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payment_vault.to_account_info(),
+                    to: ctx.accounts.buyer_payment_account.to_account_info(),
+                    authority: offering.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            purchase.amount_paid,
+        )?;
+        
+        // Log the refund
+        msg!("Offering purchase refunded: {} to {}", purchase.amount_paid, purchase.buyer);
+        
+        Ok(())
+    }
+}
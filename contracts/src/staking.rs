@@ -0,0 +1,813 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::errors::*;
+use crate::tokenomics::{PauseFlag, RealToken};
+
+/// Fixed-point scale used by the reward-per-token accumulator
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Basis points denominator
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Maximum unstake cooldown (30 days)
+pub const MAX_UNSTAKE_COOLDOWN: i64 = 30 * 24 * 60 * 60;
+
+/// Most checkpoints a holder keeps
+pub const MAX_CHECKPOINTS: usize = 64;
+
+/// Oldest a proposal snapshot can be while still read (30 days); caps governance voting periods
+pub const MAX_SNAPSHOT_AGE: i64 = 30 * 24 * 60 * 60;
+
+/// REAL staking pool data structure
+#[account]
+pub struct StakePool {
+    /// The REAL token this pool belongs to
+    pub real_token: Pubkey,
+    
+    /// Mint of the staked token (REAL)
+    pub stake_mint: Pubkey,
+    
+    /// Vault holding staked tokens
+    pub stake_vault: Pubkey,
+    
+    /// Mint in which fees are paid out to stakers
+    pub reward_mint: Pubkey,
+    
+    /// Vault holding undistributed staker rewards
+    pub reward_vault: Pubkey,
+    
+    /// Share of collected fees routed to stakers (basis points)
+    pub fee_share_bps: u16,
+    
+    /// Delay between unstaking and withdrawal (in seconds)
+    pub unstake_cooldown: i64,
+    
+    /// Total tokens currently staked
+    pub total_staked: u64,
+    
+    /// Accumulated rewards per staked token, scaled by REWARD_PRECISION
+    pub reward_per_token: u128,
+    
+    /// Total rewards distributed to stakers
+    pub total_rewards_distributed: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Last update timestamp
+    pub updated_at: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl StakePool {
+    /// Space required for a StakePool account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // real_token
+        32 + // stake_mint
+        32 + // stake_vault
+        32 + // reward_mint
+        32 + // reward_vault
+        2 + // fee_share_bps
+        8 + // unstake_cooldown
+        8 + // total_staked
+        16 + // reward_per_token
+        8 + // total_rewards_distributed
+        8 + // created_at
+        8 + // updated_at
+        1; // bump
+    
+    /// Credit `amount` of rewards, already in the reward vault, to current stakers
+    pub fn add_rewards(&mut self, amount: u64, current_timestamp: i64) -> Result<()> {
+        let increment = (amount as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(RealStackError::MathOverflow)?
+            / self.total_staked as u128;
+        self.reward_per_token = self.reward_per_token
+            .checked_add(increment)
+            .ok_or(RealStackError::MathOverflow)?;
+        self.total_rewards_distributed = self.total_rewards_distributed
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        self.updated_at = current_timestamp;
+        Ok(())
+    }
+    
+    /// Stakers' share of `fee`, rounded down
+    pub fn staker_share(&self, fee: u64) -> Result<u64> {
+        let share = (fee as u128)
+            .checked_mul(self.fee_share_bps as u128)
+            .ok_or(RealStackError::MathOverflow)?
+            / BPS_DENOMINATOR as u128;
+        Ok(share as u64)
+    }
+}
+
+/// Credit stakers with their share of a `fee` just taken in `fee_mint`, returning the amount to move into the reward vault
+pub fn accrue_staker_fees(stake_pool: &AccountInfo, fee: u64, fee_mint: Pubkey, current_timestamp: i64) -> Result<u64> {
+    if stake_pool.data_is_empty() || fee == 0 {
+        return Ok(0);
+    }
+    let mut pool = Account::<StakePool>::try_from(stake_pool)?;
+    if pool.reward_mint != fee_mint || pool.total_staked == 0 {
+        return Ok(0);
+    }
+    let staker_share = pool.staker_share(fee)?;
+    if staker_share > 0 {
+        pool.add_rewards(staker_share, current_timestamp)?;
+        pool.exit(&crate::ID)?;
+    }
+    Ok(staker_share)
+}
+
+/// Per-holder staking position
+#[account]
+pub struct StakeAccount {
+    /// Owner of the staked tokens
+    pub owner: Pubkey,
+    
+    /// The stake pool
+    pub stake_pool: Pubkey,
+    
+    /// Amount currently staked
+    pub staked_amount: u64,
+    
+    /// Pool accumulator value at the last settlement
+    pub reward_per_token_paid: u128,
+    
+    /// Rewards settled but not yet claimed
+    pub pending_rewards: u64,
+    
+    /// Amount unstaked and waiting for the cooldown
+    pub pending_unstake: u64,
+    
+    /// When the pending unstake can be withdrawn
+    pub unstake_available_at: i64,
+    
+    /// Last stake timestamp
+    pub last_staked_at: i64,
+    
+    /// Until when a conviction vote keeps the stake from being unstaked
+    pub vote_locked_until: i64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl StakeAccount {
+    /// Space required for a StakeAccount account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // stake_pool
+        8 + // staked_amount
+        16 + // reward_per_token_paid
+        8 + // pending_rewards
+        8 + // pending_unstake
+        8 + // unstake_available_at
+        8 + // last_staked_at
+        8 + // vote_locked_until
+        1; // bump
+    
+    /// Voting power contributed by this stake
+    pub fn voting_power(&self) -> u64 {
+        self.staked_amount
+    }
+}
+
+/// A holder's voting power from a slot onwards
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Checkpoint {
+    /// Slot the power took effect in
+    pub slot: u64,
+    
+    /// Unix timestamp of that slot
+    pub timestamp: i64,
+    
+    /// Voting power from that slot on
+    pub power: u64,
+}
+
+/// History of a holder's staked voting power, read at proposal snapshots
+#[account]
+pub struct VotingPowerCheckpoints {
+    /// The holder
+    pub owner: Pubkey,
+    
+    /// The stake pool the power is staked in
+    pub stake_pool: Pubkey,
+    
+    /// Checkpoints in increasing slot order
+    pub checkpoints: Vec<Checkpoint>,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl VotingPowerCheckpoints {
+    /// Space required for a VotingPowerCheckpoints account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // stake_pool
+        4 + MAX_CHECKPOINTS * 24 + // checkpoints
+        1; // bump
+    
+    /// Record the holder's power as of `slot`; never refused, so a stake change is never blocked
+    pub fn record(&mut self, slot: u64, timestamp: i64, power: u64) {
+        if let Some(last) = self.checkpoints.last_mut() {
+            if last.slot == slot {
+                last.power = power;
+                return;
+            }
+        }
+        
+        // The oldest checkpoint may only go once its successor predates every snapshot still read,
+        // so the checkpoint at or before the oldest live snapshot is always kept
+        if self.checkpoints.len() == MAX_CHECKPOINTS {
+            let horizon = timestamp.saturating_sub(MAX_SNAPSHOT_AGE);
+            if self.checkpoints[1].timestamp < horizon {
+                self.checkpoints.remove(0);
+            } else {
+                // Otherwise the two newest merge at the earlier slot with the lower power: snapshots
+                // between them may read less than was staked, never more, and current power stays exact
+                let newest = self.checkpoints.remove(MAX_CHECKPOINTS - 1);
+                let previous = &mut self.checkpoints[MAX_CHECKPOINTS - 2];
+                previous.power = previous.power.min(newest.power);
+            }
+        }
+        self.checkpoints.push(Checkpoint { slot, timestamp, power });
+    }
+    
+    /// Power held before `slot`; zero if no checkpoint that old is kept
+    pub fn power_before(&self, slot: u64) -> u64 {
+        // Binary search for the last checkpoint written before the slot
+        let index = self.checkpoints.partition_point(|checkpoint| checkpoint.slot < slot);
+        match index {
+            0 => 0,
+            _ => self.checkpoints[index - 1].power,
+        }
+    }
+}
+
+/// Context for creating the REAL stake pool
+#[derive(Accounts)]
+pub struct InitializeStakePool<'info> {
+    /// The REAL token authority
+    #[account(
+        mut,
+        constraint = real_token.authority == authority.key() @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// The REAL token account
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The stake pool to create
+    #[account(
+        init,
+        payer = authority,
+        space = StakePool::LEN,
+        seeds = [b"stake_pool".as_ref(), real_token.key().as_ref()],
+        bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The REAL mint
+    #[account(
+        constraint = stake_mint.key() == real_token.mint @ RealStackError::InvalidTokenMint
+    )]
+    pub stake_mint: Account<'info, Mint>,
+    
+    /// The mint fees are paid in
+    pub reward_mint: Account<'info, Mint>,
+    
+    /// Vault for staked tokens
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stake_vault".as_ref(), stake_pool.key().as_ref()],
+        bump,
+        token::mint = stake_mint,
+        token::authority = stake_pool
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    /// Vault for staker rewards
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward_vault".as_ref(), stake_pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = stake_pool
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Context for staking REAL
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    /// The staker
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// The stake pool
+    #[account(mut)]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        constraint = stake_pool.real_token == real_token.key() @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The staker's position, created on first stake
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = StakeAccount::LEN,
+        seeds = [b"stake_account".as_ref(), stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+    
+    /// The staker's voting power history, created on first stake
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = VotingPowerCheckpoints::LEN,
+        seeds = [b"voting_power".as_ref(), stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, VotingPowerCheckpoints>,
+    
+    /// The staker's REAL token account
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == owner.key() @ RealStackError::InvalidTokenAccount,
+        constraint = owner_token_account.mint == stake_pool.stake_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    /// Vault for staked tokens
+    #[account(
+        mut,
+        address = stake_pool.stake_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for starting an unstake cooldown
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    /// The staker
+    pub owner: Signer<'info>,
+    
+    /// The stake pool
+    #[account(mut)]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The staker's position
+    #[account(
+        mut,
+        seeds = [b"stake_account".as_ref(), stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+    
+    /// The staker's voting power history
+    #[account(
+        mut,
+        seeds = [b"voting_power".as_ref(), stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Account<'info, VotingPowerCheckpoints>,
+}
+
+/// Context for withdrawing unstaked tokens after the cooldown
+#[derive(Accounts)]
+pub struct WithdrawUnstaked<'info> {
+    /// The staker
+    pub owner: Signer<'info>,
+    
+    /// The stake pool
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The staker's position
+    #[account(
+        mut,
+        seeds = [b"stake_account".as_ref(), stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+    
+    /// The staker's REAL token account
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == stake_pool.stake_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    /// Vault for staked tokens
+    #[account(
+        mut,
+        address = stake_pool.stake_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for claiming staking rewards
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    /// The staker
+    pub owner: Signer<'info>,
+    
+    /// The stake pool
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// The staker's position
+    #[account(
+        mut,
+        seeds = [b"stake_account".as_ref(), stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_account.bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+    
+    /// Account receiving the rewards
+    #[account(
+        mut,
+        constraint = reward_destination.mint == stake_pool.reward_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub reward_destination: Account<'info, TokenAccount>,
+    
+    /// Vault for staker rewards
+    #[account(
+        mut,
+        address = stake_pool.reward_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for routing collected fees to stakers
+#[derive(Accounts)]
+pub struct DistributeStakingRewards<'info> {
+    /// The fee recipient configured in `FeeConfig`
+    #[account(
+        constraint = real_token.fee_config.fee_recipient == fee_recipient.key() @ RealStackError::Unauthorized
+    )]
+    pub fee_recipient: Signer<'info>,
+    
+    /// The REAL token account
+    #[account(
+        constraint = stake_pool.real_token == real_token.key() @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The stake pool
+    #[account(mut)]
+    pub stake_pool: Account<'info, StakePool>,
+    
+    /// Token account holding the collected fees
+    #[account(
+        mut,
+        constraint = fee_source.owner == fee_recipient.key() @ RealStackError::InvalidTokenAccount,
+        constraint = fee_source.mint == stake_pool.reward_mint @ RealStackError::InvalidTokenMint
+    )]
+    pub fee_source: Account<'info, TokenAccount>,
+    
+    /// Vault for staker rewards
+    #[account(
+        mut,
+        address = stake_pool.reward_vault @ RealStackError::InvalidTokenAccount
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Implementation of staking operations
+pub mod staking_operations {
+    use super::*;
+    
+    /// Create the REAL stake pool
+    pub fn initialize_stake_pool(
+        ctx: Context<InitializeStakePool>,
+        fee_share_bps: u16,
+        unstake_cooldown: i64,
+    ) -> Result<()> {
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // Validate parameters
+        require!(
+            fee_share_bps as u64 <= BPS_DENOMINATOR,
+            RealStackError::InvalidParameters
+        );
+        require!(
+            (0..=MAX_UNSTAKE_COOLDOWN).contains(&unstake_cooldown),
+            RealStackError::InvalidParameters
+        );
+        
+        // Initialize the pool
+        stake_pool.real_token = ctx.accounts.real_token.key();
+        stake_pool.stake_mint = ctx.accounts.stake_mint.key();
+        stake_pool.stake_vault = ctx.accounts.stake_vault.key();
+        stake_pool.reward_mint = ctx.accounts.reward_mint.key();
+        stake_pool.reward_vault = ctx.accounts.reward_vault.key();
+        stake_pool.fee_share_bps = fee_share_bps;
+        stake_pool.unstake_cooldown = unstake_cooldown;
+        stake_pool.total_staked = 0;
+        stake_pool.reward_per_token = 0;
+        stake_pool.total_rewards_distributed = 0;
+        stake_pool.created_at = current_timestamp;
+        stake_pool.updated_at = current_timestamp;
+        stake_pool.bump = *ctx.bumps.get("stake_pool").unwrap();
+        
+        // Log the creation
+        msg!("Stake pool initialized for: {}", stake_pool.real_token);
+        msg!("Fee share: {}bps, Unstake cooldown: {}s", fee_share_bps, unstake_cooldown);
+        
+        Ok(())
+    }
+    
+    /// Stake REAL into the pool
+    pub fn stake(
+        ctx: Context<Stake>,
+        amount: u64,
+    ) -> Result<()> {
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        let owner = &ctx.accounts.owner;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        // New stake is new voting power, so it stops while governance is paused
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(amount > 0, RealStackError::InvalidParameters);
+        
+        // Set up a freshly created position
+        if stake_account.owner == Pubkey::default() {
+            stake_account.owner = owner.key();
+            stake_account.stake_pool = stake_pool.key();
+            stake_account.staked_amount = 0;
+            stake_account.reward_per_token_paid = stake_pool.reward_per_token;
+            stake_account.pending_rewards = 0;
+            stake_account.pending_unstake = 0;
+            stake_account.unstake_available_at = 0;
+            stake_account.vote_locked_until = 0;
+            stake_account.bump = *ctx.bumps.get("stake_account").unwrap();
+        }
+        let checkpoints = &mut ctx.accounts.checkpoints;
+        if checkpoints.owner == Pubkey::default() {
+            checkpoints.owner = owner.key();
+            checkpoints.stake_pool = stake_pool.key();
+            checkpoints.checkpoints = vec![];
+            checkpoints.bump = *ctx.bumps.get("checkpoints").unwrap();
+        }
+        
+        // Settle rewards earned at the old balance
+        settle_rewards(stake_pool, stake_account)?;
+        
+        // Move tokens into the vault
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_token_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        
+        // Update balances
+        stake_account.staked_amount = stake_account.staked_amount
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        stake_account.last_staked_at = current_timestamp;
+        stake_pool.total_staked = stake_pool.total_staked
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        stake_pool.updated_at = current_timestamp;
+        checkpoints.record(Clock::get()?.slot, current_timestamp, stake_account.voting_power());
+        
+        // Log the stake
+        msg!("Staked {} by {}", amount, owner.key());
+        msg!("Total staked: {}", stake_pool.total_staked);
+        
+        Ok(())
+    }
+    
+    /// Unstake REAL, starting the cooldown before it can be withdrawn
+    pub fn unstake(
+        ctx: Context<Unstake>,
+        amount: u64,
+    ) -> Result<()> {
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(amount > 0, RealStackError::InvalidParameters);
+        require!(
+            amount <= stake_account.staked_amount,
+            RealStackError::InsufficientStake
+        );
+        require!(
+            current_timestamp >= stake_account.vote_locked_until,
+            RealStackError::StakeLockedByVote
+        );
+        
+        // Settle rewards earned at the old balance
+        settle_rewards(stake_pool, stake_account)?;
+        
+        // Move the amount out of the active stake
+        stake_account.staked_amount = stake_account.staked_amount
+            .checked_sub(amount)
+            .ok_or(RealStackError::MathUnderflow)?;
+        stake_account.pending_unstake = stake_account.pending_unstake
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        stake_account.unstake_available_at = current_timestamp
+            .checked_add(stake_pool.unstake_cooldown)
+            .ok_or(RealStackError::MathOverflow)?;
+        stake_pool.total_staked = stake_pool.total_staked
+            .checked_sub(amount)
+            .ok_or(RealStackError::MathUnderflow)?;
+        stake_pool.updated_at = current_timestamp;
+        ctx.accounts.checkpoints.record(Clock::get()?.slot, current_timestamp, stake_account.voting_power());
+        
+        // Log the unstake
+        msg!("Unstaked {} by {}", amount, stake_account.owner);
+        msg!("Withdrawable at: {}", stake_account.unstake_available_at);
+        
+        Ok(())
+    }
+    
+    /// Withdraw unstaked REAL once the cooldown has passed
+    pub fn withdraw_unstaked(
+        ctx: Context<WithdrawUnstaked>,
+    ) -> Result<()> {
+        let stake_pool = &ctx.accounts.stake_pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        let amount = stake_account.pending_unstake;
+        require!(amount > 0, RealStackError::NoPendingUnstake);
+        require!(
+            current_timestamp >= stake_account.unstake_available_at,
+            RealStackError::StakeCooldownActive
+        );
+        
+        stake_account.pending_unstake = 0;
+        
+        // Release tokens from the vault
+        let real_token_key = stake_pool.real_token;
+        let seeds = &[b"stake_pool".as_ref(), real_token_key.as_ref(), &[stake_pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.stake_vault.to_account_info(),
+                    to: ctx.accounts.owner_token_account.to_account_info(),
+                    authority: stake_pool.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+        
+        // Log the withdrawal
+        msg!("Withdrew {} unstaked tokens for {}", amount, stake_account.owner);
+        
+        Ok(())
+    }
+    
+    /// Claim accrued staking rewards
+    pub fn claim_rewards(
+        ctx: Context<ClaimRewards>,
+    ) -> Result<()> {
+        let stake_pool = &ctx.accounts.stake_pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        
+        // Settle rewards up to the current accumulator
+        settle_rewards(stake_pool, stake_account)?;
+        
+        let amount = stake_account.pending_rewards;
+        require!(amount > 0, RealStackError::NoRewardsToClaim);
+        stake_account.pending_rewards = 0;
+        
+        // Pay out from the reward vault
+        let real_token_key = stake_pool.real_token;
+        let seeds = &[b"stake_pool".as_ref(), real_token_key.as_ref(), &[stake_pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    to: ctx.accounts.reward_destination.to_account_info(),
+                    authority: stake_pool.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+        
+        // Log the claim
+        msg!("Claimed {} rewards for {}", amount, stake_account.owner);
+        
+        Ok(())
+    }
+    
+    /// Route the stakers' share of fees collected outside the order books and pools into the reward vault
+    pub fn distribute_staking_rewards(
+        ctx: Context<DistributeStakingRewards>,
+        collected_fees: u64,
+    ) -> Result<()> {
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        require!(stake_pool.total_staked > 0, RealStackError::NoStakedTokens);
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::IncomeDistribution, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        
+        let staker_share = stake_pool.staker_share(collected_fees)?;
+        require!(staker_share > 0, RealStackError::InvalidDistributionAmount);
+        
+        // Move the stakers' share into the reward vault
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.fee_source.to_account_info(),
+                    to: ctx.accounts.reward_vault.to_account_info(),
+                    authority: ctx.accounts.fee_recipient.to_account_info(),
+                },
+            ),
+            staker_share,
+        )?;
+        
+        // Advance the accumulator
+        stake_pool.add_rewards(staker_share, current_timestamp)?;
+        
+        // Log the distribution
+        msg!("Distributed {} of {} collected fees to stakers", staker_share, collected_fees);
+        msg!("Reward per token: {}", stake_pool.reward_per_token);
+        
+        Ok(())
+    }
+    
+    /// Rewards earned by `staked_amount` since the accumulator was at `reward_per_token_paid`
+    pub fn earned_rewards(
+        staked_amount: u64,
+        reward_per_token: u128,
+        reward_per_token_paid: u128,
+    ) -> Option<u64> {
+        let delta = reward_per_token.checked_sub(reward_per_token_paid)?;
+        let earned = (staked_amount as u128).checked_mul(delta)? / REWARD_PRECISION;
+        u64::try_from(earned).ok()
+    }
+    
+    /// Move rewards accrued since the last settlement into `pending_rewards`
+    fn settle_rewards(stake_pool: &StakePool, stake_account: &mut StakeAccount) -> Result<()> {
+        let earned = earned_rewards(
+            stake_account.staked_amount,
+            stake_pool.reward_per_token,
+            stake_account.reward_per_token_paid,
+        )
+        .ok_or(RealStackError::MathOverflow)?;
+        
+        stake_account.pending_rewards = stake_account.pending_rewards
+            .checked_add(earned)
+            .ok_or(RealStackError::MathOverflow)?;
+        stake_account.reward_per_token_paid = stake_pool.reward_per_token;
+        
+        Ok(())
+    }
+}
@@ -1,6 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
 use crate::errors::*;
+use crate::access_control::{Role, Roles};
+
+/// Longest a pause can last before it lapses automatically (7 days)
+pub const MAX_PAUSE_DURATION: i64 = 7 * 24 * 60 * 60;
+
+/// Time after a pause ends before the pauser can pause again (1 day)
+pub const PAUSE_COOLDOWN: i64 = 24 * 60 * 60;
+
+/// Highest transaction fee the REAL token can charge (basis points)
+pub const MAX_TRANSACTION_FEE_BPS: u16 = 1000;
 
 /// Main REAL token data structure
 #[account]
@@ -29,7 +39,7 @@ pub struct RealToken {
     /// Token mint address
     pub mint: Pubkey,
     
-    /// If transfers are paused (emergency only)
+    /// If transfers and trading are paused (emergency only)
     pub transfers_paused: bool,
     
     /// Pending authority (for authority transfer)
@@ -40,6 +50,9 @@ pub struct RealToken {
     
     /// Token distribution details
     pub distribution: TokenDistribution,
+    
+    /// Emergency pause state
+    pub pause_state: PauseState,
 }
 
 /// Fee configuration for the REAL token
@@ -55,6 +68,57 @@ pub struct FeeConfig {
     pub fees_enabled: bool,
 }
 
+/// Emergency pause state (trading is tracked by `transfers_paused`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PauseState {
+    /// If income distribution is paused
+    pub income_distribution_paused: bool,
+    
+    /// If governance is paused
+    pub governance_paused: bool,
+    
+    /// If asset creation is paused
+    pub asset_creation_paused: bool,
+    
+    /// When the current pause lapses
+    pub expires_at: i64,
+    
+    /// When the pauser may pause again
+    pub cooldown_ends_at: i64,
+}
+
+/// Pause flags requested by the pauser
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PauseFlags {
+    /// Pause trading and transfers
+    pub trading: bool,
+    
+    /// Pause income distribution
+    pub income_distribution: bool,
+    
+    /// Pause governance
+    pub governance: bool,
+    
+    /// Pause asset creation
+    pub asset_creation: bool,
+}
+
+impl PauseFlags {
+    /// Whether any flag is set
+    pub fn any(&self) -> bool {
+        self.trading || self.income_distribution || self.governance || self.asset_creation
+    }
+}
+
+/// Platform areas covered by the circuit breaker
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PauseFlag {
+    Trading,
+    IncomeDistribution,
+    Governance,
+    AssetCreation,
+}
+
 /// Token distribution details
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
 pub struct TokenDistribution {
@@ -74,6 +138,30 @@ pub struct TokenDistribution {
     pub team_allocation: u64,
 }
 
+impl TokenDistribution {
+    /// Split a total supply into the fixed allocation buckets
+    pub fn from_total_supply(total_supply: u64) -> Self {
+        use token_operations::calculate_percentage;
+        
+        TokenDistribution {
+            community_allocation: calculate_percentage(total_supply, 40),
+            asset_reserve_allocation: calculate_percentage(total_supply, 25),
+            development_allocation: calculate_percentage(total_supply, 20),
+            liquidity_allocation: calculate_percentage(total_supply, 10),
+            team_allocation: calculate_percentage(total_supply, 5),
+        }
+    }
+    
+    /// Sum of all buckets
+    pub fn total(&self) -> Option<u64> {
+        self.community_allocation
+            .checked_add(self.asset_reserve_allocation)?
+            .checked_add(self.development_allocation)?
+            .checked_add(self.liquidity_allocation)?
+            .checked_add(self.team_allocation)
+    }
+}
+
 impl RealToken {
     /// Space required for a RealToken account
     pub const LEN: usize = 8 + // discriminator
@@ -94,21 +182,57 @@ impl RealToken {
         8 + // asset_reserve_allocation
         8 + // development_allocation
         8 + // liquidity_allocation
-        8; // team_allocation
+        8 + // team_allocation
+        1 + // income_distribution_paused
+        1 + // governance_paused
+        1 + // asset_creation_paused
+        8 + // pause expires_at
+        8; // pause cooldown_ends_at
+    
+    /// Current pause flags, ignoring expiry
+    pub fn pause_flags(&self) -> PauseFlags {
+        PauseFlags {
+            trading: self.transfers_paused,
+            income_distribution: self.pause_state.income_distribution_paused,
+            governance: self.pause_state.governance_paused,
+            asset_creation: self.pause_state.asset_creation_paused,
+        }
+    }
+    
+    /// Whether an area is paused; pauses lapse at `expires_at`
+    pub fn is_paused(&self, flag: PauseFlag, current_timestamp: i64) -> bool {
+        if current_timestamp >= self.pause_state.expires_at {
+            return false;
+        }
+        let flags = self.pause_flags();
+        match flag {
+            PauseFlag::Trading => flags.trading,
+            PauseFlag::IncomeDistribution => flags.income_distribution,
+            PauseFlag::Governance => flags.governance,
+            PauseFlag::AssetCreation => flags.asset_creation,
+        }
+    }
 }
 
 /// Context for transferring the REAL token authority
 #[derive(Accounts)]
 pub struct TransferAuthority<'info> {
-    /// The current authority
+    /// The current admin
     #[account(
-        constraint = real_token.authority == current_authority.key() @ RealStackError::Unauthorized
+        constraint = roles.has_role(current_authority.key(), Role::Admin) @ RealStackError::Unauthorized
     )]
     pub current_authority: Signer<'info>,
     
     /// The REAL token account to update
     #[account(mut)]
     pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), real_token.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
 /// Context for accepting authority transfer
@@ -123,34 +247,57 @@ pub struct AcceptAuthority<'info> {
     /// The REAL token account to update
     #[account(mut)]
     pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        mut,
+        seeds = [b"roles".as_ref(), real_token.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
 /// Context for updating fee configuration
 #[derive(Accounts)]
 pub struct UpdateFeeConfig<'info> {
-    /// The authority
+    /// A fee manager
     #[account(
-        constraint = real_token.authority == authority.key() @ RealStackError::Unauthorized
+        constraint = roles.has_role(authority.key(), Role::FeeManager) @ RealStackError::Unauthorized
     )]
     pub authority: Signer<'info>,
     
     /// The REAL token account to update
     #[account(mut)]
     pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), real_token.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
 /// Context for pausing token transfers
 #[derive(Accounts)]
 pub struct SetTransferPause<'info> {
-    /// The authority
+    /// A pauser, or the admin (unpause only)
     #[account(
-        constraint = real_token.authority == authority.key() @ RealStackError::Unauthorized
+        constraint = roles.has_role(authority.key(), Role::Pauser)
+            || roles.has_role(authority.key(), Role::Admin) @ RealStackError::Unauthorized
     )]
     pub authority: Signer<'info>,
     
     /// The REAL token account to update
     #[account(mut)]
     pub real_token: Account<'info, RealToken>,
+    
+    /// The role registry
+    #[account(
+        seeds = [b"roles".as_ref(), real_token.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
 }
 
 /// Implementation of tokenomics operations
@@ -175,11 +322,7 @@ pub mod token_operations {
         require!(total_supply > 0, RealStackError::InvalidParameters);
         
         // Calculate distribution values
-        let community_allocation = calculate_percentage(total_supply, 40);
-        let asset_reserve_allocation = calculate_percentage(total_supply, 25);
-        let development_allocation = calculate_percentage(total_supply, 20);
-        let liquidity_allocation = calculate_percentage(total_supply, 10);
-        let team_allocation = calculate_percentage(total_supply, 5);
+        let distribution = TokenDistribution::from_total_supply(total_supply);
         
         // Initialize the REAL token data
         real_token.name = name;
@@ -192,6 +335,20 @@ pub mod token_operations {
         real_token.mint = ctx.accounts.mint.key();
         real_token.transfers_paused = false;
         real_token.pending_authority = None;
+        real_token.pause_state = PauseState {
+            income_distribution_paused: false,
+            governance_paused: false,
+            asset_creation_paused: false,
+            expires_at: 0,
+            cooldown_ends_at: 0,
+        };
+        
+        // The authority starts as the sole admin
+        let roles = &mut ctx.accounts.roles;
+        roles.real_token = real_token.key();
+        roles.admin = authority.key();
+        roles.members = vec![];
+        roles.bump = *ctx.bumps.get("roles").unwrap();
         
         // Set fee config
         real_token.fee_config = FeeConfig {
@@ -201,19 +358,14 @@ pub mod token_operations {
         };
         
         // Set token distribution
-        real_token.distribution = TokenDistribution {
-            community_allocation,
-            asset_reserve_allocation,
-            development_allocation,
-            liquidity_allocation,
-            team_allocation,
-        };
+        real_token.distribution = distribution;
         
         // Log the creation
         msg!("REAL token initialized with total supply of {}", total_supply);
         msg!("Distribution: Community: {}, Asset Reserve: {}, Development: {}, Liquidity: {}, Team: {}",
-            community_allocation, asset_reserve_allocation, development_allocation, 
-            liquidity_allocation, team_allocation);
+            distribution.community_allocation, distribution.asset_reserve_allocation,
+            distribution.development_allocation, distribution.liquidity_allocation,
+            distribution.team_allocation);
         
         Ok(())
     }
@@ -245,8 +397,9 @@ pub mod token_operations {
         let new_authority = &ctx.accounts.new_authority;
         let current_timestamp = Clock::get()?.unix_timestamp;
         
-        // Complete authority transfer
+        // Complete authority transfer, handing over the admin role
         real_token.authority = new_authority.key();
+        ctx.accounts.roles.admin = new_authority.key();
         real_token.pending_authority = None;
         real_token.last_update_timestamp = current_timestamp;
         
@@ -267,7 +420,7 @@ pub mod token_operations {
         let current_timestamp = Clock::get()?.unix_timestamp;
         
         // Verify fee basis points are reasonable
-        require!(transaction_fee_bps <= 1000, RealStackError::InvalidParameters); // Max 10%
+        require!(transaction_fee_bps <= MAX_TRANSACTION_FEE_BPS, RealStackError::InvalidParameters);
         
         // Update fee config
         real_token.fee_config = FeeConfig {
@@ -290,15 +443,17 @@ pub mod token_operations {
         paused: bool,
     ) -> Result<()> {
         let real_token = &mut ctx.accounts.real_token;
+        let is_pauser = ctx.accounts.roles.has_role(ctx.accounts.authority.key(), Role::Pauser);
         let current_timestamp = Clock::get()?.unix_timestamp;
         
-        // Update pause state
-        real_token.transfers_paused = paused;
-        real_token.last_update_timestamp = current_timestamp;
+        // Only the trading flag changes
+        let mut flags = active_pause_flags(real_token, current_timestamp);
+        flags.trading = paused;
+        apply_pause_flags(real_token, is_pauser, flags, current_timestamp)?;
         
         // Log the update
         if paused {
-            msg!("Token transfers have been PAUSED");
+            msg!("Token transfers have been PAUSED until {}", real_token.pause_state.expires_at);
         } else {
             msg!("Token transfers have been UNPAUSED");
         }
@@ -306,9 +461,86 @@ pub mod token_operations {
         Ok(())
     }
     
+    /// Set all pause flags at once (emergency function)
+    pub fn set_pause_flags(
+        ctx: Context<SetTransferPause>,
+        flags: PauseFlags,
+    ) -> Result<()> {
+        let real_token = &mut ctx.accounts.real_token;
+        let is_pauser = ctx.accounts.roles.has_role(ctx.accounts.authority.key(), Role::Pauser);
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        apply_pause_flags(real_token, is_pauser, flags, current_timestamp)?;
+        
+        // Log the update
+        msg!("Pause flags: trading: {}, income: {}, governance: {}, asset creation: {}",
+            flags.trading, flags.income_distribution, flags.governance, flags.asset_creation);
+        if flags.any() {
+            msg!("Pause lapses at: {}", real_token.pause_state.expires_at);
+        }
+        
+        Ok(())
+    }
+    
+    /// Pause flags still in force (a lapsed pause reads as all clear)
+    fn active_pause_flags(real_token: &RealToken, current_timestamp: i64) -> PauseFlags {
+        if current_timestamp >= real_token.pause_state.expires_at {
+            return PauseFlags {
+                trading: false,
+                income_distribution: false,
+                governance: false,
+                asset_creation: false,
+            };
+        }
+        real_token.pause_flags()
+    }
+    
+    /// Apply new pause flags, enforcing the pause window and cooldown
+    fn apply_pause_flags(
+        real_token: &mut RealToken,
+        is_pauser: bool,
+        flags: PauseFlags,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        let was_paused = active_pause_flags(real_token, current_timestamp).any();
+        
+        if flags.any() {
+            // Only a pauser can pause
+            require!(is_pauser, RealStackError::Unauthorized);
+            
+            // A new pause gets a fixed window; an ongoing one cannot be extended
+            if !was_paused {
+                require!(
+                    current_timestamp >= real_token.pause_state.cooldown_ends_at,
+                    RealStackError::PauseCooldownActive
+                );
+                real_token.pause_state.expires_at = current_timestamp
+                    .checked_add(MAX_PAUSE_DURATION)
+                    .ok_or(RealStackError::MathOverflow)?;
+                real_token.pause_state.cooldown_ends_at = real_token.pause_state.expires_at
+                    .checked_add(PAUSE_COOLDOWN)
+                    .ok_or(RealStackError::MathOverflow)?;
+            }
+        } else if was_paused {
+            // Ending a pause early still starts the cooldown
+            real_token.pause_state.expires_at = current_timestamp;
+            real_token.pause_state.cooldown_ends_at = current_timestamp
+                .checked_add(PAUSE_COOLDOWN)
+                .ok_or(RealStackError::MathOverflow)?;
+        }
+        
+        real_token.transfers_paused = flags.trading;
+        real_token.pause_state.income_distribution_paused = flags.income_distribution;
+        real_token.pause_state.governance_paused = flags.governance;
+        real_token.pause_state.asset_creation_paused = flags.asset_creation;
+        real_token.last_update_timestamp = current_timestamp;
+        
+        Ok(())
+    }
+    
     /// Helper function to calculate percentage of a value
-    fn calculate_percentage(value: u64, percentage: u8) -> u64 {
-        // Multiply by percentage and divide by 100
+    pub fn calculate_percentage(value: u64, percentage: u8) -> u64 {
+        // Multiply by percentage and divide by 100; never exceeds value for percentage <= 100
         (value as u128 * percentage as u128 / 100) as u64
     }
 } 
\ No newline at end of file
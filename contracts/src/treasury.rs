@@ -0,0 +1,785 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::errors::*;
+use crate::governance::{governance_operations, GovernanceConfig, Proposal, ProposalType};
+use crate::tokenomics::{PauseFlag, RealToken};
+
+/// Longest spending period a treasury can use (365 days)
+pub const MAX_SPENDING_PERIOD: i64 = 365 * 24 * 60 * 60;
+
+/// Governance treasury whose PDA owns every vault's tokens
+#[account]
+pub struct Treasury {
+    /// Governance config whose proposals control the treasury
+    pub governance_config: Pubkey,
+    
+    /// Length of a spending period (in seconds)
+    pub spending_period: i64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// PDA bump (the treasury signs for its vaults)
+    pub bump: u8,
+}
+
+impl Treasury {
+    /// Space required for a Treasury account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance_config
+        8 + // spending_period
+        8 + // created_at
+        1; // bump
+}
+
+/// One token held by the treasury, with its spending cap
+#[account]
+pub struct TreasuryVault {
+    /// The treasury owning the vault
+    pub treasury: Pubkey,
+    
+    /// Mint of the held token
+    pub mint: Pubkey,
+    
+    /// Token account holding the funds (authority: the treasury)
+    pub token_account: Pubkey,
+    
+    /// Most that can be committed in one spending period
+    pub spending_cap: u64,
+    
+    /// When the current spending period started
+    pub period_started_at: i64,
+    
+    /// Amount committed in the current spending period
+    pub spent_in_period: u64,
+    
+    /// Funds owed to streams but not yet claimed
+    pub reserved: u64,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl TreasuryVault {
+    /// Space required for a TreasuryVault account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // treasury
+        32 + // mint
+        32 + // token_account
+        8 + // spending_cap
+        8 + // period_started_at
+        8 + // spent_in_period
+        8 + // reserved
+        1; // bump
+}
+
+/// A grant paid out linearly between two timestamps
+#[account]
+pub struct TreasuryStream {
+    /// The treasury paying the stream
+    pub treasury: Pubkey,
+    
+    /// The vault the stream is paid from
+    pub vault: Pubkey,
+    
+    /// The proposal that started the stream
+    pub proposal: Pubkey,
+    
+    /// Token account receiving the payments
+    pub recipient: Pubkey,
+    
+    /// Amount paid over the whole stream
+    pub total_amount: u64,
+    
+    /// Amount claimed so far
+    pub claimed_amount: u64,
+    
+    /// When payments start accruing
+    pub start_at: i64,
+    
+    /// When the full amount has accrued
+    pub end_at: i64,
+    
+    /// When governance cancelled the stream, if it did
+    pub cancelled_at: Option<i64>,
+    
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl TreasuryStream {
+    /// Space required for a TreasuryStream account
+    pub const LEN: usize = 8 + // discriminator
+        32 + // treasury
+        32 + // vault
+        32 + // proposal
+        32 + // recipient
+        8 + // total_amount
+        8 + // claimed_amount
+        8 + // start_at
+        8 + // end_at
+        9 + // cancelled_at (Option<i64>)
+        1; // bump
+    
+    /// Amount accrued by `timestamp`, frozen at cancellation
+    pub fn vested_at(&self, timestamp: i64) -> u64 {
+        let until = self.cancelled_at.map_or(timestamp, |cancelled_at| cancelled_at.min(timestamp));
+        treasury_operations::vested_amount(self.total_amount, self.start_at, self.end_at, until)
+    }
+}
+
+/// What an approved treasury proposal does, stored in its execution data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TreasuryAction {
+    /// Pay `amount` of `mint` to a token account at once
+    Transfer {
+        mint: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+    },
+    
+    /// Pay `amount` of `mint` to a token account linearly from `start_at` to `end_at`
+    Stream {
+        mint: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+        start_at: i64,
+        end_at: i64,
+    },
+    
+    /// Stop a stream; what has accrued stays claimable
+    CancelStream {
+        stream: Pubkey,
+    },
+}
+
+impl TreasuryAction {
+    /// The proposal type the action is voted on as
+    pub fn proposal_type(&self) -> ProposalType {
+        match self {
+            TreasuryAction::Stream { .. } => ProposalType::CommunityFunding,
+            _ => ProposalType::TreasuryTransfer,
+        }
+    }
+    
+    /// Reject actions that could never execute
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            TreasuryAction::Transfer { amount, .. } => {
+                require!(amount > 0, RealStackError::InvalidParameters);
+            },
+            TreasuryAction::Stream { amount, start_at, end_at, .. } => {
+                require!(amount > 0, RealStackError::InvalidParameters);
+                require!(end_at > start_at, RealStackError::InvalidParameters);
+            },
+            TreasuryAction::CancelStream { .. } => {},
+        }
+        Ok(())
+    }
+}
+
+/// Context for creating the governance treasury
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    /// The governance authority
+    #[account(
+        mut,
+        constraint = governance_config.authority == authority.key() @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// Governance config
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The treasury to create
+    #[account(
+        init,
+        payer = authority,
+        space = Treasury::LEN,
+        seeds = [b"treasury".as_ref(), governance_config.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for adding a token to the treasury
+#[derive(Accounts)]
+pub struct CreateTreasuryVault<'info> {
+    /// The governance authority
+    #[account(
+        mut,
+        constraint = governance_config.authority == authority.key() @ RealStackError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    /// Governance config
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The treasury
+    #[account(
+        has_one = governance_config @ RealStackError::InvalidParameters
+    )]
+    pub treasury: Account<'info, Treasury>,
+    
+    /// Mint of the token to hold
+    pub mint: Account<'info, Mint>,
+    
+    /// The vault to create
+    #[account(
+        init,
+        payer = authority,
+        space = TreasuryVault::LEN,
+        seeds = [b"treasury_vault".as_ref(), treasury.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TreasuryVault>,
+    
+    /// Token account holding the vault's funds
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"treasury_tokens".as_ref(), vault.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = treasury
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Context for paying out an approved treasury transfer
+#[derive(Accounts)]
+pub struct ExecuteTreasuryTransfer<'info> {
+    /// Anyone can execute an approved proposal
+    pub executor: Signer<'info>,
+    
+    /// The proposal to execute
+    #[account(
+        mut,
+        constraint = proposal.governance_config == treasury.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Governance config
+    #[account(
+        address = treasury.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The treasury
+    pub treasury: Account<'info, Treasury>,
+    
+    /// The vault paying the transfer
+    #[account(
+        mut,
+        has_one = treasury @ RealStackError::InvalidParameters
+    )]
+    pub vault: Account<'info, TreasuryVault>,
+    
+    /// The vault's token account
+    #[account(
+        mut,
+        address = vault.token_account @ RealStackError::InvalidTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    /// The recipient's token account
+    #[account(mut)]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Context for starting an approved treasury stream
+#[derive(Accounts)]
+pub struct ExecuteTreasuryStream<'info> {
+    /// Anyone can execute an approved proposal; the executor pays the stream's rent
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    
+    /// The proposal to execute
+    #[account(
+        mut,
+        constraint = proposal.governance_config == treasury.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Governance config
+    #[account(
+        address = treasury.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The treasury
+    pub treasury: Account<'info, Treasury>,
+    
+    /// The vault paying the stream
+    #[account(
+        mut,
+        has_one = treasury @ RealStackError::InvalidParameters
+    )]
+    pub vault: Account<'info, TreasuryVault>,
+    
+    /// The vault's token account
+    #[account(
+        address = vault.token_account @ RealStackError::InvalidTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    /// The stream to create
+    #[account(
+        init,
+        payer = executor,
+        space = TreasuryStream::LEN,
+        seeds = [b"treasury_stream".as_ref(), proposal.key().as_ref()],
+        bump
+    )]
+    pub stream: Account<'info, TreasuryStream>,
+    
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for cancelling a stream through an approved proposal
+#[derive(Accounts)]
+pub struct ExecuteStreamCancellation<'info> {
+    /// Anyone can execute an approved proposal
+    pub executor: Signer<'info>,
+    
+    /// The proposal to execute
+    #[account(
+        mut,
+        constraint = proposal.governance_config == treasury.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Governance config
+    #[account(
+        address = treasury.governance_config @ RealStackError::InvalidParameters
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// The treasury
+    pub treasury: Account<'info, Treasury>,
+    
+    /// The vault paying the stream
+    #[account(
+        mut,
+        has_one = treasury @ RealStackError::InvalidParameters
+    )]
+    pub vault: Account<'info, TreasuryVault>,
+    
+    /// The stream to cancel
+    #[account(
+        mut,
+        has_one = vault @ RealStackError::InvalidParameters
+    )]
+    pub stream: Account<'info, TreasuryStream>,
+}
+
+/// Context for paying out what a stream has accrued
+#[derive(Accounts)]
+pub struct ClaimStream<'info> {
+    /// Anyone can crank a claim; funds only go to the recipient
+    pub claimer: Signer<'info>,
+    
+    /// The treasury
+    pub treasury: Account<'info, Treasury>,
+    
+    /// The vault paying the stream
+    #[account(
+        mut,
+        has_one = treasury @ RealStackError::InvalidParameters
+    )]
+    pub vault: Account<'info, TreasuryVault>,
+    
+    /// The vault's token account
+    #[account(
+        mut,
+        address = vault.token_account @ RealStackError::InvalidTokenAccount
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    
+    /// The stream to claim from
+    #[account(
+        mut,
+        has_one = vault @ RealStackError::InvalidParameters
+    )]
+    pub stream: Account<'info, TreasuryStream>,
+    
+    /// The recipient's token account
+    #[account(
+        mut,
+        address = stream.recipient @ RealStackError::InvalidTokenAccount
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Treasury operations implementation
+pub mod treasury_operations {
+    use super::*;
+    
+    /// Create the treasury for a governance config
+    pub fn initialize_treasury(
+        ctx: Context<InitializeTreasury>,
+        spending_period: i64,
+    ) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+        
+        require!(
+            spending_period > 0 && spending_period <= MAX_SPENDING_PERIOD,
+            RealStackError::InvalidParameters
+        );
+        
+        treasury.governance_config = ctx.accounts.governance_config.key();
+        treasury.spending_period = spending_period;
+        treasury.created_at = Clock::get()?.unix_timestamp;
+        treasury.bump = *ctx.bumps.get("treasury").unwrap();
+        
+        // Log the initialization
+        msg!("Treasury initialized for: {}", treasury.governance_config);
+        msg!("Spending period: {}s", spending_period);
+        
+        Ok(())
+    }
+    
+    /// Add a token vault with a per-period spending cap
+    pub fn create_treasury_vault(
+        ctx: Context<CreateTreasuryVault>,
+        spending_cap: u64,
+    ) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        
+        require!(spending_cap > 0, RealStackError::InvalidParameters);
+        
+        vault.treasury = ctx.accounts.treasury.key();
+        vault.mint = ctx.accounts.mint.key();
+        vault.token_account = ctx.accounts.vault_token_account.key();
+        vault.spending_cap = spending_cap;
+        vault.period_started_at = Clock::get()?.unix_timestamp;
+        vault.spent_in_period = 0;
+        vault.reserved = 0;
+        vault.bump = *ctx.bumps.get("vault").unwrap();
+        
+        // Log the creation
+        msg!("Treasury vault created for mint: {}", vault.mint);
+        msg!("Spending cap: {} per period", spending_cap);
+        
+        Ok(())
+    }
+    
+    /// Pay out an approved treasury transfer
+    pub fn execute_treasury_transfer(
+        ctx: Context<ExecuteTreasuryTransfer>,
+    ) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let action = match finalize_proposal(
+            &mut ctx.accounts.proposal,
+            &ctx.accounts.real_token,
+            ctx.accounts.executor.key(),
+            current_timestamp,
+        )? {
+            Some(action) => action,
+            None => return Ok(()),
+        };
+        let (mint, recipient, amount) = match action {
+            TreasuryAction::Transfer { mint, recipient, amount } => (mint, recipient, amount),
+            _ => return err!(RealStackError::WrongExecutionInstruction),
+        };
+        require!(ctx.accounts.vault.mint == mint, RealStackError::InvalidTokenMint);
+        require!(
+            ctx.accounts.recipient_token_account.key() == recipient,
+            RealStackError::InvalidTokenAccount
+        );
+        
+        commit_spending(
+            &mut ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            &ctx.accounts.vault_token_account,
+            amount,
+            current_timestamp,
+        )?;
+        
+        // Pay the recipient, signed by the treasury
+        let treasury = &ctx.accounts.treasury;
+        let seeds = &[b"treasury".as_ref(), treasury.governance_config.as_ref(), &[treasury.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: treasury.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
+        )?;
+        
+        // Log the transfer
+        msg!("Treasury transfer executed: {}", ctx.accounts.proposal.title);
+        msg!("Amount: {}, Recipient: {}", amount, recipient);
+        
+        Ok(())
+    }
+    
+    /// Start an approved treasury stream
+    pub fn execute_treasury_stream(
+        ctx: Context<ExecuteTreasuryStream>,
+    ) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let action = match finalize_proposal(
+            &mut ctx.accounts.proposal,
+            &ctx.accounts.real_token,
+            ctx.accounts.executor.key(),
+            current_timestamp,
+        )? {
+            Some(action) => action,
+            None => return Ok(()),
+        };
+        let (mint, recipient, amount, start_at, end_at) = match action {
+            TreasuryAction::Stream { mint, recipient, amount, start_at, end_at } => (mint, recipient, amount, start_at, end_at),
+            _ => return err!(RealStackError::WrongExecutionInstruction),
+        };
+        require!(ctx.accounts.vault.mint == mint, RealStackError::InvalidTokenMint);
+        
+        // The whole stream counts against the cap and is set aside up front
+        commit_spending(
+            &mut ctx.accounts.vault,
+            &ctx.accounts.treasury,
+            &ctx.accounts.vault_token_account,
+            amount,
+            current_timestamp,
+        )?;
+        let vault = &mut ctx.accounts.vault;
+        vault.reserved = vault.reserved
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        
+        let stream = &mut ctx.accounts.stream;
+        stream.treasury = ctx.accounts.treasury.key();
+        stream.vault = vault.key();
+        stream.proposal = ctx.accounts.proposal.key();
+        stream.recipient = recipient;
+        stream.total_amount = amount;
+        stream.claimed_amount = 0;
+        stream.start_at = start_at;
+        stream.end_at = end_at;
+        stream.cancelled_at = None;
+        stream.bump = *ctx.bumps.get("stream").unwrap();
+        
+        // Log the stream
+        msg!("Treasury stream started: {}", ctx.accounts.proposal.title);
+        msg!("Amount: {}, Recipient: {}, From: {}, To: {}", amount, recipient, start_at, end_at);
+        
+        Ok(())
+    }
+    
+    /// Cancel a stream through an approved proposal
+    pub fn execute_stream_cancellation(
+        ctx: Context<ExecuteStreamCancellation>,
+    ) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let action = match finalize_proposal(
+            &mut ctx.accounts.proposal,
+            &ctx.accounts.real_token,
+            ctx.accounts.executor.key(),
+            current_timestamp,
+        )? {
+            Some(action) => action,
+            None => return Ok(()),
+        };
+        let stream_key = match action {
+            TreasuryAction::CancelStream { stream: stream_key } => stream_key,
+            _ => return err!(RealStackError::WrongExecutionInstruction),
+        };
+        require!(
+            ctx.accounts.stream.key() == stream_key,
+            RealStackError::InvalidParameters
+        );
+        
+        let stream = &mut ctx.accounts.stream;
+        require!(stream.cancelled_at.is_none(), RealStackError::StreamCancelled);
+        
+        // Release what had not accrued yet back to the vault
+        let vested = stream.vested_at(current_timestamp);
+        stream.cancelled_at = Some(current_timestamp);
+        let unvested = stream.total_amount
+            .checked_sub(vested)
+            .ok_or(RealStackError::MathUnderflow)?;
+        let vault = &mut ctx.accounts.vault;
+        vault.reserved = vault.reserved
+            .checked_sub(unvested)
+            .ok_or(RealStackError::MathUnderflow)?;
+        
+        // Log the cancellation
+        msg!("Treasury stream cancelled: {}", stream.key());
+        msg!("Accrued: {}, Released: {}", vested, unvested);
+        
+        Ok(())
+    }
+    
+    /// Pay a stream's recipient everything accrued and not yet claimed
+    pub fn claim_stream(
+        ctx: Context<ClaimStream>,
+    ) -> Result<()> {
+        let stream = &mut ctx.accounts.stream;
+        let vault = &mut ctx.accounts.vault;
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        
+        let claimable = stream.vested_at(current_timestamp)
+            .checked_sub(stream.claimed_amount)
+            .ok_or(RealStackError::MathUnderflow)?;
+        require!(claimable > 0, RealStackError::NothingToClaim);
+        
+        stream.claimed_amount = stream.claimed_amount
+            .checked_add(claimable)
+            .ok_or(RealStackError::MathOverflow)?;
+        vault.reserved = vault.reserved
+            .checked_sub(claimable)
+            .ok_or(RealStackError::MathUnderflow)?;
+        
+        // Pay the recipient, signed by the treasury
+        let treasury = &ctx.accounts.treasury;
+        let seeds = &[b"treasury".as_ref(), treasury.governance_config.as_ref(), &[treasury.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: treasury.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            claimable,
+        )?;
+        
+        // Log the claim
+        msg!("Stream claimed: {}", claimable);
+        msg!("Claimed so far: {} of {}", stream.claimed_amount, stream.total_amount);
+        
+        Ok(())
+    }
+    
+    /// Check an ended proposal's result, mark it executed and return its action if approved
+    fn finalize_proposal(
+        proposal: &mut Proposal,
+        real_token: &RealToken,
+        executor: Pubkey,
+        current_timestamp: i64,
+    ) -> Result<Option<TreasuryAction>> {
+        require!(
+            !real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(
+            matches!(proposal.proposal_type, ProposalType::TreasuryTransfer | ProposalType::CommunityFunding),
+            RealStackError::WrongExecutionInstruction
+        );
+        require!(proposal.is_active, RealStackError::ProposalInactive);
+        require!(
+            current_timestamp >= proposal.voting_ends_at,
+            RealStackError::VotingPeriodNotEnded
+        );
+        require!(!proposal.executed, RealStackError::ProposalAlreadyExecuted);
+        
+        // Check if quorum was reached; abstentions count toward it
+        let total_votes = governance_operations::total_votes(proposal)?;
+        require!(
+            total_votes >= proposal.quorum_votes,
+            RealStackError::InvalidParameters
+        );
+        let action = TreasuryAction::try_from_slice(&proposal.execution_data)?;
+        
+        proposal.executed = true;
+        proposal.is_active = false;
+        proposal.executed_at = current_timestamp;
+        proposal.executor = Some(executor);
+        
+        if !governance_operations::is_approved(
+            proposal.yes_votes,
+            proposal.no_votes,
+            proposal.approval_threshold_percentage,
+        ) {
+            msg!("Treasury proposal failed: {}", proposal.title);
+            return Ok(None);
+        }
+        Ok(Some(action))
+    }
+    
+    /// Count `amount` against the vault's cap and unreserved balance
+    fn commit_spending(
+        vault: &mut TreasuryVault,
+        treasury: &Treasury,
+        vault_token_account: &TokenAccount,
+        amount: u64,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        // Start a new spending period once the current one has elapsed
+        let period_ends_at = vault.period_started_at
+            .checked_add(treasury.spending_period)
+            .ok_or(RealStackError::MathOverflow)?;
+        if current_timestamp >= period_ends_at {
+            vault.period_started_at = current_timestamp;
+            vault.spent_in_period = 0;
+        }
+        
+        let spent = vault.spent_in_period
+            .checked_add(amount)
+            .ok_or(RealStackError::MathOverflow)?;
+        require!(spent <= vault.spending_cap, RealStackError::SpendingCapExceeded);
+        
+        let available = vault_token_account.amount.saturating_sub(vault.reserved);
+        require!(amount <= available, RealStackError::InsufficientTreasuryFunds);
+        
+        vault.spent_in_period = spent;
+        Ok(())
+    }
+    
+    /// Amount of a linear stream of `total` accrued by `timestamp`
+    pub fn vested_amount(total: u64, start_at: i64, end_at: i64, timestamp: i64) -> u64 {
+        if timestamp <= start_at {
+            return 0;
+        }
+        if timestamp >= end_at {
+            return total;
+        }
+        let elapsed = (timestamp as i128 - start_at as i128) as u128;
+        let duration = (end_at as i128 - start_at as i128) as u128;
+        (total as u128 * elapsed / duration) as u64
+    }
+}
@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::errors::*;
+use crate::governance::{governance_operations, GovernanceConfig, Proposal, ProposalType};
+use crate::tokenomics::{PauseFlag, RealToken};
+
+/// Delay between the end of voting and the earliest upgrade (72 hours)
+pub const UPGRADE_TIMELOCK: i64 = 3 * 24 * 60 * 60;
+
+/// Buffer deployment carried by a `ProgramUpgrade` proposal in its execution data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UpgradeAction {
+    /// Loader buffer holding the new program (authority: the upgrade authority PDA)
+    pub buffer: Pubkey,
+    
+    /// SHA-256 of the program bytes written to the buffer
+    pub buffer_hash: [u8; 32],
+}
+
+impl UpgradeAction {
+    /// Reject actions that could never execute
+    pub fn validate(&self) -> Result<()> {
+        require!(self.buffer != Pubkey::default(), RealStackError::InvalidParameters);
+        Ok(())
+    }
+}
+
+/// PDA that governance hands the program's upgrade authority to
+pub fn upgrade_authority(governance_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"upgrade_authority".as_ref(), governance_config.as_ref()], &crate::ID)
+}
+
+/// Context for executing an approved program upgrade
+#[derive(Accounts)]
+pub struct ExecuteProgramUpgrade<'info> {
+    /// Anyone can execute an approved proposal
+    pub executor: Signer<'info>,
+    
+    /// The proposal to execute
+    #[account(
+        mut,
+        constraint = proposal.governance_config == governance_config.key() @ RealStackError::InvalidParameters
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Governance config
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    /// The REAL token account whose pause flags apply
+    #[account(
+        address = governance_config.real_token @ RealStackError::InvalidParameters
+    )]
+    pub real_token: Account<'info, RealToken>,
+    
+    /// CHECK: PDA holding the program's upgrade authority; signs the upgrade
+    #[account(
+        seeds = [b"upgrade_authority".as_ref(), governance_config.key().as_ref()],
+        bump
+    )]
+    pub upgrade_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: This program, validated by address
+    #[account(
+        mut,
+        address = crate::ID @ RealStackError::InvalidParameters
+    )]
+    pub program: UncheckedAccount<'info>,
+    
+    /// CHECK: This program's data account, validated by the loader's derivation
+    #[account(
+        mut,
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID
+    )]
+    pub program_data: UncheckedAccount<'info>,
+    
+    /// CHECK: Buffer named by the proposal; its owner and hash are checked before use
+    #[account(mut)]
+    pub buffer: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives the buffer's lamports once it is consumed
+    #[account(mut)]
+    pub spill: UncheckedAccount<'info>,
+    
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+    
+    /// Clock sysvar
+    pub clock: Sysvar<'info, Clock>,
+    
+    /// CHECK: The BPF upgradeable loader
+    #[account(
+        address = bpf_loader_upgradeable::ID @ RealStackError::InvalidParameters
+    )]
+    pub bpf_loader_upgradeable: UncheckedAccount<'info>,
+}
+
+pub mod upgrade_operations {
+    use super::*;
+    
+    /// Upgrade the program from an approved proposal's buffer once the timelock has passed
+    pub fn execute_program_upgrade(
+        ctx: Context<ExecuteProgramUpgrade>,
+    ) -> Result<()> {
+        let current_timestamp = ctx.accounts.clock.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        
+        require!(
+            !ctx.accounts.real_token.is_paused(PauseFlag::Governance, current_timestamp),
+            RealStackError::OperationPaused
+        );
+        require!(
+            proposal.proposal_type == ProposalType::ProgramUpgrade,
+            RealStackError::WrongExecutionInstruction
+        );
+        require!(proposal.asset_token.is_none(), RealStackError::ProposalScopeMismatch);
+        require!(proposal.is_active, RealStackError::ProposalInactive);
+        require!(
+            current_timestamp >= proposal.voting_ends_at,
+            RealStackError::VotingPeriodNotEnded
+        );
+        require!(!proposal.executed, RealStackError::ProposalAlreadyExecuted);
+        
+        // Check if quorum was reached; abstentions count toward it
+        let total_votes = governance_operations::total_votes(proposal)?;
+        require!(
+            total_votes >= proposal.quorum_votes,
+            RealStackError::InvalidParameters
+        );
+        let action = UpgradeAction::try_from_slice(&proposal.execution_data)?;
+        
+        let approved = governance_operations::is_approved(
+            proposal.yes_votes,
+            proposal.no_votes,
+            proposal.approval_threshold_percentage,
+        );
+        if approved {
+            // Holders get the timelock to exit before the new code goes live
+            let unlocks_at = proposal
+                .voting_ends_at
+                .checked_add(UPGRADE_TIMELOCK)
+                .ok_or(RealStackError::MathOverflow)?;
+            require!(current_timestamp >= unlocks_at, RealStackError::TimelockNotExpired);
+        }
+        
+        proposal.executed = true;
+        proposal.is_active = false;
+        proposal.executed_at = current_timestamp;
+        proposal.executor = Some(ctx.accounts.executor.key());
+        
+        if !approved {
+            msg!("Upgrade proposal failed: {}", proposal.title);
+            return Ok(());
+        }
+        
+        // The buffer must be the one voted on, holding exactly the bytes voted on
+        let buffer = &ctx.accounts.buffer;
+        require!(buffer.key() == action.buffer, RealStackError::InvalidUpgradeBuffer);
+        require!(
+            *buffer.owner == bpf_loader_upgradeable::ID,
+            RealStackError::InvalidUpgradeBuffer
+        );
+        {
+            let data = buffer.try_borrow_data()?;
+            let metadata_len = UpgradeableLoaderState::size_of_buffer_metadata();
+            require!(data.len() > metadata_len, RealStackError::InvalidUpgradeBuffer);
+            require!(
+                hash(&data[metadata_len..]).to_bytes() == action.buffer_hash,
+                RealStackError::BufferHashMismatch
+            );
+        }
+        
+        // Deploy the buffer, signed by the upgrade authority
+        let governance_config = ctx.accounts.governance_config.key();
+        let bump = *ctx.bumps.get("upgrade_authority").unwrap();
+        let seeds = &[b"upgrade_authority".as_ref(), governance_config.as_ref(), &[bump]];
+        invoke_signed(
+            &bpf_loader_upgradeable::upgrade(
+                &crate::ID,
+                &action.buffer,
+                &ctx.accounts.upgrade_authority.key(),
+                &ctx.accounts.spill.key(),
+            ),
+            &[
+                ctx.accounts.program_data.to_account_info(),
+                ctx.accounts.program.to_account_info(),
+                buffer.to_account_info(),
+                ctx.accounts.spill.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+                ctx.accounts.clock.to_account_info(),
+                ctx.accounts.upgrade_authority.to_account_info(),
+                ctx.accounts.bpf_loader_upgradeable.to_account_info(),
+            ],
+            &[&seeds[..]],
+        )?;
+        
+        // Log the upgrade
+        msg!("Program upgraded by proposal: {}", ctx.accounts.proposal.title);
+        msg!("Buffer: {}", action.buffer);
+        
+        Ok(())
+    }
+}
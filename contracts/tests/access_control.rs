@@ -0,0 +1,96 @@
+mod common;
+
+use common::*;
+use realstack::{RealStackError, Role, MAX_ROLE_MEMBERS};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+fn revoke_role_ix(fixture: &TestFixture, admin: &Pubkey, member: Pubkey, role: Role) -> Instruction {
+    ix(
+        realstack::accounts::ManageRoles {
+            admin: *admin,
+            roles: fixture.roles,
+        },
+        realstack::instruction::RevokeRole { member, role },
+    )
+}
+
+#[tokio::test]
+async fn grant_and_revoke_roles() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let member = Pubkey::new_unique();
+
+    fixture.grant_role(member, Role::Treasurer).await;
+    fixture.grant_role(member, Role::Verifier).await;
+    let roles = fixture.roles_state().await;
+    assert!(roles.has_role(member, Role::Treasurer));
+    assert!(roles.has_role(member, Role::Verifier));
+
+    let instruction = revoke_role_ix(&fixture, &payer, member, Role::Treasurer);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let roles = fixture.roles_state().await;
+    assert!(!roles.has_role(member, Role::Treasurer));
+    assert!(roles.has_role(member, Role::Verifier));
+
+    // The entry is dropped with its last role
+    let instruction = revoke_role_ix(&fixture, &payer, member, Role::Verifier);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let roles = fixture.roles_state().await;
+    assert!(roles.members.iter().all(|m| m.member != member));
+}
+
+#[tokio::test]
+async fn role_changes_reject_admin_role() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let member = Pubkey::new_unique();
+
+    let instruction = fixture.grant_role_ix(&payer, member, Role::Admin);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = revoke_role_ix(&fixture, &payer, payer, Role::Admin);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+}
+
+#[tokio::test]
+async fn revoke_rejects_role_not_held() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+
+    let instruction = revoke_role_ix(&fixture, &payer, Pubkey::new_unique(), Role::Pauser);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::RoleNotHeld);
+}
+
+#[tokio::test]
+async fn grant_rejects_full_registry() {
+    let mut fixture = TestFixture::new().await;
+    let roles = fixture.roles_state().await;
+
+    for _ in roles.members.len()..MAX_ROLE_MEMBERS {
+        fixture.grant_role(Pubkey::new_unique(), Role::Treasurer).await;
+    }
+
+    let instruction = fixture.grant_role_ix(&fixture.payer_key(), Pubkey::new_unique(), Role::Treasurer);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::RoleListFull);
+}
+
+#[tokio::test]
+async fn role_changes_require_admin() {
+    let mut fixture = TestFixture::new().await;
+    let intruder = fixture.new_actor().await;
+
+    let instruction = fixture.grant_role_ix(&intruder.pubkey(), intruder.pubkey(), Role::Pauser);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = revoke_role_ix(&fixture, &intruder.pubkey(), fixture.payer_key(), Role::Pauser);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
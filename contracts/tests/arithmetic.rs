@@ -0,0 +1,334 @@
+//! Property tests for the pure arithmetic behind tokenomics and governance.
+//!
+//! These run without a validator: they exercise the helpers that the
+//! instruction handlers delegate to.
+
+use proptest::prelude::*;
+use realstack::governance_operations::{
+    add_votes, conviction_multiplier, is_approved, resolve_plurality, resolve_ranked_choice, voting_weight,
+    yes_percentage,
+};
+use realstack::token_operations::calculate_percentage;
+use realstack::treasury_operations::vested_amount;
+use realstack::{
+    RankedBallot, TokenDistribution, VotingMode, VotingPowerCheckpoints, CONVICTION_PERIOD, MAX_CHECKPOINTS,
+    MAX_CONVICTION_LOCK, MAX_CONVICTION_MULTIPLIER, MAX_PROPOSAL_OPTIONS, MAX_SNAPSHOT_AGE,
+};
+use solana_sdk::pubkey::Pubkey;
+
+/// An option count and ballots ranking a prefix of a shuffle of its options
+fn ranked_ballots() -> impl Strategy<Value = (usize, Vec<RankedBallot>)> {
+    (2..=MAX_PROPOSAL_OPTIONS).prop_flat_map(|option_count| {
+        let ballot = (
+            Just((0..option_count as u8).collect::<Vec<u8>>()).prop_shuffle(),
+            1..=option_count,
+            1u64..1_000_000,
+        )
+            .prop_map(|(mut ranking, length, weight)| {
+                ranking.truncate(length);
+                RankedBallot { ranking, weight }
+            });
+        (Just(option_count), prop::collection::vec(ballot, 0..16))
+    })
+}
+
+/// Sum each voter's tally weight under `mode`
+fn tally(mode: VotingMode, votes: &[(u64, i64)]) -> u64 {
+    votes
+        .iter()
+        .map(|&(tokens, lock_duration)| voting_weight(mode, tokens, lock_duration).unwrap())
+        .sum()
+}
+
+#[test]
+fn quadratic_weight_matches_known_vectors() {
+    let vectors = [(0, 0), (1, 1), (3, 1), (4, 2), (99, 9), (100, 10), (1_000_000, 1_000), (u64::MAX, u32::MAX as u64)];
+    for (tokens, weight) in vectors {
+        assert_eq!(voting_weight(VotingMode::Quadratic, tokens, 0).unwrap(), weight);
+    }
+}
+
+#[test]
+fn conviction_weight_matches_known_vectors() {
+    let vectors = [
+        (0, 100),
+        (CONVICTION_PERIOD - 1, 100),
+        (CONVICTION_PERIOD, 200),
+        (3 * CONVICTION_PERIOD + 1, 400),
+        (MAX_CONVICTION_LOCK, 600),
+    ];
+    for (lock_duration, weight) in vectors {
+        assert_eq!(voting_weight(VotingMode::Conviction, 100, lock_duration).unwrap(), weight);
+    }
+
+    assert!(voting_weight(VotingMode::Conviction, 100, -1).is_err());
+    assert!(voting_weight(VotingMode::Conviction, 100, MAX_CONVICTION_LOCK + 1).is_err());
+    assert!(voting_weight(VotingMode::Conviction, u64::MAX, CONVICTION_PERIOD).is_err());
+}
+
+#[test]
+fn tallies_match_known_vectors() {
+    // One whale against four smaller holders
+    let whale = [(10_000, 0)];
+    let holders = [(900, 0); 4];
+    assert_eq!((tally(VotingMode::Linear, &whale), tally(VotingMode::Linear, &holders)), (10_000, 3_600));
+    assert_eq!((tally(VotingMode::Quadratic, &whale), tally(VotingMode::Quadratic, &holders)), (100, 120));
+
+    // Holders locking for two periods triple their weight; the whale does not lock
+    let locked_holders = [(900, 2 * CONVICTION_PERIOD); 4];
+    assert_eq!(tally(VotingMode::Conviction, &whale), 10_000);
+    assert_eq!(tally(VotingMode::Conviction, &locked_holders), 10_800);
+}
+
+#[test]
+fn full_checkpoint_history_still_records() {
+    let mut history = VotingPowerCheckpoints {
+        owner: Pubkey::default(),
+        stake_pool: Pubkey::default(),
+        checkpoints: vec![],
+        bump: 0,
+    };
+    // Stake 100 more every slot until the window is full of recent checkpoints
+    for slot in 1..=MAX_CHECKPOINTS as u64 {
+        history.record(slot, slot as i64, slot * 100);
+    }
+
+    // Unstaking everything is still recorded, and the two newest merge at the lower power
+    let exit = MAX_CHECKPOINTS as u64 + 1;
+    history.record(exit, exit as i64, 0);
+    assert_eq!(history.checkpoints.len(), MAX_CHECKPOINTS);
+    assert_eq!(history.power_before(exit + 1), 0);
+    assert_eq!(history.power_before(exit), (exit - 2) * 100);
+    assert_eq!(history.power_before(exit - 1), (exit - 2) * 100);
+    assert_eq!(history.power_before(2), 100);
+}
+
+proptest! {
+    #[test]
+    fn distribution_never_exceeds_total_supply(total_supply in any::<u64>()) {
+        let distribution = TokenDistribution::from_total_supply(total_supply);
+        let allocated = distribution.total().expect("buckets sum without overflow");
+
+        prop_assert!(allocated <= total_supply);
+        // Five buckets each round down by less than one unit
+        prop_assert!(total_supply - allocated < 5);
+    }
+
+    #[test]
+    fn percentage_is_bounded_and_monotonic(value in any::<u64>(), low in 0u8..=100, high in 0u8..=100) {
+        let (low, high) = (low.min(high), low.max(high));
+
+        prop_assert!(calculate_percentage(value, high) <= value);
+        prop_assert!(calculate_percentage(value, low) <= calculate_percentage(value, high));
+        prop_assert_eq!(calculate_percentage(value, 100), value);
+        prop_assert_eq!(calculate_percentage(value, 0), 0);
+    }
+
+    #[test]
+    fn yes_percentage_stays_within_bounds(yes_votes in any::<u64>(), no_votes in any::<u64>()) {
+        let percentage = yes_percentage(yes_votes, no_votes);
+
+        prop_assert!(percentage <= 100);
+        if no_votes == 0 && yes_votes > 0 {
+            prop_assert_eq!(percentage, 100);
+        }
+        if yes_votes == 0 {
+            prop_assert_eq!(percentage, 0);
+        }
+    }
+
+    #[test]
+    fn approval_is_monotonic_in_yes_votes(
+        yes_votes in any::<u64>(),
+        extra_yes in any::<u64>(),
+        no_votes in any::<u64>(),
+        threshold in 0u8..=100,
+    ) {
+        let more_yes = yes_votes.saturating_add(extra_yes);
+
+        if is_approved(yes_votes, no_votes, threshold) {
+            prop_assert!(is_approved(more_yes, no_votes, threshold));
+        }
+    }
+
+    #[test]
+    fn approval_is_antitone_in_no_votes(
+        yes_votes in any::<u64>(),
+        no_votes in any::<u64>(),
+        extra_no in any::<u64>(),
+        threshold in 0u8..=100,
+    ) {
+        let more_no = no_votes.saturating_add(extra_no);
+
+        if is_approved(yes_votes, more_no, threshold) {
+            prop_assert!(is_approved(yes_votes, no_votes, threshold));
+        }
+    }
+
+    #[test]
+    fn add_votes_never_wraps(tally in any::<u64>(), weight in any::<u64>()) {
+        let exact = tally as u128 + weight as u128;
+
+        match add_votes(tally, weight) {
+            Ok(sum) => prop_assert_eq!(sum as u128, exact),
+            Err(_) => prop_assert!(exact > u64::MAX as u128),
+        }
+    }
+
+    #[test]
+    fn plurality_winner_strictly_leads(tallies in prop::collection::vec(0u64..100, 2..=MAX_PROPOSAL_OPTIONS)) {
+        match resolve_plurality(&tallies) {
+            Some(winner) => {
+                let winner = winner as usize;
+                for (option, &votes) in tallies.iter().enumerate() {
+                    if option != winner {
+                        prop_assert!(votes < tallies[winner]);
+                    }
+                }
+            }
+            None => {
+                let max_votes = *tallies.iter().max().unwrap();
+                prop_assert!(max_votes == 0 || tallies.iter().filter(|&&votes| votes == max_votes).count() > 1);
+            }
+        }
+    }
+
+    #[test]
+    fn ranked_choice_elects_first_choice_majority((option_count, ballots) in ranked_ballots()) {
+        let mut first_choices = vec![0u128; option_count];
+        for ballot in &ballots {
+            first_choices[ballot.ranking[0] as usize] += ballot.weight as u128;
+        }
+        let total: u128 = first_choices.iter().sum();
+
+        let winner = resolve_ranked_choice(option_count, &ballots);
+        if let Some(majority) = (0..option_count).find(|&option| first_choices[option] * 2 > total) {
+            prop_assert_eq!(winner, Some(majority as u8));
+        }
+        if let Some(winner) = winner {
+            prop_assert!((winner as usize) < option_count);
+            prop_assert!(ballots.iter().any(|ballot| ballot.ranking.contains(&winner)));
+        }
+    }
+
+    #[test]
+    fn single_choice_ranked_ballots_agree_with_plurality_majority((option_count, ballots) in ranked_ballots()) {
+        let first_only: Vec<RankedBallot> = ballots
+            .iter()
+            .map(|ballot| RankedBallot { ranking: ballot.ranking[..1].to_vec(), weight: ballot.weight })
+            .collect();
+        let mut tallies = vec![0u64; option_count];
+        for ballot in &first_only {
+            tallies[ballot.ranking[0] as usize] += ballot.weight;
+        }
+
+        // Without transfers, a ranked-choice winner is also the plurality winner
+        if let Some(winner) = resolve_ranked_choice(option_count, &first_only) {
+            prop_assert_eq!(resolve_plurality(&tallies), Some(winner));
+        }
+    }
+
+    #[test]
+    fn quadratic_weight_is_floor_square_root(tokens in any::<u64>()) {
+        let weight = voting_weight(VotingMode::Quadratic, tokens, 0).unwrap() as u128;
+
+        prop_assert!(weight * weight <= tokens as u128);
+        prop_assert!((weight + 1) * (weight + 1) > tokens as u128);
+    }
+
+    #[test]
+    fn conviction_weight_grows_with_lock(
+        tokens in 0u64..=u64::MAX / MAX_CONVICTION_MULTIPLIER,
+        short_lock in 0..=MAX_CONVICTION_LOCK,
+        long_lock in 0..=MAX_CONVICTION_LOCK,
+    ) {
+        let (short_lock, long_lock) = (short_lock.min(long_lock), short_lock.max(long_lock));
+        let short_weight = voting_weight(VotingMode::Conviction, tokens, short_lock).unwrap();
+        let long_weight = voting_weight(VotingMode::Conviction, tokens, long_lock).unwrap();
+
+        prop_assert!(tokens <= short_weight);
+        prop_assert!(short_weight <= long_weight);
+        prop_assert_eq!(long_weight, tokens * conviction_multiplier(long_lock));
+    }
+
+    #[test]
+    fn stream_vesting_is_bounded_and_monotonic(
+        total in any::<u64>(),
+        start_at in any::<i64>(),
+        duration in 1i64..=i64::MAX,
+        earlier in any::<i64>(),
+        later in any::<i64>(),
+    ) {
+        let end_at = start_at.saturating_add(duration);
+        prop_assume!(end_at > start_at);
+        let (earlier, later) = (earlier.min(later), earlier.max(later));
+        let vested_earlier = vested_amount(total, start_at, end_at, earlier);
+        let vested_later = vested_amount(total, start_at, end_at, later);
+
+        prop_assert!(vested_earlier <= vested_later);
+        prop_assert!(vested_later <= total);
+        prop_assert_eq!(vested_amount(total, start_at, end_at, start_at), 0);
+        prop_assert_eq!(vested_amount(total, start_at, end_at, end_at), total);
+    }
+
+    #[test]
+    fn tally_accumulates_exactly_or_fails(weights in prop::collection::vec(any::<u64>(), 0..16)) {
+        let mut tally = 0u64;
+        let mut exact = 0u128;
+
+        for weight in weights {
+            exact += weight as u128;
+            match add_votes(tally, weight) {
+                Ok(sum) => tally = sum,
+                Err(_) => {
+                    prop_assert!(exact > u64::MAX as u128);
+                    return Ok(());
+                }
+            }
+            prop_assert_eq!(tally as u128, exact);
+        }
+    }
+
+    #[test]
+    fn checkpoint_lookup_matches_a_linear_scan(
+        updates in prop::collection::vec(
+            (prop_oneof![Just(0u64), 1..MAX_SNAPSHOT_AGE as u64 / 16], any::<u64>()),
+            0..3 * MAX_CHECKPOINTS,
+        ),
+        probe_age in 0..=MAX_SNAPSHOT_AGE as u64,
+    ) {
+        let mut history = VotingPowerCheckpoints {
+            owner: Pubkey::default(),
+            stake_pool: Pubkey::default(),
+            checkpoints: vec![],
+            bump: 0,
+        };
+        // One slot per second keeps slots and timestamps interchangeable
+        let mut slot = 1u64;
+        let mut written = Vec::new();
+        let mut merged = false;
+        for (gap, power) in updates {
+            slot += gap;
+            // A full history of recent checkpoints merges rather than drops
+            let full = history.checkpoints.len() == MAX_CHECKPOINTS;
+            merged |= full
+                && history.checkpoints.last().unwrap().slot != slot
+                && history.checkpoints[1].timestamp >= (slot as i64).saturating_sub(MAX_SNAPSHOT_AGE);
+            history.record(slot, slot as i64, power);
+            written.push((slot, power));
+        }
+
+        prop_assert!(history.checkpoints.len() <= MAX_CHECKPOINTS);
+        prop_assert!(history.checkpoints.windows(2).all(|pair| pair[0].slot < pair[1].slot));
+        prop_assert_eq!(history.power_before(slot + 1), written.last().map_or(0, |(_, power)| *power));
+
+        // Any snapshot young enough to be read sees the full history, or less once checkpoints merged
+        let probe = slot.saturating_sub(probe_age);
+        let expected = written.iter().rev().find(|(slot, _)| *slot < probe).map_or(0, |(_, power)| *power);
+        if merged {
+            prop_assert!(history.power_before(probe) <= expected);
+        } else {
+            prop_assert_eq!(history.power_before(probe), expected);
+        }
+    }
+}
@@ -0,0 +1,636 @@
+mod common;
+
+use common::*;
+use realstack::{AssetStatus, AssetToken, PauseFlags, RealStackError, Redemption, Role};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const PROCEEDS: u64 = 500_000;
+
+/// Accounts of a wound-down asset
+struct Redeeming {
+    asset: AssetFixture,
+    proceeds_mint: Pubkey,
+    redemption: Pubkey,
+    proceeds_vault: Pubkey,
+}
+
+fn redemption_pdas(asset_token: &Pubkey) -> (Pubkey, Pubkey) {
+    let redemption = pda(&[b"redemption", asset_token.as_ref()]);
+    let proceeds_vault = pda(&[b"redemption_vault", redemption.as_ref()]);
+    (redemption, proceeds_vault)
+}
+
+fn update_valuation_ix(fixture: &TestFixture, authority: &Pubkey, asset_token: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::UpdateAssetValuation {
+            authority: *authority,
+            asset_token,
+            roles: fixture.roles,
+        },
+        realstack::instruction::UpdateAssetValuation {
+            new_valuation: 2_000_000,
+            new_share_price: 200,
+        },
+    )
+}
+
+fn distribute_income_ix(
+    fixture: &TestFixture,
+    authority: &Pubkey,
+    asset_token: Pubkey,
+    real_token: Pubkey,
+    income_source: Pubkey,
+) -> Instruction {
+    ix(
+        realstack::accounts::DistributeIncome {
+            authority: *authority,
+            asset_token,
+            real_token,
+            roles: fixture.roles,
+            income_source,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::DistributeIncome { amount: 1_000 },
+    )
+}
+
+fn burn_ix(
+    fixture: &TestFixture,
+    authority: &Pubkey,
+    asset: &AssetFixture,
+    proceeds_mint: Pubkey,
+    proceeds_source: Pubkey,
+    proceeds_amount: u64,
+) -> Instruction {
+    let (redemption, proceeds_vault) = redemption_pdas(&asset.asset_token);
+    ix(
+        realstack::accounts::BurnAssetToken {
+            authority: *authority,
+            asset_token: asset.asset_token,
+            roles: fixture.roles,
+            share_mint: asset.share_mint,
+            redemption,
+            proceeds_mint,
+            proceeds_vault,
+            proceeds_source,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: anchor_lang::solana_program::sysvar::rent::ID,
+        },
+        realstack::instruction::BurnAssetToken { proceeds_amount },
+    )
+}
+
+fn redeem_ix(
+    asset: &AssetFixture,
+    holder: &Pubkey,
+    holder_share_account: Pubkey,
+    holder_proceeds_account: Pubkey,
+    amount: u64,
+) -> Instruction {
+    let (redemption, proceeds_vault) = redemption_pdas(&asset.asset_token);
+    ix(
+        realstack::accounts::RedeemShares {
+            holder: *holder,
+            asset_token: asset.asset_token,
+            redemption,
+            share_mint: asset.share_mint,
+            holder_share_account,
+            proceeds_vault,
+            holder_proceeds_account,
+            holder_record: holder_record_of(&asset.asset_token, holder),
+            freeze_authority: freeze_authority_of(&asset.asset_token),
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::RedeemShares { amount },
+    )
+}
+
+fn close_ix(fixture: &TestFixture, authority: &Pubkey, asset: &AssetFixture) -> Instruction {
+    let (redemption, proceeds_vault) = redemption_pdas(&asset.asset_token);
+    ix(
+        realstack::accounts::CloseAssetToken {
+            authority: *authority,
+            asset_token: asset.asset_token,
+            roles: fixture.roles,
+            share_mint: asset.share_mint,
+            redemption,
+            proceeds_vault,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::CloseAssetToken {},
+    )
+}
+
+fn mint_additional_ix(
+    fixture: &TestFixture,
+    authority: &Pubkey,
+    approver: &Pubkey,
+    asset: &AssetFixture,
+    amount: u64,
+) -> Instruction {
+    ix(
+        realstack::accounts::MintAdditionalShares {
+            authority: *authority,
+            approver: *approver,
+            asset_token: asset.asset_token,
+            roles: fixture.roles,
+            real_token: fixture.real_token,
+            share_mint: asset.share_mint,
+            mint_authority: pda(&[b"mint_authority", asset.asset_token.as_ref()]),
+            freeze_authority: freeze_authority_of(&asset.asset_token),
+            destination: asset.issuer_shares,
+            destination_investor_record: None,
+            destination_holder_record: holder_record_of(&asset.asset_token, &fixture.payer_key()),
+            token_program: anchor_spl::token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::MintAdditionalShares { amount },
+    )
+}
+
+fn lock_supply_ix(fixture: &TestFixture, authority: &Pubkey, asset_token: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::LockSupply {
+            authority: *authority,
+            asset_token,
+            roles: fixture.roles,
+        },
+        realstack::instruction::LockSupply {},
+    )
+}
+
+/// Create an asset and wind it down with `PROCEEDS` in sale proceeds
+async fn wind_down(fixture: &mut TestFixture) -> Redeeming {
+    let asset = fixture.create_asset(false).await;
+    let payer = fixture.payer_key();
+    let proceeds_mint = fixture.create_mint(6).await;
+    let proceeds_source = fixture.create_funded_token_account(&proceeds_mint, &payer, PROCEEDS).await;
+
+    let instruction = burn_ix(fixture, &payer, &asset, proceeds_mint, proceeds_source, PROCEEDS);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let (redemption, proceeds_vault) = redemption_pdas(&asset.asset_token);
+    Redeeming {
+        asset,
+        proceeds_mint,
+        redemption,
+        proceeds_vault,
+    }
+}
+
+#[tokio::test]
+async fn create_asset_token_records_asset() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let asset = fixture.create_asset(true).await;
+
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(asset_token.authority, payer);
+    assert_eq!(asset_token.mint, asset.share_mint);
+    assert_eq!(asset_token.real_token, fixture.real_token);
+    assert_eq!(asset_token.valuation, ASSET_VALUATION);
+    assert_eq!(asset_token.total_shares, ASSET_TOTAL_SHARES);
+    assert_eq!(asset_token.current_share_price, ASSET_SHARE_PRICE);
+    assert!(asset_token.can_mint_additional);
+    assert!(!asset_token.is_verified);
+    assert!(!asset_token.is_tradable);
+    assert!(asset_token.status == AssetStatus::Active);
+}
+
+#[tokio::test]
+async fn create_asset_token_requires_asset_manager() {
+    let mut fixture = TestFixture::new().await;
+    let intruder = fixture.new_actor().await;
+    let asset = Keypair::new();
+    let shares = fixture.create_share_mint(&asset.pubkey()).await;
+
+    let instruction = fixture.create_asset_ix(&intruder.pubkey(), asset.pubkey(), shares, false);
+    let result = fixture.process(&[instruction], &[&intruder, &asset]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
+
+#[tokio::test]
+async fn create_asset_token_respects_pause() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let asset = Keypair::new();
+    let shares = fixture.create_share_mint(&asset.pubkey()).await;
+    fixture.pause(PauseFlags { asset_creation: true, ..no_pause() }).await;
+
+    let instruction = fixture.create_asset_ix(&payer, asset.pubkey(), shares, false);
+    let result = fixture.process(&[instruction], &[&asset]).await;
+    assert_error(result, RealStackError::OperationPaused);
+}
+
+#[tokio::test]
+async fn update_asset_valuation_checks_role() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let asset = fixture.create_asset(false).await;
+
+    let instruction = update_valuation_ix(&fixture, &payer, asset.asset_token);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(asset_token.valuation, 2_000_000);
+    assert_eq!(asset_token.current_share_price, 200);
+
+    let instruction = update_valuation_ix(&fixture, &intruder.pubkey(), asset.asset_token);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // An asset manager cannot touch assets it is not the authority of
+    fixture.grant_role(intruder.pubkey(), Role::AssetManager).await;
+    let instruction = update_valuation_ix(&fixture, &intruder.pubkey(), asset.asset_token);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
+
+#[tokio::test]
+async fn verify_asset_checks_role() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let asset = fixture.create_asset(false).await;
+
+    // Managing assets does not make the payer a verifier
+    let instruction = fixture.verify_asset_ix(&payer, asset.asset_token);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    fixture.verify_asset(asset.asset_token).await;
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert!(asset_token.is_verified);
+    assert_eq!(asset_token.verifier, Some(fixture.verifier.pubkey()));
+}
+
+#[tokio::test]
+async fn toggle_tradability_checks_role_and_status() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let asset = fixture.create_asset(false).await;
+
+    fixture.set_tradable(asset.asset_token, true).await;
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert!(asset_token.is_tradable);
+
+    let instruction = fixture.toggle_tradability_ix(&intruder.pubkey(), asset.asset_token, false);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let redeeming = wind_down(&mut fixture).await;
+    let instruction = fixture.toggle_tradability_ix(&payer, redeeming.asset.asset_token, true);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetBurned);
+}
+
+#[tokio::test]
+async fn distribute_income_records_amount() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let asset = fixture.create_asset(false).await;
+    let real_mint = fixture.real_mint;
+    let income_source = fixture.create_token_account(&real_mint, &payer).await;
+
+    let instruction = distribute_income_ix(&fixture, &payer, asset.asset_token, fixture.real_token, income_source);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(asset_token.total_income_distributed, 1_000);
+    assert!(asset_token.last_income_distribution > 0);
+}
+
+#[tokio::test]
+async fn distribute_income_rejects_invalid_calls() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let asset = fixture.create_asset(false).await;
+    let real_mint = fixture.real_mint;
+    let income_source = fixture.create_token_account(&real_mint, &payer).await;
+
+    let instruction =
+        distribute_income_ix(&fixture, &intruder.pubkey(), asset.asset_token, fixture.real_token, income_source);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // The pause flags must come from the asset's own REAL token
+    let other_real_token = Keypair::new();
+    let instruction = fixture.initialize_ix(other_real_token.pubkey(), "Other", "OTH", REAL_SUPPLY);
+    fixture.process(&[instruction], &[&other_real_token]).await.unwrap();
+    let instruction =
+        distribute_income_ix(&fixture, &payer, asset.asset_token, other_real_token.pubkey(), income_source);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let redeeming = wind_down(&mut fixture).await;
+    let instruction =
+        distribute_income_ix(&fixture, &payer, redeeming.asset.asset_token, fixture.real_token, income_source);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetBurned);
+
+    fixture.pause(PauseFlags { income_distribution: true, ..no_pause() }).await;
+    let instruction = distribute_income_ix(&fixture, &payer, asset.asset_token, fixture.real_token, income_source);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::OperationPaused);
+}
+
+#[tokio::test]
+async fn burn_asset_token_opens_redemption() {
+    let mut fixture = TestFixture::new().await;
+    let redeeming = wind_down(&mut fixture).await;
+
+    let asset_token: AssetToken = fixture.get(&redeeming.asset.asset_token).await;
+    assert!(asset_token.is_burned);
+    assert!(!asset_token.is_tradable);
+    assert!(asset_token.status == AssetStatus::Redeeming);
+
+    let redemption: Redemption = fixture.get(&redeeming.redemption).await;
+    assert_eq!(redemption.proceeds_mint, redeeming.proceeds_mint);
+    assert_eq!(redemption.total_proceeds, PROCEEDS);
+    assert_eq!(redemption.initial_supply, ASSET_TOTAL_SHARES);
+    assert_eq!(fixture.token_balance(&redeeming.proceeds_vault).await, PROCEEDS);
+}
+
+#[tokio::test]
+async fn burn_asset_token_rejects_invalid_calls() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let asset = fixture.create_asset(false).await;
+    let proceeds_mint = fixture.create_mint(6).await;
+    let proceeds_source = fixture.create_funded_token_account(&proceeds_mint, &payer, PROCEEDS).await;
+    let other_mint = fixture.create_mint(6).await;
+    let wrong_source = fixture.create_funded_token_account(&other_mint, &payer, PROCEEDS).await;
+
+    let instruction = burn_ix(&fixture, &payer, &asset, proceeds_mint, proceeds_source, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = burn_ix(&fixture, &payer, &asset, proceeds_mint, wrong_source, PROCEEDS);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidTokenMint);
+
+    let instruction = burn_ix(&fixture, &intruder.pubkey(), &asset, proceeds_mint, proceeds_source, PROCEEDS);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // The redemption record can only be created once
+    let instruction = burn_ix(&fixture, &payer, &asset, proceeds_mint, proceeds_source, PROCEEDS / 2);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = burn_ix(&fixture, &payer, &asset, proceeds_mint, proceeds_source, PROCEEDS / 2);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_failed(result);
+}
+
+#[tokio::test]
+async fn redeem_shares_pays_pro_rata_and_retires() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let redeeming = wind_down(&mut fixture).await;
+    let holder_proceeds = fixture.create_token_account(&redeeming.proceeds_mint, &payer).await;
+    let asset = &redeeming.asset;
+
+    let instruction = redeem_ix(asset, &payer, asset.issuer_shares, holder_proceeds, ASSET_TOTAL_SHARES / 4);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&holder_proceeds).await, PROCEEDS / 4);
+
+    let instruction = redeem_ix(
+        asset,
+        &payer,
+        asset.issuer_shares,
+        holder_proceeds,
+        ASSET_TOTAL_SHARES - ASSET_TOTAL_SHARES / 4,
+    );
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&holder_proceeds).await, PROCEEDS);
+    assert_eq!(fixture.token_balance(&redeeming.proceeds_vault).await, 0);
+
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert!(asset_token.status == AssetStatus::Retired);
+    let redemption: Redemption = fixture.get(&redeeming.redemption).await;
+    assert_eq!(redemption.shares_redeemed, ASSET_TOTAL_SHARES);
+    assert_eq!(redemption.proceeds_paid, PROCEEDS);
+}
+
+#[tokio::test]
+async fn redeem_shares_rejects_invalid_calls() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let redeeming = wind_down(&mut fixture).await;
+    let holder_proceeds = fixture.create_token_account(&redeeming.proceeds_mint, &payer).await;
+    let asset = &redeeming.asset;
+
+    let instruction = redeem_ix(asset, &payer, asset.issuer_shares, holder_proceeds, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = redeem_ix(asset, &payer, asset.issuer_shares, holder_proceeds, ASSET_TOTAL_SHARES + 1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::MathOverflow);
+
+    // Only the owner of the share account can redeem from it
+    let instruction = redeem_ix(asset, &intruder.pubkey(), asset.issuer_shares, holder_proceeds, 1);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+
+    let instruction = redeem_ix(asset, &payer, asset.issuer_shares, asset.issuer_shares, 1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidTokenMint);
+}
+
+#[tokio::test]
+async fn redeem_shares_requires_redeeming_asset() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let redeeming = wind_down(&mut fixture).await;
+    let holder_proceeds = fixture.create_token_account(&redeeming.proceeds_mint, &payer).await;
+    let asset = &redeeming.asset;
+
+    let instruction = redeem_ix(asset, &payer, asset.issuer_shares, holder_proceeds, ASSET_TOTAL_SHARES);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    // Retired assets no longer redeem
+    let instruction = redeem_ix(asset, &payer, asset.issuer_shares, holder_proceeds, 1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetNotRedeeming);
+}
+
+#[tokio::test]
+async fn close_asset_token_requires_retired_asset() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let redeeming = wind_down(&mut fixture).await;
+    let holder_proceeds = fixture.create_token_account(&redeeming.proceeds_mint, &payer).await;
+    let asset = &redeeming.asset;
+
+    let instruction = close_ix(&fixture, &payer, asset);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetNotRetired);
+
+    let instruction = redeem_ix(asset, &payer, asset.issuer_shares, holder_proceeds, ASSET_TOTAL_SHARES);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = close_ix(&fixture, &intruder.pubkey(), asset);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = close_ix(&fixture, &payer, asset);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert!(!fixture.account_exists(&asset.asset_token).await);
+    assert!(!fixture.account_exists(&redeeming.redemption).await);
+    assert!(!fixture.account_exists(&redeeming.proceeds_vault).await);
+}
+
+#[tokio::test]
+async fn mint_additional_shares_dilutes_price() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let verifier = clone_keypair(&fixture.verifier);
+    let asset = fixture.create_asset(true).await;
+
+    let instruction = mint_additional_ix(&fixture, &payer, &verifier.pubkey(), &asset, ASSET_TOTAL_SHARES);
+    fixture.process(&[instruction], &[&verifier]).await.unwrap();
+
+    assert_eq!(fixture.token_balance(&asset.issuer_shares).await, 2 * ASSET_TOTAL_SHARES);
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(asset_token.total_shares, 2 * ASSET_TOTAL_SHARES);
+    assert_eq!(asset_token.current_share_price, ASSET_VALUATION / (2 * ASSET_TOTAL_SHARES));
+}
+
+#[tokio::test]
+async fn mint_additional_shares_requires_two_parties() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let verifier = clone_keypair(&fixture.verifier);
+    let asset = fixture.create_asset(true).await;
+
+    // The approver must be a verifier
+    let instruction = mint_additional_ix(&fixture, &payer, &intruder.pubkey(), &asset, 1);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // ...other than the asset manager
+    fixture.grant_role(payer, Role::Verifier).await;
+    let instruction = mint_additional_ix(&fixture, &payer, &payer, &asset, 1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = mint_additional_ix(&fixture, &intruder.pubkey(), &verifier.pubkey(), &asset, 1);
+    let result = fixture.process(&[instruction], &[&intruder, &verifier]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
+
+#[tokio::test]
+async fn mint_additional_shares_rejects_invalid_calls() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let verifier = clone_keypair(&fixture.verifier);
+
+    let asset = fixture.create_asset(true).await;
+    let instruction = mint_additional_ix(&fixture, &payer, &verifier.pubkey(), &asset, 0);
+    let result = fixture.process(&[instruction], &[&verifier]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    // Issuance needs the flag
+    let fixed = fixture.create_asset(false).await;
+    let instruction = mint_additional_ix(&fixture, &payer, &verifier.pubkey(), &fixed, 1);
+    let result = fixture.process(&[instruction], &[&verifier]).await;
+    assert_error(result, RealStackError::CannotMintAdditional);
+
+    fixture.pause(PauseFlags { asset_creation: true, ..no_pause() }).await;
+    let instruction = mint_additional_ix(&fixture, &payer, &verifier.pubkey(), &asset, 1);
+    let result = fixture.process(&[instruction], &[&verifier]).await;
+    assert_error(result, RealStackError::OperationPaused);
+}
+
+#[tokio::test]
+async fn create_asset_token_requires_program_controlled_supply() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+
+    // The payer still holds the mint authority
+    let asset = Keypair::new();
+    let mint_authority = pda(&[b"mint_authority", asset.pubkey().as_ref()]);
+    let mint = fixture.create_mint_with_authorities(0, &payer, Some(&payer)).await;
+    let supply = fixture.create_funded_token_account(&mint, &payer, ASSET_TOTAL_SHARES).await;
+    let instruction = fixture.create_asset_ix(&payer, asset.pubkey(), (mint, supply), false);
+    let result = fixture.process(&[instruction], &[&asset]).await;
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintMintMintAuthority);
+
+    // The payer still holds the freeze authority, so share accounts could be thawed outside the program
+    fixture.set_mint_authority(&mint, &mint_authority).await;
+    let instruction = fixture.create_asset_ix(&payer, asset.pubkey(), (mint, supply), false);
+    let result = fixture.process(&[instruction], &[&asset]).await;
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintMintFreezeAuthority);
+
+    // The supply must match the declared share count
+    let mint = fixture.create_mint_with_authorities(0, &payer, Some(&payer)).await;
+    let supply = fixture.create_funded_token_account(&mint, &payer, ASSET_TOTAL_SHARES - 1).await;
+    fixture.set_mint_authority(&mint, &mint_authority).await;
+    fixture.set_freeze_authority(&mint, &freeze_authority_of(&asset.pubkey())).await;
+    let instruction = fixture.create_asset_ix(&payer, asset.pubkey(), (mint, supply), false);
+    let result = fixture.process(&[instruction], &[&asset]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    // The whole supply must sit in the account handed to the program to be frozen
+    let mint = fixture.create_mint_with_authorities(0, &payer, Some(&payer)).await;
+    let supply = fixture.create_funded_token_account(&mint, &payer, ASSET_TOTAL_SHARES - 1).await;
+    fixture.create_funded_token_account(&mint, &payer, 1).await;
+    fixture.set_mint_authority(&mint, &mint_authority).await;
+    fixture.set_freeze_authority(&mint, &freeze_authority_of(&asset.pubkey())).await;
+    let instruction = fixture.create_asset_ix(&payer, asset.pubkey(), (mint, supply), false);
+    let result = fixture.process(&[instruction], &[&asset]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+}
+
+#[tokio::test]
+async fn mint_additional_shares_rejects_burned_asset() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let verifier = clone_keypair(&fixture.verifier);
+    let asset = fixture.create_asset(true).await;
+    let proceeds_mint = fixture.create_mint(6).await;
+    let proceeds_source = fixture.create_funded_token_account(&proceeds_mint, &payer, PROCEEDS).await;
+
+    let instruction = burn_ix(&fixture, &payer, &asset, proceeds_mint, proceeds_source, PROCEEDS);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = mint_additional_ix(&fixture, &payer, &verifier.pubkey(), &asset, 1);
+    let result = fixture.process(&[instruction], &[&verifier]).await;
+    assert_error(result, RealStackError::AssetBurned);
+}
+
+#[tokio::test]
+async fn lock_supply_is_permanent() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let verifier = clone_keypair(&fixture.verifier);
+    let asset = fixture.create_asset(true).await;
+
+    let instruction = lock_supply_ix(&fixture, &intruder.pubkey(), asset.asset_token);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = lock_supply_ix(&fixture, &payer, asset.asset_token);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert!(!asset_token.can_mint_additional);
+
+    let instruction = lock_supply_ix(&fixture, &payer, asset.asset_token);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::CannotMintAdditional);
+
+    let instruction = mint_additional_ix(&fixture, &payer, &verifier.pubkey(), &asset, 1);
+    let result = fixture.process(&[instruction], &[&verifier]).await;
+    assert_error(result, RealStackError::CannotMintAdditional);
+}
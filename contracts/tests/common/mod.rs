@@ -0,0 +1,750 @@
+//! Shared fixture for the realstack integration tests.
+//!
+//! `TestFixture::new` deploys the program, creates the REAL mint, initializes
+//! `RealToken` and its role registry, and hands the payer the day-to-day roles.
+//! Per-module setups (assets, pools, offerings, ...) build on top of it.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{Clock, Pubkey};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+use realstack::{ComplianceConfig, GovernanceConfig, RealStackError, RealToken, Role, Roles};
+
+/// Total REAL supply used by the fixture
+pub const REAL_SUPPLY: u64 = 100_000_000;
+
+/// Lamports given to freshly created actors
+pub const ACTOR_LAMPORTS: u64 = 10_000_000_000;
+
+/// Default asset parameters
+pub const ASSET_VALUATION: u64 = 1_000_000;
+pub const ASSET_TOTAL_SHARES: u64 = 10_000;
+pub const ASSET_SHARE_PRICE: u64 = 100;
+
+pub type TxResult = Result<(), BanksClientError>;
+
+/// Build a realstack instruction from its Anchor account and data structs
+pub fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: realstack::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Derive a realstack PDA
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &realstack::ID).0
+}
+
+/// Derive the record counting `wallet` among an asset's holders
+pub fn holder_record_of(asset_token: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    pda(&[b"holder", asset_token.as_ref(), wallet.as_ref()])
+}
+
+/// Derive the PDA holding freeze authority over an asset's share mint
+pub fn freeze_authority_of(asset_token: &Pubkey) -> Pubkey {
+    pda(&[b"freeze_authority", asset_token.as_ref()])
+}
+
+/// Derive the lock on shares `wallet` bought in an asset's offering
+pub fn share_lock_of(asset_token: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    pda(&[b"share_lock", asset_token.as_ref(), wallet.as_ref()])
+}
+
+/// Assert that a transaction failed with the given program error
+pub fn assert_error(result: TxResult, error: RealStackError) {
+    assert_custom_error(result, u32::from(error));
+}
+
+/// Assert that a transaction failed with the given Anchor framework error
+pub fn assert_anchor_error(result: TxResult, error: anchor_lang::error::ErrorCode) {
+    assert_custom_error(result, u32::from(error));
+}
+
+/// Assert that a transaction failed with a custom error code
+pub fn assert_custom_error(result: TxResult, code: u32) {
+    let error = result.expect_err("transaction should have failed").unwrap();
+    match error {
+        TransactionError::InstructionError(_, InstructionError::Custom(actual)) => {
+            assert_eq!(actual, code, "expected error {}, got {}", code, actual)
+        }
+        other => panic!("expected custom error {}, got {:?}", code, other),
+    }
+}
+
+/// Assert that a transaction failed, whatever the reason
+pub fn assert_failed(result: TxResult) {
+    assert!(result.is_err(), "transaction should have failed");
+}
+
+/// An asset created through the fixture
+pub struct AssetFixture {
+    /// The AssetToken account
+    pub asset_token: Pubkey,
+
+    /// The share mint (mint and freeze authority: the asset's PDAs)
+    pub share_mint: Pubkey,
+
+    /// The payer's share account holding the whole supply, frozen like every wallet's share account
+    pub issuer_shares: Pubkey,
+}
+
+/// The REAL stake pool created through the fixture
+pub struct StakePoolFixture {
+    /// The StakePool PDA
+    pub stake_pool: Pubkey,
+
+    /// Vault holding staked REAL
+    pub stake_vault: Pubkey,
+
+    /// Mint rewards are paid in (mint authority: the payer)
+    pub reward_mint: Pubkey,
+
+    /// Vault holding undistributed rewards
+    pub reward_vault: Pubkey,
+}
+
+/// Deployed program with an initialized REAL token
+pub struct TestFixture {
+    /// The program test context
+    pub context: ProgramTestContext,
+
+    /// The RealToken account
+    pub real_token: Pubkey,
+
+    /// The REAL mint (mint authority: the payer)
+    pub real_mint: Pubkey,
+
+    /// The role registry PDA
+    pub roles: Pubkey,
+
+    /// Holder of the Verifier role
+    pub verifier: Keypair,
+}
+
+impl TestFixture {
+    /// Deploy the program and initialize REAL, granting the payer the
+    /// AssetManager, Treasurer, FeeManager and Pauser roles
+    pub async fn new() -> Self {
+        let program = ProgramTest::new("realstack", realstack::ID, processor!(realstack::entry));
+        let context = program.start_with_context().await;
+
+        let real_token_keypair = Keypair::new();
+        let real_token = real_token_keypair.pubkey();
+        let roles = pda(&[b"roles", real_token.as_ref()]);
+        let mut fixture = TestFixture {
+            context,
+            real_token,
+            real_mint: Pubkey::default(),
+            roles,
+            verifier: Keypair::new(),
+        };
+
+        fixture.real_mint = fixture.create_mint(0).await;
+        let payer = fixture.payer_key();
+        fixture
+            .process(
+                &[fixture.initialize_ix(real_token, "REAL Token", "REAL", REAL_SUPPLY)],
+                &[&real_token_keypair],
+            )
+            .await
+            .unwrap();
+
+        for role in [Role::AssetManager, Role::Treasurer, Role::FeeManager, Role::Pauser] {
+            fixture.grant_role(payer, role).await;
+        }
+        let verifier = fixture.verifier.pubkey();
+        fixture.fund(&verifier).await;
+        fixture.grant_role(verifier, Role::Verifier).await;
+
+        fixture
+    }
+
+    /// Instruction initializing a RealToken account
+    pub fn initialize_ix(&self, real_token: Pubkey, name: &str, symbol: &str, total_supply: u64) -> Instruction {
+        ix(
+            realstack::accounts::Initialize {
+                real_token,
+                roles: pda(&[b"roles", real_token.as_ref()]),
+                mint: self.real_mint,
+                authority: self.payer_key(),
+                system_program: anchor_lang::system_program::ID,
+                token_program: anchor_spl::token::ID,
+                rent: anchor_lang::solana_program::sysvar::rent::ID,
+            },
+            realstack::instruction::Initialize {
+                name: name.to_string(),
+                symbol: symbol.to_string(),
+                uri: "https://realstack.finance/metadata/real-token.json".to_string(),
+                total_supply,
+            },
+        )
+    }
+
+    /// The transaction fee payer, which is also the REAL admin
+    pub fn payer(&self) -> Keypair {
+        clone_keypair(&self.context.payer)
+    }
+
+    /// Address of the fee payer
+    pub fn payer_key(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Send instructions in one transaction, signed by the payer and `signers`.
+    /// Each transaction gets a fresh blockhash so retries are not deduplicated.
+    pub async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> TxResult {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let payer = self.context.payer.pubkey();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend(signers.iter().filter(|signer| signer.pubkey() != payer));
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    /// Send lamports to an account
+    pub async fn fund(&mut self, to: &Pubkey) {
+        let payer = self.payer_key();
+        self.process(&[system_instruction::transfer(&payer, to, ACTOR_LAMPORTS)], &[])
+            .await
+            .unwrap();
+    }
+
+    /// Create a funded keypair
+    pub async fn new_actor(&mut self) -> Keypair {
+        let actor = Keypair::new();
+        self.fund(&actor.pubkey()).await;
+        actor
+    }
+
+    /// Create a mint controlled by the payer
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let authority = self.payer_key();
+        self.create_mint_with_authority(decimals, &authority).await
+    }
+
+    /// Create a mint with the given mint authority
+    pub async fn create_mint_with_authority(&mut self, decimals: u8, authority: &Pubkey) -> Pubkey {
+        self.create_mint_with_authorities(decimals, authority, None).await
+    }
+
+    /// Create a mint with the given mint and freeze authorities
+    pub async fn create_mint_with_authorities(
+        &mut self,
+        decimals: u8,
+        authority: &Pubkey,
+        freeze_authority: Option<&Pubkey>,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer_key();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), authority, freeze_authority, decimals)
+                .unwrap(),
+        ];
+        self.process(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    /// Create a token account
+    pub async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let payer = self.payer_key();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_account(&spl_token::ID, &account.pubkey(), mint, owner).unwrap(),
+        ];
+        self.process(&instructions, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    /// Create a token account and mint tokens into it (the payer must be the mint authority)
+    pub async fn create_funded_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = self.create_token_account(mint, owner).await;
+        if amount > 0 {
+            self.mint_to(mint, &account, amount).await;
+        }
+        account
+    }
+
+    /// Mint tokens (the payer must be the mint authority)
+    pub async fn mint_to(&mut self, mint: &Pubkey, account: &Pubkey, amount: u64) {
+        let payer = self.payer_key();
+        let instruction =
+            spl_token::instruction::mint_to(&spl_token::ID, mint, account, &payer, &[], amount).unwrap();
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    /// Transfer tokens out of an account owned by `owner`
+    pub async fn transfer_tokens(&mut self, from: &Pubkey, to: &Pubkey, owner: &Keypair, amount: u64) {
+        let instruction =
+            spl_token::instruction::transfer(&spl_token::ID, from, to, &owner.pubkey(), &[], amount).unwrap();
+        self.process(&[instruction], &[owner]).await.unwrap();
+    }
+
+    /// Hand the mint authority of a payer-controlled mint to another account
+    pub async fn set_mint_authority(&mut self, mint: &Pubkey, new_authority: &Pubkey) {
+        self.set_authority(mint, new_authority, spl_token::instruction::AuthorityType::MintTokens).await;
+    }
+
+    /// Hand the freeze authority of a payer-controlled mint to another account
+    pub async fn set_freeze_authority(&mut self, mint: &Pubkey, new_authority: &Pubkey) {
+        self.set_authority(mint, new_authority, spl_token::instruction::AuthorityType::FreezeAccount).await;
+    }
+
+    async fn set_authority(
+        &mut self,
+        mint: &Pubkey,
+        new_authority: &Pubkey,
+        authority_type: spl_token::instruction::AuthorityType,
+    ) {
+        let payer = self.payer_key();
+        let instruction = spl_token::instruction::set_authority(
+            &spl_token::ID,
+            mint,
+            Some(new_authority),
+            authority_type,
+            &payer,
+            &[],
+        )
+        .unwrap();
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    /// Token balance of an account
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        let data = self.account_data(account).await;
+        spl_token::state::Account::unpack(&data).unwrap().amount
+    }
+
+    /// Supply of a mint
+    pub async fn mint_supply(&mut self, mint: &Pubkey) -> u64 {
+        let data = self.account_data(mint).await;
+        spl_token::state::Mint::unpack(&data).unwrap().supply
+    }
+
+    /// Raw data of an account that must exist
+    pub async fn account_data(&mut self, address: &Pubkey) -> Vec<u8> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {} not found", address))
+            .data
+    }
+
+    /// Whether an account exists
+    pub async fn account_exists(&mut self, address: &Pubkey) -> bool {
+        self.context.banks_client.get_account(*address).await.unwrap().is_some()
+    }
+
+    /// Lamport balance of an account
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context.banks_client.get_balance(*address).await.unwrap()
+    }
+
+    /// Deserialize a program account
+    pub async fn get<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let data = self.account_data(address).await;
+        T::try_deserialize(&mut data.as_slice()).unwrap()
+    }
+
+    /// The RealToken account
+    pub async fn real_token_state(&mut self) -> RealToken {
+        let address = self.real_token;
+        self.get(&address).await
+    }
+
+    /// The role registry
+    pub async fn roles_state(&mut self) -> Roles {
+        let address = self.roles;
+        self.get(&address).await
+    }
+
+    /// Current cluster time
+    pub async fn now(&mut self) -> i64 {
+        self.context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp
+    }
+
+    /// Move the clock forward
+    pub async fn warp_forward(&mut self, seconds: i64) {
+        let mut clock = self.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Instruction granting a role, signed by `admin`
+    pub fn grant_role_ix(&self, admin: &Pubkey, member: Pubkey, role: Role) -> Instruction {
+        ix(
+            realstack::accounts::ManageRoles {
+                admin: *admin,
+                roles: self.roles,
+            },
+            realstack::instruction::GrantRole { member, role },
+        )
+    }
+
+    /// Grant a role as the admin
+    pub async fn grant_role(&mut self, member: Pubkey, role: Role) {
+        let instruction = self.grant_role_ix(&self.payer_key(), member, role);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    /// Instruction creating an asset token whose supply sits in `issuer_shares`, signed by `authority`
+    pub fn create_asset_ix(
+        &self,
+        authority: &Pubkey,
+        asset_token: Pubkey,
+        (mint, issuer_shares): (Pubkey, Pubkey),
+        can_mint_additional: bool,
+    ) -> Instruction {
+        self.create_restricted_asset_ix(
+            authority,
+            asset_token,
+            (mint, issuer_shares),
+            can_mint_additional,
+            ComplianceConfig::default(),
+        )
+    }
+
+    /// Instruction creating an asset token with investor restrictions, signed by `authority`
+    pub fn create_restricted_asset_ix(
+        &self,
+        authority: &Pubkey,
+        asset_token: Pubkey,
+        (mint, issuer_shares): (Pubkey, Pubkey),
+        can_mint_additional: bool,
+        compliance: ComplianceConfig,
+    ) -> Instruction {
+        ix(
+            realstack::accounts::CreateAssetToken {
+                authority: *authority,
+                real_token: self.real_token,
+                roles: self.roles,
+                asset_token,
+                mint,
+                mint_authority: pda(&[b"mint_authority", asset_token.as_ref()]),
+                freeze_authority: freeze_authority_of(&asset_token),
+                issuer_share_account: issuer_shares,
+                system_program: anchor_lang::system_program::ID,
+                token_program: anchor_spl::token::ID,
+                rent: anchor_lang::solana_program::sysvar::rent::ID,
+            },
+            realstack::instruction::CreateAssetToken {
+                name: "Harbor Lofts".to_string(),
+                symbol: "HBL".to_string(),
+                category: "real-estate".to_string(),
+                description: "Twelve-unit residential building.".to_string(),
+                uri: "https://realstack.finance/metadata/assets/harbor-lofts.json".to_string(),
+                valuation: ASSET_VALUATION,
+                total_shares: ASSET_TOTAL_SHARES,
+                share_price: ASSET_SHARE_PRICE,
+                can_mint_additional,
+                compliance,
+            },
+        )
+    }
+
+    /// Issue an asset's whole share supply to the payer and hand the mint to the asset's mint and freeze authority PDAs
+    pub async fn create_share_mint(&mut self, asset_token: &Pubkey) -> (Pubkey, Pubkey) {
+        let payer = self.payer_key();
+        let share_mint = self.create_mint_with_authorities(0, &payer, Some(&payer)).await;
+        let issuer_shares = self.create_funded_token_account(&share_mint, &payer, ASSET_TOTAL_SHARES).await;
+        self.set_mint_authority(&share_mint, &pda(&[b"mint_authority", asset_token.as_ref()])).await;
+        self.set_freeze_authority(&share_mint, &freeze_authority_of(asset_token)).await;
+        (share_mint, issuer_shares)
+    }
+
+    /// Create an asset whose whole share supply sits in the payer's account
+    pub async fn create_asset(&mut self, can_mint_additional: bool) -> AssetFixture {
+        self.create_restricted_asset(can_mint_additional, ComplianceConfig::default()).await
+    }
+
+    /// Create an asset with investor restrictions, its whole share supply in the payer's account
+    pub async fn create_restricted_asset(&mut self, can_mint_additional: bool, compliance: ComplianceConfig) -> AssetFixture {
+        let payer = self.payer_key();
+        let asset_keypair = Keypair::new();
+        let (share_mint, issuer_shares) = self.create_share_mint(&asset_keypair.pubkey()).await;
+        let instruction = self.create_restricted_asset_ix(
+            &payer,
+            asset_keypair.pubkey(),
+            (share_mint, issuer_shares),
+            can_mint_additional,
+            compliance,
+        );
+        self.process(&[instruction], &[&asset_keypair]).await.unwrap();
+
+        AssetFixture {
+            asset_token: asset_keypair.pubkey(),
+            share_mint,
+            issuer_shares,
+        }
+    }
+
+    /// Move `amount` of the payer's shares to `recipient_share_account` through the program, the only
+    /// way to move frozen share accounts; the asset is made tradable for the transfer if it is not
+    pub async fn issue_shares(&mut self, asset: &AssetFixture, recipient_share_account: &Pubkey, amount: u64) {
+        let payer = self.payer_key();
+        let data = self.account_data(recipient_share_account).await;
+        let recipient = spl_token::state::Account::unpack(&data).unwrap().owner;
+        let transfer = ix(
+            realstack::accounts::TransferShares {
+                sender: payer,
+                asset_token: asset.asset_token,
+                real_token: self.real_token,
+                sender_share_account: asset.issuer_shares,
+                sender_holder_record: holder_record_of(&asset.asset_token, &payer),
+                recipient_share_account: *recipient_share_account,
+                recipient_investor_record: None,
+                recipient_holder_record: holder_record_of(&asset.asset_token, &recipient),
+                share_mint: asset.share_mint,
+                freeze_authority: freeze_authority_of(&asset.asset_token),
+                token_program: anchor_spl::token::ID,
+                system_program: anchor_lang::system_program::ID,
+            },
+            realstack::instruction::TransferShares { amount },
+        );
+        let asset_token: realstack::AssetToken = self.get(&asset.asset_token).await;
+        let instructions = if asset_token.is_tradable {
+            vec![transfer]
+        } else {
+            vec![
+                self.toggle_tradability_ix(&payer, asset.asset_token, true),
+                transfer,
+                self.toggle_tradability_ix(&payer, asset.asset_token, false),
+            ]
+        };
+        self.process(&instructions, &[]).await.unwrap();
+    }
+
+    /// Wind an asset down with `proceeds` of a fresh proceeds mint, returning that mint
+    pub async fn burn_asset(&mut self, asset: &AssetFixture, proceeds: u64) -> Pubkey {
+        let payer = self.payer_key();
+        let proceeds_mint = self.create_mint(6).await;
+        let proceeds_source = self.create_funded_token_account(&proceeds_mint, &payer, proceeds).await;
+        let redemption = pda(&[b"redemption", asset.asset_token.as_ref()]);
+        let instruction = ix(
+            realstack::accounts::BurnAssetToken {
+                authority: payer,
+                asset_token: asset.asset_token,
+                roles: self.roles,
+                share_mint: asset.share_mint,
+                redemption,
+                proceeds_mint,
+                proceeds_vault: pda(&[b"redemption_vault", redemption.as_ref()]),
+                proceeds_source,
+                system_program: anchor_lang::system_program::ID,
+                token_program: anchor_spl::token::ID,
+                rent: anchor_lang::solana_program::sysvar::rent::ID,
+            },
+            realstack::instruction::BurnAssetToken { proceeds_amount: proceeds },
+        );
+        self.process(&[instruction], &[]).await.unwrap();
+        proceeds_mint
+    }
+
+    /// Address the next proposal under a governance config is created at
+    pub async fn next_proposal(&mut self, governance_config: &Pubkey) -> Pubkey {
+        let config: GovernanceConfig = self.get(governance_config).await;
+        pda(&[b"proposal", governance_config.as_ref(), &config.proposal_count.to_le_bytes()])
+    }
+
+    /// Instruction verifying an asset, signed by `verifier`
+    pub fn verify_asset_ix(&self, verifier: &Pubkey, asset_token: Pubkey) -> Instruction {
+        ix(
+            realstack::accounts::VerifyAsset {
+                verifier: *verifier,
+                asset_token,
+                roles: self.roles,
+            },
+            realstack::instruction::VerifyAsset {},
+        )
+    }
+
+    /// Verify an asset with the fixture verifier
+    pub async fn verify_asset(&mut self, asset_token: Pubkey) {
+        let verifier = clone_keypair(&self.verifier);
+        let instruction = self.verify_asset_ix(&verifier.pubkey(), asset_token);
+        self.process(&[instruction], &[&verifier]).await.unwrap();
+    }
+
+    /// Instruction toggling tradability, signed by `authority`
+    pub fn toggle_tradability_ix(&self, authority: &Pubkey, asset_token: Pubkey, is_tradable: bool) -> Instruction {
+        ix(
+            realstack::accounts::ToggleTradability {
+                authority: *authority,
+                asset_token,
+                roles: self.roles,
+            },
+            realstack::instruction::ToggleTradability { is_tradable },
+        )
+    }
+
+    /// Make an asset tradable
+    pub async fn set_tradable(&mut self, asset_token: Pubkey, is_tradable: bool) {
+        let instruction = self.toggle_tradability_ix(&self.payer_key(), asset_token, is_tradable);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    /// Instruction creating the REAL stake pool, signed by `authority`
+    pub fn initialize_stake_pool_ix(
+        &self,
+        authority: &Pubkey,
+        reward_mint: Pubkey,
+        fee_share_bps: u16,
+        unstake_cooldown: i64,
+    ) -> Instruction {
+        let stake_pool = pda(&[b"stake_pool", self.real_token.as_ref()]);
+        ix(
+            realstack::accounts::InitializeStakePool {
+                authority: *authority,
+                real_token: self.real_token,
+                stake_pool,
+                stake_mint: self.real_mint,
+                reward_mint,
+                stake_vault: pda(&[b"stake_vault", stake_pool.as_ref()]),
+                reward_vault: pda(&[b"reward_vault", stake_pool.as_ref()]),
+                system_program: anchor_lang::system_program::ID,
+                token_program: anchor_spl::token::ID,
+                rent: anchor_lang::solana_program::sysvar::rent::ID,
+            },
+            realstack::instruction::InitializeStakePool {
+                fee_share_bps,
+                unstake_cooldown,
+            },
+        )
+    }
+
+    /// The REAL stake pool PDA and its reward vault, whether or not the pool exists yet
+    pub fn stake_pool_pdas(&self) -> (Pubkey, Pubkey) {
+        let stake_pool = pda(&[b"stake_pool", self.real_token.as_ref()]);
+        (stake_pool, pda(&[b"reward_vault", stake_pool.as_ref()]))
+    }
+
+    /// Create the REAL stake pool with a fresh reward mint
+    pub async fn create_stake_pool(&mut self, fee_share_bps: u16, unstake_cooldown: i64) -> StakePoolFixture {
+        let reward_mint = self.create_mint(6).await;
+        self.create_stake_pool_paying(reward_mint, fee_share_bps, unstake_cooldown).await
+    }
+
+    /// Create the REAL stake pool paying rewards in `reward_mint`
+    pub async fn create_stake_pool_paying(
+        &mut self,
+        reward_mint: Pubkey,
+        fee_share_bps: u16,
+        unstake_cooldown: i64,
+    ) -> StakePoolFixture {
+        let instruction =
+            self.initialize_stake_pool_ix(&self.payer_key(), reward_mint, fee_share_bps, unstake_cooldown);
+        self.process(&[instruction], &[]).await.unwrap();
+
+        let (stake_pool, reward_vault) = self.stake_pool_pdas();
+        StakePoolFixture {
+            stake_pool,
+            stake_vault: pda(&[b"stake_vault", stake_pool.as_ref()]),
+            reward_mint,
+            reward_vault,
+        }
+    }
+
+    /// Instruction staking REAL from `owner_token_account`, signed by `owner`
+    pub fn stake_ix(
+        &self,
+        pool: &StakePoolFixture,
+        owner: &Pubkey,
+        owner_token_account: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        ix(
+            realstack::accounts::Stake {
+                owner: *owner,
+                stake_pool: pool.stake_pool,
+                real_token: self.real_token,
+                stake_account: pda(&[b"stake_account", pool.stake_pool.as_ref(), owner.as_ref()]),
+                checkpoints: pda(&[b"voting_power", pool.stake_pool.as_ref(), owner.as_ref()]),
+                owner_token_account,
+                stake_vault: pool.stake_vault,
+                system_program: anchor_lang::system_program::ID,
+                token_program: anchor_spl::token::ID,
+            },
+            realstack::instruction::Stake { amount },
+        )
+    }
+
+    /// Create a funded actor holding `amount` REAL, returning the actor and its REAL account
+    pub async fn new_real_holder(&mut self, amount: u64) -> (Keypair, Pubkey) {
+        let holder = self.new_actor().await;
+        let real_mint = self.real_mint;
+        let account = self.create_funded_token_account(&real_mint, &holder.pubkey(), amount).await;
+        (holder, account)
+    }
+
+    /// Create a funded actor with `amount` REAL staked in `pool`, giving it that much voting power
+    pub async fn new_staker(&mut self, pool: &StakePoolFixture, amount: u64) -> Keypair {
+        let (staker, staker_real) = self.new_real_holder(amount).await;
+        let instruction = self.stake_ix(pool, &staker.pubkey(), staker_real, amount);
+        self.process(&[instruction], &[&staker]).await.unwrap();
+        staker
+    }
+
+    /// Instruction setting all pause flags, signed by `authority`
+    pub fn set_pause_flags_ix(&self, authority: &Pubkey, flags: realstack::PauseFlags) -> Instruction {
+        ix(
+            realstack::accounts::SetTransferPause {
+                authority: *authority,
+                real_token: self.real_token,
+                roles: self.roles,
+            },
+            realstack::instruction::SetPauseFlags { flags },
+        )
+    }
+
+    /// Pause the given areas as the payer (which holds the Pauser role)
+    pub async fn pause(&mut self, flags: realstack::PauseFlags) {
+        let instruction = self.set_pause_flags_ix(&self.payer_key(), flags);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+}
+
+/// Copy a keypair (Keypair is not Clone)
+pub fn clone_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
+
+/// Pause flags with nothing set
+pub fn no_pause() -> realstack::PauseFlags {
+    realstack::PauseFlags {
+        trading: false,
+        income_distribution: false,
+        governance: false,
+        asset_creation: false,
+    }
+}
@@ -0,0 +1,448 @@
+mod common;
+
+use common::*;
+use realstack::{AssetToken, ComplianceConfig, HolderRecord, InvestorRecord, KycStatus, RealStackError, Role, MAX_ALLOWED_JURISDICTIONS};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const YEAR: i64 = 365 * 24 * 60 * 60;
+
+fn set_record_ix(
+    fixture: &TestFixture,
+    provider: &Pubkey,
+    wallet: Pubkey,
+    kyc_status: KycStatus,
+    jurisdiction: [u8; 2],
+    accredited: bool,
+    expires_at: i64,
+) -> Instruction {
+    ix(
+        realstack::accounts::SetInvestorRecord {
+            provider: *provider,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+            investor_record: record_of(fixture, &wallet),
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::SetInvestorRecord {
+            wallet,
+            kyc_status,
+            jurisdiction,
+            accredited,
+            expires_at,
+        },
+    )
+}
+
+fn transfer_ix(
+    fixture: &TestFixture,
+    asset: &AssetFixture,
+    recipient: Pubkey,
+    recipient_share_account: Pubkey,
+    recipient_investor_record: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    let payer = fixture.payer_key();
+    transfer_from_ix(
+        fixture,
+        asset,
+        (payer, asset.issuer_shares),
+        (recipient, recipient_share_account),
+        recipient_investor_record,
+        amount,
+    )
+}
+
+/// Transfer between `(wallet, share account)` pairs
+fn transfer_from_ix(
+    fixture: &TestFixture,
+    asset: &AssetFixture,
+    (sender, sender_share_account): (Pubkey, Pubkey),
+    (recipient, recipient_share_account): (Pubkey, Pubkey),
+    recipient_investor_record: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    ix(
+        realstack::accounts::TransferShares {
+            sender,
+            asset_token: asset.asset_token,
+            real_token: fixture.real_token,
+            sender_share_account,
+            sender_holder_record: holder_record_of(&asset.asset_token, &sender),
+            recipient_share_account,
+            recipient_investor_record,
+            recipient_holder_record: holder_record_of(&asset.asset_token, &recipient),
+            share_mint: asset.share_mint,
+            freeze_authority: freeze_authority_of(&asset.asset_token),
+            token_program: anchor_spl::token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::TransferShares { amount },
+    )
+}
+
+fn close_holder_ix(asset: &AssetFixture, wallet: &Pubkey, share_account: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::CloseHolderRecord {
+            wallet: *wallet,
+            asset_token: asset.asset_token,
+            holder_record: holder_record_of(&asset.asset_token, wallet),
+            share_account,
+        },
+        realstack::instruction::CloseHolderRecord {},
+    )
+}
+
+fn record_of(fixture: &TestFixture, wallet: &Pubkey) -> Pubkey {
+    pda(&[b"investor", fixture.real_token.as_ref(), wallet.as_ref()])
+}
+
+/// Accredited investors in the US or UK only
+fn accredited_us_uk() -> ComplianceConfig {
+    ComplianceConfig {
+        kyc_required: true,
+        allowed_jurisdictions: vec![*b"US", *b"GB"],
+        accredited_only: true,
+        ..ComplianceConfig::default()
+    }
+}
+
+/// Write a verified record for `wallet`, valid for a year, with the payer as KYC provider
+async fn verify_investor(fixture: &mut TestFixture, wallet: Pubkey, jurisdiction: [u8; 2], accredited: bool) {
+    let expires_at = fixture.now().await + YEAR;
+    let payer = fixture.payer_key();
+    let instruction =
+        set_record_ix(fixture, &payer, wallet, KycStatus::Verified, jurisdiction, accredited, expires_at);
+    fixture.process(&[instruction], &[]).await.unwrap();
+}
+
+/// A fresh wallet with an empty share account for `asset`
+async fn new_investor(fixture: &mut TestFixture, asset: &AssetFixture) -> (Keypair, Pubkey) {
+    let investor = fixture.new_actor().await;
+    let shares = fixture.create_token_account(&asset.share_mint, &investor.pubkey()).await;
+    (investor, shares)
+}
+
+#[tokio::test]
+async fn kyc_providers_write_investor_records() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let wallet = Pubkey::new_unique();
+    let expires_at = fixture.now().await + YEAR;
+
+    // Only registered KYC providers write records
+    let instruction = set_record_ix(&fixture, &payer, wallet, KycStatus::Verified, *b"US", true, expires_at);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    fixture.grant_role(payer, Role::KycProvider).await;
+    let instruction = set_record_ix(&fixture, &payer, wallet, KycStatus::Verified, *b"us", true, expires_at);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    // A verification must not already have lapsed
+    let now = fixture.now().await;
+    let instruction = set_record_ix(&fixture, &payer, wallet, KycStatus::Verified, *b"US", true, now);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = set_record_ix(&fixture, &payer, wallet, KycStatus::Verified, *b"US", true, expires_at);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let record: InvestorRecord = fixture.get(&record_of(&fixture, &wallet)).await;
+    assert_eq!(record.wallet, wallet);
+    assert_eq!(record.real_token, fixture.real_token);
+    assert_eq!(record.kyc_status, KycStatus::Verified);
+    assert_eq!(record.jurisdiction, *b"US");
+    assert!(record.accredited);
+    assert_eq!(record.expires_at, expires_at);
+    assert_eq!(record.provider, payer);
+
+    // Providers revoke by rewriting the record
+    let instruction = set_record_ix(&fixture, &payer, wallet, KycStatus::Revoked, *b"US", false, 0);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let record: InvestorRecord = fixture.get(&record_of(&fixture, &wallet)).await;
+    assert_eq!(record.kyc_status, KycStatus::Revoked);
+    assert!(!record.accredited);
+}
+
+#[tokio::test]
+async fn asset_creation_validates_requirements() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+
+    let asset = fixture.create_restricted_asset(false, accredited_us_uk()).await;
+    let state: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(state.compliance, accredited_us_uk());
+
+    let invalid = [
+        ComplianceConfig {
+            allowed_jurisdictions: vec![*b"U1"],
+            ..accredited_us_uk()
+        },
+        ComplianceConfig {
+            allowed_jurisdictions: vec![*b"US"; MAX_ALLOWED_JURISDICTIONS + 1],
+            ..accredited_us_uk()
+        },
+    ];
+    for compliance in invalid {
+        let asset = Keypair::new();
+        let shares = fixture.create_share_mint(&asset.pubkey()).await;
+        let instruction = fixture.create_restricted_asset_ix(&payer, asset.pubkey(), shares, false, compliance);
+        let result = fixture.process(&[instruction], &[&asset]).await;
+        assert_error(result, RealStackError::InvalidParameters);
+    }
+}
+
+#[tokio::test]
+async fn transfers_of_restricted_shares_need_an_eligible_recipient() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    fixture.grant_role(payer, Role::KycProvider).await;
+    let asset = fixture.create_restricted_asset(false, accredited_us_uk()).await;
+    fixture.set_tradable(asset.asset_token, true).await;
+    let (investor, shares) = new_investor(&mut fixture, &asset).await;
+    let record = record_of(&fixture, &investor.pubkey());
+
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, None, 10);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvestorNotVerified);
+
+    // Another wallet's record does not vouch for the recipient
+    let (other, _) = new_investor(&mut fixture, &asset).await;
+    verify_investor(&mut fixture, other.pubkey(), *b"US", true).await;
+    let other_record = record_of(&fixture, &other.pubkey());
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, Some(other_record), 10);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidInvestorRecord);
+
+    verify_investor(&mut fixture, investor.pubkey(), *b"DE", true).await;
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, Some(record), 10);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::JurisdictionNotAllowed);
+
+    verify_investor(&mut fixture, investor.pubkey(), *b"GB", false).await;
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, Some(record), 10);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AccreditationRequired);
+
+    verify_investor(&mut fixture, investor.pubkey(), *b"GB", true).await;
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, Some(record), 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&shares).await, 10);
+
+    // Lapsed verifications no longer qualify
+    fixture.warp_forward(YEAR).await;
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, Some(record), 10);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvestorNotVerified);
+}
+
+#[tokio::test]
+async fn restricted_shares_only_move_through_the_program() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    fixture.grant_role(payer, Role::KycProvider).await;
+    let asset = fixture.create_restricted_asset(false, accredited_us_uk()).await;
+    fixture.set_tradable(asset.asset_token, true).await;
+    let (investor, shares) = new_investor(&mut fixture, &asset).await;
+    verify_investor(&mut fixture, investor.pubkey(), *b"US", true).await;
+    let record = record_of(&fixture, &investor.pubkey());
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, Some(record), 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    // A plain SPL transfer to an unverified wallet hits the frozen share account
+    let (_, outsider_shares) = new_investor(&mut fixture, &asset).await;
+    let frozen = spl_token::error::TokenError::AccountFrozen as u32;
+    let instruction =
+        spl_token::instruction::transfer(&spl_token::ID, &shares, &outsider_shares, &investor.pubkey(), &[], 10).unwrap();
+    let result = fixture.process(&[instruction], &[&investor]).await;
+    assert_custom_error(result, frozen);
+    let instruction =
+        spl_token::instruction::transfer(&spl_token::ID, &asset.issuer_shares, &outsider_shares, &payer, &[], 10).unwrap();
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_custom_error(result, frozen);
+
+    // Nor can a holder thaw their own account
+    let instruction = spl_token::instruction::thaw_account(
+        &spl_token::ID,
+        &shares,
+        &asset.share_mint,
+        &investor.pubkey(),
+        &[],
+    )
+    .unwrap();
+    let result = fixture.process(&[instruction], &[&investor]).await;
+    assert_failed(result);
+    assert_eq!(fixture.token_balance(&shares).await, 10);
+    assert_eq!(fixture.token_balance(&outsider_shares).await, 0);
+}
+
+#[tokio::test]
+async fn unrestricted_shares_transfer_without_records() {
+    let mut fixture = TestFixture::new().await;
+    let asset = fixture.create_asset(false).await;
+    let (investor, shares) = new_investor(&mut fixture, &asset).await;
+
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, None, 10);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetNotTradable);
+
+    fixture.set_tradable(asset.asset_token, true).await;
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, None, 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&shares).await, 10);
+    assert_eq!(fixture.token_balance(&asset.issuer_shares).await, ASSET_TOTAL_SHARES - 10);
+}
+
+#[tokio::test]
+async fn holdings_are_capped_per_wallet() {
+    let mut fixture = TestFixture::new().await;
+    let compliance = ComplianceConfig {
+        max_holding_bps: 500,
+        ..ComplianceConfig::default()
+    };
+    let asset = fixture.create_restricted_asset(false, compliance).await;
+    fixture.set_tradable(asset.asset_token, true).await;
+    let (investor, shares) = new_investor(&mut fixture, &asset).await;
+    let cap = ASSET_TOTAL_SHARES * 500 / 10_000;
+
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, None, cap - 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    // The cap covers what the wallet already holds
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, None, 11);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::HoldingCapExceeded);
+
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, None, 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&shares).await, cap);
+
+    // A second share account counts toward the same holding
+    let second_shares = fixture.create_token_account(&asset.share_mint, &investor.pubkey()).await;
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), second_shares, None, 1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::HoldingCapExceeded);
+    let record: HolderRecord = fixture.get(&holder_record_of(&asset.asset_token, &investor.pubkey())).await;
+    assert_eq!(record.balance, cap);
+
+    // Caps above the whole supply are rejected
+    let payer = fixture.payer_key();
+    let asset = Keypair::new();
+    let shares = fixture.create_share_mint(&asset.pubkey()).await;
+    let compliance = ComplianceConfig {
+        max_holding_bps: 10_001,
+        ..ComplianceConfig::default()
+    };
+    let instruction = fixture.create_restricted_asset_ix(&payer, asset.pubkey(), shares, false, compliance);
+    let result = fixture.process(&[instruction], &[&asset]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+}
+
+#[tokio::test]
+async fn holder_limit_counts_distinct_wallets() {
+    let mut fixture = TestFixture::new().await;
+    let compliance = ComplianceConfig {
+        max_holders: 2,
+        ..ComplianceConfig::default()
+    };
+    let asset = fixture.create_restricted_asset(false, compliance).await;
+    fixture.set_tradable(asset.asset_token, true).await;
+    let (first, first_shares) = new_investor(&mut fixture, &asset).await;
+    let (second, second_shares) = new_investor(&mut fixture, &asset).await;
+    let (third, third_shares) = new_investor(&mut fixture, &asset).await;
+
+    for (wallet, shares) in [(first.pubkey(), first_shares), (second.pubkey(), second_shares)] {
+        let instruction = transfer_ix(&fixture, &asset, wallet, shares, None, 10);
+        fixture.process(&[instruction], &[]).await.unwrap();
+    }
+    let record: HolderRecord = fixture.get(&holder_record_of(&asset.asset_token, &first.pubkey())).await;
+    assert_eq!(record.wallet, first.pubkey());
+    assert_eq!(record.asset_token, asset.asset_token);
+    let state: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(state.holder_count, 2);
+
+    // Existing holders keep buying; new ones are turned away
+    let instruction = transfer_ix(&fixture, &asset, first.pubkey(), first_shares, None, 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = transfer_ix(&fixture, &asset, third.pubkey(), third_shares, None, 10);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::HolderLimitReached);
+
+    // A holder frees their slot once they sell out, not by pointing at an empty second account
+    let instruction = close_holder_ix(&asset, &first.pubkey(), first_shares);
+    let result = fixture.process(&[instruction], &[&first]).await;
+    assert_error(result, RealStackError::SharesStillHeld);
+    let empty_shares = fixture.create_token_account(&asset.share_mint, &first.pubkey()).await;
+    let instruction = transfer_from_ix(
+        &fixture,
+        &asset,
+        (first.pubkey(), first_shares),
+        (first.pubkey(), empty_shares),
+        None,
+        20,
+    );
+    fixture.process(&[instruction], &[&first]).await.unwrap();
+    let instruction = close_holder_ix(&asset, &first.pubkey(), first_shares);
+    let result = fixture.process(&[instruction], &[&first]).await;
+    assert_error(result, RealStackError::SharesStillHeld);
+
+    let instruction = transfer_from_ix(
+        &fixture,
+        &asset,
+        (first.pubkey(), empty_shares),
+        (second.pubkey(), second_shares),
+        None,
+        20,
+    );
+    fixture.process(&[instruction], &[&first]).await.unwrap();
+    let record: HolderRecord = fixture.get(&holder_record_of(&asset.asset_token, &first.pubkey())).await;
+    assert_eq!(record.balance, 0);
+    let instruction = close_holder_ix(&asset, &first.pubkey(), first_shares);
+    fixture.process(&[instruction], &[&first]).await.unwrap();
+    let state: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(state.holder_count, 1);
+
+    let instruction = transfer_ix(&fixture, &asset, third.pubkey(), third_shares, None, 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(state.holder_count, 2);
+}
+
+#[tokio::test]
+async fn holder_records_close_only_once_the_shares_are_gone() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let asset = fixture.create_asset(false).await;
+    fixture.set_tradable(asset.asset_token, true).await;
+    let (investor, shares) = new_investor(&mut fixture, &asset).await;
+
+    // The issuer buys shares back, then sells them on while the rest of the supply stays untracked
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, None, 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = transfer_from_ix(
+        &fixture,
+        &asset,
+        (investor.pubkey(), shares),
+        (payer, asset.issuer_shares),
+        None,
+        10,
+    );
+    fixture.process(&[instruction], &[&investor]).await.unwrap();
+    let instruction = transfer_ix(&fixture, &asset, investor.pubkey(), shares, None, 10);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let record: HolderRecord = fixture.get(&holder_record_of(&asset.asset_token, &payer)).await;
+    assert_eq!(record.balance, 0);
+
+    // A zero tracked balance is not enough while the wallet's share account holds shares
+    let instruction = close_holder_ix(&asset, &payer, asset.issuer_shares);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::SharesStillHeld);
+
+    // Only the wallet's own share accounts count
+    let instruction = close_holder_ix(&asset, &payer, shares);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+}
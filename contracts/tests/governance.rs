@@ -0,0 +1,2168 @@
+mod common;
+
+use anchor_lang::InstructionData;
+use common::*;
+use realstack::{
+    AssetToken, Ballot, Delegation, GovernanceConfig, ParameterAction, PauseFlags, Proposal, ProposalOptionInput,
+    ProposalType, ProposerState, RealStackError, RealToken, ResolutionMethod, StakeAccount, StakePool, VoteChoice,
+    VoteRecord, VotingMode,
+    ASSET_QUORUM_PERCENTAGE, CONVICTION_PERIOD, MAX_CONVICTION_LOCK, MAX_OPTION_DATA_LEN, MAX_OPTION_LABEL_LEN,
+    MAX_SNAPSHOT_AGE, OPTIMISTIC_OBJECTION_PERCENTAGE, PROPOSAL_RETENTION_PERIOD,
+};
+use solana_program_test::tokio;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const DAY: i64 = 24 * 60 * 60;
+const MIN_VOTING_PERIOD: i64 = DAY;
+const MAX_VOTING_PERIOD: i64 = 7 * DAY;
+const QUORUM: u64 = 100;
+const THRESHOLD: u8 = 51;
+const MIN_VOTE_BALANCE: u64 = 10;
+const MIN_PROPOSAL_BALANCE: u64 = 200;
+const PROPOSAL_DEPOSIT: u64 = 50;
+const MAX_ACTIVE_PROPOSALS: u8 = 3;
+const PROPOSER_BALANCE: u64 = 1_000;
+
+/// Governance set up on top of the base fixture
+struct Governance {
+    real_token: Pubkey,
+    config: Pubkey,
+    pool: StakePoolFixture,
+    treasury: Pubkey,
+    /// The payer's REAL account, which funds proposal deposits
+    proposer_real: Pubkey,
+}
+
+fn initialize_governance_ix(
+    fixture: &TestFixture,
+    authority: &Pubkey,
+    treasury: Pubkey,
+    min_voting_period: i64,
+    max_voting_period: i64,
+    approval_threshold: u8,
+    voting_mode: VotingMode,
+) -> Instruction {
+    ix(
+        realstack::accounts::InitializeGovernance {
+            authority: *authority,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+            treasury,
+            governance_config: pda(&[b"governance_config", fixture.real_token.as_ref()]),
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::InitializeGovernance {
+            min_voting_period,
+            max_voting_period,
+            min_quorum_votes: QUORUM,
+            approval_threshold,
+            min_proposal_balance: MIN_PROPOSAL_BALANCE,
+            min_vote_balance: MIN_VOTE_BALANCE,
+            proposal_deposit: PROPOSAL_DEPOSIT,
+            max_active_proposals: MAX_ACTIVE_PROPOSALS,
+            voting_mode,
+        },
+    )
+}
+
+fn create_proposal_accounts(
+    fixture: &TestFixture,
+    governance: &Governance,
+    proposer: &Pubkey,
+    proposer_token_account: Pubkey,
+    proposal: Pubkey,
+) -> realstack::accounts::CreateProposal {
+    realstack::accounts::CreateProposal {
+        proposer: *proposer,
+        proposal,
+        governance_config: governance.config,
+        real_token: fixture.real_token,
+        real_mint: fixture.real_mint,
+        proposer_token_account,
+        deposit_escrow: deposit_escrow_of(&proposal),
+        proposer_state: proposer_state_of(governance, proposer),
+        stake_pool: None,
+        system_program: anchor_lang::system_program::ID,
+        token_program: anchor_spl::token::ID,
+        rent: solana_sdk::sysvar::rent::ID,
+    }
+}
+
+fn create_proposal_ix(
+    fixture: &TestFixture,
+    governance: &Governance,
+    proposer: &Pubkey,
+    proposer_token_account: Pubkey,
+    proposal: Pubkey,
+    title: &str,
+    voting_ends_at: i64,
+) -> Instruction {
+    ix(
+        create_proposal_accounts(fixture, governance, proposer, proposer_token_account, proposal),
+        realstack::instruction::CreateProposal {
+            title: title.to_string(),
+            description: "Raise the stakers' fee share to 60%.".to_string(),
+            voting_ends_at,
+        },
+    )
+}
+
+fn vote_accounts(
+    governance: &Governance,
+    voter: &Pubkey,
+    proposal: Pubkey,
+    stake_account: Option<Pubkey>,
+) -> realstack::accounts::VoteOnProposal {
+    realstack::accounts::VoteOnProposal {
+        voter: *voter,
+        proposal,
+        vote_record: vote_record_of(&proposal, voter),
+        governance_config: governance.config,
+        real_token: governance.real_token,
+        stake_pool: governance.pool.stake_pool,
+        stake_account,
+        checkpoints: Some(checkpoints_of(governance, voter)),
+        real_mint: None,
+        voter_real_account: None,
+        vote_escrow: None,
+        system_program: anchor_lang::system_program::ID,
+        token_program: anchor_spl::token::ID,
+    }
+}
+
+fn vote_ix(
+    governance: &Governance,
+    voter: &Pubkey,
+    proposal: Pubkey,
+    stake_account: Option<Pubkey>,
+    choice: VoteChoice,
+    vote_weight: u64,
+) -> Instruction {
+    ix(
+        vote_accounts(governance, voter, proposal, stake_account),
+        realstack::instruction::VoteOnProposal {
+            choice,
+            vote_weight,
+            lock_duration: 0,
+        },
+    )
+}
+
+/// A vote of staked REAL that locks the stake for `lock_duration` after voting ends
+fn conviction_vote_ix(
+    governance: &Governance,
+    voter: &Pubkey,
+    proposal: Pubkey,
+    choice: VoteChoice,
+    vote_weight: u64,
+    lock_duration: i64,
+) -> Instruction {
+    let stake_account = pda(&[b"stake_account", governance.pool.stake_pool.as_ref(), voter.as_ref()]);
+    let mut instruction = vote_ix(governance, voter, proposal, Some(stake_account), choice, vote_weight);
+    instruction.data = realstack::instruction::VoteOnProposal {
+        choice,
+        vote_weight,
+        lock_duration,
+    }
+    .data();
+    instruction
+}
+
+/// A vote covered by `voter_real` beyond the voter's stake, if they have `staked` at all
+#[allow(clippy::too_many_arguments)]
+fn liquid_vote_ix(
+    fixture: &TestFixture,
+    governance: &Governance,
+    voter: &Pubkey,
+    voter_real: Pubkey,
+    staked: bool,
+    proposal: Pubkey,
+    choice: VoteChoice,
+    vote_weight: u64,
+) -> Instruction {
+    ix(
+        realstack::accounts::VoteOnProposal {
+            checkpoints: staked.then(|| checkpoints_of(governance, voter)),
+            real_mint: Some(fixture.real_mint),
+            voter_real_account: Some(voter_real),
+            vote_escrow: Some(vote_escrow_of(&proposal, voter)),
+            ..vote_accounts(governance, voter, proposal, None)
+        },
+        realstack::instruction::VoteOnProposal {
+            choice,
+            vote_weight,
+            lock_duration: 0,
+        },
+    )
+}
+
+fn change_vote_ix(
+    governance: &Governance,
+    voter: &Pubkey,
+    proposal: Pubkey,
+    choice: VoteChoice,
+    vote_weight: u64,
+) -> Instruction {
+    ix(
+        realstack::accounts::ChangeVote {
+            voter: *voter,
+            proposal,
+            vote_record: vote_record_of(&proposal, voter),
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            stake_pool: governance.pool.stake_pool,
+            stake_account: None,
+            checkpoints: Some(checkpoints_of(governance, voter)),
+            real_mint: None,
+            voter_real_account: None,
+            vote_escrow: None,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::ChangeVote { choice, vote_weight },
+    )
+}
+
+fn create_option_proposal_ix(
+    fixture: &TestFixture,
+    governance: &Governance,
+    proposal: Pubkey,
+    voting_ends_at: i64,
+    options: Vec<ProposalOptionInput>,
+    resolution: ResolutionMethod,
+) -> Instruction {
+    ix(
+        realstack::accounts::CreateOptionProposal {
+            proposer: fixture.payer_key(),
+            proposal,
+            governance_config: governance.config,
+            real_token: fixture.real_token,
+            real_mint: fixture.real_mint,
+            proposer_token_account: governance.proposer_real,
+            deposit_escrow: deposit_escrow_of(&proposal),
+            proposer_state: proposer_state_of(governance, &fixture.payer_key()),
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        realstack::instruction::CreateOptionProposal {
+            title: "Choose a property manager".to_string(),
+            description: "Select the manager for the downtown portfolio.".to_string(),
+            voting_ends_at,
+            options,
+            resolution,
+        },
+    )
+}
+
+fn vote_on_options_ix(
+    governance: &Governance,
+    voter: &Pubkey,
+    proposal: Pubkey,
+    ranking: Vec<u8>,
+    vote_weight: u64,
+) -> Instruction {
+    ix(
+        realstack::accounts::VoteOnOptions {
+            voter: *voter,
+            proposal,
+            vote_record: vote_record_of(&proposal, voter),
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            stake_pool: governance.pool.stake_pool,
+            stake_account: None,
+            checkpoints: Some(checkpoints_of(governance, voter)),
+            real_mint: None,
+            voter_real_account: None,
+            vote_escrow: None,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::VoteOnOptions {
+            ranking,
+            vote_weight,
+            lock_duration: 0,
+        },
+    )
+}
+
+fn execute_ix(fixture: &TestFixture, governance: &Governance, executor: &Pubkey, proposal: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ExecuteProposal {
+            executor: *executor,
+            proposal,
+            governance_config: governance.config,
+            real_token: fixture.real_token,
+        },
+        realstack::instruction::ExecuteProposal {},
+    )
+}
+
+fn settle_deposit_ix(
+    fixture: &TestFixture,
+    governance: &Governance,
+    proposal: Pubkey,
+    proposer: &Pubkey,
+    proposer_token_account: Option<Pubkey>,
+) -> Instruction {
+    ix(
+        realstack::accounts::SettleProposalDeposit {
+            settler: fixture.payer_key(),
+            proposal,
+            proposer: *proposer,
+            governance_config: governance.config,
+            real_token: fixture.real_token,
+            proposer_state: proposer_state_of(governance, proposer),
+            deposit_escrow: deposit_escrow_of(&proposal),
+            proposer_token_account,
+            treasury: governance.treasury,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::SettleProposalDeposit {},
+    )
+}
+
+fn close_vote_record_ix(voter: &Pubkey, proposal: Pubkey, vote_record: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::CloseVoteRecord {
+            voter: *voter,
+            proposal,
+            vote_record,
+        },
+        realstack::instruction::CloseVoteRecord {},
+    )
+}
+
+fn close_proposal_ix(proposer: &Pubkey, proposal: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::CloseProposal {
+            proposer: *proposer,
+            proposal,
+        },
+        realstack::instruction::CloseProposal {},
+    )
+}
+
+fn deposit_escrow_of(proposal: &Pubkey) -> Pubkey {
+    pda(&[b"proposal_deposit", proposal.as_ref()])
+}
+
+fn proposer_state_of(governance: &Governance, proposer: &Pubkey) -> Pubkey {
+    pda(&[b"proposer_state", governance.config.as_ref(), proposer.as_ref()])
+}
+
+fn vote_record_of(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+    pda(&[b"vote_record", proposal.as_ref(), voter.as_ref()])
+}
+
+fn checkpoints_of(governance: &Governance, holder: &Pubkey) -> Pubkey {
+    pda(&[b"voting_power", governance.pool.stake_pool.as_ref(), holder.as_ref()])
+}
+
+fn delegation_of(governance: &Governance, delegator: &Pubkey, proposal_type: Option<ProposalType>) -> Pubkey {
+    pda(&[
+        b"delegation",
+        governance.config.as_ref(),
+        delegator.as_ref(),
+        &[Delegation::scope_seed(proposal_type)],
+    ])
+}
+
+fn delegate_ix(
+    governance: &Governance,
+    delegator: &Pubkey,
+    delegate: Pubkey,
+    proposal_type: Option<ProposalType>,
+) -> Instruction {
+    ix(
+        realstack::accounts::Delegate {
+            delegator: *delegator,
+            governance_config: governance.config,
+            delegation: delegation_of(governance, delegator, proposal_type),
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::Delegate { delegate, proposal_type },
+    )
+}
+
+fn undelegate_ix(delegator: &Pubkey, delegation: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::Undelegate {
+            delegator: *delegator,
+            delegation,
+        },
+        realstack::instruction::Undelegate {},
+    )
+}
+
+fn cast_delegated_vote_ix(
+    governance: &Governance,
+    delegate: &Pubkey,
+    proposal: Pubkey,
+    delegation: Pubkey,
+    delegator: &Pubkey,
+    choice: VoteChoice,
+) -> Instruction {
+    ix(
+        realstack::accounts::CastDelegatedVote {
+            delegate: *delegate,
+            proposal,
+            delegation,
+            type_delegation: delegation_of(governance, delegator, Some(ProposalType::Text)),
+            vote_record: vote_record_of(&proposal, delegator),
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            stake_pool: governance.pool.stake_pool,
+            checkpoints: checkpoints_of(governance, delegator),
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::CastDelegatedVote { choice },
+    )
+}
+
+async fn setup_governance(fixture: &mut TestFixture) -> Governance {
+    setup_governance_with_mode(fixture, VotingMode::Linear).await
+}
+
+async fn setup_governance_with_mode(fixture: &mut TestFixture, voting_mode: VotingMode) -> Governance {
+    let pool = fixture.create_stake_pool(5_000, DAY).await;
+    let payer = fixture.payer_key();
+    let real_mint = fixture.real_mint;
+    let treasury = fixture.create_token_account(&real_mint, &Pubkey::new_unique()).await;
+    let proposer_real = fixture.create_funded_token_account(&real_mint, &payer, PROPOSER_BALANCE).await;
+    let instruction = initialize_governance_ix(
+        fixture,
+        &payer,
+        treasury,
+        MIN_VOTING_PERIOD,
+        MAX_VOTING_PERIOD,
+        THRESHOLD,
+        voting_mode,
+    );
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    Governance {
+        real_token: fixture.real_token,
+        config: pda(&[b"governance_config", fixture.real_token.as_ref()]),
+        pool,
+        treasury,
+        proposer_real,
+    }
+}
+
+/// Create a proposal from the payer ending after the minimum voting period
+async fn create_proposal(fixture: &mut TestFixture, governance: &Governance) -> Pubkey {
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let voting_ends_at = fixture.now().await + MIN_VOTING_PERIOD + 60;
+    let instruction = create_proposal_ix(
+        fixture,
+        governance,
+        &fixture.payer_key(),
+        governance.proposer_real,
+        proposal,
+        "Raise fee share",
+        voting_ends_at,
+    );
+    fixture.process(&[instruction], &[]).await.unwrap();
+    proposal
+}
+
+/// A fresh voter with `weight` REAL staked; it can vote on proposals created after this
+async fn new_voter(fixture: &mut TestFixture, governance: &Governance, weight: u64) -> Keypair {
+    fixture.new_staker(&governance.pool, weight).await
+}
+
+/// Cast a vote of `weight` from a voter who staked before the proposal was created
+async fn cast_vote(
+    fixture: &mut TestFixture,
+    governance: &Governance,
+    proposal: Pubkey,
+    voter: &Keypair,
+    choice: VoteChoice,
+    weight: u64,
+) {
+    let instruction = vote_ix(governance, &voter.pubkey(), proposal, None, choice, weight);
+    fixture.process(&[instruction], &[voter]).await.unwrap();
+}
+
+#[tokio::test]
+async fn initialize_governance_validates_parameters() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let real_mint = fixture.real_mint;
+    let treasury = fixture.create_token_account(&real_mint, &Pubkey::new_unique()).await;
+    let mode = VotingMode::Linear;
+
+    let instruction = initialize_governance_ix(&fixture, &payer, treasury, 0, MAX_VOTING_PERIOD, THRESHOLD, mode);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidVotingPeriod);
+
+    let instruction = initialize_governance_ix(
+        &fixture,
+        &payer,
+        treasury,
+        MAX_VOTING_PERIOD,
+        MIN_VOTING_PERIOD,
+        THRESHOLD,
+        mode,
+    );
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidVotingPeriod);
+
+    // Votes could outlive the voting power history they read
+    let instruction = initialize_governance_ix(
+        &fixture,
+        &payer,
+        treasury,
+        MIN_VOTING_PERIOD,
+        MAX_SNAPSHOT_AGE + 1,
+        THRESHOLD,
+        mode,
+    );
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidVotingPeriod);
+
+    for threshold in [0, 101] {
+        let instruction = initialize_governance_ix(
+            &fixture,
+            &payer,
+            treasury,
+            MIN_VOTING_PERIOD,
+            MAX_VOTING_PERIOD,
+            threshold,
+            mode,
+        );
+        let result = fixture.process(&[instruction], &[]).await;
+        assert_error(result, RealStackError::InvalidParameters);
+    }
+
+    let instruction = initialize_governance_ix(
+        &fixture,
+        &intruder.pubkey(),
+        treasury,
+        MIN_VOTING_PERIOD,
+        MAX_VOTING_PERIOD,
+        THRESHOLD,
+        mode,
+    );
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // Slashed deposits must be paid in REAL
+    let usdc = fixture.create_mint(6).await;
+    let wrong_treasury = fixture.create_token_account(&usdc, &Pubkey::new_unique()).await;
+    let instruction = initialize_governance_ix(
+        &fixture,
+        &payer,
+        wrong_treasury,
+        MIN_VOTING_PERIOD,
+        MAX_VOTING_PERIOD,
+        THRESHOLD,
+        mode,
+    );
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidTokenMint);
+
+    let governance = setup_governance(&mut fixture).await;
+    let config: GovernanceConfig = fixture.get(&governance.config).await;
+    assert_eq!(config.real_token, fixture.real_token);
+    assert_eq!(config.approval_threshold, THRESHOLD);
+    assert!(config.governance_active);
+    assert_eq!(config.treasury, governance.treasury);
+    assert_eq!(config.proposal_deposit, PROPOSAL_DEPOSIT);
+}
+
+#[tokio::test]
+async fn create_proposal_validates_parameters() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let now = fixture.now().await;
+
+    for (title, voting_ends_at, error) in [
+        ("", now + 2 * DAY, RealStackError::InvalidParameters),
+        ("Too short", now + MIN_VOTING_PERIOD / 2, RealStackError::InvalidVotingPeriod),
+        ("Too long", now + MAX_VOTING_PERIOD + DAY, RealStackError::InvalidVotingPeriod),
+    ] {
+        let proposal = fixture.next_proposal(&governance.config).await;
+        let instruction =
+            create_proposal_ix(&fixture, &governance, &payer, governance.proposer_real, proposal, title, voting_ends_at);
+        let result = fixture.process(&[instruction], &[]).await;
+        assert_error(result, error);
+    }
+
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.proposer, payer);
+    assert_eq!(state.governance_config, governance.config);
+    assert_eq!(state.quorum_votes, QUORUM);
+    assert!(state.is_active);
+}
+
+#[tokio::test]
+async fn proposal_passes_with_quorum_and_majority() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let yes_voter = new_voter(&mut fixture, &governance, 80).await;
+    let no_voter = new_voter(&mut fixture, &governance, 40).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    cast_vote(&mut fixture, &governance, proposal, &yes_voter, VoteChoice::Yes, 80).await;
+    cast_vote(&mut fixture, &governance, proposal, &no_voter, VoteChoice::No, 40).await;
+
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 80);
+    assert_eq!(state.no_votes, 40);
+    let record: VoteRecord = fixture.get(&vote_record_of(&proposal, &yes_voter.pubkey())).await;
+    assert_eq!(record.ballot, Ballot::Choice(VoteChoice::Yes));
+    assert_eq!(record.vote_weight, 80);
+
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::VotingPeriodNotEnded);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+    assert!(!state.is_active);
+    assert_eq!(state.executor, Some(payer));
+
+    // Execution deactivates the proposal, so a second attempt reads as inactive
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::ProposalInactive);
+}
+
+#[tokio::test]
+async fn execute_proposal_requires_quorum() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let voter = new_voter(&mut fixture, &governance, QUORUM - 1).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    cast_vote(&mut fixture, &governance, proposal, &voter, VoteChoice::Yes, QUORUM - 1).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+}
+
+#[tokio::test]
+async fn abstentions_count_toward_quorum_only() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let ballots = [(VoteChoice::Yes, 40), (VoteChoice::No, 20), (VoteChoice::Abstain, QUORUM - 60)];
+    let mut voters = Vec::new();
+    for (_, weight) in ballots {
+        voters.push(new_voter(&mut fixture, &governance, weight).await);
+    }
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    for (voter, (choice, weight)) in voters.iter().zip(ballots) {
+        cast_vote(&mut fixture, &governance, proposal, voter, choice, weight).await;
+    }
+
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 40);
+    assert_eq!(state.no_votes, 20);
+    assert_eq!(state.abstain_votes, QUORUM - 60);
+
+    // Yes and no alone miss quorum; the abstentions make it up
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+}
+
+#[tokio::test]
+async fn change_vote_moves_weight_between_choices() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let voter = new_voter(&mut fixture, &governance, 50).await;
+    let stranger = new_voter(&mut fixture, &governance, 50).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    let instruction = change_vote_ix(&governance, &stranger.pubkey(), proposal, VoteChoice::No, 50);
+    let result = fixture.process(&[instruction], &[&stranger]).await;
+    assert_failed(result);
+
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::Yes, 50);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+
+    let instruction = change_vote_ix(&governance, &voter.pubkey(), proposal, VoteChoice::No, 40);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!((state.yes_votes, state.no_votes, state.abstain_votes), (0, 40, 0));
+
+    let instruction = change_vote_ix(&governance, &voter.pubkey(), proposal, VoteChoice::Abstain, 50);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!((state.yes_votes, state.no_votes, state.abstain_votes), (0, 0, 50));
+    let record: VoteRecord = fixture.get(&vote_record_of(&proposal, &voter.pubkey())).await;
+    assert_eq!(record.ballot, Ballot::Choice(VoteChoice::Abstain));
+    assert_eq!(record.vote_weight, 50);
+
+    let instruction = change_vote_ix(&governance, &voter.pubkey(), proposal, VoteChoice::Yes, 51);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+
+    let instruction = change_vote_ix(&governance, &voter.pubkey(), proposal, VoteChoice::Yes, MIN_VOTE_BALANCE - 1);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = change_vote_ix(&governance, &voter.pubkey(), proposal, VoteChoice::Yes, 50);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::VotingPeriodEnded);
+}
+
+#[tokio::test]
+async fn liquid_real_votes_from_escrow_on_top_of_stake() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let (holder, holder_real) = fixture.new_real_holder(150).await;
+    let (staker, staker_real) = fixture.new_real_holder(70).await;
+    let instruction = fixture.stake_ix(&governance.pool, &staker.pubkey(), staker_real, 40);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    let holder_escrow = vote_escrow_of(&proposal, &holder.pubkey());
+
+    // A holder who never staked votes with their REAL balance, which moves into escrow
+    let instruction =
+        liquid_vote_ix(&fixture, &governance, &holder.pubkey(), holder_real, false, proposal, VoteChoice::Yes, 151);
+    let result = fixture.process(&[instruction], &[&holder]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+    let instruction =
+        liquid_vote_ix(&fixture, &governance, &holder.pubkey(), holder_real, false, proposal, VoteChoice::Yes, 100);
+    fixture.process(&[instruction], &[&holder]).await.unwrap();
+    assert_eq!(fixture.token_balance(&holder_real).await, 50);
+    assert_eq!(fixture.token_balance(&holder_escrow).await, 100);
+
+    // Stake at the snapshot counts first, and only the rest comes from the REAL balance
+    let instruction =
+        liquid_vote_ix(&fixture, &governance, &staker.pubkey(), staker_real, true, proposal, VoteChoice::No, 70);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+    assert_eq!(fixture.token_balance(&staker_real).await, 0);
+    assert_eq!(fixture.token_balance(&vote_escrow_of(&proposal, &staker.pubkey())).await, 30);
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!((state.yes_votes, state.no_votes), (100, 70));
+
+    // A changed vote reuses the escrowed REAL and tops it up
+    let instruction = ix(
+        realstack::accounts::ChangeVote {
+            voter: holder.pubkey(),
+            proposal,
+            vote_record: vote_record_of(&proposal, &holder.pubkey()),
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            stake_pool: governance.pool.stake_pool,
+            stake_account: None,
+            checkpoints: None,
+            real_mint: Some(fixture.real_mint),
+            voter_real_account: Some(holder_real),
+            vote_escrow: Some(holder_escrow),
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::ChangeVote {
+            choice: VoteChoice::Yes,
+            vote_weight: 150,
+        },
+    );
+    fixture.process(&[instruction], &[&holder]).await.unwrap();
+    assert_eq!(fixture.token_balance(&holder_real).await, 0);
+    assert_eq!(fixture.token_balance(&holder_escrow).await, 150);
+
+    // The REAL stays escrowed until the proposal is finalized
+    let instruction = reclaim_vote_shares_ix(&governance, &holder.pubkey(), proposal, holder_real, None);
+    let result = fixture.process(&[instruction], &[&holder]).await;
+    assert_error(result, RealStackError::ProposalNotFinalized);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = reclaim_vote_shares_ix(&governance, &holder.pubkey(), proposal, holder_real, None);
+    fixture.process(&[instruction], &[&holder]).await.unwrap();
+    assert_eq!(fixture.token_balance(&holder_real).await, 150);
+    assert!(!fixture.account_exists(&holder_escrow).await);
+}
+
+#[tokio::test]
+async fn change_vote_takes_over_delegated_vote() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let delegate = fixture.new_actor().await;
+    let alice = new_delegator(&mut fixture, &governance, &delegate.pubkey(), 300).await;
+    let alice_delegation = delegation_of(&governance, &alice.pubkey(), None);
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    let instruction =
+        cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, alice_delegation, &alice.pubkey(), VoteChoice::Yes);
+    fixture.process(&[instruction], &[&delegate]).await.unwrap();
+
+    let instruction = change_vote_ix(&governance, &alice.pubkey(), proposal, VoteChoice::No, 300);
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!((state.yes_votes, state.no_votes), (0, 300));
+    let record: VoteRecord = fixture.get(&vote_record_of(&proposal, &alice.pubkey())).await;
+    assert_eq!(record.delegate, None);
+    assert_eq!(record.payer, delegate.pubkey());
+}
+
+#[tokio::test]
+async fn vote_on_proposal_rejects_invalid_votes() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let voter = new_voter(&mut fixture, &governance, 50).await;
+    let other = new_voter(&mut fixture, &governance, 50).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::Yes, MIN_VOTE_BALANCE - 1);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::Yes, 51);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+
+    // Borrowing someone else's voting power
+    let mut instruction = vote_ix(&governance, &other.pubkey(), proposal, None, VoteChoice::Yes, 50);
+    let checkpoints = checkpoints_of(&governance, &other.pubkey());
+    let meta = instruction.accounts.iter_mut().find(|meta| meta.pubkey == checkpoints).unwrap();
+    meta.pubkey = checkpoints_of(&governance, &voter.pubkey());
+    let result = fixture.process(&[instruction], &[&other]).await;
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    // One vote per voter
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::Yes, 50);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::No, 50);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::AlreadyVoted);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = vote_ix(&governance, &other.pubkey(), proposal, None, VoteChoice::Yes, MIN_VOTE_BALANCE);
+    let result = fixture.process(&[instruction], &[&other]).await;
+    assert_error(result, RealStackError::VotingPeriodEnded);
+}
+
+#[tokio::test]
+async fn voting_power_is_read_at_the_proposal_snapshot() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let (voter, voter_real) = fixture.new_real_holder(150).await;
+    let instruction = fixture.stake_ix(&governance.pool, &voter.pubkey(), voter_real, 70);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.snapshot_slot > 0);
+
+    // Stake added after the snapshot, like liquid REAL, carries no weight
+    let instruction = fixture.stake_ix(&governance.pool, &voter.pubkey(), voter_real, 30);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::Yes, 100);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+
+    // A holder who only staked after the snapshot has no power at all
+    let latecomer = new_voter(&mut fixture, &governance, 500).await;
+    let instruction = vote_ix(&governance, &latecomer.pubkey(), proposal, None, VoteChoice::No, MIN_VOTE_BALANCE);
+    let result = fixture.process(&[instruction], &[&latecomer]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::Yes, 70);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 70);
+
+    // The next proposal snapshots the larger stake
+    let next = create_proposal(&mut fixture, &governance).await;
+    let instruction = vote_ix(&governance, &voter.pubkey(), next, None, VoteChoice::Yes, 100);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let state: Proposal = fixture.get(&next).await;
+    assert_eq!(state.yes_votes, 100);
+}
+
+#[tokio::test]
+async fn governance_respects_pause() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let voter = new_voter(&mut fixture, &governance, QUORUM).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    fixture.pause(PauseFlags { governance: true, ..no_pause() }).await;
+
+    let new_proposal = fixture.next_proposal(&governance.config).await;
+    let voting_ends_at = fixture.now().await + 2 * DAY;
+    let instruction =
+        create_proposal_ix(&fixture, &governance, &payer, governance.proposer_real, new_proposal, "Paused", voting_ends_at);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::OperationPaused);
+
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::Yes, QUORUM);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::OperationPaused);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::OperationPaused);
+}
+
+#[tokio::test]
+async fn close_vote_record_after_voting_ends() {
+    let mut fixture = TestFixture::new().await;
+    let intruder = fixture.new_actor().await;
+    let governance = setup_governance(&mut fixture).await;
+    let voter = new_voter(&mut fixture, &governance, 20).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    cast_vote(&mut fixture, &governance, proposal, &voter, VoteChoice::Yes, 20).await;
+    let vote_record = vote_record_of(&proposal, &voter.pubkey());
+
+    let instruction = close_vote_record_ix(&voter.pubkey(), proposal, vote_record);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::ProposalNotFinalized);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = close_vote_record_ix(&intruder.pubkey(), proposal, vote_record);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = close_vote_record_ix(&voter.pubkey(), proposal, vote_record);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    assert!(!fixture.account_exists(&vote_record).await);
+}
+
+#[tokio::test]
+async fn close_proposal_after_retention_period() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let governance = setup_governance(&mut fixture).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    let instruction = close_proposal_ix(&payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::ProposalNotFinalized);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = close_proposal_ix(&payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::DepositNotSettled);
+
+    let instruction = settle_deposit_ix(&fixture, &governance, proposal, &payer, Some(governance.proposer_real));
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = close_proposal_ix(&payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::RetentionPeriodActive);
+
+    fixture.warp_forward(PROPOSAL_RETENTION_PERIOD).await;
+    let instruction = close_proposal_ix(&intruder.pubkey(), proposal);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = close_proposal_ix(&payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert!(!fixture.account_exists(&proposal).await);
+
+    // Closed addresses are never reused, so old vote records cannot resurface
+    let next = create_proposal(&mut fixture, &governance).await;
+    assert_ne!(next, proposal);
+    let state: Proposal = fixture.get(&next).await;
+    assert_eq!(state.proposal_id, 1);
+}
+
+/// Delegate all of a fresh voter's `weight` staked REAL to `delegate`
+async fn new_delegator(fixture: &mut TestFixture, governance: &Governance, delegate: &Pubkey, weight: u64) -> Keypair {
+    let delegator = new_voter(fixture, governance, weight).await;
+    let instruction = delegate_ix(governance, &delegator.pubkey(), *delegate, None);
+    fixture.process(&[instruction], &[&delegator]).await.unwrap();
+    delegator
+}
+
+#[tokio::test]
+async fn delegate_votes_aggregate_delegators_weights() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let delegate = fixture.new_actor().await;
+    let intruder = fixture.new_actor().await;
+    let alice = new_delegator(&mut fixture, &governance, &delegate.pubkey(), 300).await;
+    let bob = new_delegator(&mut fixture, &governance, &delegate.pubkey(), 200).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    let delegation: Delegation = fixture.get(&delegation_of(&governance, &alice.pubkey(), None)).await;
+    assert_eq!(delegation.delegate, delegate.pubkey());
+    assert_eq!(delegation.proposal_type, None);
+
+    let alice_delegation = delegation_of(&governance, &alice.pubkey(), None);
+    let instruction =
+        cast_delegated_vote_ix(&governance, &intruder.pubkey(), proposal, alice_delegation, &alice.pubkey(), VoteChoice::Yes);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    for delegator in [&alice, &bob] {
+        let delegation = delegation_of(&governance, &delegator.pubkey(), None);
+        let instruction =
+            cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, delegation, &delegator.pubkey(), VoteChoice::Yes);
+        fixture.process(&[instruction], &[&delegate]).await.unwrap();
+    }
+
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 500);
+    let record: VoteRecord = fixture.get(&vote_record_of(&proposal, &alice.pubkey())).await;
+    assert_eq!(record.voter, alice.pubkey());
+    assert_eq!(record.vote_weight, 300);
+    assert_eq!(record.delegate, Some(delegate.pubkey()));
+    assert_eq!(record.payer, delegate.pubkey());
+
+    // Each delegator's power is voted once
+    let instruction =
+        cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, alice_delegation, &alice.pubkey(), VoteChoice::No);
+    let result = fixture.process(&[instruction], &[&delegate]).await;
+    assert_failed(result);
+}
+
+#[tokio::test]
+async fn direct_vote_overrides_delegated_vote() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let delegate = fixture.new_actor().await;
+    let alice = new_delegator(&mut fixture, &governance, &delegate.pubkey(), 300).await;
+    let alice_delegation = delegation_of(&governance, &alice.pubkey(), None);
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    let instruction =
+        cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, alice_delegation, &alice.pubkey(), VoteChoice::Yes);
+    fixture.process(&[instruction], &[&delegate]).await.unwrap();
+
+    let instruction = vote_ix(&governance, &alice.pubkey(), proposal, None, VoteChoice::No, 250);
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 0);
+    assert_eq!(state.no_votes, 250);
+    let record: VoteRecord = fixture.get(&vote_record_of(&proposal, &alice.pubkey())).await;
+    assert_eq!(record.ballot, Ballot::Choice(VoteChoice::No));
+    assert_eq!(record.delegate, None);
+
+    // The override is final, and the delegate cannot vote the power again
+    let instruction = vote_ix(&governance, &alice.pubkey(), proposal, None, VoteChoice::Yes, 300);
+    let result = fixture.process(&[instruction], &[&alice]).await;
+    assert_error(result, RealStackError::AlreadyVoted);
+    let instruction =
+        cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, alice_delegation, &alice.pubkey(), VoteChoice::Yes);
+    let result = fixture.process(&[instruction], &[&delegate]).await;
+    assert_failed(result);
+
+    // The delegate paid for the record and reclaims its rent
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let vote_record = vote_record_of(&proposal, &alice.pubkey());
+    let instruction = close_vote_record_ix(&alice.pubkey(), proposal, vote_record);
+    let result = fixture.process(&[instruction], &[&alice]).await;
+    assert_error(result, RealStackError::Unauthorized);
+    let instruction = close_vote_record_ix(&delegate.pubkey(), proposal, vote_record);
+    fixture.process(&[instruction], &[&delegate]).await.unwrap();
+}
+
+#[tokio::test]
+async fn type_delegation_takes_precedence_over_global() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let generalist = fixture.new_actor().await;
+    let specialist = fixture.new_actor().await;
+    let alice = new_delegator(&mut fixture, &governance, &generalist.pubkey(), 300).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    let global = delegation_of(&governance, &alice.pubkey(), None);
+    let text_only = delegation_of(&governance, &alice.pubkey(), Some(ProposalType::Text));
+
+    let instruction = delegate_ix(&governance, &alice.pubkey(), specialist.pubkey(), Some(ProposalType::Text));
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+
+    let instruction =
+        cast_delegated_vote_ix(&governance, &generalist.pubkey(), proposal, global, &alice.pubkey(), VoteChoice::Yes);
+    let result = fixture.process(&[instruction], &[&generalist]).await;
+    assert_error(result, RealStackError::DelegationNotApplicable);
+
+    // Revoking the type delegation hands Text proposals back to the global delegate
+    let instruction = undelegate_ix(&generalist.pubkey(), text_only);
+    let result = fixture.process(&[instruction], &[&generalist]).await;
+    assert_error(result, RealStackError::Unauthorized);
+    let instruction = undelegate_ix(&alice.pubkey(), text_only);
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    assert!(!fixture.account_exists(&text_only).await);
+
+    let instruction =
+        cast_delegated_vote_ix(&governance, &generalist.pubkey(), proposal, global, &alice.pubkey(), VoteChoice::Yes);
+    fixture.process(&[instruction], &[&generalist]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 300);
+}
+
+#[tokio::test]
+async fn delegation_rejects_invalid_use() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let delegate = fixture.new_actor().await;
+    let alice = new_voter(&mut fixture, &governance, 300).await;
+    let (empty, empty_real) = fixture.new_real_holder(MIN_VOTE_BALANCE).await;
+    let instruction = delegate_ix(&governance, &empty.pubkey(), delegate.pubkey(), None);
+    fixture.process(&[instruction], &[&empty]).await.unwrap();
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    let instruction = delegate_ix(&governance, &alice.pubkey(), alice.pubkey(), None);
+    let result = fixture.process(&[instruction], &[&alice]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    // A fee-only delegation does not cover a text proposal
+    let fees_only = delegation_of(&governance, &alice.pubkey(), Some(ProposalType::UpdateFees));
+    let instruction = delegate_ix(&governance, &alice.pubkey(), delegate.pubkey(), Some(ProposalType::UpdateFees));
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    let instruction =
+        cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, fees_only, &alice.pubkey(), VoteChoice::Yes);
+    let result = fixture.process(&[instruction], &[&delegate]).await;
+    assert_error(result, RealStackError::DelegationNotApplicable);
+
+    // Stake added after the snapshot is not delegated power
+    let instruction = fixture.stake_ix(&governance.pool, &empty.pubkey(), empty_real, MIN_VOTE_BALANCE);
+    fixture.process(&[instruction], &[&empty]).await.unwrap();
+    let empty_delegation = delegation_of(&governance, &empty.pubkey(), None);
+    let instruction =
+        cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, empty_delegation, &empty.pubkey(), VoteChoice::Yes);
+    let result = fixture.process(&[instruction], &[&delegate]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+
+    // Neither is a delegation set after the snapshot
+    let global = delegation_of(&governance, &alice.pubkey(), None);
+    let instruction = delegate_ix(&governance, &alice.pubkey(), delegate.pubkey(), None);
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    let instruction =
+        cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, global, &alice.pubkey(), VoteChoice::Yes);
+    let result = fixture.process(&[instruction], &[&delegate]).await;
+    assert_error(result, RealStackError::DelegationAfterSnapshot);
+
+    fixture.pause(PauseFlags { governance: true, ..no_pause() }).await;
+    let instruction =
+        cast_delegated_vote_ix(&governance, &delegate.pubkey(), proposal, global, &alice.pubkey(), VoteChoice::Yes);
+    let result = fixture.process(&[instruction], &[&delegate]).await;
+    assert_error(result, RealStackError::OperationPaused);
+}
+
+/// Options labeled after candidate property managers, each carrying its index as execution data
+fn manager_options(count: u8) -> Vec<ProposalOptionInput> {
+    (0..count)
+        .map(|index| ProposalOptionInput {
+            label: format!("Manager {}", index),
+            execution_data: vec![index],
+        })
+        .collect()
+}
+
+/// Create a three-option proposal from the payer
+async fn create_option_proposal(fixture: &mut TestFixture, governance: &Governance, resolution: ResolutionMethod) -> Pubkey {
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let voting_ends_at = fixture.now().await + MIN_VOTING_PERIOD + 60;
+    let instruction =
+        create_option_proposal_ix(fixture, governance, proposal, voting_ends_at, manager_options(3), resolution);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    proposal
+}
+
+/// Rank options with `weight` from a voter who staked before the proposal was created
+async fn cast_ranking(
+    fixture: &mut TestFixture,
+    governance: &Governance,
+    proposal: Pubkey,
+    voter: &Keypair,
+    ranking: Vec<u8>,
+    weight: u64,
+) {
+    let instruction = vote_on_options_ix(governance, &voter.pubkey(), proposal, ranking, weight);
+    fixture.process(&[instruction], &[voter]).await.unwrap();
+}
+
+#[tokio::test]
+async fn create_option_proposal_validates_options() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let voting_ends_at = fixture.now().await + 2 * DAY;
+
+    let mut long_label = manager_options(2);
+    long_label[0].label = "x".repeat(MAX_OPTION_LABEL_LEN + 1);
+    let mut empty_label = manager_options(2);
+    empty_label[1].label.clear();
+    let mut long_data = manager_options(2);
+    long_data[0].execution_data = vec![0; MAX_OPTION_DATA_LEN + 1];
+    for options in [manager_options(1), manager_options(5), long_label, empty_label, long_data] {
+        let proposal = fixture.next_proposal(&governance.config).await;
+        let instruction =
+            create_option_proposal_ix(&fixture, &governance, proposal, voting_ends_at, options, ResolutionMethod::Plurality);
+        let result = fixture.process(&[instruction], &[]).await;
+        assert_error(result, RealStackError::InvalidParameters);
+    }
+
+    let proposal = create_option_proposal(&mut fixture, &governance, ResolutionMethod::RankedChoice).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.options.len(), 3);
+    assert_eq!(state.options[2].label, "Manager 2");
+    assert_eq!(state.options[2].execution_data, vec![2]);
+    assert_eq!(state.resolution, ResolutionMethod::RankedChoice);
+    assert_eq!(state.winning_option, None);
+}
+
+#[tokio::test]
+async fn plurality_proposal_picks_option_with_most_votes() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let ballots = [(vec![1], 70), (vec![0], 50), (vec![2], 30)];
+    let mut voters = Vec::new();
+    for (_, weight) in &ballots {
+        voters.push(new_voter(&mut fixture, &governance, *weight).await);
+    }
+    let proposal = create_option_proposal(&mut fixture, &governance, ResolutionMethod::Plurality).await;
+
+    for (voter, (ranking, weight)) in voters.iter().zip(ballots) {
+        cast_ranking(&mut fixture, &governance, proposal, voter, ranking, weight).await;
+    }
+
+    let state: Proposal = fixture.get(&proposal).await;
+    let tallies: Vec<u64> = state.options.iter().map(|option| option.votes).collect();
+    assert_eq!(tallies, vec![50, 70, 30]);
+    assert!(state.ranked_ballots.is_empty());
+    let record: VoteRecord = fixture.get(&vote_record_of(&proposal, &voters[0].pubkey())).await;
+    assert_eq!(record.ballot, Ballot::Options(vec![1]));
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+    assert_eq!(state.winning_option, Some(1));
+}
+
+#[tokio::test]
+async fn ranked_choice_transfers_eliminated_votes() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    // Option 0 leads on first choices, but option 2's voters prefer option 1 next
+    let ballots = [(vec![0], 40), (vec![1, 0], 35), (vec![2, 1], 20), (vec![2, 1], 10)];
+    let mut voters = Vec::new();
+    for (_, weight) in &ballots {
+        voters.push(new_voter(&mut fixture, &governance, *weight).await);
+    }
+    let proposal = create_option_proposal(&mut fixture, &governance, ResolutionMethod::RankedChoice).await;
+
+    for (voter, (ranking, weight)) in voters.iter().zip(ballots) {
+        cast_ranking(&mut fixture, &governance, proposal, voter, ranking, weight).await;
+    }
+
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.options[0].votes, 40);
+    assert_eq!(state.options[2].votes, 30);
+    assert_eq!(state.ranked_ballots.len(), 3);
+    assert_eq!(state.ranked_ballots[2].weight, 30);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.winning_option, Some(1));
+}
+
+#[tokio::test]
+async fn ballots_must_match_the_proposal_kind() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let voter = new_voter(&mut fixture, &governance, 100).await;
+    let first = new_voter(&mut fixture, &governance, 60).await;
+    let second = new_voter(&mut fixture, &governance, 60).await;
+    let binary = create_proposal(&mut fixture, &governance).await;
+    let plurality = create_option_proposal(&mut fixture, &governance, ResolutionMethod::Plurality).await;
+    let ranked = create_option_proposal(&mut fixture, &governance, ResolutionMethod::RankedChoice).await;
+
+    let invalid = [
+        (binary, vec![0]),
+        (plurality, vec![0, 1]),
+        (ranked, vec![]),
+        (ranked, vec![3]),
+        (ranked, vec![1, 1]),
+    ];
+    for (proposal, ranking) in invalid {
+        let instruction = vote_on_options_ix(&governance, &voter.pubkey(), proposal, ranking, 100);
+        let result = fixture.process(&[instruction], &[&voter]).await;
+        assert_error(result, RealStackError::InvalidBallot);
+    }
+
+    let instruction = vote_ix(&governance, &voter.pubkey(), plurality, None, VoteChoice::Yes, 100);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InvalidBallot);
+
+    // A tied plurality executes without a winner
+    cast_ranking(&mut fixture, &governance, plurality, &first, vec![0], 60).await;
+    cast_ranking(&mut fixture, &governance, plurality, &second, vec![2], 60).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, plurality);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&plurality).await;
+    assert!(state.executed);
+    assert_eq!(state.winning_option, None);
+}
+
+#[tokio::test]
+async fn deposit_is_refunded_once_quorum_is_reached() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let voter = new_voter(&mut fixture, &governance, 120).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    let escrow = deposit_escrow_of(&proposal);
+
+    assert_eq!(fixture.token_balance(&governance.proposer_real).await, PROPOSER_BALANCE - PROPOSAL_DEPOSIT);
+    assert_eq!(fixture.token_balance(&escrow).await, PROPOSAL_DEPOSIT);
+    let state: ProposerState = fixture.get(&proposer_state_of(&governance, &payer)).await;
+    assert_eq!(state.active_proposals, 1);
+
+    let instruction = settle_deposit_ix(&fixture, &governance, proposal, &payer, Some(governance.proposer_real));
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::VotingPeriodNotEnded);
+
+    cast_vote(&mut fixture, &governance, proposal, &voter, VoteChoice::No, 120).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+
+    // A refund needs somewhere to go
+    let instruction = settle_deposit_ix(&fixture, &governance, proposal, &payer, None);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+
+    // Reaching quorum is enough; the proposal does not have to pass
+    let instruction = settle_deposit_ix(&fixture, &governance, proposal, &payer, Some(governance.proposer_real));
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&governance.proposer_real).await, PROPOSER_BALANCE);
+    assert_eq!(fixture.token_balance(&governance.treasury).await, 0);
+    assert!(!fixture.account_exists(&escrow).await);
+    let state: ProposerState = fixture.get(&proposer_state_of(&governance, &payer)).await;
+    assert_eq!(state.active_proposals, 0);
+    let proposal_state: Proposal = fixture.get(&proposal).await;
+    assert!(proposal_state.deposit_settled);
+
+    let instruction = settle_deposit_ix(&fixture, &governance, proposal, &payer, Some(governance.proposer_real));
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_failed(result);
+}
+
+#[tokio::test]
+async fn deposit_is_slashed_without_quorum() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let voter = new_voter(&mut fixture, &governance, QUORUM - 1).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    cast_vote(&mut fixture, &governance, proposal, &voter, VoteChoice::Yes, QUORUM - 1).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+
+    // Refunds only ever go to the proposer's own account
+    let (_, other_real) = fixture.new_real_holder(0).await;
+    let instruction = settle_deposit_ix(&fixture, &governance, proposal, &payer, Some(other_real));
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+
+    // Slashing does not need the proposer's token account at all
+    let instruction = settle_deposit_ix(&fixture, &governance, proposal, &payer, None);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&governance.proposer_real).await, PROPOSER_BALANCE - PROPOSAL_DEPOSIT);
+    assert_eq!(fixture.token_balance(&governance.treasury).await, PROPOSAL_DEPOSIT);
+    assert!(!fixture.account_exists(&deposit_escrow_of(&proposal)).await);
+}
+
+#[tokio::test]
+async fn proposers_need_balance_and_a_free_slot() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let voting_ends_at = fixture.now().await + 2 * DAY;
+
+    let (poor, poor_real) = fixture.new_real_holder(MIN_PROPOSAL_BALANCE - 1).await;
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let instruction =
+        create_proposal_ix(&fixture, &governance, &poor.pubkey(), poor_real, proposal, "Spam", voting_ends_at);
+    let result = fixture.process(&[instruction], &[&poor]).await;
+    assert_error(result, RealStackError::InsufficientProposalBalance);
+
+    let mut proposals = Vec::new();
+    for _ in 0..MAX_ACTIVE_PROPOSALS {
+        proposals.push(create_proposal(&mut fixture, &governance).await);
+    }
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let instruction =
+        create_proposal_ix(&fixture, &governance, &payer, governance.proposer_real, proposal, "One more", voting_ends_at);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::TooManyActiveProposals);
+
+    // Settling a deposit frees the proposer's slot
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = settle_deposit_ix(&fixture, &governance, proposals[0], &payer, Some(governance.proposer_real));
+    fixture.process(&[instruction], &[]).await.unwrap();
+    create_proposal(&mut fixture, &governance).await;
+    let state: ProposerState = fixture.get(&proposer_state_of(&governance, &payer)).await;
+    assert_eq!(state.active_proposals, MAX_ACTIVE_PROPOSALS);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_asset_proposal_ix(
+    fixture: &TestFixture,
+    governance: &Governance,
+    asset: &AssetFixture,
+    proposer: &Pubkey,
+    proposer_share_account: Pubkey,
+    proposal: Pubkey,
+    voting_ends_at: i64,
+    action: &Instruction,
+) -> Instruction {
+    ix(
+        realstack::accounts::CreateAssetProposal {
+            proposer: *proposer,
+            proposal,
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            asset_token: asset.asset_token,
+            share_mint: asset.share_mint,
+            proposer_share_account,
+            real_mint: fixture.real_mint,
+            deposit_escrow: deposit_escrow_of(&proposal),
+            proposer_state: proposer_state_of(governance, proposer),
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        realstack::instruction::CreateAssetProposal {
+            title: "Open trading".to_string(),
+            description: "Let share holders trade the asset".to_string(),
+            voting_ends_at,
+            accounts: action.accounts.iter().map(|meta| meta.pubkey).collect(),
+            data: action.data.clone(),
+        },
+    )
+}
+
+fn vote_on_asset_ix(
+    governance: &Governance,
+    asset: &AssetFixture,
+    voter: &Pubkey,
+    proposal: Pubkey,
+    voter_share_account: Pubkey,
+    choice: VoteChoice,
+    vote_weight: u64,
+) -> Instruction {
+    ix(
+        realstack::accounts::VoteOnAssetProposal {
+            voter: *voter,
+            proposal,
+            vote_record: vote_record_of(&proposal, voter),
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            asset_token: asset.asset_token,
+            share_mint: asset.share_mint,
+            voter_share_account,
+            freeze_authority: freeze_authority_of(&asset.asset_token),
+            vote_escrow: vote_escrow_of(&proposal, voter),
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        realstack::instruction::VoteOnAssetProposal { choice, vote_weight },
+    )
+}
+
+/// Reclaim escrowed REAL, or the shares of `asset`
+fn reclaim_vote_shares_ix(
+    governance: &Governance,
+    voter: &Pubkey,
+    proposal: Pubkey,
+    voter_share_account: Pubkey,
+    asset: Option<&AssetFixture>,
+) -> Instruction {
+    ix(
+        realstack::accounts::ReclaimVoteShares {
+            voter: *voter,
+            proposal,
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            vote_escrow: vote_escrow_of(&proposal, voter),
+            voter_share_account,
+            asset_token: asset.map(|asset| asset.asset_token),
+            share_mint: asset.map(|asset| asset.share_mint),
+            freeze_authority: asset.map(|asset| freeze_authority_of(&asset.asset_token)),
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::ReclaimVoteShares {},
+    )
+}
+
+fn vote_escrow_of(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+    pda(&[b"vote_escrow", proposal.as_ref(), voter.as_ref()])
+}
+
+fn execute_asset_ix(governance: &Governance, executor: &Pubkey, proposal: Pubkey, asset: &AssetFixture, action: &Instruction) -> Instruction {
+    let mut instruction = ix(
+        realstack::accounts::ExecuteAssetProposal {
+            executor: *executor,
+            proposal,
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            asset_governance_signer: AssetToken::governance_signer(&asset.asset_token).0,
+            realstack_program: realstack::ID,
+        },
+        realstack::instruction::ExecuteAssetProposal {},
+    );
+    instruction.accounts.extend(action.accounts.iter().map(|meta| {
+        if meta.is_writable {
+            AccountMeta::new(meta.pubkey, false)
+        } else {
+            AccountMeta::new_readonly(meta.pubkey, false)
+        }
+    }));
+    instruction
+}
+
+/// Propose the wrapped asset instruction from the payer, who holds every share
+async fn create_asset_proposal(fixture: &mut TestFixture, governance: &Governance, asset: &AssetFixture, action: &Instruction) -> Pubkey {
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let voting_ends_at = fixture.now().await + MIN_VOTING_PERIOD + 60;
+    let instruction = create_asset_proposal_ix(
+        fixture,
+        governance,
+        asset,
+        &fixture.payer_key(),
+        asset.issuer_shares,
+        proposal,
+        voting_ends_at,
+        action,
+    );
+    fixture.process(&[instruction], &[]).await.unwrap();
+    proposal
+}
+
+/// Give a fresh actor `shares` of the asset and vote them
+async fn cast_share_vote(
+    fixture: &mut TestFixture,
+    governance: &Governance,
+    asset: &AssetFixture,
+    proposal: Pubkey,
+    choice: VoteChoice,
+    shares: u64,
+) -> Keypair {
+    let voter = fixture.new_actor().await;
+    let voter_shares = fixture.create_token_account(&asset.share_mint, &voter.pubkey()).await;
+    fixture.issue_shares(asset, &voter_shares, shares).await;
+    let instruction = vote_on_asset_ix(governance, asset, &voter.pubkey(), proposal, voter_shares, choice, shares);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    voter
+}
+
+#[tokio::test]
+async fn share_holders_toggle_tradability_of_their_asset() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let asset = fixture.create_asset(false).await;
+    let signer = AssetToken::governance_signer(&asset.asset_token).0;
+    let action = fixture.toggle_tradability_ix(&signer, asset.asset_token, true);
+
+    let proposal = create_asset_proposal(&mut fixture, &governance, &asset, &action).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.proposal_type, ProposalType::AssetAction);
+    assert_eq!(state.asset_token, Some(asset.asset_token));
+    assert_eq!(state.quorum_votes, ASSET_TOTAL_SHARES * ASSET_QUORUM_PERCENTAGE as u64 / 100);
+
+    cast_share_vote(&mut fixture, &governance, &asset, proposal, VoteChoice::Yes, 3_000).await;
+    cast_share_vote(&mut fixture, &governance, &asset, proposal, VoteChoice::No, 1_000).await;
+
+    // REAL voting power does not count on asset proposals
+    let voter = new_voter(&mut fixture, &governance, MIN_VOTE_BALANCE).await;
+    let instruction = vote_ix(&governance, &voter.pubkey(), proposal, None, VoteChoice::Yes, MIN_VOTE_BALANCE);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::ProposalScopeMismatch);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let payer = fixture.payer_key();
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::ProposalScopeMismatch);
+
+    let instruction = execute_asset_ix(&governance, &payer, proposal, &asset, &action);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let asset_state: AssetToken = fixture.get(&asset.asset_token).await;
+    assert!(asset_state.is_tradable);
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+}
+
+#[tokio::test]
+async fn share_holders_approve_additional_issuance() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let asset = fixture.create_asset(true).await;
+    let signer = AssetToken::governance_signer(&asset.asset_token).0;
+    let action = ix(
+        realstack::accounts::MintAdditionalShares {
+            authority: signer,
+            approver: signer,
+            asset_token: asset.asset_token,
+            roles: fixture.roles,
+            real_token: fixture.real_token,
+            share_mint: asset.share_mint,
+            mint_authority: pda(&[b"mint_authority", asset.asset_token.as_ref()]),
+            freeze_authority: freeze_authority_of(&asset.asset_token),
+            destination: asset.issuer_shares,
+            destination_investor_record: None,
+            destination_holder_record: holder_record_of(&asset.asset_token, &fixture.payer_key()),
+            token_program: anchor_spl::token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::MintAdditionalShares { amount: 1_000 },
+    );
+    // The governance signer pays for the issuer's new holder record
+    fixture.fund(&signer).await;
+
+    let proposal = create_asset_proposal(&mut fixture, &governance, &asset, &action).await;
+    cast_share_vote(&mut fixture, &governance, &asset, proposal, VoteChoice::Yes, 3_000).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let payer = fixture.payer_key();
+    let instruction = execute_asset_ix(&governance, &payer, proposal, &asset, &action);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let asset_state: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(asset_state.total_shares, ASSET_TOTAL_SHARES + 1_000);
+    assert_eq!(fixture.mint_supply(&asset.share_mint).await, ASSET_TOTAL_SHARES + 1_000);
+}
+
+#[tokio::test]
+async fn rejected_asset_proposal_leaves_asset_untouched() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let asset = fixture.create_asset(false).await;
+    let signer = AssetToken::governance_signer(&asset.asset_token).0;
+    let action = fixture.toggle_tradability_ix(&signer, asset.asset_token, true);
+    let payer = fixture.payer_key();
+
+    let proposal = create_asset_proposal(&mut fixture, &governance, &asset, &action).await;
+    cast_share_vote(&mut fixture, &governance, &asset, proposal, VoteChoice::Yes, 1_000).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_asset_ix(&governance, &payer, proposal, &asset, &action);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let proposal = create_asset_proposal(&mut fixture, &governance, &asset, &action).await;
+    cast_share_vote(&mut fixture, &governance, &asset, proposal, VoteChoice::No, 3_000).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_asset_ix(&governance, &payer, proposal, &asset, &action);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let asset_state: AssetToken = fixture.get(&asset.asset_token).await;
+    assert!(!asset_state.is_tradable);
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+}
+
+#[tokio::test]
+async fn voted_shares_are_escrowed_until_the_proposal_is_finalized() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance(&mut fixture).await;
+    let asset = fixture.create_asset(false).await;
+    let signer = AssetToken::governance_signer(&asset.asset_token).0;
+    let action = fixture.toggle_tradability_ix(&signer, asset.asset_token, true);
+    let proposal = create_asset_proposal(&mut fixture, &governance, &asset, &action).await;
+
+    let voter = fixture.new_actor().await;
+    let voter_shares = fixture.create_token_account(&asset.share_mint, &voter.pubkey()).await;
+    fixture.issue_shares(&asset, &voter_shares, 3_000).await;
+    let instruction = vote_on_asset_ix(&governance, &asset, &voter.pubkey(), proposal, voter_shares, VoteChoice::Yes, 3_000);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let escrow = vote_escrow_of(&proposal, &voter.pubkey());
+    assert_eq!(fixture.token_balance(&voter_shares).await, 0);
+    assert_eq!(fixture.token_balance(&escrow).await, 3_000);
+
+    // The voted shares cannot be moved to a second wallet and voted again
+    let accomplice = fixture.new_actor().await;
+    let accomplice_shares = fixture.create_token_account(&asset.share_mint, &accomplice.pubkey()).await;
+    let transfer =
+        spl_token::instruction::transfer(&spl_token::ID, &voter_shares, &accomplice_shares, &voter.pubkey(), &[], 3_000).unwrap();
+    assert!(fixture.process(&[transfer], &[&voter]).await.is_err());
+    let instruction =
+        vote_on_asset_ix(&governance, &asset, &accomplice.pubkey(), proposal, accomplice_shares, VoteChoice::Yes, 3_000);
+    let result = fixture.process(&[instruction], &[&accomplice]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 3_000);
+
+    // Shares stay escrowed while voting is open
+    let instruction = reclaim_vote_shares_ix(&governance, &voter.pubkey(), proposal, voter_shares, Some(&asset));
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::ProposalNotFinalized);
+
+    // Returned shares are frozen again, so the asset's accounts must come along
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let fresh_shares = fixture.create_token_account(&asset.share_mint, &voter.pubkey()).await;
+    let instruction = reclaim_vote_shares_ix(&governance, &voter.pubkey(), proposal, fresh_shares, None);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+
+    let instruction = reclaim_vote_shares_ix(&governance, &voter.pubkey(), proposal, voter_shares, Some(&asset));
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    assert_eq!(fixture.token_balance(&voter_shares).await, 3_000);
+    assert!(!fixture.account_exists(&escrow).await);
+    let data = fixture.account_data(&voter_shares).await;
+    assert!(spl_token::state::Account::unpack(&data).unwrap().is_frozen());
+}
+
+#[tokio::test]
+async fn asset_proposals_count_toward_the_active_proposal_limit() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let asset = fixture.create_asset(false).await;
+    let signer = AssetToken::governance_signer(&asset.asset_token).0;
+    let action = fixture.toggle_tradability_ix(&signer, asset.asset_token, true);
+
+    let mut proposals = Vec::new();
+    for _ in 0..MAX_ACTIVE_PROPOSALS {
+        proposals.push(create_asset_proposal(&mut fixture, &governance, &asset, &action).await);
+    }
+    let state: Proposal = fixture.get(&proposals[0]).await;
+    assert_eq!(state.deposit, 0);
+    assert!(!state.deposit_settled);
+
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let voting_ends_at = fixture.now().await + MIN_VOTING_PERIOD + 60;
+    let instruction =
+        create_asset_proposal_ix(&fixture, &governance, &asset, &payer, asset.issuer_shares, proposal, voting_ends_at, &action);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::TooManyActiveProposals);
+
+    // Settling the empty deposit frees the proposer's slot
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = settle_deposit_ix(&fixture, &governance, proposals[0], &payer, None);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert!(!fixture.account_exists(&deposit_escrow_of(&proposals[0])).await);
+    create_asset_proposal(&mut fixture, &governance, &asset, &action).await;
+    let state: ProposerState = fixture.get(&proposer_state_of(&governance, &payer)).await;
+    assert_eq!(state.active_proposals, MAX_ACTIVE_PROPOSALS);
+}
+
+#[tokio::test]
+async fn create_asset_proposal_validates_action_and_proposer() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let asset = fixture.create_asset(false).await;
+    let other_asset = fixture.create_asset(false).await;
+    let signer = AssetToken::governance_signer(&asset.asset_token).0;
+    let voting_ends_at = fixture.now().await + 2 * DAY;
+
+    // Fee changes are platform-wide, not asset actions
+    let mut unsupported = fixture.toggle_tradability_ix(&signer, asset.asset_token, true);
+    unsupported.data = realstack::instruction::UpdateFeeConfig {
+        transaction_fee_bps: 50,
+        fee_recipient: payer,
+        fees_enabled: true,
+    }
+    .data();
+    let wrong_signer = fixture.toggle_tradability_ix(&payer, asset.asset_token, true);
+    let wrong_asset = fixture.toggle_tradability_ix(&signer, other_asset.asset_token, true);
+    let invalid = [
+        (unsupported, RealStackError::UnsupportedAdminAction),
+        (wrong_signer, RealStackError::InvalidParameters),
+        (wrong_asset, RealStackError::InvalidParameters),
+    ];
+    for (action, error) in invalid {
+        let proposal = fixture.next_proposal(&governance.config).await;
+        let instruction =
+            create_asset_proposal_ix(&fixture, &governance, &asset, &payer, asset.issuer_shares, proposal, voting_ends_at, &action);
+        let result = fixture.process(&[instruction], &[]).await;
+        assert_error(result, error);
+    }
+
+    // Proposing takes at least one percent of the shares
+    let action = fixture.toggle_tradability_ix(&signer, asset.asset_token, true);
+    let small_holder = fixture.new_actor().await;
+    let small_shares = fixture.create_token_account(&asset.share_mint, &small_holder.pubkey()).await;
+    fixture.issue_shares(&asset, &small_shares, ASSET_TOTAL_SHARES / 100 - 1).await;
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let instruction = create_asset_proposal_ix(
+        &fixture,
+        &governance,
+        &asset,
+        &small_holder.pubkey(),
+        small_shares,
+        proposal,
+        voting_ends_at,
+        &action,
+    );
+    let result = fixture.process(&[instruction], &[&small_holder]).await;
+    assert_error(result, RealStackError::InsufficientProposalBalance);
+
+    // Votes are capped by the voter's shares
+    let proposal = create_asset_proposal(&mut fixture, &governance, &asset, &action).await;
+    let instruction =
+        vote_on_asset_ix(&governance, &asset, &small_holder.pubkey(), proposal, small_shares, VoteChoice::Yes, ASSET_TOTAL_SHARES / 100);
+    let result = fixture.process(&[instruction], &[&small_holder]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+}
+
+#[tokio::test]
+async fn quadratic_voting_lets_many_holders_outvote_a_whale() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance_with_mode(&mut fixture, VotingMode::Quadratic).await;
+    let whale = new_voter(&mut fixture, &governance, 10_000).await;
+    let mut holders = Vec::new();
+    for _ in 0..4 {
+        holders.push(new_voter(&mut fixture, &governance, 900).await);
+    }
+    let staker = new_voter(&mut fixture, &governance, 100).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.voting_mode, VotingMode::Quadratic);
+    assert_eq!(state.quorum_votes, 10);
+
+    cast_vote(&mut fixture, &governance, proposal, &whale, VoteChoice::Yes, 10_000).await;
+    for holder in &holders {
+        cast_vote(&mut fixture, &governance, proposal, holder, VoteChoice::No, 900).await;
+    }
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 100);
+    assert_eq!(state.no_votes, 120);
+    let record: VoteRecord = fixture.get(&vote_record_of(&proposal, &whale.pubkey())).await;
+    assert_eq!(record.vote_weight, 100);
+
+    // Locking stake only earns weight under conviction voting
+    let instruction = conviction_vote_ix(
+        &governance,
+        &staker.pubkey(),
+        proposal,
+        VoteChoice::Yes,
+        100,
+        CONVICTION_PERIOD,
+    );
+    let result = fixture.process(&[instruction], &[&staker]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+    assert!(state.yes_votes < state.no_votes);
+}
+
+#[tokio::test]
+async fn quadratic_quorum_is_one_quorum_sized_vote() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance_with_mode(&mut fixture, VotingMode::Quadratic).await;
+    let short = new_voter(&mut fixture, &governance, QUORUM - 1).await;
+    let exact = new_voter(&mut fixture, &governance, QUORUM).await;
+
+    // A single holder just short of the configured quorum misses it
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    cast_vote(&mut fixture, &governance, proposal, &short, VoteChoice::Yes, QUORUM - 1).await;
+    let missed = proposal;
+
+    // A single holder with exactly the configured quorum meets it
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    cast_vote(&mut fixture, &governance, proposal, &exact, VoteChoice::Yes, QUORUM).await;
+    let met = proposal;
+
+    // Spread across holders, far less REAL reaches the same weight
+    let mut holders = Vec::new();
+    for _ in 0..2 {
+        holders.push(new_voter(&mut fixture, &governance, QUORUM / 4).await);
+    }
+    let proposal = create_proposal(&mut fixture, &governance).await;
+    for holder in &holders {
+        cast_vote(&mut fixture, &governance, proposal, holder, VoteChoice::Yes, QUORUM / 4).await;
+    }
+    let spread = proposal;
+    let state: Proposal = fixture.get(&spread).await;
+    assert_eq!(state.yes_votes, state.quorum_votes);
+
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, missed);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+    for proposal in [met, spread] {
+        let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+        fixture.process(&[instruction], &[]).await.unwrap();
+        let state: Proposal = fixture.get(&proposal).await;
+        assert!(state.executed);
+    }
+}
+
+#[tokio::test]
+async fn conviction_vote_multiplies_weight_and_locks_stake() {
+    let mut fixture = TestFixture::new().await;
+    let governance = setup_governance_with_mode(&mut fixture, VotingMode::Conviction).await;
+    let (voter, voter_real) = fixture.new_real_holder(150).await;
+    let instruction = fixture.stake_ix(&governance.pool, &voter.pubkey(), voter_real, 100);
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    let opponent = new_voter(&mut fixture, &governance, 250).await;
+    let proposal = create_proposal(&mut fixture, &governance).await;
+
+    // Only staked REAL can be locked, and no longer than the maximum
+    let instruction = conviction_vote_ix(
+        &governance,
+        &voter.pubkey(),
+        proposal,
+        VoteChoice::Yes,
+        150,
+        CONVICTION_PERIOD,
+    );
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+    let too_long = MAX_CONVICTION_LOCK + 1;
+    let instruction = conviction_vote_ix(&governance, &voter.pubkey(), proposal, VoteChoice::Yes, 100, too_long);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = conviction_vote_ix(
+        &governance,
+        &voter.pubkey(),
+        proposal,
+        VoteChoice::Yes,
+        100,
+        2 * CONVICTION_PERIOD,
+    );
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    cast_vote(&mut fixture, &governance, proposal, &opponent, VoteChoice::No, 250).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.yes_votes, 300);
+    assert_eq!(state.no_votes, 250);
+
+    // The stake stays locked for the chosen duration after voting ends
+    let stake_account = pda(&[b"stake_account", governance.pool.stake_pool.as_ref(), voter.pubkey().as_ref()]);
+    let stake: StakeAccount = fixture.get(&stake_account).await;
+    assert_eq!(stake.vote_locked_until, state.voting_ends_at + 2 * CONVICTION_PERIOD);
+    let unstake = ix(
+        realstack::accounts::Unstake {
+            owner: voter.pubkey(),
+            stake_pool: governance.pool.stake_pool,
+            stake_account,
+            checkpoints: checkpoints_of(&governance, &voter.pubkey()),
+        },
+        realstack::instruction::Unstake { amount: 100 },
+    );
+    fixture.warp_forward(MIN_VOTING_PERIOD + CONVICTION_PERIOD).await;
+    let result = fixture.process(std::slice::from_ref(&unstake), &[&voter]).await;
+    assert_error(result, RealStackError::StakeLockedByVote);
+
+    fixture.warp_forward(CONVICTION_PERIOD + 60).await;
+    fixture.process(&[unstake], &[&voter]).await.unwrap();
+    let stake: StakeAccount = fixture.get(&stake_account).await;
+    assert_eq!(stake.staked_amount, 0);
+}
+
+fn create_optimistic_proposal_ix(
+    fixture: &TestFixture,
+    governance: &Governance,
+    proposal: Pubkey,
+    challenge_ends_at: i64,
+    action: ParameterAction,
+) -> Instruction {
+    let payer = fixture.payer_key();
+    ix(
+        realstack::accounts::CreateProposal {
+            stake_pool: Some(governance.pool.stake_pool),
+            ..create_proposal_accounts(fixture, governance, &payer, governance.proposer_real, proposal)
+        },
+        realstack::instruction::CreateOptimisticProposal {
+            title: "Lower trading fee".to_string(),
+            description: "Cut the trading fee from 30 to 25 basis points.".to_string(),
+            challenge_ends_at,
+            action,
+        },
+    )
+}
+
+/// Cut the trading fee to 25 basis points, paid to `fee_recipient`
+fn lower_fee(fee_recipient: Pubkey) -> ParameterAction {
+    ParameterAction::Fees {
+        transaction_fee_bps: 25,
+        fee_recipient,
+        fees_enabled: true,
+    }
+}
+
+fn object_ix(governance: &Governance, objector: &Pubkey, proposal: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ObjectToProposal {
+            objector: *objector,
+            proposal,
+            objection_record: pda(&[b"objection", proposal.as_ref(), objector.as_ref()]),
+            governance_config: governance.config,
+            real_token: governance.real_token,
+            stake_pool: governance.pool.stake_pool,
+            checkpoints: checkpoints_of(governance, objector),
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::ObjectToProposal {},
+    )
+}
+
+/// Create an optimistic proposal from the payer with a minimum-length challenge window
+async fn create_optimistic_proposal(
+    fixture: &mut TestFixture,
+    governance: &Governance,
+    action: ParameterAction,
+) -> Pubkey {
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let challenge_ends_at = fixture.now().await + MIN_VOTING_PERIOD + 60;
+    let instruction = create_optimistic_proposal_ix(fixture, governance, proposal, challenge_ends_at, action);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    proposal
+}
+
+#[tokio::test]
+async fn unchallenged_optimistic_proposal_passes_without_a_vote() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let objector = new_voter(&mut fixture, &governance, 20).await;
+    new_voter(&mut fixture, &governance, 1_000).await;
+    let fee_recipient = Pubkey::new_unique();
+
+    // A change that could never be applied cannot skip the vote
+    let proposal = fixture.next_proposal(&governance.config).await;
+    let challenge_ends_at = fixture.now().await + MIN_VOTING_PERIOD + 60;
+    let action = ParameterAction::Fees {
+        transaction_fee_bps: 1_001,
+        fee_recipient,
+        fees_enabled: true,
+    };
+    let instruction = create_optimistic_proposal_ix(&fixture, &governance, proposal, challenge_ends_at, action);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    // The objection threshold is a share of the stake that objections are weighed by
+    let proposal = create_optimistic_proposal(&mut fixture, &governance, lower_fee(fee_recipient)).await;
+    let pool: StakePool = fixture.get(&governance.pool.stake_pool).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.optimistic);
+    assert_eq!(state.proposal_type, ProposalType::UpdateFees);
+    assert_eq!(pool.total_staked, 1_020);
+    assert_eq!(state.objection_threshold, pool.total_staked * OPTIMISTIC_OBJECTION_PERCENTAGE as u64 / 100);
+    assert!(state.objection_threshold > 20);
+
+    // Holders object rather than vote while the proposal is on the fast path
+    let instruction = vote_ix(&governance, &objector.pubkey(), proposal, None, VoteChoice::No, 20);
+    let result = fixture.process(&[instruction], &[&objector]).await;
+    assert_error(result, RealStackError::OptimisticProposal);
+    let instruction = object_ix(&governance, &objector.pubkey(), proposal);
+    fixture.process(&[instruction], &[&objector]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.optimistic);
+    assert_eq!(state.objection_votes, 20);
+
+    let instruction = object_ix(&governance, &objector.pubkey(), proposal);
+    let result = fixture.process(&[instruction], &[&objector]).await;
+    assert_failed(result);
+
+    // Stake taken after the proposal was created carries no objection weight
+    let late = new_voter(&mut fixture, &governance, 500).await;
+    let instruction = object_ix(&governance, &late.pubkey(), proposal);
+    let result = fixture.process(&[instruction], &[&late]).await;
+    assert_error(result, RealStackError::InsufficientVotingPower);
+
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::VotingPeriodNotEnded);
+
+    // No quorum is needed once the challenge window closes, and the fee change is applied
+    fixture.warp_forward(MIN_VOTING_PERIOD + 60).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+    assert!(!state.is_active);
+    let real_token: RealToken = fixture.get(&governance.real_token).await;
+    assert_eq!(real_token.fee_config.transaction_fee_bps, 25);
+    assert_eq!(real_token.fee_config.fee_recipient, fee_recipient);
+    assert!(real_token.fee_config.fees_enabled);
+
+    let instruction = settle_deposit_ix(&fixture, &governance, proposal, &payer, Some(governance.proposer_real));
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&governance.proposer_real).await, PROPOSER_BALANCE);
+}
+
+#[tokio::test]
+async fn objections_over_the_threshold_escalate_to_a_full_vote() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let governance = setup_governance(&mut fixture).await;
+    let first = new_voter(&mut fixture, &governance, 40).await;
+    let second = new_voter(&mut fixture, &governance, 40).await;
+    let voter = new_voter(&mut fixture, &governance, QUORUM).await;
+    new_voter(&mut fixture, &governance, 1_000).await;
+    let action = ParameterAction::Voting {
+        min_voting_period: 2 * DAY,
+        max_voting_period: MAX_VOTING_PERIOD,
+        min_quorum_votes: 2 * QUORUM,
+        approval_threshold: 60,
+    };
+    let proposal = create_optimistic_proposal(&mut fixture, &governance, action).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    let challenge_ends_at = state.voting_ends_at;
+    assert!(state.objection_threshold >= 40 && state.objection_threshold < 80);
+
+    let instruction = object_ix(&governance, &first.pubkey(), proposal);
+    fixture.process(&[instruction], &[&first]).await.unwrap();
+    fixture.warp_forward(MIN_VOTING_PERIOD / 2).await;
+    let escalated_at = fixture.now().await;
+    let instruction = object_ix(&governance, &second.pubkey(), proposal);
+    fixture.process(&[instruction], &[&second]).await.unwrap();
+
+    // The full vote runs at least a minimum voting period from the escalation
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(!state.optimistic);
+    assert_eq!(state.objection_votes, 80);
+    assert!(state.voting_ends_at >= escalated_at + MIN_VOTING_PERIOD);
+    assert!(state.voting_ends_at > challenge_ends_at);
+
+    let instruction = object_ix(&governance, &voter.pubkey(), proposal);
+    let result = fixture.process(&[instruction], &[&voter]).await;
+    assert_error(result, RealStackError::NotOptimisticProposal);
+
+    // Objectors vote like everyone else, and quorum applies again
+    cast_vote(&mut fixture, &governance, proposal, &first, VoteChoice::No, 40).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD / 2 + 120).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::VotingPeriodNotEnded);
+
+    cast_vote(&mut fixture, &governance, proposal, &voter, VoteChoice::Yes, QUORUM).await;
+    fixture.warp_forward(MIN_VOTING_PERIOD / 2).await;
+    let instruction = execute_ix(&fixture, &governance, &payer, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+    assert_eq!(state.yes_votes, QUORUM);
+    assert_eq!(state.no_votes, 40);
+
+    // Passing the full vote applies the change just the same
+    let config: GovernanceConfig = fixture.get(&governance.config).await;
+    assert_eq!(config.min_voting_period, 2 * DAY);
+    assert_eq!(config.min_quorum_votes, 2 * QUORUM);
+    assert_eq!(config.approval_threshold, 60);
+}
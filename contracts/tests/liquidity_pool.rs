@@ -0,0 +1,465 @@
+mod common;
+
+use common::*;
+use realstack::{AssetToken, ComplianceConfig, HolderRecord, LiquidityPool, PauseFlags, RealStackError, SwapDirection, MINIMUM_LIQUIDITY};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const SEED_SHARES: u64 = 1_000;
+const SEED_QUOTE: u64 = 100_000;
+
+/// An asset pool seeded by the payer
+struct PoolFixture {
+    asset: AssetFixture,
+    liquidity_pool: Pubkey,
+    share_vault: Pubkey,
+    quote_vault: Pubkey,
+    lp_mint: Pubkey,
+    locked_lp_vault: Pubkey,
+    quote_mint: Pubkey,
+    payer_quote: Pubkey,
+    payer_lp: Pubkey,
+    payer: Pubkey,
+}
+
+/// Token accounts a provider or trader uses against the pool
+struct Wallet {
+    owner: Pubkey,
+    shares: Pubkey,
+    quote: Pubkey,
+    lp: Pubkey,
+}
+
+fn create_pool_ix(fixture: &TestFixture, authority: &Pubkey, asset: &AssetFixture, quote_mint: Pubkey) -> Instruction {
+    let liquidity_pool = pda(&[b"liquidity_pool", asset.asset_token.as_ref()]);
+    ix(
+        realstack::accounts::CreatePool {
+            authority: *authority,
+            asset_token: asset.asset_token,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+            liquidity_pool,
+            share_mint: asset.share_mint,
+            quote_mint,
+            share_vault: pda(&[b"pool_shares", liquidity_pool.as_ref()]),
+            quote_vault: pda(&[b"pool_quote", liquidity_pool.as_ref()]),
+            lp_mint: pda(&[b"lp_mint", liquidity_pool.as_ref()]),
+            locked_lp_vault: pda(&[b"pool_locked_lp", liquidity_pool.as_ref()]),
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: anchor_lang::solana_program::sysvar::rent::ID,
+        },
+        realstack::instruction::CreatePool {},
+    )
+}
+
+fn modify_accounts(fixture: &TestFixture, pool: &PoolFixture, wallet: &Wallet) -> realstack::accounts::ModifyLiquidity {
+    realstack::accounts::ModifyLiquidity {
+        provider: wallet.owner,
+        asset_token: pool.asset.asset_token,
+        real_token: fixture.real_token,
+        liquidity_pool: pool.liquidity_pool,
+        share_vault: pool.share_vault,
+        quote_vault: pool.quote_vault,
+        share_mint: pool.asset.share_mint,
+        freeze_authority: freeze_authority_of(&pool.asset.asset_token),
+        lp_mint: pool.lp_mint,
+        locked_lp_vault: pool.locked_lp_vault,
+        provider_share_account: wallet.shares,
+        provider_quote_account: wallet.quote,
+        provider_lp_account: wallet.lp,
+        holder_record: holder_record_of(&pool.asset.asset_token, &wallet.owner),
+        token_program: anchor_spl::token::ID,
+        system_program: anchor_lang::system_program::ID,
+    }
+}
+
+fn add_liquidity_ix(
+    fixture: &TestFixture,
+    pool: &PoolFixture,
+    wallet: &Wallet,
+    max_share_amount: u64,
+    max_quote_amount: u64,
+    min_lp_amount: u64,
+) -> Instruction {
+    ix(
+        modify_accounts(fixture, pool, wallet),
+        realstack::instruction::AddLiquidity {
+            max_share_amount,
+            max_quote_amount,
+            min_lp_amount,
+        },
+    )
+}
+
+fn remove_liquidity_ix(
+    fixture: &TestFixture,
+    pool: &PoolFixture,
+    wallet: &Wallet,
+    lp_amount: u64,
+    min_share_amount: u64,
+) -> Instruction {
+    ix(
+        modify_accounts(fixture, pool, wallet),
+        realstack::instruction::RemoveLiquidity {
+            lp_amount,
+            min_share_amount,
+            min_quote_amount: 0,
+        },
+    )
+}
+
+fn swap_ix(
+    fixture: &TestFixture,
+    pool: &PoolFixture,
+    wallet: &Wallet,
+    fee_account: Pubkey,
+    direction: SwapDirection,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let (stake_pool, reward_vault) = fixture.stake_pool_pdas();
+    ix(
+        realstack::accounts::Swap {
+            trader: wallet.owner,
+            asset_token: pool.asset.asset_token,
+            real_token: fixture.real_token,
+            liquidity_pool: pool.liquidity_pool,
+            share_vault: pool.share_vault,
+            quote_vault: pool.quote_vault,
+            share_mint: pool.asset.share_mint,
+            freeze_authority: freeze_authority_of(&pool.asset.asset_token),
+            trader_share_account: wallet.shares,
+            trader_quote_account: wallet.quote,
+            fee_account,
+            stake_pool,
+            reward_vault,
+            investor_record: None,
+            holder_record: holder_record_of(&pool.asset.asset_token, &wallet.owner),
+            token_program: anchor_spl::token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::Swap {
+            direction,
+            amount_in,
+            min_amount_out,
+        },
+    )
+}
+
+impl PoolFixture {
+    fn payer_wallet(&self) -> Wallet {
+        Wallet {
+            owner: self.payer,
+            shares: self.asset.issuer_shares,
+            quote: self.payer_quote,
+            lp: self.payer_lp,
+        }
+    }
+}
+
+/// Create a tradable asset and its pool, without liquidity
+async fn open_pool(fixture: &mut TestFixture) -> PoolFixture {
+    let asset = fixture.create_asset(false).await;
+    open_pool_for(fixture, asset).await
+}
+
+/// Make `asset` tradable and create its pool, without liquidity
+async fn open_pool_for(fixture: &mut TestFixture, asset: AssetFixture) -> PoolFixture {
+    let payer = fixture.payer_key();
+    fixture.set_tradable(asset.asset_token, true).await;
+    let quote_mint = fixture.create_mint(6).await;
+
+    let instruction = create_pool_ix(fixture, &payer, &asset, quote_mint);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let liquidity_pool = pda(&[b"liquidity_pool", asset.asset_token.as_ref()]);
+    let lp_mint = pda(&[b"lp_mint", liquidity_pool.as_ref()]);
+    let payer_quote = fixture.create_funded_token_account(&quote_mint, &payer, SEED_QUOTE * 10).await;
+    let payer_lp = fixture.create_token_account(&lp_mint, &payer).await;
+    PoolFixture {
+        asset,
+        liquidity_pool,
+        share_vault: pda(&[b"pool_shares", liquidity_pool.as_ref()]),
+        quote_vault: pda(&[b"pool_quote", liquidity_pool.as_ref()]),
+        lp_mint,
+        locked_lp_vault: pda(&[b"pool_locked_lp", liquidity_pool.as_ref()]),
+        quote_mint,
+        payer_quote,
+        payer_lp,
+        payer,
+    }
+}
+
+/// Create a pool and seed it with the payer's liquidity
+async fn seeded_pool(fixture: &mut TestFixture) -> PoolFixture {
+    let pool = open_pool(fixture).await;
+    seed(fixture, pool).await
+}
+
+/// Seed a pool with the payer's liquidity
+async fn seed(fixture: &mut TestFixture, pool: PoolFixture) -> PoolFixture {
+    let instruction = add_liquidity_ix(fixture, &pool, &pool.payer_wallet(), SEED_SHARES, SEED_QUOTE, 0);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    pool
+}
+
+/// A trader holding only quote tokens
+async fn new_trader(fixture: &mut TestFixture, pool: &PoolFixture, quote: u64) -> (Keypair, Wallet) {
+    let trader = fixture.new_actor().await;
+    let wallet = Wallet {
+        owner: trader.pubkey(),
+        shares: fixture.create_token_account(&pool.asset.share_mint, &trader.pubkey()).await,
+        quote: fixture.create_funded_token_account(&pool.quote_mint, &trader.pubkey(), quote).await,
+        lp: fixture.create_token_account(&pool.lp_mint, &trader.pubkey()).await,
+    };
+    (trader, wallet)
+}
+
+#[tokio::test]
+async fn create_pool_links_asset_once() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let asset = fixture.create_asset(false).await;
+    let quote_mint = fixture.create_mint(6).await;
+
+    let instruction = create_pool_ix(&fixture, &intruder.pubkey(), &asset, quote_mint);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = create_pool_ix(&fixture, &payer, &asset, quote_mint);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let liquidity_pool = pda(&[b"liquidity_pool", asset.asset_token.as_ref()]);
+    let asset_token: AssetToken = fixture.get(&asset.asset_token).await;
+    assert_eq!(asset_token.liquidity_pool, Some(liquidity_pool));
+    let pool: LiquidityPool = fixture.get(&liquidity_pool).await;
+    assert_eq!(pool.share_mint, asset.share_mint);
+    assert_eq!(pool.quote_mint, quote_mint);
+
+    let instruction = create_pool_ix(&fixture, &payer, &asset, quote_mint);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::LiquidityPoolExists);
+}
+
+#[tokio::test]
+async fn add_and_remove_liquidity() {
+    let mut fixture = TestFixture::new().await;
+    let pool = seeded_pool(&mut fixture).await;
+
+    // The first deposit mints the geometric mean, less the locked minimum
+    assert_eq!(fixture.token_balance(&pool.payer_lp).await, 10_000 - MINIMUM_LIQUIDITY);
+    assert_eq!(fixture.token_balance(&pool.locked_lp_vault).await, MINIMUM_LIQUIDITY);
+    assert_eq!(fixture.token_balance(&pool.share_vault).await, SEED_SHARES);
+    assert_eq!(fixture.token_balance(&pool.quote_vault).await, SEED_QUOTE);
+
+    // Later deposits are capped by the scarcer side
+    let (provider, wallet) = new_trader(&mut fixture, &pool, 50_000).await;
+    fixture.issue_shares(&pool.asset, &wallet.shares, 100).await;
+    let instruction = add_liquidity_ix(&fixture, &pool, &wallet, 100, 50_000, 0);
+    fixture.process(&[instruction], &[&provider]).await.unwrap();
+    assert_eq!(fixture.token_balance(&wallet.lp).await, 1_000);
+    assert_eq!(fixture.token_balance(&wallet.shares).await, 0);
+    assert_eq!(fixture.token_balance(&wallet.quote).await, 40_000);
+
+    let instruction = remove_liquidity_ix(&fixture, &pool, &wallet, 1_000, 0);
+    fixture.process(&[instruction], &[&provider]).await.unwrap();
+    assert_eq!(fixture.token_balance(&wallet.lp).await, 0);
+    assert_eq!(fixture.token_balance(&wallet.shares).await, 100);
+    assert_eq!(fixture.token_balance(&wallet.quote).await, 50_000);
+
+    // Withdrawing everything else leaves the locked tenth of the reserves behind
+    let instruction = remove_liquidity_ix(&fixture, &pool, &pool.payer_wallet(), 10_000 - MINIMUM_LIQUIDITY, 0);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&pool.payer_lp).await, 0);
+    assert_eq!(fixture.token_balance(&pool.share_vault).await, SEED_SHARES / 10);
+    assert_eq!(fixture.token_balance(&pool.quote_vault).await, SEED_QUOTE / 10);
+}
+
+#[tokio::test]
+async fn providers_exit_without_eligibility_or_holding_checks() {
+    let mut fixture = TestFixture::new().await;
+    let compliance = ComplianceConfig {
+        kyc_required: true,
+        max_holding_bps: 500,
+        ..ComplianceConfig::default()
+    };
+    let asset = fixture.create_restricted_asset(false, compliance).await;
+    let pool = open_pool_for(&mut fixture, asset).await;
+    let pool = seed(&mut fixture, pool).await;
+    let wallet = pool.payer_wallet();
+
+    // The unverified payer takes back more than the holding cap
+    let instruction = remove_liquidity_ix(&fixture, &pool, &wallet, 10_000 - MINIMUM_LIQUIDITY, 0);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let record: HolderRecord = fixture.get(&holder_record_of(&pool.asset.asset_token, &pool.payer)).await;
+    assert_eq!(record.balance, SEED_SHARES * 9 / 10);
+    assert!(record.balance > ASSET_TOTAL_SHARES * 500 / 10_000);
+}
+
+#[tokio::test]
+async fn first_deposit_must_exceed_the_locked_minimum() {
+    let mut fixture = TestFixture::new().await;
+    let pool = open_pool(&mut fixture).await;
+    let wallet = pool.payer_wallet();
+
+    // sqrt(10 * 100_000) == MINIMUM_LIQUIDITY leaves nothing for the provider
+    let instruction = add_liquidity_ix(&fixture, &pool, &wallet, 10, 100_000, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InsufficientLiquidity);
+
+    let instruction = add_liquidity_ix(&fixture, &pool, &wallet, SEED_SHARES, SEED_QUOTE, 10_000 - MINIMUM_LIQUIDITY + 1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::SlippageExceeded);
+
+    let instruction = add_liquidity_ix(&fixture, &pool, &wallet, SEED_SHARES, SEED_QUOTE, 10_000 - MINIMUM_LIQUIDITY);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&pool.locked_lp_vault).await, MINIMUM_LIQUIDITY);
+}
+
+#[tokio::test]
+async fn modify_liquidity_rejects_invalid_calls() {
+    let mut fixture = TestFixture::new().await;
+    let pool = seeded_pool(&mut fixture).await;
+    let wallet = pool.payer_wallet();
+
+    let instruction = add_liquidity_ix(&fixture, &pool, &wallet, 0, 1_000, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = add_liquidity_ix(&fixture, &pool, &wallet, 100, 10_000, 1_001);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::SlippageExceeded);
+
+    let other = fixture.create_asset(false).await;
+    let mut instruction = add_liquidity_ix(&fixture, &pool, &wallet, 100, 10_000, 0);
+    instruction.accounts[1].pubkey = other.asset_token;
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetNotFound);
+
+    let instruction = remove_liquidity_ix(&fixture, &pool, &wallet, 0, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = remove_liquidity_ix(&fixture, &pool, &wallet, 10_001, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InsufficientLiquidity);
+
+    let instruction = remove_liquidity_ix(&fixture, &pool, &wallet, 1_000, 101);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::SlippageExceeded);
+
+    fixture.pause(PauseFlags { trading: true, ..no_pause() }).await;
+    let instruction = add_liquidity_ix(&fixture, &pool, &wallet, 100, 10_000, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::TransfersPaused);
+}
+
+#[tokio::test]
+async fn swap_prices_on_the_curve_and_charges_fee() {
+    let mut fixture = TestFixture::new().await;
+    let pool = seeded_pool(&mut fixture).await;
+    let (trader, wallet) = new_trader(&mut fixture, &pool, 10_000).await;
+
+    // 10_000 in, 25 bps fee, 9_975 priced against 1_000 x 100_000
+    let fee = 25;
+    let out = SEED_SHARES * (10_000 - fee) / (SEED_QUOTE + 10_000 - fee);
+    let instruction = swap_ix(&fixture, &pool, &wallet, pool.payer_quote, SwapDirection::QuoteToShares, 10_000, out);
+    fixture.process(&[instruction], &[&trader]).await.unwrap();
+    assert_eq!(fixture.token_balance(&wallet.shares).await, out);
+    assert_eq!(fixture.token_balance(&wallet.quote).await, 0);
+    assert_eq!(fixture.token_balance(&pool.quote_vault).await, SEED_QUOTE + 10_000 - fee);
+    assert_eq!(fixture.token_balance(&pool.payer_quote).await, SEED_QUOTE * 9 + fee);
+
+    // Selling shares back pays the fee off the quote output
+    let share_reserve = SEED_SHARES - out;
+    let quote_reserve = SEED_QUOTE + 10_000 - fee;
+    let gross = quote_reserve * out / (share_reserve + out);
+    let sell_fee = gross * 25 / 10_000;
+    let instruction = swap_ix(&fixture, &pool, &wallet, pool.payer_quote, SwapDirection::SharesToQuote, out, gross - sell_fee);
+    fixture.process(&[instruction], &[&trader]).await.unwrap();
+    assert_eq!(fixture.token_balance(&wallet.shares).await, 0);
+    assert_eq!(fixture.token_balance(&wallet.quote).await, gross - sell_fee);
+    assert_eq!(fixture.token_balance(&pool.quote_vault).await, quote_reserve - gross);
+    assert_eq!(fixture.token_balance(&pool.payer_quote).await, SEED_QUOTE * 9 + fee + sell_fee);
+}
+
+#[tokio::test]
+async fn swaps_never_shrink_the_reserve_product() {
+    let mut fixture = TestFixture::new().await;
+    let pool = seeded_pool(&mut fixture).await;
+    let (trader, wallet) = new_trader(&mut fixture, &pool, 50_000).await;
+
+    let mut k = SEED_SHARES as u128 * SEED_QUOTE as u128;
+    for (direction, amount_in) in [
+        (SwapDirection::QuoteToShares, 7_919),
+        (SwapDirection::QuoteToShares, 333),
+        (SwapDirection::SharesToQuote, 41),
+        (SwapDirection::QuoteToShares, 12_345),
+        (SwapDirection::SharesToQuote, 97),
+    ] {
+        let instruction = swap_ix(&fixture, &pool, &wallet, pool.payer_quote, direction, amount_in, 1);
+        fixture.process(&[instruction], &[&trader]).await.unwrap();
+        let shares = fixture.token_balance(&pool.share_vault).await as u128;
+        let quote = fixture.token_balance(&pool.quote_vault).await as u128;
+        assert!(shares * quote >= k);
+        k = shares * quote;
+    }
+}
+
+#[tokio::test]
+async fn swap_rejects_invalid_calls() {
+    let mut fixture = TestFixture::new().await;
+    let pool = seeded_pool(&mut fixture).await;
+    let (trader, wallet) = new_trader(&mut fixture, &pool, 10_000).await;
+
+    let instruction = swap_ix(&fixture, &pool, &wallet, pool.payer_quote, SwapDirection::QuoteToShares, 0, 0);
+    let result = fixture.process(&[instruction], &[&trader]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = swap_ix(&fixture, &pool, &wallet, pool.payer_quote, SwapDirection::QuoteToShares, 10_000, 91);
+    let result = fixture.process(&[instruction], &[&trader]).await;
+    assert_error(result, RealStackError::SlippageExceeded);
+
+    // Swapping through another asset's pool
+    let other = fixture.create_asset(false).await;
+    let mut instruction = swap_ix(&fixture, &pool, &wallet, pool.payer_quote, SwapDirection::QuoteToShares, 10_000, 0);
+    instruction.accounts[1].pubkey = other.asset_token;
+    let result = fixture.process(&[instruction], &[&trader]).await;
+    assert_error(result, RealStackError::LiquidityPoolNotFound);
+
+    // The fee is always taken in the quote mint
+    let instruction = swap_ix(&fixture, &pool, &wallet, pool.asset.issuer_shares, SwapDirection::SharesToQuote, 10, 0);
+    let result = fixture.process(&[instruction], &[&trader]).await;
+    assert_error(result, RealStackError::InvalidTokenMint);
+
+    fixture.set_tradable(pool.asset.asset_token, false).await;
+    let instruction = swap_ix(&fixture, &pool, &wallet, pool.payer_quote, SwapDirection::QuoteToShares, 10_000, 0);
+    let result = fixture.process(&[instruction], &[&trader]).await;
+    assert_error(result, RealStackError::AssetNotTradable);
+
+    fixture.set_tradable(pool.asset.asset_token, true).await;
+    fixture.pause(PauseFlags { trading: true, ..no_pause() }).await;
+    let instruction = swap_ix(&fixture, &pool, &wallet, pool.payer_quote, SwapDirection::QuoteToShares, 10_000, 0);
+    let result = fixture.process(&[instruction], &[&trader]).await;
+    assert_error(result, RealStackError::TransfersPaused);
+}
+
+#[tokio::test]
+async fn last_provider_out_of_a_wound_down_pool_takes_the_locked_minimum() {
+    let mut fixture = TestFixture::new().await;
+    let pool = seeded_pool(&mut fixture).await;
+    let wallet = pool.payer_wallet();
+    let issuer_shares = fixture.token_balance(&wallet.shares).await;
+
+    fixture.burn_asset(&pool.asset, 1_000).await;
+    let instruction = remove_liquidity_ix(&fixture, &pool, &wallet, 10_000 - MINIMUM_LIQUIDITY, SEED_SHARES);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&wallet.shares).await, issuer_shares + SEED_SHARES);
+    assert_eq!(fixture.token_balance(&pool.share_vault).await, 0);
+    assert_eq!(fixture.token_balance(&pool.quote_vault).await, 0);
+    assert_eq!(fixture.token_balance(&pool.locked_lp_vault).await, 0);
+    assert_eq!(fixture.mint_supply(&pool.lp_mint).await, 0);
+}
@@ -0,0 +1,320 @@
+const anchor = require('@project-serum/anchor');
+const { PublicKey, SystemProgram, Keypair, LAMPORTS_PER_SOL } = anchor.web3;
+const { TOKEN_PROGRAM_ID, Token } = require('@solana/spl-token');
+const { assert } = require('chai');
+
+describe('Marketplace', () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Realstack;
+  const payer = provider.wallet.payer;
+
+  // Accounts shared by the scenarios
+  const realTokenAccount = Keypair.generate();
+  const assetTokenAccount = Keypair.generate();
+  const seller = Keypair.generate();
+  const buyer = Keypair.generate();
+
+  let roles;
+  let realMint;
+  let shareMint;
+  let paymentMint;
+  let orderBook;
+  let freezeAuthority;
+  let baseVault;
+  let quoteVault;
+  let sellerShares;
+  let sellerPayment;
+  let buyerShares;
+  let buyerPayment;
+  let feeAccount;
+  let buyerHolderRecord;
+  let sellerHolderRecord;
+  let buyerInvestorRecord;
+  let stakePool;
+  let rewardVault;
+
+  const balance = async (mint, account) => (await mint.getAccountInfo(account)).amount.toNumber();
+
+  const placeOrder = (trader, shares, payment, side, price, quantity) =>
+    program.methods
+      .placeOrder(side, new anchor.BN(price), new anchor.BN(quantity))
+      .accounts({
+        owner: trader.publicKey,
+        assetToken: assetTokenAccount.publicKey,
+        realToken: realTokenAccount.publicKey,
+        orderBook,
+        ownerShareAccount: shares,
+        ownerPaymentAccount: payment,
+        baseVault,
+        quoteVault,
+        shareMint: shareMint.publicKey,
+        freezeAuthority,
+        investorRecord: null,
+        evictedOwnerAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([trader])
+      .rpc();
+
+  const cancelOrder = (trader, shares, payment, side, orderId) =>
+    program.methods
+      .cancelOrder(side, new anchor.BN(orderId))
+      .accounts({
+        owner: trader.publicKey,
+        orderBook,
+        ownerShareAccount: shares,
+        ownerPaymentAccount: payment,
+        baseVault,
+        quoteVault,
+        shareMint: shareMint.publicKey,
+        freezeAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([trader])
+      .rpc();
+
+  const matchOrders = () =>
+    program.methods
+      .matchOrders()
+      .accounts({
+        cranker: provider.wallet.publicKey,
+        assetToken: assetTokenAccount.publicKey,
+        realToken: realTokenAccount.publicKey,
+        orderBook,
+        baseVault,
+        quoteVault,
+        shareMint: shareMint.publicKey,
+        freezeAuthority,
+        buyerShareAccount: buyerShares,
+        buyerPaymentAccount: buyerPayment,
+        sellerPaymentAccount: sellerPayment,
+        feeAccount,
+        stakePool,
+        rewardVault,
+        buyerInvestorRecord,
+        buyerHolderRecord,
+        sellerHolderRecord,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const BID = { bid: {} };
+  const ASK = { ask: {} };
+
+  before(async () => {
+    // Fund the traders
+    for (const trader of [seller, buyer]) {
+      const signature = await provider.connection.requestAirdrop(trader.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(signature);
+    }
+
+    // Create the mints
+    realMint = await Token.createMint(provider.connection, payer, payer.publicKey, null, 0, TOKEN_PROGRAM_ID);
+    shareMint = await Token.createMint(provider.connection, payer, payer.publicKey, payer.publicKey, 0, TOKEN_PROGRAM_ID);
+    paymentMint = await Token.createMint(provider.connection, payer, payer.publicKey, null, 0, TOKEN_PROGRAM_ID);
+
+    // Initialize REAL (default fee: 25bps to the provider wallet)
+    [roles] = await PublicKey.findProgramAddress(
+      [Buffer.from("roles"), realTokenAccount.publicKey.toBuffer()], program.programId);
+    await program.methods
+      .initialize("REAL Token", "REAL", "https://realstack.finance/metadata/real-token.json", new anchor.BN(100_000_000))
+      .accounts({
+        realToken: realTokenAccount.publicKey,
+        roles,
+        mint: realMint.publicKey,
+        authority: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([realTokenAccount])
+      .rpc();
+
+    // The provider wallet manages assets and holds the pause switch
+    for (const role of [{ assetManager: {} }, { pauser: {} }]) {
+      await program.methods
+        .grantRole(provider.wallet.publicKey, role)
+        .accounts({ admin: provider.wallet.publicKey, roles })
+        .rpc();
+    }
+
+    // Issue the whole share supply to the seller and hand the share mint and its freeze authority to the asset
+    const [mintAuthority] = await PublicKey.findProgramAddress(
+      [Buffer.from("mint_authority"), assetTokenAccount.publicKey.toBuffer()], program.programId);
+    [freezeAuthority] = await PublicKey.findProgramAddress(
+      [Buffer.from("freeze_authority"), assetTokenAccount.publicKey.toBuffer()], program.programId);
+    sellerShares = await shareMint.createAccount(seller.publicKey);
+    await shareMint.mintTo(sellerShares, payer, [], 10_000);
+    await shareMint.setAuthority(shareMint.publicKey, mintAuthority, 'MintTokens', payer, []);
+    await shareMint.setAuthority(shareMint.publicKey, freezeAuthority, 'FreezeAccount', payer, []);
+
+    // Create a tradable asset
+    await program.methods
+      .createAssetToken("Harbor Lofts", "HBL", "real-estate", "Twelve-unit residential building.",
+        "https://realstack.finance/metadata/assets/harbor-lofts.json",
+        new anchor.BN(1_000_000), new anchor.BN(10_000), new anchor.BN(100), false,
+        { kycRequired: false, allowedJurisdictions: [], accreditedOnly: false, maxHoldingBps: 0, maxHolders: 0, lockupDuration: new anchor.BN(0) })
+      .accounts({
+        authority: provider.wallet.publicKey,
+        realToken: realTokenAccount.publicKey,
+        roles,
+        assetToken: assetTokenAccount.publicKey,
+        mint: shareMint.publicKey,
+        mintAuthority,
+        freezeAuthority,
+        issuerShareAccount: sellerShares,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([assetTokenAccount])
+      .rpc();
+
+    await program.methods
+      .toggleTradability(true)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        assetToken: assetTokenAccount.publicKey,
+        roles,
+      })
+      .rpc();
+
+    // Create the order book
+    [orderBook] = await PublicKey.findProgramAddress(
+      [Buffer.from("order_book"), assetTokenAccount.publicKey.toBuffer()], program.programId);
+    [baseVault] = await PublicKey.findProgramAddress(
+      [Buffer.from("order_book_base"), orderBook.toBuffer()], program.programId);
+    [quoteVault] = await PublicKey.findProgramAddress(
+      [Buffer.from("order_book_quote"), orderBook.toBuffer()], program.programId);
+    [stakePool] = await PublicKey.findProgramAddress(
+      [Buffer.from("stake_pool"), realTokenAccount.publicKey.toBuffer()], program.programId);
+    [rewardVault] = await PublicKey.findProgramAddress(
+      [Buffer.from("reward_vault"), stakePool.toBuffer()], program.programId);
+    [buyerHolderRecord] = await PublicKey.findProgramAddress(
+      [Buffer.from("holder"), assetTokenAccount.publicKey.toBuffer(), buyer.publicKey.toBuffer()], program.programId);
+    [sellerHolderRecord] = await PublicKey.findProgramAddress(
+      [Buffer.from("holder"), assetTokenAccount.publicKey.toBuffer(), seller.publicKey.toBuffer()], program.programId);
+    [buyerInvestorRecord] = await PublicKey.findProgramAddress(
+      [Buffer.from("investor"), realTokenAccount.publicKey.toBuffer(), buyer.publicKey.toBuffer()], program.programId);
+
+    await program.methods
+      .createOrderBook(new anchor.BN(100))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        assetToken: assetTokenAccount.publicKey,
+        realToken: realTokenAccount.publicKey,
+        roles,
+        orderBook,
+        shareMint: shareMint.publicKey,
+        paymentMint: paymentMint.publicKey,
+        baseVault,
+        quoteVault,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    // Fund the traders' token accounts
+    sellerPayment = await paymentMint.createAccount(seller.publicKey);
+    buyerShares = await shareMint.createAccount(buyer.publicKey);
+    buyerPayment = await paymentMint.createAccount(buyer.publicKey);
+    feeAccount = await paymentMint.createAccount(provider.wallet.publicKey);
+
+    await paymentMint.mintTo(buyerPayment, payer, [], 10_000);
+  });
+
+  it('Partially fills crossing orders at the resting price', async () => {
+    // Seller rests 10 shares at 100, buyer crosses with 4 at 110
+    await placeOrder(seller, sellerShares, sellerPayment, ASK, 100, 10);
+    await placeOrder(buyer, buyerShares, buyerPayment, BID, 110, 4);
+
+    await matchOrders();
+
+    // Buyer pays 400 for 4 shares and gets the 40 price improvement back
+    assert.equal(await balance(shareMint, buyerShares), 4);
+    assert.equal(await balance(paymentMint, buyerPayment), 10_000 - 400);
+
+    // 25bps fee on 400 goes to the fee recipient
+    assert.equal(await balance(paymentMint, feeAccount), 1);
+    assert.equal(await balance(paymentMint, sellerPayment), 399);
+
+    // The ask stays on the book with the unfilled remainder
+    const book = await program.account.orderBook.fetch(orderBook);
+    assert.equal(book.bids.length, 0);
+    assert.equal(book.asks.length, 1);
+    assert.equal(book.asks[0].orderId.toNumber(), 0);
+    assert.equal(book.asks[0].quantity.toNumber(), 6);
+  });
+
+  it('Rejects matching when the book does not cross', async () => {
+    await placeOrder(buyer, buyerShares, buyerPayment, BID, 90, 5);
+
+    try {
+      await matchOrders();
+      assert.fail("match_orders should fail when the best bid is below the best ask");
+    } catch (err) {
+      assert.include(err.toString(), "OrdersNotCrossing");
+    }
+  });
+
+  it('Refunds escrowed payment when a bid is cancelled', async () => {
+    const before = await balance(paymentMint, buyerPayment);
+
+    await cancelOrder(buyer, buyerShares, buyerPayment, BID, 2);
+
+    assert.equal(await balance(paymentMint, buyerPayment), before + 450);
+    const book = await program.account.orderBook.fetch(orderBook);
+    assert.equal(book.bids.length, 0);
+  });
+
+  it('Refunds remaining shares when a partially filled ask is cancelled', async () => {
+    await cancelOrder(seller, sellerShares, sellerPayment, ASK, 0);
+
+    assert.equal(await balance(shareMint, sellerShares), 10_000 - 4);
+    assert.equal(await balance(shareMint, baseVault), 0);
+    const book = await program.account.orderBook.fetch(orderBook);
+    assert.equal(book.asks.length, 0);
+  });
+
+  it('Rejects cancellation by someone other than the owner', async () => {
+    await placeOrder(seller, sellerShares, sellerPayment, ASK, 150, 1);
+
+    try {
+      await cancelOrder(buyer, buyerShares, buyerPayment, ASK, 3);
+      assert.fail("cancel_order should fail for a non-owner");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+
+  it('Rejects new orders while trading is paused', async () => {
+    const setTradingPause = (paused) =>
+      program.methods
+        .setTransferPause(paused)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          realToken: realTokenAccount.publicKey,
+          roles,
+        })
+        .rpc();
+
+    await setTradingPause(true);
+    const token = await program.account.realToken.fetch(realTokenAccount.publicKey);
+    assert.equal(token.transfersPaused, true);
+    assert.isAbove(token.pauseState.expiresAt.toNumber(), 0);
+
+    try {
+      await placeOrder(seller, sellerShares, sellerPayment, ASK, 150, 1);
+      assert.fail("place_order should fail while trading is paused");
+    } catch (err) {
+      assert.include(err.toString(), "TransfersPaused");
+    }
+
+    await setTradingPause(false);
+  });
+});
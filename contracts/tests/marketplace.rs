@@ -0,0 +1,556 @@
+mod common;
+
+use common::*;
+use realstack::{
+    ComplianceConfig, KycStatus, OrderBook, OrderSide, PauseFlags, RealStackError, Role, StakePool, MAX_ORDERS_PER_OWNER,
+    MAX_ORDERS_PER_SIDE,
+};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const TRADER_SHARES: u64 = 1_000;
+const TRADER_PAYMENT: u64 = 1_000_000;
+const MIN_NOTIONAL: u64 = 100;
+
+/// A tradable asset with its order book
+struct Market {
+    asset: AssetFixture,
+    order_book: Pubkey,
+    base_vault: Pubkey,
+    quote_vault: Pubkey,
+    payment_mint: Pubkey,
+    fee_account: Pubkey,
+}
+
+/// A trader with share and payment accounts
+struct Trader {
+    keypair: Keypair,
+    shares: Pubkey,
+    payments: Pubkey,
+}
+
+fn order_book_pdas(asset_token: &Pubkey) -> (Pubkey, Pubkey, Pubkey) {
+    let order_book = pda(&[b"order_book", asset_token.as_ref()]);
+    let base_vault = pda(&[b"order_book_base", order_book.as_ref()]);
+    let quote_vault = pda(&[b"order_book_quote", order_book.as_ref()]);
+    (order_book, base_vault, quote_vault)
+}
+
+fn create_order_book_ix(fixture: &TestFixture, authority: &Pubkey, asset: &AssetFixture, payment_mint: Pubkey) -> Instruction {
+    let (order_book, base_vault, quote_vault) = order_book_pdas(&asset.asset_token);
+    ix(
+        realstack::accounts::CreateOrderBook {
+            authority: *authority,
+            asset_token: asset.asset_token,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+            order_book,
+            share_mint: asset.share_mint,
+            payment_mint,
+            base_vault,
+            quote_vault,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: anchor_lang::solana_program::sysvar::rent::ID,
+        },
+        realstack::instruction::CreateOrderBook {
+            min_order_notional: MIN_NOTIONAL,
+        },
+    )
+}
+
+fn place_ix(fixture: &TestFixture, market: &Market, trader: &Trader, side: OrderSide, price: u64, quantity: u64) -> Instruction {
+    place_evicting_ix(fixture, market, trader, side, price, quantity, None)
+}
+
+fn place_evicting_ix(
+    fixture: &TestFixture,
+    market: &Market,
+    trader: &Trader,
+    side: OrderSide,
+    price: u64,
+    quantity: u64,
+    evicted_owner_account: Option<Pubkey>,
+) -> Instruction {
+    ix(
+        realstack::accounts::PlaceOrder {
+            owner: trader.keypair.pubkey(),
+            asset_token: market.asset.asset_token,
+            real_token: fixture.real_token,
+            order_book: market.order_book,
+            owner_share_account: trader.shares,
+            owner_payment_account: trader.payments,
+            base_vault: market.base_vault,
+            quote_vault: market.quote_vault,
+            share_mint: market.asset.share_mint,
+            freeze_authority: freeze_authority_of(&market.asset.asset_token),
+            investor_record: None,
+            evicted_owner_account,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::PlaceOrder { side, price, quantity },
+    )
+}
+
+fn cancel_ix(market: &Market, trader: &Trader, side: OrderSide, order_id: u64) -> Instruction {
+    ix(
+        realstack::accounts::CancelOrder {
+            owner: trader.keypair.pubkey(),
+            order_book: market.order_book,
+            owner_share_account: trader.shares,
+            owner_payment_account: trader.payments,
+            base_vault: market.base_vault,
+            quote_vault: market.quote_vault,
+            share_mint: market.asset.share_mint,
+            freeze_authority: freeze_authority_of(&market.asset.asset_token),
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::CancelOrder { side, order_id },
+    )
+}
+
+fn unwind_ix(fixture: &TestFixture, market: &Market, owner: &Trader, side: OrderSide, order_id: u64) -> Instruction {
+    ix(
+        realstack::accounts::UnwindOrder {
+            cranker: fixture.payer_key(),
+            asset_token: market.asset.asset_token,
+            order_book: market.order_book,
+            owner_share_account: owner.shares,
+            owner_payment_account: owner.payments,
+            base_vault: market.base_vault,
+            quote_vault: market.quote_vault,
+            share_mint: market.asset.share_mint,
+            freeze_authority: freeze_authority_of(&market.asset.asset_token),
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::UnwindOrder { side, order_id },
+    )
+}
+
+fn match_ix(fixture: &TestFixture, market: &Market, buyer: &Trader, seller: &Trader) -> Instruction {
+    let (stake_pool, reward_vault) = fixture.stake_pool_pdas();
+    ix(
+        realstack::accounts::MatchOrders {
+            cranker: fixture.payer_key(),
+            asset_token: market.asset.asset_token,
+            real_token: fixture.real_token,
+            order_book: market.order_book,
+            base_vault: market.base_vault,
+            quote_vault: market.quote_vault,
+            share_mint: market.asset.share_mint,
+            freeze_authority: freeze_authority_of(&market.asset.asset_token),
+            buyer_share_account: buyer.shares,
+            buyer_payment_account: buyer.payments,
+            seller_payment_account: seller.payments,
+            fee_account: market.fee_account,
+            stake_pool,
+            reward_vault,
+            buyer_investor_record: investor_record_of(fixture, &buyer.keypair.pubkey()),
+            buyer_holder_record: holder_record_of(&market.asset.asset_token, &buyer.keypair.pubkey()),
+            seller_holder_record: holder_record_of(&market.asset.asset_token, &seller.keypair.pubkey()),
+            token_program: anchor_spl::token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::MatchOrders {},
+    )
+}
+
+fn investor_record_of(fixture: &TestFixture, wallet: &Pubkey) -> Pubkey {
+    pda(&[b"investor", fixture.real_token.as_ref(), wallet.as_ref()])
+}
+
+/// Create a tradable asset and its order book
+async fn open_market(fixture: &mut TestFixture) -> Market {
+    let asset = fixture.create_asset(false).await;
+    open_market_for(fixture, asset).await
+}
+
+/// Make an asset tradable and open its order book
+async fn open_market_for(fixture: &mut TestFixture, asset: AssetFixture) -> Market {
+    let payer = fixture.payer_key();
+    fixture.set_tradable(asset.asset_token, true).await;
+    let payment_mint = fixture.create_mint(6).await;
+
+    let instruction = create_order_book_ix(fixture, &payer, &asset, payment_mint);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let fee_account = fixture.create_token_account(&payment_mint, &payer).await;
+    let (order_book, base_vault, quote_vault) = order_book_pdas(&asset.asset_token);
+    Market {
+        asset,
+        order_book,
+        base_vault,
+        quote_vault,
+        payment_mint,
+        fee_account,
+    }
+}
+
+/// A fresh trader holding `TRADER_SHARES` shares and `TRADER_PAYMENT` payment tokens
+async fn new_trader(fixture: &mut TestFixture, market: &Market) -> Trader {
+    let keypair = fixture.new_actor().await;
+    let shares = fixture.create_token_account(&market.asset.share_mint, &keypair.pubkey()).await;
+    fixture.issue_shares(&market.asset, &shares, TRADER_SHARES).await;
+    let payments = fixture.create_funded_token_account(&market.payment_mint, &keypair.pubkey(), TRADER_PAYMENT).await;
+    Trader {
+        keypair,
+        shares,
+        payments,
+    }
+}
+
+async fn place(fixture: &mut TestFixture, market: &Market, trader: &Trader, side: OrderSide, price: u64, quantity: u64) {
+    let instruction = place_ix(fixture, market, trader, side, price, quantity);
+    fixture.process(&[instruction], &[&trader.keypair]).await.unwrap();
+}
+
+#[tokio::test]
+async fn create_order_book_checks_authority_and_status() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let asset = fixture.create_asset(false).await;
+    let payment_mint = fixture.create_mint(6).await;
+
+    let instruction = create_order_book_ix(&fixture, &intruder.pubkey(), &asset, payment_mint);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = create_order_book_ix(&fixture, &payer, &asset, payment_mint);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let (order_book, _, _) = order_book_pdas(&asset.asset_token);
+    let book: OrderBook = fixture.get(&order_book).await;
+    assert_eq!(book.asset_token, asset.asset_token);
+    assert_eq!(book.real_token, fixture.real_token);
+    assert_eq!(book.payment_mint, payment_mint);
+}
+
+#[tokio::test]
+async fn orders_rest_in_price_time_priority() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let alice = new_trader(&mut fixture, &market).await;
+    let bob = new_trader(&mut fixture, &market).await;
+
+    place(&mut fixture, &market, &alice, OrderSide::Bid, 90, 10).await;
+    place(&mut fixture, &market, &bob, OrderSide::Bid, 95, 10).await;
+    place(&mut fixture, &market, &alice, OrderSide::Ask, 120, 5).await;
+    place(&mut fixture, &market, &bob, OrderSide::Ask, 110, 5).await;
+
+    let book: OrderBook = fixture.get(&market.order_book).await;
+    assert_eq!(book.bids.iter().map(|o| o.price).collect::<Vec<_>>(), vec![95, 90]);
+    assert_eq!(book.asks.iter().map(|o| o.price).collect::<Vec<_>>(), vec![110, 120]);
+    assert_eq!(fixture.token_balance(&market.quote_vault).await, 90 * 10 + 95 * 10);
+    assert_eq!(fixture.token_balance(&market.base_vault).await, 10);
+}
+
+#[tokio::test]
+async fn place_order_rejects_invalid_orders() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let trader = new_trader(&mut fixture, &market).await;
+
+    for (price, quantity) in [(0, 10), (100, 0)] {
+        let instruction = place_ix(&fixture, &market, &trader, OrderSide::Bid, price, quantity);
+        let result = fixture.process(&[instruction], &[&trader.keypair]).await;
+        assert_error(result, RealStackError::InvalidParameters);
+    }
+
+    // The book only takes orders for its own asset
+    let other = fixture.create_asset(false).await;
+    let mut instruction = place_ix(&fixture, &market, &trader, OrderSide::Bid, 100, 1);
+    instruction.accounts[1].pubkey = other.asset_token;
+    let result = fixture.process(&[instruction], &[&trader.keypair]).await;
+    assert_error(result, RealStackError::AssetNotFound);
+
+    // Orders must be worth at least the book's minimum
+    let instruction = place_ix(&fixture, &market, &trader, OrderSide::Bid, MIN_NOTIONAL - 1, 1);
+    let result = fixture.process(&[instruction], &[&trader.keypair]).await;
+    assert_error(result, RealStackError::OrderBelowMinimum);
+}
+
+#[tokio::test]
+async fn place_order_caps_orders_per_owner_and_side() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let trader = new_trader(&mut fixture, &market).await;
+
+    for _ in 0..MAX_ORDERS_PER_OWNER {
+        place(&mut fixture, &market, &trader, OrderSide::Ask, 100, 1).await;
+    }
+    let instruction = place_ix(&fixture, &market, &trader, OrderSide::Ask, 100, 1);
+    let result = fixture.process(&[instruction], &[&trader.keypair]).await;
+    assert_error(result, RealStackError::OwnerOrderLimitReached);
+
+    // The cap is per side
+    place(&mut fixture, &market, &trader, OrderSide::Bid, 90, 2).await;
+
+    // Filling the side takes many owners
+    for _ in 1..MAX_ORDERS_PER_SIDE / MAX_ORDERS_PER_OWNER {
+        let other = new_trader(&mut fixture, &market).await;
+        for _ in 0..MAX_ORDERS_PER_OWNER {
+            place(&mut fixture, &market, &other, OrderSide::Ask, 100, 1).await;
+        }
+    }
+    let late = new_trader(&mut fixture, &market).await;
+    let instruction = place_ix(&fixture, &market, &late, OrderSide::Ask, 100, 1);
+    let result = fixture.process(&[instruction], &[&late.keypair]).await;
+    assert_error(result, RealStackError::OrderBookFull);
+}
+
+#[tokio::test]
+async fn better_priced_order_evicts_the_worst_on_a_full_side() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+
+    // Single-share asks far above the market fill the side
+    let mut squatters = Vec::new();
+    for _ in 0..MAX_ORDERS_PER_SIDE / MAX_ORDERS_PER_OWNER {
+        let squatter = new_trader(&mut fixture, &market).await;
+        for _ in 0..MAX_ORDERS_PER_OWNER {
+            place(&mut fixture, &market, &squatter, OrderSide::Ask, 10_000, 1).await;
+        }
+        squatters.push(squatter);
+    }
+    let last = squatters.last().unwrap();
+    let seller = new_trader(&mut fixture, &market).await;
+    let instruction = place_ix(&fixture, &market, &seller, OrderSide::Ask, 10_000, 1);
+    let result = fixture.process(&[instruction], &[&seller.keypair]).await;
+    assert_error(result, RealStackError::OrderBookFull);
+
+    // The evicted owner must be refunded
+    let instruction = place_evicting_ix(&fixture, &market, &seller, OrderSide::Ask, 100, 10, None);
+    let result = fixture.process(&[instruction], &[&seller.keypair]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+    let instruction = place_evicting_ix(&fixture, &market, &seller, OrderSide::Ask, 100, 10, Some(squatters[0].shares));
+    let result = fixture.process(&[instruction], &[&seller.keypair]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+
+    // A market-priced ask takes the place of the latest worst-priced one
+    let instruction = place_evicting_ix(&fixture, &market, &seller, OrderSide::Ask, 100, 10, Some(last.shares));
+    fixture.process(&[instruction], &[&seller.keypair]).await.unwrap();
+    let book: OrderBook = fixture.get(&market.order_book).await;
+    assert_eq!(book.asks.len(), MAX_ORDERS_PER_SIDE);
+    assert_eq!(book.asks[0].owner, seller.keypair.pubkey());
+    assert_eq!(book.asks.iter().filter(|order| order.owner == last.keypair.pubkey()).count(), MAX_ORDERS_PER_OWNER - 1);
+    assert_eq!(fixture.token_balance(&last.shares).await, TRADER_SHARES - MAX_ORDERS_PER_OWNER as u64 + 1);
+}
+
+#[tokio::test]
+async fn place_order_requires_tradable_asset() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let trader = new_trader(&mut fixture, &market).await;
+
+    fixture.set_tradable(market.asset.asset_token, false).await;
+    let instruction = place_ix(&fixture, &market, &trader, OrderSide::Ask, 100, 1);
+    let result = fixture.process(&[instruction], &[&trader.keypair]).await;
+    assert_error(result, RealStackError::AssetNotTradable);
+
+    fixture.set_tradable(market.asset.asset_token, true).await;
+    fixture.pause(PauseFlags { trading: true, ..no_pause() }).await;
+    let instruction = place_ix(&fixture, &market, &trader, OrderSide::Ask, 100, 1);
+    let result = fixture.process(&[instruction], &[&trader.keypair]).await;
+    assert_error(result, RealStackError::TransfersPaused);
+}
+
+#[tokio::test]
+async fn cancel_order_refunds_owner_only() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let alice = new_trader(&mut fixture, &market).await;
+    let bob = new_trader(&mut fixture, &market).await;
+
+    place(&mut fixture, &market, &alice, OrderSide::Bid, 100, 10).await;
+    assert_eq!(fixture.token_balance(&alice.payments).await, TRADER_PAYMENT - 1_000);
+
+    let instruction = cancel_ix(&market, &bob, OrderSide::Bid, 0);
+    let result = fixture.process(&[instruction], &[&bob.keypair]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let instruction = cancel_ix(&market, &alice, OrderSide::Ask, 0);
+    let result = fixture.process(&[instruction], &[&alice.keypair]).await;
+    assert_error(result, RealStackError::OrderNotFound);
+
+    let instruction = cancel_ix(&market, &alice, OrderSide::Bid, 0);
+    fixture.process(&[instruction], &[&alice.keypair]).await.unwrap();
+    assert_eq!(fixture.token_balance(&alice.payments).await, TRADER_PAYMENT);
+    let book: OrderBook = fixture.get(&market.order_book).await;
+    assert!(book.bids.is_empty());
+}
+
+#[tokio::test]
+async fn match_orders_settles_fill_with_fee() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let seller = new_trader(&mut fixture, &market).await;
+    let buyer = new_trader(&mut fixture, &market).await;
+
+    // The resting ask sets the price; the buyer gets the improvement back
+    place(&mut fixture, &market, &seller, OrderSide::Ask, 100, 40).await;
+    place(&mut fixture, &market, &buyer, OrderSide::Bid, 110, 50).await;
+
+    let instruction = match_ix(&fixture, &market, &buyer, &seller);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let quote = 40 * 100;
+    let fee = quote * 25 / 10_000;
+    assert_eq!(fixture.token_balance(&buyer.shares).await, TRADER_SHARES + 40);
+    assert_eq!(fixture.token_balance(&seller.payments).await, TRADER_PAYMENT + quote - fee);
+    assert_eq!(fixture.token_balance(&market.fee_account).await, fee);
+    assert_eq!(fixture.token_balance(&buyer.payments).await, TRADER_PAYMENT - 50 * 110 + 40 * 10);
+
+    let book: OrderBook = fixture.get(&market.order_book).await;
+    assert!(book.asks.is_empty());
+    assert_eq!(book.bids[0].quantity, 10);
+
+    let instruction = match_ix(&fixture, &market, &buyer, &seller);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::OrdersNotCrossing);
+}
+
+#[tokio::test]
+async fn match_orders_cancels_the_bid_of_a_buyer_whose_kyc_lapsed() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    fixture.grant_role(payer, Role::KycProvider).await;
+    let compliance = ComplianceConfig {
+        kyc_required: true,
+        ..ComplianceConfig::default()
+    };
+    let asset = fixture.create_restricted_asset(false, compliance).await;
+    let market = open_market_for(&mut fixture, asset).await;
+    let seller = new_trader(&mut fixture, &market).await;
+    let lapsing = new_trader(&mut fixture, &market).await;
+    let buyer = new_trader(&mut fixture, &market).await;
+
+    let now = fixture.now().await;
+    for (trader, expires_at) in [(&lapsing, now + 60), (&buyer, now + 365 * 24 * 60 * 60)] {
+        let wallet = trader.keypair.pubkey();
+        let instruction = ix(
+            realstack::accounts::SetInvestorRecord {
+                provider: payer,
+                real_token: fixture.real_token,
+                roles: fixture.roles,
+                investor_record: investor_record_of(&fixture, &wallet),
+                system_program: anchor_lang::system_program::ID,
+            },
+            realstack::instruction::SetInvestorRecord {
+                wallet,
+                kyc_status: KycStatus::Verified,
+                jurisdiction: *b"US",
+                accredited: false,
+                expires_at,
+            },
+        );
+        fixture.process(&[instruction], &[]).await.unwrap();
+    }
+    place(&mut fixture, &market, &seller, OrderSide::Ask, 100, 40).await;
+    for (trader, price) in [(&lapsing, 110), (&buyer, 105)] {
+        let mut instruction = place_ix(&fixture, &market, trader, OrderSide::Bid, price, 40);
+        instruction.accounts[8].pubkey = investor_record_of(&fixture, &trader.keypair.pubkey());
+        fixture.process(&[instruction], &[&trader.keypair]).await.unwrap();
+    }
+
+    // The best bid's KYC lapses; matching refunds it instead of failing
+    fixture.warp_forward(120).await;
+    let instruction = match_ix(&fixture, &market, &lapsing, &seller);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&lapsing.payments).await, TRADER_PAYMENT);
+    assert_eq!(fixture.token_balance(&lapsing.shares).await, TRADER_SHARES);
+    let book: OrderBook = fixture.get(&market.order_book).await;
+    assert_eq!(book.bids.len(), 1);
+    assert_eq!(book.asks[0].quantity, 40);
+
+    // The next bid fills
+    let instruction = match_ix(&fixture, &market, &buyer, &seller);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&buyer.shares).await, TRADER_SHARES + 40);
+    let book: OrderBook = fixture.get(&market.order_book).await;
+    assert!(book.bids.is_empty() && book.asks.is_empty());
+}
+
+#[tokio::test]
+async fn match_orders_routes_the_staker_share_of_the_fee() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let pool = fixture.create_stake_pool_paying(market.payment_mint, 4_000, 0).await;
+    fixture.new_staker(&pool, 1_000).await;
+    let seller = new_trader(&mut fixture, &market).await;
+    let buyer = new_trader(&mut fixture, &market).await;
+
+    place(&mut fixture, &market, &seller, OrderSide::Ask, 100, 40).await;
+    place(&mut fixture, &market, &buyer, OrderSide::Bid, 100, 40).await;
+    let instruction = match_ix(&fixture, &market, &buyer, &seller);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    // 40% of the 10 fee goes to stakers without the fee recipient acting
+    let fee = 40 * 100 * 25 / 10_000;
+    let staker_fee = fee * 4_000 / 10_000;
+    assert_eq!(fixture.token_balance(&pool.reward_vault).await, staker_fee);
+    assert_eq!(fixture.token_balance(&market.fee_account).await, fee - staker_fee);
+    let state: StakePool = fixture.get(&pool.stake_pool).await;
+    assert_eq!(state.total_rewards_distributed, staker_fee);
+}
+
+#[tokio::test]
+async fn match_orders_rejects_invalid_settlement() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let seller = new_trader(&mut fixture, &market).await;
+    let buyer = new_trader(&mut fixture, &market).await;
+
+    place(&mut fixture, &market, &seller, OrderSide::Ask, 100, 10).await;
+    place(&mut fixture, &market, &buyer, OrderSide::Bid, 100, 10).await;
+
+    // Settlement accounts swapped between the traders
+    let instruction = match_ix(&fixture, &market, &seller, &buyer);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+
+    fixture.set_tradable(market.asset.asset_token, false).await;
+    let instruction = match_ix(&fixture, &market, &buyer, &seller);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetNotTradable);
+
+    fixture.set_tradable(market.asset.asset_token, true).await;
+    fixture.pause(PauseFlags { trading: true, ..no_pause() }).await;
+    let instruction = match_ix(&fixture, &market, &buyer, &seller);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::TransfersPaused);
+}
+
+#[tokio::test]
+async fn orders_of_a_wound_down_asset_unwind_to_their_owners() {
+    let mut fixture = TestFixture::new().await;
+    let market = open_market(&mut fixture).await;
+    let alice = new_trader(&mut fixture, &market).await;
+    let bob = new_trader(&mut fixture, &market).await;
+    place(&mut fixture, &market, &alice, OrderSide::Ask, 120, 5).await;
+    place(&mut fixture, &market, &bob, OrderSide::Bid, 90, 10).await;
+
+    // Live books only unwind through their owners
+    let instruction = unwind_ix(&fixture, &market, &alice, OrderSide::Ask, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetNotRedeeming);
+
+    fixture.burn_asset(&market.asset, 1_000).await;
+
+    // The escrow only goes back to the order's owner
+    let instruction = unwind_ix(&fixture, &market, &bob, OrderSide::Ask, 0);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+
+    let instruction = unwind_ix(&fixture, &market, &alice, OrderSide::Ask, 0);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = unwind_ix(&fixture, &market, &bob, OrderSide::Bid, 1);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&alice.shares).await, TRADER_SHARES);
+    assert_eq!(fixture.token_balance(&bob.payments).await, TRADER_PAYMENT);
+    assert_eq!(fixture.token_balance(&market.base_vault).await, 0);
+    assert_eq!(fixture.token_balance(&market.quote_vault).await, 0);
+    let book: OrderBook = fixture.get(&market.order_book).await;
+    assert!(book.bids.is_empty() && book.asks.is_empty());
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use realstack::{ActionAccount, AdminAction, AdminActionKind, Multisig, RealStackError, Role, MAX_ACTION_LIFETIME};
+use solana_program_test::tokio;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const LIFETIME: i64 = 24 * 60 * 60;
+const NEW_FEE_BPS: u16 = 50;
+
+/// A 2-of-3 multisig holding the fee manager role
+struct MultisigFixture {
+    multisig: Pubkey,
+    signer: Pubkey,
+    owners: Vec<Keypair>,
+}
+
+fn create_multisig_ix(payer: &Pubkey, multisig: &Pubkey, owners: Vec<Pubkey>, threshold: u8, action_lifetime: i64) -> Instruction {
+    ix(
+        realstack::accounts::CreateMultisig {
+            payer: *payer,
+            multisig: *multisig,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::CreateMultisig {
+            owners,
+            threshold,
+            action_lifetime,
+        },
+    )
+}
+
+fn action_address(multisig: &Pubkey, index: u64) -> Pubkey {
+    pda(&[b"admin_action", multisig.as_ref(), &index.to_le_bytes()])
+}
+
+/// The wrapped fee update, signed by the multisig signer PDA
+fn fee_update(fixture: &TestFixture, ms: &MultisigFixture) -> (Vec<ActionAccount>, Vec<u8>) {
+    let accounts = realstack::accounts::UpdateFeeConfig {
+        authority: ms.signer,
+        real_token: fixture.real_token,
+        roles: fixture.roles,
+    }
+    .to_account_metas(None)
+    .into_iter()
+    .map(|meta| ActionAccount {
+        pubkey: meta.pubkey,
+        is_writable: meta.is_writable,
+    })
+    .collect();
+    let data = realstack::instruction::UpdateFeeConfig {
+        transaction_fee_bps: NEW_FEE_BPS,
+        fee_recipient: fixture.payer_key(),
+        fees_enabled: true,
+    }
+    .data();
+    (accounts, data)
+}
+
+fn propose_ix(ms: &MultisigFixture, proposer: &Pubkey, index: u64, accounts: Vec<ActionAccount>, data: Vec<u8>) -> Instruction {
+    ix(
+        realstack::accounts::ProposeAdminAction {
+            proposer: *proposer,
+            multisig: ms.multisig,
+            admin_action: action_address(&ms.multisig, index),
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::ProposeAdminAction { accounts, data },
+    )
+}
+
+fn approve_ix(ms: &MultisigFixture, owner: &Pubkey, index: u64) -> Instruction {
+    ix(
+        realstack::accounts::ApproveAdminAction {
+            owner: *owner,
+            multisig: ms.multisig,
+            admin_action: action_address(&ms.multisig, index),
+        },
+        realstack::instruction::ApproveAdminAction {},
+    )
+}
+
+fn execute_ix(ms: &MultisigFixture, executor: &Pubkey, index: u64, wrapped: &[ActionAccount]) -> Instruction {
+    let mut instruction = ix(
+        realstack::accounts::ExecuteAdminAction {
+            executor: *executor,
+            multisig: ms.multisig,
+            admin_action: action_address(&ms.multisig, index),
+            multisig_signer: ms.signer,
+            realstack_program: realstack::ID,
+        },
+        realstack::instruction::ExecuteAdminAction {},
+    );
+    instruction.accounts.extend(wrapped.iter().map(|account| {
+        if account.is_writable {
+            AccountMeta::new(account.pubkey, false)
+        } else {
+            AccountMeta::new_readonly(account.pubkey, false)
+        }
+    }));
+    instruction
+}
+
+/// Create a 2-of-3 multisig and make its signer a fee manager
+async fn create_multisig(fixture: &mut TestFixture) -> MultisigFixture {
+    let payer = fixture.payer_key();
+    let multisig = Keypair::new();
+    let mut owners = Vec::new();
+    for _ in 0..3 {
+        owners.push(fixture.new_actor().await);
+    }
+
+    let owner_keys = owners.iter().map(|owner| owner.pubkey()).collect();
+    let instruction = create_multisig_ix(&payer, &multisig.pubkey(), owner_keys, 2, LIFETIME);
+    fixture.process(&[instruction], &[&multisig]).await.unwrap();
+
+    let signer = pda(&[b"multisig_signer", multisig.pubkey().as_ref()]);
+    fixture.grant_role(signer, Role::FeeManager).await;
+    MultisigFixture {
+        multisig: multisig.pubkey(),
+        signer,
+        owners,
+    }
+}
+
+#[tokio::test]
+async fn create_multisig_validates_parameters() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let alice = Pubkey::new_unique();
+    let bob = Pubkey::new_unique();
+    let multisig = Keypair::new();
+
+    let invalid = [
+        (vec![], 1, LIFETIME),
+        (vec![alice, alice], 1, LIFETIME),
+        (vec![alice, bob], 0, LIFETIME),
+        (vec![alice, bob], 3, LIFETIME),
+        (vec![alice, bob], 2, 0),
+        (vec![alice, bob], 2, MAX_ACTION_LIFETIME + 1),
+    ];
+    for (owners, threshold, lifetime) in invalid {
+        let instruction = create_multisig_ix(&payer, &multisig.pubkey(), owners, threshold, lifetime);
+        let result = fixture.process(&[instruction], &[&multisig]).await;
+        assert_error(result, RealStackError::InvalidParameters);
+    }
+
+    let instruction = create_multisig_ix(&payer, &multisig.pubkey(), vec![alice, bob], 2, LIFETIME);
+    fixture.process(&[instruction], &[&multisig]).await.unwrap();
+    let state: Multisig = fixture.get(&multisig.pubkey()).await;
+    assert_eq!(state.owners, vec![alice, bob]);
+    assert_eq!(state.threshold, 2);
+    assert_eq!(state.action_lifetime, LIFETIME);
+    assert_eq!(state.action_count, 0);
+}
+
+#[tokio::test]
+async fn approved_action_executes_as_multisig_signer() {
+    let mut fixture = TestFixture::new().await;
+    let ms = create_multisig(&mut fixture).await;
+    let [alice, bob, carol] = [0, 1, 2].map(|i| clone_keypair(&ms.owners[i]));
+    let (accounts, data) = fee_update(&fixture, &ms);
+
+    let instruction = propose_ix(&ms, &alice.pubkey(), 0, accounts.clone(), data);
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    let action: AdminAction = fixture.get(&action_address(&ms.multisig, 0)).await;
+    assert_eq!(action.kind, AdminActionKind::UpdateFeeConfig);
+    assert_eq!(action.approvals, vec![alice.pubkey()]);
+
+    // The proposal counts as the proposer's approval
+    let instruction = approve_ix(&ms, &alice.pubkey(), 0);
+    let result = fixture.process(&[instruction], &[&alice]).await;
+    assert_error(result, RealStackError::ActionAlreadyApproved);
+
+    let instruction = execute_ix(&ms, &carol.pubkey(), 0, &accounts);
+    let result = fixture.process(&[instruction], &[&carol]).await;
+    assert_error(result, RealStackError::InsufficientApprovals);
+
+    let instruction = approve_ix(&ms, &bob.pubkey(), 0);
+    fixture.process(&[instruction], &[&bob]).await.unwrap();
+    let instruction = execute_ix(&ms, &carol.pubkey(), 0, &accounts);
+    fixture.process(&[instruction], &[&carol]).await.unwrap();
+    assert_eq!(fixture.real_token_state().await.fee_config.transaction_fee_bps, NEW_FEE_BPS);
+
+    let instruction = execute_ix(&ms, &carol.pubkey(), 0, &accounts);
+    let result = fixture.process(&[instruction], &[&carol]).await;
+    assert_error(result, RealStackError::ActionAlreadyExecuted);
+
+    let instruction = approve_ix(&ms, &carol.pubkey(), 0);
+    let result = fixture.process(&[instruction], &[&carol]).await;
+    assert_error(result, RealStackError::ActionAlreadyExecuted);
+}
+
+#[tokio::test]
+async fn propose_rejects_outsiders_and_unsupported_instructions() {
+    let mut fixture = TestFixture::new().await;
+    let ms = create_multisig(&mut fixture).await;
+    let alice = clone_keypair(&ms.owners[0]);
+    let intruder = fixture.new_actor().await;
+    let (accounts, data) = fee_update(&fixture, &ms);
+
+    let instruction = propose_ix(&ms, &intruder.pubkey(), 0, accounts.clone(), data);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // Role management is not an action the multisig can wrap
+    let data = realstack::instruction::GrantRole {
+        member: intruder.pubkey(),
+        role: Role::FeeManager,
+    }
+    .data();
+    let instruction = propose_ix(&ms, &alice.pubkey(), 0, accounts, data);
+    let result = fixture.process(&[instruction], &[&alice]).await;
+    assert_error(result, RealStackError::UnsupportedAdminAction);
+
+    let instruction = approve_ix(&ms, &intruder.pubkey(), 0);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_failed(result);
+}
+
+#[tokio::test]
+async fn execute_rejects_expired_or_mismatched_actions() {
+    let mut fixture = TestFixture::new().await;
+    let ms = create_multisig(&mut fixture).await;
+    let [alice, bob] = [0, 1].map(|i| clone_keypair(&ms.owners[i]));
+    let (accounts, data) = fee_update(&fixture, &ms);
+
+    let instruction = propose_ix(&ms, &alice.pubkey(), 0, accounts.clone(), data);
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    let instruction = approve_ix(&ms, &bob.pubkey(), 0);
+    fixture.process(&[instruction], &[&bob]).await.unwrap();
+
+    // Remaining accounts must match the proposal exactly
+    let instruction = execute_ix(&ms, &alice.pubkey(), 0, &accounts[..1]);
+    let result = fixture.process(&[instruction], &[&alice]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let mut swapped = accounts.clone();
+    swapped.swap(1, 2);
+    let instruction = execute_ix(&ms, &alice.pubkey(), 0, &swapped);
+    let result = fixture.process(&[instruction], &[&alice]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    fixture.warp_forward(LIFETIME).await;
+    let instruction = execute_ix(&ms, &alice.pubkey(), 0, &accounts);
+    let result = fixture.process(&[instruction], &[&alice]).await;
+    assert_error(result, RealStackError::ActionExpired);
+}
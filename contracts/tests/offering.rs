@@ -0,0 +1,708 @@
+mod common;
+
+use common::*;
+use realstack::{AssetToken, ComplianceConfig, HolderRecord, KycStatus, Offering, OfferingPurchase, OfferingStatus, PauseFlags, RealStackError, Role, ShareLock};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const DURATION: i64 = 3 * 24 * 60 * 60;
+const MIN_RAISE: u64 = 50_000;
+const MAX_RAISE: u64 = 100_000;
+const MIN_PURCHASE: u64 = 10;
+const WALLET_LIMIT: u64 = 600;
+const LOCKUP: i64 = 30 * 24 * 60 * 60;
+
+/// Accounts of an offering
+struct OfferingFixture {
+    asset: AssetFixture,
+    offering: Pubkey,
+    share_vault: Pubkey,
+    payment_vault: Pubkey,
+    payment_mint: Pubkey,
+}
+
+/// Offering parameters starting now and running for `DURATION`
+fn params(now: i64) -> realstack::instruction::CreateOffering {
+    realstack::instruction::CreateOffering {
+        start_time: now,
+        end_time: now + DURATION,
+        min_raise: MIN_RAISE,
+        max_raise: MAX_RAISE,
+        min_purchase_shares: MIN_PURCHASE,
+        max_shares_per_wallet: WALLET_LIMIT,
+    }
+}
+
+fn offering_pdas(asset_token: &Pubkey, offering_id: u64) -> (Pubkey, Pubkey, Pubkey) {
+    let offering = pda(&[b"offering", asset_token.as_ref(), &offering_id.to_le_bytes()]);
+    let share_vault = pda(&[b"offering_shares", offering.as_ref()]);
+    let payment_vault = pda(&[b"offering_payments", offering.as_ref()]);
+    (offering, share_vault, payment_vault)
+}
+
+fn create_offering_ix(
+    fixture: &TestFixture,
+    authority: &Pubkey,
+    asset: &AssetFixture,
+    payment_mint: Pubkey,
+    params: realstack::instruction::CreateOffering,
+) -> Instruction {
+    create_nth_offering_ix(fixture, authority, asset, 0, payment_mint, params)
+}
+
+fn create_nth_offering_ix(
+    fixture: &TestFixture,
+    authority: &Pubkey,
+    asset: &AssetFixture,
+    offering_id: u64,
+    payment_mint: Pubkey,
+    params: realstack::instruction::CreateOffering,
+) -> Instruction {
+    let (offering, share_vault, payment_vault) = offering_pdas(&asset.asset_token, offering_id);
+    ix(
+        realstack::accounts::CreateOffering {
+            authority: *authority,
+            asset_token: asset.asset_token,
+            roles: fixture.roles,
+            real_token: fixture.real_token,
+            offering,
+            share_mint: asset.share_mint,
+            freeze_authority: freeze_authority_of(&asset.asset_token),
+            payment_mint,
+            issuer_share_account: asset.issuer_shares,
+            share_vault,
+            payment_vault,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: anchor_lang::solana_program::sysvar::rent::ID,
+        },
+        params,
+    )
+}
+
+fn buy_ix(fixture: &TestFixture, setup: &OfferingFixture, buyer: &Pubkey, buyer_payment_account: Pubkey, shares: u64) -> Instruction {
+    buy_as_investor_ix(fixture, setup, buyer, buyer_payment_account, shares, None)
+}
+
+/// A purchase presenting the buyer's investor record
+fn buy_as_investor_ix(
+    fixture: &TestFixture,
+    setup: &OfferingFixture,
+    buyer: &Pubkey,
+    buyer_payment_account: Pubkey,
+    shares: u64,
+    investor_record: Option<Pubkey>,
+) -> Instruction {
+    ix(
+        realstack::accounts::BuyShares {
+            buyer: *buyer,
+            offering: setup.offering,
+            asset_token: setup.asset.asset_token,
+            real_token: fixture.real_token,
+            purchase: purchase_of(setup, buyer),
+            buyer_payment_account,
+            payment_vault: setup.payment_vault,
+            investor_record,
+            holder_record: holder_record_of(&setup.asset.asset_token, buyer),
+            share_lock: share_lock_of(&setup.asset.asset_token, buyer),
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::BuyShares { shares },
+    )
+}
+
+fn finalize_ix(
+    setup: &OfferingFixture,
+    payer: &Pubkey,
+    issuer_share_account: Pubkey,
+    issuer_payment_account: Pubkey,
+) -> Instruction {
+    ix(
+        realstack::accounts::FinalizeOffering {
+            payer: *payer,
+            offering: setup.offering,
+            share_vault: setup.share_vault,
+            payment_vault: setup.payment_vault,
+            issuer_share_account,
+            issuer_payment_account,
+            share_mint: setup.asset.share_mint,
+            freeze_authority: freeze_authority_of(&setup.asset.asset_token),
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::FinalizeOffering {},
+    )
+}
+
+fn claim_ix(setup: &OfferingFixture, buyer: &Pubkey, buyer_share_account: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ClaimOfferingShares {
+            buyer: *buyer,
+            offering: setup.offering,
+            asset_token: setup.asset.asset_token,
+            purchase: purchase_of(setup, buyer),
+            share_vault: setup.share_vault,
+            buyer_share_account,
+            share_mint: setup.asset.share_mint,
+            freeze_authority: freeze_authority_of(&setup.asset.asset_token),
+            share_lock: share_lock_of(&setup.asset.asset_token, buyer),
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::ClaimOfferingShares {},
+    )
+}
+
+fn refund_ix(setup: &OfferingFixture, buyer: &Pubkey, buyer_payment_account: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::RefundPurchase {
+            buyer: *buyer,
+            offering: setup.offering,
+            purchase: purchase_of(setup, buyer),
+            payment_vault: setup.payment_vault,
+            buyer_payment_account,
+            share_lock: share_lock_of(&setup.asset.asset_token, buyer),
+            holder_record: holder_record_of(&setup.asset.asset_token, buyer),
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::RefundPurchase {},
+    )
+}
+
+/// Transfer shares between `(wallet, share account)` pairs
+fn transfer_ix(
+    fixture: &TestFixture,
+    setup: &OfferingFixture,
+    (sender, sender_share_account): (Pubkey, Pubkey),
+    (recipient, recipient_share_account): (Pubkey, Pubkey),
+    amount: u64,
+) -> Instruction {
+    let asset_token = setup.asset.asset_token;
+    ix(
+        realstack::accounts::TransferShares {
+            sender,
+            asset_token,
+            real_token: fixture.real_token,
+            sender_share_account,
+            sender_holder_record: holder_record_of(&asset_token, &sender),
+            recipient_share_account,
+            recipient_investor_record: None,
+            recipient_holder_record: holder_record_of(&asset_token, &recipient),
+            share_mint: setup.asset.share_mint,
+            freeze_authority: freeze_authority_of(&asset_token),
+            token_program: anchor_spl::token::ID,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::TransferShares { amount },
+    )
+}
+
+fn purchase_of(setup: &OfferingFixture, buyer: &Pubkey) -> Pubkey {
+    pda(&[b"offering_purchase", setup.offering.as_ref(), buyer.as_ref()])
+}
+
+/// Create and verify an asset, then open an offering for it
+async fn open_offering(fixture: &mut TestFixture) -> OfferingFixture {
+    let asset = fixture.create_asset(false).await;
+    open_offering_for(fixture, asset).await
+}
+
+/// Verify an asset and open an offering for it
+async fn open_offering_for(fixture: &mut TestFixture, asset: AssetFixture) -> OfferingFixture {
+    fixture.verify_asset(asset.asset_token).await;
+    let payment_mint = fixture.create_mint(6).await;
+    let now = fixture.now().await;
+
+    let instruction = create_offering_ix(fixture, &fixture.payer_key(), &asset, payment_mint, params(now));
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let (offering, share_vault, payment_vault) = offering_pdas(&asset.asset_token, 0);
+    OfferingFixture {
+        asset,
+        offering,
+        share_vault,
+        payment_vault,
+        payment_mint,
+    }
+}
+
+/// A funded buyer holding `balance` of the payment token
+async fn new_buyer(fixture: &mut TestFixture, setup: &OfferingFixture, balance: u64) -> (Keypair, Pubkey) {
+    let buyer = fixture.new_actor().await;
+    let account = fixture.create_funded_token_account(&setup.payment_mint, &buyer.pubkey(), balance).await;
+    (buyer, account)
+}
+
+/// Buy `shares` as a fresh buyer
+async fn buy(fixture: &mut TestFixture, setup: &OfferingFixture, shares: u64) -> (Keypair, Pubkey) {
+    let (buyer, account) = new_buyer(fixture, setup, shares * ASSET_SHARE_PRICE).await;
+    let instruction = buy_ix(fixture, setup, &buyer.pubkey(), account, shares);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    (buyer, account)
+}
+
+/// Finalize into fresh issuer accounts, returning the issuer payment account
+async fn finalize(fixture: &mut TestFixture, setup: &OfferingFixture) -> Pubkey {
+    let payer = fixture.payer_key();
+    let issuer_payments = fixture.create_token_account(&setup.payment_mint, &payer).await;
+    let instruction = finalize_ix(setup, &payer, setup.asset.issuer_shares, issuer_payments);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    issuer_payments
+}
+
+#[tokio::test]
+async fn create_offering_escrows_shares() {
+    let mut fixture = TestFixture::new().await;
+    let setup = open_offering(&mut fixture).await;
+
+    let offering: Offering = fixture.get(&setup.offering).await;
+    assert_eq!(offering.share_price, ASSET_SHARE_PRICE);
+    assert_eq!(offering.shares_offered, MAX_RAISE / ASSET_SHARE_PRICE);
+    assert!(offering.status == OfferingStatus::Active);
+    assert_eq!(fixture.token_balance(&setup.share_vault).await, MAX_RAISE / ASSET_SHARE_PRICE);
+    assert_eq!(
+        fixture.token_balance(&setup.asset.issuer_shares).await,
+        ASSET_TOTAL_SHARES - MAX_RAISE / ASSET_SHARE_PRICE
+    );
+}
+
+#[tokio::test]
+async fn assets_raise_again_after_an_offering_closes() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let setup = open_offering(&mut fixture).await;
+
+    // A failed raise can be retried under the next offering id
+    fixture.warp_forward(DURATION).await;
+    finalize(&mut fixture, &setup).await;
+    let offering: Offering = fixture.get(&setup.offering).await;
+    assert!(offering.status == OfferingStatus::Failed);
+    let now = fixture.now().await;
+    let instruction = create_offering_ix(&fixture, &payer, &setup.asset, setup.payment_mint, params(now));
+    assert!(fixture.process(&[instruction], &[]).await.is_err());
+    let instruction = create_nth_offering_ix(&fixture, &payer, &setup.asset, 1, setup.payment_mint, params(now));
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let (retry, share_vault, _) = offering_pdas(&setup.asset.asset_token, 1);
+    let offering: Offering = fixture.get(&retry).await;
+    assert_eq!(offering.offering_id, 1);
+    assert!(offering.status == OfferingStatus::Active);
+    assert_eq!(fixture.token_balance(&share_vault).await, MAX_RAISE / ASSET_SHARE_PRICE);
+    let asset_state: AssetToken = fixture.get(&setup.asset.asset_token).await;
+    assert_eq!(asset_state.offering_count, 2);
+}
+
+#[tokio::test]
+async fn create_offering_validates_asset() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let payment_mint = fixture.create_mint(6).await;
+    let now = fixture.now().await;
+
+    let asset = fixture.create_asset(false).await;
+    let instruction = create_offering_ix(&fixture, &payer, &asset, payment_mint, params(now));
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetNotVerified);
+
+    fixture.verify_asset(asset.asset_token).await;
+    let instruction = create_offering_ix(&fixture, &intruder.pubkey(), &asset, payment_mint, params(now));
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // An asset priced at zero cannot be offered
+    let free_asset = Keypair::new();
+    let (free_mint, free_shares) = fixture.create_share_mint(&free_asset.pubkey()).await;
+    let mut instruction = fixture.create_asset_ix(&payer, free_asset.pubkey(), (free_mint, free_shares), false);
+    instruction.data = anchor_lang::InstructionData::data(&realstack::instruction::CreateAssetToken {
+        name: "Free".to_string(),
+        symbol: "FREE".to_string(),
+        category: "real-estate".to_string(),
+        description: "Zero-priced asset.".to_string(),
+        uri: String::new(),
+        valuation: 0,
+        total_shares: ASSET_TOTAL_SHARES,
+        share_price: 0,
+        can_mint_additional: false,
+        compliance: ComplianceConfig::default(),
+    });
+    fixture.process(&[instruction], &[&free_asset]).await.unwrap();
+    fixture.verify_asset(free_asset.pubkey()).await;
+    let free = AssetFixture {
+        asset_token: free_asset.pubkey(),
+        share_mint: free_mint,
+        issuer_shares: free_shares,
+    };
+    let instruction = create_offering_ix(&fixture, &payer, &free, payment_mint, params(now));
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::SharePriceTooLow);
+}
+
+#[tokio::test]
+async fn create_offering_validates_parameters() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let asset = fixture.create_asset(false).await;
+    fixture.verify_asset(asset.asset_token).await;
+    let payment_mint = fixture.create_mint(6).await;
+    let now = fixture.now().await;
+
+    let invalid = [
+        realstack::instruction::CreateOffering { end_time: now - 1, ..params(now) },
+        realstack::instruction::CreateOffering { start_time: now - 2 * DURATION, end_time: now - DURATION, ..params(now) },
+        realstack::instruction::CreateOffering { min_raise: 0, ..params(now) },
+        realstack::instruction::CreateOffering { min_raise: MAX_RAISE + 1, ..params(now) },
+        realstack::instruction::CreateOffering { min_purchase_shares: 0, ..params(now) },
+        realstack::instruction::CreateOffering { min_purchase_shares: WALLET_LIMIT + 1, ..params(now) },
+        realstack::instruction::CreateOffering { min_raise: 1, max_raise: ASSET_SHARE_PRICE - 1, ..params(now) },
+    ];
+    for params in invalid {
+        let instruction = create_offering_ix(&fixture, &payer, &asset, payment_mint, params);
+        let result = fixture.process(&[instruction], &[]).await;
+        assert_error(result, RealStackError::InvalidParameters);
+    }
+
+    let oversized = realstack::instruction::CreateOffering {
+        max_raise: (ASSET_TOTAL_SHARES + 1) * ASSET_SHARE_PRICE,
+        ..params(now)
+    };
+    let instruction = create_offering_ix(&fixture, &payer, &asset, payment_mint, oversized);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::TotalSharesExceedsMaximum);
+
+    fixture.pause(PauseFlags { asset_creation: true, ..no_pause() }).await;
+    let instruction = create_offering_ix(&fixture, &payer, &asset, payment_mint, params(now));
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::OperationPaused);
+}
+
+#[tokio::test]
+async fn create_offering_rejects_burned_asset() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let asset = fixture.create_asset(false).await;
+    fixture.verify_asset(asset.asset_token).await;
+    let payment_mint = fixture.create_mint(6).await;
+    let proceeds_source = fixture.create_funded_token_account(&payment_mint, &payer, 1_000).await;
+
+    let redemption = pda(&[b"redemption", asset.asset_token.as_ref()]);
+    let proceeds_vault = pda(&[b"redemption_vault", redemption.as_ref()]);
+    let burn = ix(
+        realstack::accounts::BurnAssetToken {
+            authority: payer,
+            asset_token: asset.asset_token,
+            roles: fixture.roles,
+            share_mint: asset.share_mint,
+            redemption,
+            proceeds_mint: payment_mint,
+            proceeds_vault,
+            proceeds_source,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: anchor_lang::solana_program::sysvar::rent::ID,
+        },
+        realstack::instruction::BurnAssetToken { proceeds_amount: 1_000 },
+    );
+    fixture.process(&[burn], &[]).await.unwrap();
+
+    let now = fixture.now().await;
+    let instruction = create_offering_ix(&fixture, &payer, &asset, payment_mint, params(now));
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::AssetBurned);
+}
+
+#[tokio::test]
+async fn buy_shares_enforces_limits() {
+    let mut fixture = TestFixture::new().await;
+    let setup = open_offering(&mut fixture).await;
+    let (buyer, account) = new_buyer(&mut fixture, &setup, 1_000_000).await;
+
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, MIN_PURCHASE - 1);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::PurchaseBelowMinimum);
+
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, 400);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    let purchase: OfferingPurchase = fixture.get(&purchase_of(&setup, &buyer.pubkey())).await;
+    assert_eq!(purchase.shares_purchased, 400);
+    assert_eq!(purchase.amount_paid, 400 * ASSET_SHARE_PRICE);
+    assert_eq!(fixture.token_balance(&setup.payment_vault).await, 400 * ASSET_SHARE_PRICE);
+
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, WALLET_LIMIT - 400 + 1);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::WalletLimitExceeded);
+
+    // 1_000 shares on offer: 400 + 550 sold, 60 more does not fit
+    buy(&mut fixture, &setup, 550).await;
+    let (late, late_account) = new_buyer(&mut fixture, &setup, 1_000_000).await;
+    let instruction = buy_ix(&fixture, &setup, &late.pubkey(), late_account, 60);
+    let result = fixture.process(&[instruction], &[&late]).await;
+    assert_error(result, RealStackError::OfferingSoldOut);
+
+    // Paying with the wrong token
+    let share_account = fixture.create_token_account(&setup.asset.share_mint, &late.pubkey()).await;
+    let instruction = buy_ix(&fixture, &setup, &late.pubkey(), share_account, MIN_PURCHASE);
+    let result = fixture.process(&[instruction], &[&late]).await;
+    assert_error(result, RealStackError::InvalidTokenMint);
+
+    // Buying against another asset
+    let other = fixture.create_asset(false).await;
+    let mut instruction = buy_ix(&fixture, &setup, &late.pubkey(), late_account, MIN_PURCHASE);
+    instruction.accounts[2].pubkey = other.asset_token;
+    let result = fixture.process(&[instruction], &[&late]).await;
+    assert_error(result, RealStackError::AssetNotFound);
+}
+
+#[tokio::test]
+async fn buy_shares_respects_window_and_pause() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let asset = fixture.create_asset(false).await;
+    fixture.verify_asset(asset.asset_token).await;
+    let payment_mint = fixture.create_mint(6).await;
+    let now = fixture.now().await;
+
+    let later = realstack::instruction::CreateOffering {
+        start_time: now + DURATION,
+        end_time: now + 2 * DURATION,
+        ..params(now)
+    };
+    let instruction = create_offering_ix(&fixture, &payer, &asset, payment_mint, later);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let (offering, share_vault, payment_vault) = offering_pdas(&asset.asset_token, 0);
+    let setup = OfferingFixture {
+        asset,
+        offering,
+        share_vault,
+        payment_vault,
+        payment_mint,
+    };
+    let (buyer, account) = new_buyer(&mut fixture, &setup, 1_000_000).await;
+
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, MIN_PURCHASE);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::OfferingNotStarted);
+
+    fixture.warp_forward(DURATION).await;
+    fixture.pause(PauseFlags { trading: true, ..no_pause() }).await;
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, MIN_PURCHASE);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::TransfersPaused);
+
+    fixture.warp_forward(DURATION).await;
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, MIN_PURCHASE);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::OfferingEnded);
+}
+
+#[tokio::test]
+async fn successful_offering_releases_proceeds_and_shares() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let setup = open_offering(&mut fixture).await;
+    let (buyer, buyer_payments) = buy(&mut fixture, &setup, 600).await;
+
+    let issuer_payments = fixture.create_token_account(&setup.payment_mint, &payer).await;
+    let instruction = finalize_ix(&setup, &payer, setup.asset.issuer_shares, issuer_payments);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::OfferingNotEnded);
+
+    let buyer_shares = fixture.create_token_account(&setup.asset.share_mint, &buyer.pubkey()).await;
+    let instruction = claim_ix(&setup, &buyer.pubkey(), buyer_shares);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::OfferingNotSucceeded);
+
+    fixture.warp_forward(DURATION).await;
+    let issuer_payments = finalize(&mut fixture, &setup).await;
+    let offering: Offering = fixture.get(&setup.offering).await;
+    assert!(offering.status == OfferingStatus::Succeeded);
+    assert_eq!(fixture.token_balance(&issuer_payments).await, 600 * ASSET_SHARE_PRICE);
+    assert_eq!(fixture.token_balance(&setup.asset.issuer_shares).await, ASSET_TOTAL_SHARES - 600);
+
+    let instruction = finalize_ix(&setup, &payer, setup.asset.issuer_shares, issuer_payments);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::OfferingAlreadyFinalized);
+
+    let instruction = claim_ix(&setup, &buyer.pubkey(), buyer_shares);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    assert_eq!(fixture.token_balance(&buyer_shares).await, 600);
+
+    let instruction = claim_ix(&setup, &buyer.pubkey(), buyer_shares);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::PurchaseAlreadySettled);
+
+    let instruction = refund_ix(&setup, &buyer.pubkey(), buyer_payments);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::OfferingNotFailed);
+
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), buyer_payments, MIN_PURCHASE);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::OfferingNotActive);
+}
+
+#[tokio::test]
+async fn failed_offering_refunds_buyers() {
+    let mut fixture = TestFixture::new().await;
+    let setup = open_offering(&mut fixture).await;
+    let (buyer, buyer_payments) = buy(&mut fixture, &setup, 100).await;
+    assert_eq!(fixture.token_balance(&buyer_payments).await, 0);
+
+    fixture.warp_forward(DURATION).await;
+    finalize(&mut fixture, &setup).await;
+    let offering: Offering = fixture.get(&setup.offering).await;
+    assert!(offering.status == OfferingStatus::Failed);
+    assert_eq!(fixture.token_balance(&setup.asset.issuer_shares).await, ASSET_TOTAL_SHARES);
+
+    let instruction = refund_ix(&setup, &buyer.pubkey(), buyer_payments);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    assert_eq!(fixture.token_balance(&buyer_payments).await, 100 * ASSET_SHARE_PRICE);
+    let share_lock: ShareLock = fixture.get(&share_lock_of(&setup.asset.asset_token, &buyer.pubkey())).await;
+    assert_eq!(share_lock.locked_amount, 0);
+    let record: HolderRecord = fixture.get(&holder_record_of(&setup.asset.asset_token, &buyer.pubkey())).await;
+    assert_eq!(record.balance, 0);
+
+    let instruction = refund_ix(&setup, &buyer.pubkey(), buyer_payments);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::PurchaseAlreadySettled);
+}
+
+#[tokio::test]
+async fn purchases_count_shares_already_held_toward_the_holding_cap() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let compliance = ComplianceConfig {
+        max_holding_bps: 500,
+        ..ComplianceConfig::default()
+    };
+    let asset = fixture.create_restricted_asset(false, compliance).await;
+    fixture.set_tradable(asset.asset_token, true).await;
+    let setup = open_offering_for(&mut fixture, asset).await;
+    let cap = ASSET_TOTAL_SHARES * 500 / 10_000;
+    let (buyer, account) = new_buyer(&mut fixture, &setup, 1_000_000).await;
+    let buyer_shares = fixture.create_token_account(&setup.asset.share_mint, &buyer.pubkey()).await;
+    let instruction = transfer_ix(&fixture, &setup, (payer, setup.asset.issuer_shares), (buyer.pubkey(), buyer_shares), 100);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, cap - 100 + 1);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::HoldingCapExceeded);
+
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, cap - 100);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    let record: HolderRecord = fixture.get(&holder_record_of(&setup.asset.asset_token, &buyer.pubkey())).await;
+    assert_eq!(record.balance, cap);
+}
+
+#[tokio::test]
+async fn restricted_offerings_sell_only_to_eligible_investors() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    fixture.grant_role(payer, Role::KycProvider).await;
+    let compliance = ComplianceConfig {
+        kyc_required: true,
+        allowed_jurisdictions: vec![],
+        accredited_only: false,
+        ..ComplianceConfig::default()
+    };
+    let asset = fixture.create_restricted_asset(false, compliance).await;
+    let setup = open_offering_for(&mut fixture, asset).await;
+    let (buyer, account) = new_buyer(&mut fixture, &setup, 1_000_000).await;
+    let record = pda(&[b"investor", fixture.real_token.as_ref(), buyer.pubkey().as_ref()]);
+
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, MIN_PURCHASE);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::InvestorNotVerified);
+
+    let expires_at = fixture.now().await + DURATION;
+    let (real_token, roles) = (fixture.real_token, fixture.roles);
+    let set_record = |kyc_status| {
+        ix(
+            realstack::accounts::SetInvestorRecord {
+                provider: payer,
+                real_token,
+                roles,
+                investor_record: record,
+                system_program: anchor_lang::system_program::ID,
+            },
+            realstack::instruction::SetInvestorRecord {
+                wallet: buyer.pubkey(),
+                kyc_status,
+                jurisdiction: *b"FR",
+                accredited: false,
+                expires_at,
+            },
+        )
+    };
+    let instruction = set_record(KycStatus::Pending);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = buy_as_investor_ix(&fixture, &setup, &buyer.pubkey(), account, MIN_PURCHASE, Some(record));
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::InvestorNotVerified);
+
+    let instruction = set_record(KycStatus::Verified);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = buy_as_investor_ix(&fixture, &setup, &buyer.pubkey(), account, MIN_PURCHASE, Some(record));
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    let purchase: OfferingPurchase = fixture.get(&purchase_of(&setup, &buyer.pubkey())).await;
+    assert_eq!(purchase.shares_purchased, MIN_PURCHASE);
+
+    // Paid-for shares can be claimed even if eligibility lapses before the offering closes
+    let instruction = buy_as_investor_ix(&fixture, &setup, &buyer.pubkey(), account, WALLET_LIMIT - MIN_PURCHASE, Some(record));
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    fixture.warp_forward(DURATION).await;
+    finalize(&mut fixture, &setup).await;
+    let buyer_shares = fixture.create_token_account(&setup.asset.share_mint, &buyer.pubkey()).await;
+    let instruction = claim_ix(&setup, &buyer.pubkey(), buyer_shares);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    assert_eq!(fixture.token_balance(&buyer_shares).await, WALLET_LIMIT);
+}
+
+#[tokio::test]
+async fn bought_shares_stay_in_escrow_for_the_lockup_period() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let compliance = ComplianceConfig {
+        lockup_duration: LOCKUP,
+        ..ComplianceConfig::default()
+    };
+    let asset = fixture.create_restricted_asset(false, compliance).await;
+    fixture.set_tradable(asset.asset_token, true).await;
+    let setup = open_offering_for(&mut fixture, asset).await;
+    let (buyer, account) = new_buyer(&mut fixture, &setup, 600 * ASSET_SHARE_PRICE).await;
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, 300);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+
+    // A second purchase holds the first back until its own unlock time
+    fixture.warp_forward(DURATION / 3).await;
+    let instruction = buy_ix(&fixture, &setup, &buyer.pubkey(), account, 300);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    let share_lock: ShareLock = fixture.get(&share_lock_of(&setup.asset.asset_token, &buyer.pubkey())).await;
+    assert_eq!(share_lock.wallet, buyer.pubkey());
+    assert_eq!(share_lock.locked_amount, 600);
+    assert_eq!(share_lock.unlocks_at, fixture.now().await + LOCKUP);
+
+    // The shares cannot be claimed, and so cannot be moved, until the lock-up ends
+    fixture.warp_forward(DURATION).await;
+    finalize(&mut fixture, &setup).await;
+    let buyer_shares = fixture.create_token_account(&setup.asset.share_mint, &buyer.pubkey()).await;
+    let instruction = claim_ix(&setup, &buyer.pubkey(), buyer_shares);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::SharesLocked);
+
+    let now = fixture.now().await;
+    fixture.warp_forward(share_lock.unlocks_at - now - 1).await;
+    let instruction = claim_ix(&setup, &buyer.pubkey(), buyer_shares);
+    let result = fixture.process(&[instruction], &[&buyer]).await;
+    assert_error(result, RealStackError::SharesLocked);
+    assert_eq!(fixture.token_balance(&buyer_shares).await, 0);
+
+    fixture.warp_forward(1).await;
+    let instruction = claim_ix(&setup, &buyer.pubkey(), buyer_shares);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    let issuer = (payer, setup.asset.issuer_shares);
+    let holder = (buyer.pubkey(), buyer_shares);
+    let instruction = transfer_ix(&fixture, &setup, holder, issuer, 600);
+    fixture.process(&[instruction], &[&buyer]).await.unwrap();
+    assert_eq!(fixture.token_balance(&buyer_shares).await, 0);
+}
@@ -15,6 +15,18 @@ describe('RealStack', () => {
   const assetTokenAccount = Keypair.generate();
   const mintKeypair = Keypair.generate();
   const assetMintKeypair = Keypair.generate();
+  let roles;
+  let mintAuthority;
+  let freezeAuthority;
+  
+  before(async () => {
+    [roles] = await PublicKey.findProgramAddress(
+      [Buffer.from("roles"), realTokenAccount.publicKey.toBuffer()], program.programId);
+    [mintAuthority] = await PublicKey.findProgramAddress(
+      [Buffer.from("mint_authority"), assetTokenAccount.publicKey.toBuffer()], program.programId);
+    [freezeAuthority] = await PublicKey.findProgramAddress(
+      [Buffer.from("freeze_authority"), assetTokenAccount.publicKey.toBuffer()], program.programId);
+  });
   
   it('Initializes the REAL token', async () => {
     // Prepare token parameters
@@ -28,6 +40,7 @@ describe('RealStack', () => {
       .initialize(name, symbol, uri, new anchor.BN(totalSupply))
       .accounts({
         realToken: realTokenAccount.publicKey,
+        roles,
         mint: mintKeypair.publicKey,
         authority: provider.wallet.publicKey,
         systemProgram: SystemProgram.programId,
@@ -48,6 +61,51 @@ describe('RealStack', () => {
     assert.equal(tokenAccount.authority.toString(), provider.wallet.publicKey.toString());
     assert.equal(tokenAccount.isInitialized, true);
     assert.equal(tokenAccount.mint.toString(), mintKeypair.publicKey.toString());
+    
+    // The authority starts as the sole admin
+    const rolesAccount = await program.account.roles.fetch(roles);
+    assert.equal(rolesAccount.admin.toString(), provider.wallet.publicKey.toString());
+    assert.equal(rolesAccount.members.length, 0);
+  });
+  
+  it('Rejects asset creation without the AssetManager role', async () => {
+    try {
+      await program.methods
+        .createAssetToken("Unmanaged", "UNM", "real-estate", "No role granted yet.", "",
+          new anchor.BN(1), new anchor.BN(1), new anchor.BN(1), false,
+          { kycRequired: false, allowedJurisdictions: [], accreditedOnly: false, maxHoldingBps: 0, maxHolders: 0, lockupDuration: new anchor.BN(0) })
+        .accounts({
+          authority: provider.wallet.publicKey,
+          realToken: realTokenAccount.publicKey,
+          roles,
+          assetToken: assetTokenAccount.publicKey,
+          mint: assetMintKeypair.publicKey,
+          mintAuthority,
+          freezeAuthority,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([assetTokenAccount])
+        .rpc();
+      assert.fail("create_asset_token should require the AssetManager role");
+    } catch (err) {
+      assert.include(err.toString(), "Unauthorized");
+    }
+  });
+  
+  it('Grants the AssetManager role', async () => {
+    await program.methods
+      .grantRole(provider.wallet.publicKey, { assetManager: {} })
+      .accounts({
+        admin: provider.wallet.publicKey,
+        roles,
+      })
+      .rpc();
+    
+    const rolesAccount = await program.account.roles.fetch(roles);
+    assert.equal(rolesAccount.members.length, 1);
+    assert.equal(rolesAccount.members[0].member.toString(), provider.wallet.publicKey.toString());
   });
   
   it('Creates an asset token', async () => {
@@ -64,11 +122,16 @@ describe('RealStack', () => {
     // Execute the create_asset_token instruction
     await program.methods
       .createAssetToken(name, symbol, category, description, uri, 
-        new anchor.BN(valuation), new anchor.BN(totalShares), new anchor.BN(sharePrice))
+        new anchor.BN(valuation), new anchor.BN(totalShares), new anchor.BN(sharePrice), false,
+        { kycRequired: false, allowedJurisdictions: [], accreditedOnly: false, maxHoldingBps: 0, maxHolders: 0, lockupDuration: new anchor.BN(0) })
       .accounts({
         authority: provider.wallet.publicKey,
+        realToken: realTokenAccount.publicKey,
+        roles,
         assetToken: assetTokenAccount.publicKey,
         mint: assetMintKeypair.publicKey,
+        mintAuthority,
+        freezeAuthority,
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -91,8 +154,10 @@ describe('RealStack', () => {
     assert.equal(assetAccount.currentSharePrice.toString(), sharePrice.toString());
     assert.equal(assetAccount.isVerified, false);
     assert.equal(assetAccount.isTradable, false);
+    assert.equal(assetAccount.canMintAdditional, false);
     assert.equal(assetAccount.authority.toString(), provider.wallet.publicKey.toString());
     assert.equal(assetAccount.mint.toString(), assetMintKeypair.publicKey.toString());
+    assert.equal(assetAccount.realToken.toString(), realTokenAccount.publicKey.toString());
   });
   
   it('Updates asset valuation', async () => {
@@ -106,6 +171,7 @@ describe('RealStack', () => {
       .accounts({
         authority: provider.wallet.publicKey,
         assetToken: assetTokenAccount.publicKey,
+        roles,
       })
       .rpc();
     
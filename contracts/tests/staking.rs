@@ -0,0 +1,281 @@
+mod common;
+
+use common::*;
+use realstack::{PauseFlags, RealStackError, StakeAccount, StakePool, VotingPowerCheckpoints, MAX_UNSTAKE_COOLDOWN};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+const COOLDOWN: i64 = 7 * 24 * 60 * 60;
+const FEE_SHARE_BPS: u16 = 5_000;
+
+fn stake_account_of(pool: &StakePoolFixture, owner: &Pubkey) -> Pubkey {
+    pda(&[b"stake_account", pool.stake_pool.as_ref(), owner.as_ref()])
+}
+
+fn checkpoints_of(pool: &StakePoolFixture, owner: &Pubkey) -> Pubkey {
+    pda(&[b"voting_power", pool.stake_pool.as_ref(), owner.as_ref()])
+}
+
+fn unstake_ix(pool: &StakePoolFixture, owner: &Pubkey, amount: u64) -> Instruction {
+    ix(
+        realstack::accounts::Unstake {
+            owner: *owner,
+            stake_pool: pool.stake_pool,
+            stake_account: stake_account_of(pool, owner),
+            checkpoints: checkpoints_of(pool, owner),
+        },
+        realstack::instruction::Unstake { amount },
+    )
+}
+
+fn withdraw_ix(pool: &StakePoolFixture, owner: &Pubkey, owner_token_account: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::WithdrawUnstaked {
+            owner: *owner,
+            stake_pool: pool.stake_pool,
+            stake_account: stake_account_of(pool, owner),
+            owner_token_account,
+            stake_vault: pool.stake_vault,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::WithdrawUnstaked {},
+    )
+}
+
+fn claim_ix(pool: &StakePoolFixture, owner: &Pubkey, reward_destination: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ClaimRewards {
+            owner: *owner,
+            stake_pool: pool.stake_pool,
+            stake_account: stake_account_of(pool, owner),
+            reward_destination,
+            reward_vault: pool.reward_vault,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::ClaimRewards {},
+    )
+}
+
+fn distribute_ix(
+    fixture: &TestFixture,
+    pool: &StakePoolFixture,
+    fee_recipient: &Pubkey,
+    fee_source: Pubkey,
+    collected_fees: u64,
+) -> Instruction {
+    ix(
+        realstack::accounts::DistributeStakingRewards {
+            fee_recipient: *fee_recipient,
+            real_token: fixture.real_token,
+            stake_pool: pool.stake_pool,
+            fee_source,
+            reward_vault: pool.reward_vault,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::DistributeStakingRewards { collected_fees },
+    )
+}
+
+#[tokio::test]
+async fn initialize_stake_pool_validates_parameters() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let reward_mint = fixture.create_mint(6).await;
+
+    let instruction = fixture.initialize_stake_pool_ix(&payer, reward_mint, 10_001, COOLDOWN);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = fixture.initialize_stake_pool_ix(&payer, reward_mint, FEE_SHARE_BPS, MAX_UNSTAKE_COOLDOWN + 1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = fixture.initialize_stake_pool_ix(&payer, reward_mint, FEE_SHARE_BPS, -1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = fixture.initialize_stake_pool_ix(&intruder.pubkey(), reward_mint, FEE_SHARE_BPS, COOLDOWN);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    let pool = fixture.create_stake_pool(FEE_SHARE_BPS, COOLDOWN).await;
+    let stake_pool: StakePool = fixture.get(&pool.stake_pool).await;
+    assert_eq!(stake_pool.real_token, fixture.real_token);
+    assert_eq!(stake_pool.stake_mint, fixture.real_mint);
+    assert_eq!(stake_pool.fee_share_bps, FEE_SHARE_BPS);
+    assert_eq!(stake_pool.unstake_cooldown, COOLDOWN);
+}
+
+#[tokio::test]
+async fn stake_unstake_and_withdraw() {
+    let mut fixture = TestFixture::new().await;
+    let pool = fixture.create_stake_pool(FEE_SHARE_BPS, COOLDOWN).await;
+    let (staker, staker_real) = fixture.new_real_holder(1_000).await;
+
+    let instruction = fixture.stake_ix(&pool, &staker.pubkey(), staker_real, 600);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+    assert_eq!(fixture.token_balance(&pool.stake_vault).await, 600);
+    let stake_account: StakeAccount = fixture.get(&stake_account_of(&pool, &staker.pubkey())).await;
+    assert_eq!(stake_account.staked_amount, 600);
+    assert_eq!(stake_account.voting_power(), 600);
+
+    let instruction = unstake_ix(&pool, &staker.pubkey(), 200);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+    let stake_account: StakeAccount = fixture.get(&stake_account_of(&pool, &staker.pubkey())).await;
+    assert_eq!(stake_account.staked_amount, 400);
+    assert_eq!(stake_account.pending_unstake, 200);
+
+    let instruction = withdraw_ix(&pool, &staker.pubkey(), staker_real);
+    let result = fixture.process(&[instruction], &[&staker]).await;
+    assert_error(result, RealStackError::StakeCooldownActive);
+
+    fixture.warp_forward(COOLDOWN).await;
+    let instruction = withdraw_ix(&pool, &staker.pubkey(), staker_real);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+    assert_eq!(fixture.token_balance(&staker_real).await, 600);
+
+    let instruction = withdraw_ix(&pool, &staker.pubkey(), staker_real);
+    let result = fixture.process(&[instruction], &[&staker]).await;
+    assert_error(result, RealStackError::NoPendingUnstake);
+}
+
+#[tokio::test]
+async fn stake_changes_are_checkpointed_by_slot() {
+    let mut fixture = TestFixture::new().await;
+    let pool = fixture.create_stake_pool(FEE_SHARE_BPS, COOLDOWN).await;
+    let (staker, staker_real) = fixture.new_real_holder(1_000).await;
+
+    let instruction = fixture.stake_ix(&pool, &staker.pubkey(), staker_real, 600);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+    let instruction = fixture.stake_ix(&pool, &staker.pubkey(), staker_real, 100);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+    let instruction = unstake_ix(&pool, &staker.pubkey(), 200);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+
+    let history: VotingPowerCheckpoints = fixture.get(&checkpoints_of(&pool, &staker.pubkey())).await;
+    assert_eq!(history.owner, staker.pubkey());
+    let powers: Vec<u64> = history.checkpoints.iter().map(|checkpoint| checkpoint.power).collect();
+    assert_eq!(powers, vec![600, 700, 500]);
+    assert!(history.checkpoints.windows(2).all(|pair| pair[0].slot < pair[1].slot));
+
+    // Power is read as of the slot before a snapshot
+    let first = history.checkpoints[0].slot;
+    let last = history.checkpoints[2].slot;
+    assert_eq!(history.power_before(first), 0);
+    assert_eq!(history.power_before(first + 1), 600);
+    assert_eq!(history.power_before(last), 700);
+    assert_eq!(history.power_before(last + 1), 500);
+}
+
+#[tokio::test]
+async fn stake_rejects_invalid_calls() {
+    let mut fixture = TestFixture::new().await;
+    let pool = fixture.create_stake_pool(FEE_SHARE_BPS, COOLDOWN).await;
+    let (staker, staker_real) = fixture.new_real_holder(1_000).await;
+    let (other, _) = fixture.new_real_holder(0).await;
+
+    let instruction = fixture.stake_ix(&pool, &staker.pubkey(), staker_real, 0);
+    let result = fixture.process(&[instruction], &[&staker]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    // Staking from someone else's account
+    let instruction = fixture.stake_ix(&pool, &other.pubkey(), staker_real, 100);
+    let result = fixture.process(&[instruction], &[&other]).await;
+    assert_error(result, RealStackError::InvalidTokenAccount);
+
+    // Staking something other than REAL
+    let reward_account = fixture.create_token_account(&pool.reward_mint, &staker.pubkey()).await;
+    let instruction = fixture.stake_ix(&pool, &staker.pubkey(), reward_account, 100);
+    let result = fixture.process(&[instruction], &[&staker]).await;
+    assert_error(result, RealStackError::InvalidTokenMint);
+
+    fixture.pause(PauseFlags { governance: true, ..no_pause() }).await;
+    let instruction = fixture.stake_ix(&pool, &staker.pubkey(), staker_real, 100);
+    let result = fixture.process(&[instruction], &[&staker]).await;
+    assert_error(result, RealStackError::OperationPaused);
+}
+
+#[tokio::test]
+async fn unstake_rejects_invalid_amounts() {
+    let mut fixture = TestFixture::new().await;
+    let pool = fixture.create_stake_pool(FEE_SHARE_BPS, COOLDOWN).await;
+    let (staker, staker_real) = fixture.new_real_holder(1_000).await;
+
+    let instruction = fixture.stake_ix(&pool, &staker.pubkey(), staker_real, 500);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+
+    let instruction = unstake_ix(&pool, &staker.pubkey(), 0);
+    let result = fixture.process(&[instruction], &[&staker]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = unstake_ix(&pool, &staker.pubkey(), 501);
+    let result = fixture.process(&[instruction], &[&staker]).await;
+    assert_error(result, RealStackError::InsufficientStake);
+}
+
+#[tokio::test]
+async fn rewards_accrue_pro_rata() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let pool = fixture.create_stake_pool(FEE_SHARE_BPS, COOLDOWN).await;
+    let (alice, alice_real) = fixture.new_real_holder(1_000).await;
+    let (bob, bob_real) = fixture.new_real_holder(1_000).await;
+    let fee_source = fixture.create_funded_token_account(&pool.reward_mint, &payer, 10_000).await;
+
+    let instruction = fixture.stake_ix(&pool, &alice.pubkey(), alice_real, 300);
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    let instruction = fixture.stake_ix(&pool, &bob.pubkey(), bob_real, 100);
+    fixture.process(&[instruction], &[&bob]).await.unwrap();
+
+    // Half of 8_000 in fees goes to stakers
+    let instruction = distribute_ix(&fixture, &pool, &payer, fee_source, 8_000);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&pool.reward_vault).await, 4_000);
+
+    let alice_rewards = fixture.create_token_account(&pool.reward_mint, &alice.pubkey()).await;
+    let instruction = claim_ix(&pool, &alice.pubkey(), alice_rewards);
+    fixture.process(&[instruction], &[&alice]).await.unwrap();
+    assert_eq!(fixture.token_balance(&alice_rewards).await, 3_000);
+
+    let bob_rewards = fixture.create_token_account(&pool.reward_mint, &bob.pubkey()).await;
+    let instruction = claim_ix(&pool, &bob.pubkey(), bob_rewards);
+    fixture.process(&[instruction], &[&bob]).await.unwrap();
+    assert_eq!(fixture.token_balance(&bob_rewards).await, 1_000);
+
+    let instruction = claim_ix(&pool, &bob.pubkey(), bob_rewards);
+    let result = fixture.process(&[instruction], &[&bob]).await;
+    assert_error(result, RealStackError::NoRewardsToClaim);
+}
+
+#[tokio::test]
+async fn distribute_staking_rewards_rejects_invalid_calls() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+    let pool = fixture.create_stake_pool(FEE_SHARE_BPS, COOLDOWN).await;
+    let fee_source = fixture.create_funded_token_account(&pool.reward_mint, &payer, 10_000).await;
+
+    let instruction = distribute_ix(&fixture, &pool, &payer, fee_source, 1_000);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::NoStakedTokens);
+
+    let (staker, staker_real) = fixture.new_real_holder(100).await;
+    let instruction = fixture.stake_ix(&pool, &staker.pubkey(), staker_real, 100);
+    fixture.process(&[instruction], &[&staker]).await.unwrap();
+
+    let instruction = distribute_ix(&fixture, &pool, &payer, fee_source, 1);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidDistributionAmount);
+
+    let instruction = distribute_ix(&fixture, &pool, &intruder.pubkey(), fee_source, 1_000);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    fixture.pause(PauseFlags { income_distribution: true, ..no_pause() }).await;
+    let instruction = distribute_ix(&fixture, &pool, &payer, fee_source, 1_000);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::OperationPaused);
+}
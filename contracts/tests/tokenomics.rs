@@ -0,0 +1,243 @@
+mod common;
+
+use common::*;
+use realstack::{PauseFlags, RealStackError, Role, MAX_PAUSE_DURATION, PAUSE_COOLDOWN};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+fn transfer_authority_ix(fixture: &TestFixture, current_authority: &Pubkey, new_authority: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::TransferAuthority {
+            current_authority: *current_authority,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+        },
+        realstack::instruction::TransferAuthority { new_authority },
+    )
+}
+
+fn accept_authority_ix(fixture: &TestFixture, new_authority: &Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::AcceptAuthority {
+            new_authority: *new_authority,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+        },
+        realstack::instruction::AcceptAuthority {},
+    )
+}
+
+fn update_fee_config_ix(fixture: &TestFixture, authority: &Pubkey, transaction_fee_bps: u16) -> Instruction {
+    ix(
+        realstack::accounts::UpdateFeeConfig {
+            authority: *authority,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+        },
+        realstack::instruction::UpdateFeeConfig {
+            transaction_fee_bps,
+            fee_recipient: *authority,
+            fees_enabled: true,
+        },
+    )
+}
+
+fn set_transfer_pause_ix(fixture: &TestFixture, authority: &Pubkey, paused: bool) -> Instruction {
+    ix(
+        realstack::accounts::SetTransferPause {
+            authority: *authority,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+        },
+        realstack::instruction::SetTransferPause { paused },
+    )
+}
+
+fn trading_only() -> PauseFlags {
+    PauseFlags {
+        trading: true,
+        ..no_pause()
+    }
+}
+
+#[tokio::test]
+async fn initialize_sets_up_token_and_roles() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+
+    let real_token = fixture.real_token_state().await;
+    assert_eq!(real_token.name, "REAL Token");
+    assert_eq!(real_token.symbol, "REAL");
+    assert_eq!(real_token.total_supply, REAL_SUPPLY);
+    assert_eq!(real_token.authority, payer);
+    assert_eq!(real_token.mint, fixture.real_mint);
+    assert_eq!(real_token.fee_config.transaction_fee_bps, 25);
+    assert_eq!(real_token.distribution.community_allocation, REAL_SUPPLY * 40 / 100);
+    assert_eq!(real_token.distribution.team_allocation, REAL_SUPPLY * 5 / 100);
+    assert!(!real_token.transfers_paused);
+
+    let roles = fixture.roles_state().await;
+    assert_eq!(roles.real_token, fixture.real_token);
+    assert_eq!(roles.admin, payer);
+    assert!(roles.has_role(payer, Role::AssetManager));
+    assert!(roles.has_role(fixture.verifier.pubkey(), Role::Verifier));
+}
+
+#[tokio::test]
+async fn initialize_rejects_invalid_parameters() {
+    let mut fixture = TestFixture::new().await;
+
+    for (name, symbol, supply) in [("", "REAL", REAL_SUPPLY), ("REAL Token", "", REAL_SUPPLY), ("REAL Token", "REAL", 0)] {
+        let real_token = Keypair::new();
+        let instruction = fixture.initialize_ix(real_token.pubkey(), name, symbol, supply);
+        let result = fixture.process(&[instruction], &[&real_token]).await;
+        assert_error(result, RealStackError::InvalidParameters);
+    }
+}
+
+#[tokio::test]
+async fn authority_handover_moves_admin_role() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let new_admin = fixture.new_actor().await;
+
+    let instruction = transfer_authority_ix(&fixture, &payer, new_admin.pubkey());
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let real_token = fixture.real_token_state().await;
+    assert_eq!(real_token.pending_authority, Some(new_admin.pubkey()));
+
+    let instruction = accept_authority_ix(&fixture, &new_admin.pubkey());
+    fixture.process(&[instruction], &[&new_admin]).await.unwrap();
+
+    let real_token = fixture.real_token_state().await;
+    assert_eq!(real_token.authority, new_admin.pubkey());
+    assert_eq!(real_token.pending_authority, None);
+    let roles = fixture.roles_state().await;
+    assert_eq!(roles.admin, new_admin.pubkey());
+
+    // The old admin can no longer start a handover
+    let instruction = transfer_authority_ix(&fixture, &payer, payer);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
+
+#[tokio::test]
+async fn transfer_authority_rejects_non_admin() {
+    let mut fixture = TestFixture::new().await;
+    let intruder = fixture.new_actor().await;
+
+    let instruction = transfer_authority_ix(&fixture, &intruder.pubkey(), intruder.pubkey());
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
+
+#[tokio::test]
+async fn accept_authority_rejects_other_signers() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let new_admin = fixture.new_actor().await;
+    let intruder = fixture.new_actor().await;
+
+    let instruction = transfer_authority_ix(&fixture, &payer, new_admin.pubkey());
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = accept_authority_ix(&fixture, &intruder.pubkey());
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
+
+#[tokio::test]
+async fn update_fee_config_checks_role_and_bounds() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+
+    let instruction = update_fee_config_ix(&fixture, &payer, 100);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let real_token = fixture.real_token_state().await;
+    assert_eq!(real_token.fee_config.transaction_fee_bps, 100);
+
+    let instruction = update_fee_config_ix(&fixture, &payer, 1001);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+
+    let instruction = update_fee_config_ix(&fixture, &intruder.pubkey(), 50);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
+
+#[tokio::test]
+async fn pause_lapses_and_enforces_cooldown() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let now = fixture.now().await;
+
+    let instruction = set_transfer_pause_ix(&fixture, &payer, true);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let real_token = fixture.real_token_state().await;
+    assert!(real_token.transfers_paused);
+    assert!(real_token.pause_state.expires_at >= now + MAX_PAUSE_DURATION);
+    assert_eq!(real_token.pause_state.cooldown_ends_at, real_token.pause_state.expires_at + PAUSE_COOLDOWN);
+
+    // Once the window passes the pause reads as lifted, but a new one waits for the cooldown
+    fixture.warp_forward(MAX_PAUSE_DURATION + 1).await;
+    let now = fixture.now().await;
+    let real_token = fixture.real_token_state().await;
+    assert!(!real_token.is_paused(realstack::PauseFlag::Trading, now));
+
+    let instruction = fixture.set_pause_flags_ix(&payer, trading_only());
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::PauseCooldownActive);
+
+    fixture.warp_forward(PAUSE_COOLDOWN).await;
+    fixture.pause(trading_only()).await;
+}
+
+#[tokio::test]
+async fn early_unpause_starts_cooldown() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+
+    fixture.pause(PauseFlags { governance: true, ..no_pause() }).await;
+    let instruction = fixture.set_pause_flags_ix(&payer, no_pause());
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = fixture.set_pause_flags_ix(&payer, trading_only());
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::PauseCooldownActive);
+}
+
+#[tokio::test]
+async fn pause_requires_pauser_role() {
+    let mut fixture = TestFixture::new().await;
+    let payer = fixture.payer_key();
+    let intruder = fixture.new_actor().await;
+
+    let instruction = set_transfer_pause_ix(&fixture, &intruder.pubkey(), true);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // The admin may lift a pause but not start one without the Pauser role
+    fixture.pause(trading_only()).await;
+    let revoke = ix(
+        realstack::accounts::ManageRoles {
+            admin: payer,
+            roles: fixture.roles,
+        },
+        realstack::instruction::RevokeRole {
+            member: payer,
+            role: Role::Pauser,
+        },
+    );
+    fixture.process(&[revoke], &[]).await.unwrap();
+
+    let instruction = set_transfer_pause_ix(&fixture, &payer, false);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    fixture.warp_forward(PAUSE_COOLDOWN).await;
+    let instruction = set_transfer_pause_ix(&fixture, &payer, true);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::Unauthorized);
+}
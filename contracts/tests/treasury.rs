@@ -0,0 +1,448 @@
+mod common;
+
+use common::*;
+use realstack::{
+    Proposal, ProposalType, RealStackError, Treasury, TreasuryAction, TreasuryStream, TreasuryVault, VoteChoice,
+    VotingMode,
+};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+const DAY: i64 = 24 * 60 * 60;
+const SPENDING_PERIOD: i64 = 30 * DAY;
+const SPENDING_CAP: u64 = 1_000;
+const VAULT_BALANCE: u64 = 1_500;
+const QUORUM: u64 = 100;
+
+/// Governance with a treasury holding a stablecoin vault
+struct TreasuryFixture {
+    config: Pubkey,
+    pool: StakePoolFixture,
+    treasury: Pubkey,
+    vault: Pubkey,
+    vault_tokens: Pubkey,
+    mint: Pubkey,
+    /// The payer's REAL account, which funds proposal deposits
+    proposer_real: Pubkey,
+}
+
+fn initialize_treasury_ix(authority: &Pubkey, config: Pubkey, spending_period: i64) -> Instruction {
+    ix(
+        realstack::accounts::InitializeTreasury {
+            authority: *authority,
+            governance_config: config,
+            treasury: pda(&[b"treasury", config.as_ref()]),
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::InitializeTreasury { spending_period },
+    )
+}
+
+fn create_vault_ix(authority: &Pubkey, t: &TreasuryFixture, mint: Pubkey) -> Instruction {
+    let vault = pda(&[b"treasury_vault", t.treasury.as_ref(), mint.as_ref()]);
+    ix(
+        realstack::accounts::CreateTreasuryVault {
+            authority: *authority,
+            governance_config: t.config,
+            treasury: t.treasury,
+            mint,
+            vault,
+            vault_token_account: pda(&[b"treasury_tokens", vault.as_ref()]),
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        realstack::instruction::CreateTreasuryVault {
+            spending_cap: SPENDING_CAP,
+        },
+    )
+}
+
+fn create_treasury_proposal_ix(
+    fixture: &TestFixture,
+    t: &TreasuryFixture,
+    proposal: Pubkey,
+    voting_ends_at: i64,
+    action: TreasuryAction,
+) -> Instruction {
+    let proposer = fixture.payer_key();
+    ix(
+        realstack::accounts::CreateProposal {
+            proposer,
+            proposal,
+            governance_config: t.config,
+            real_token: fixture.real_token,
+            real_mint: fixture.real_mint,
+            proposer_token_account: t.proposer_real,
+            deposit_escrow: pda(&[b"proposal_deposit", proposal.as_ref()]),
+            proposer_state: pda(&[b"proposer_state", t.config.as_ref(), proposer.as_ref()]),
+            stake_pool: None,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        realstack::instruction::CreateTreasuryProposal {
+            title: "Fund the grants program".to_string(),
+            description: "Pay contributors from the treasury.".to_string(),
+            voting_ends_at,
+            action,
+        },
+    )
+}
+
+fn execute_transfer_ix(fixture: &TestFixture, t: &TreasuryFixture, proposal: Pubkey, recipient: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ExecuteTreasuryTransfer {
+            executor: fixture.payer_key(),
+            proposal,
+            governance_config: t.config,
+            real_token: fixture.real_token,
+            treasury: t.treasury,
+            vault: t.vault,
+            vault_token_account: t.vault_tokens,
+            recipient_token_account: recipient,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::ExecuteTreasuryTransfer {},
+    )
+}
+
+fn stream_of(proposal: &Pubkey) -> Pubkey {
+    pda(&[b"treasury_stream", proposal.as_ref()])
+}
+
+fn execute_stream_ix(fixture: &TestFixture, t: &TreasuryFixture, proposal: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ExecuteTreasuryStream {
+            executor: fixture.payer_key(),
+            proposal,
+            governance_config: t.config,
+            real_token: fixture.real_token,
+            treasury: t.treasury,
+            vault: t.vault,
+            vault_token_account: t.vault_tokens,
+            stream: stream_of(&proposal),
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::ExecuteTreasuryStream {},
+    )
+}
+
+fn execute_cancellation_ix(fixture: &TestFixture, t: &TreasuryFixture, proposal: Pubkey, stream: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ExecuteStreamCancellation {
+            executor: fixture.payer_key(),
+            proposal,
+            governance_config: t.config,
+            real_token: fixture.real_token,
+            treasury: t.treasury,
+            vault: t.vault,
+            stream,
+        },
+        realstack::instruction::ExecuteStreamCancellation {},
+    )
+}
+
+fn claim_stream_ix(claimer: &Pubkey, t: &TreasuryFixture, stream: Pubkey, recipient: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ClaimStream {
+            claimer: *claimer,
+            treasury: t.treasury,
+            vault: t.vault,
+            vault_token_account: t.vault_tokens,
+            stream,
+            recipient_token_account: recipient,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::ClaimStream {},
+    )
+}
+
+/// Set up governance, its treasury and a funded stablecoin vault
+async fn setup_treasury(fixture: &mut TestFixture) -> TreasuryFixture {
+    let pool = fixture.create_stake_pool(5_000, DAY).await;
+    let payer = fixture.payer_key();
+    let real_mint = fixture.real_mint;
+    let slashed = fixture.create_token_account(&real_mint, &Pubkey::new_unique()).await;
+    let proposer_real = fixture.create_funded_token_account(&real_mint, &payer, 1_000).await;
+    let config = pda(&[b"governance_config", fixture.real_token.as_ref()]);
+    let instruction = ix(
+        realstack::accounts::InitializeGovernance {
+            authority: payer,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+            treasury: slashed,
+            governance_config: config,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::InitializeGovernance {
+            min_voting_period: DAY,
+            max_voting_period: 7 * DAY,
+            min_quorum_votes: QUORUM,
+            approval_threshold: 51,
+            min_proposal_balance: 200,
+            min_vote_balance: 10,
+            proposal_deposit: 50,
+            max_active_proposals: 5,
+            voting_mode: VotingMode::Linear,
+        },
+    );
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = initialize_treasury_ix(&payer, config, SPENDING_PERIOD);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let treasury = pda(&[b"treasury", config.as_ref()]);
+
+    let mint = fixture.create_mint(6).await;
+    let vault = pda(&[b"treasury_vault", treasury.as_ref(), mint.as_ref()]);
+    let vault_tokens = pda(&[b"treasury_tokens", vault.as_ref()]);
+    let t = TreasuryFixture {
+        config,
+        pool,
+        treasury,
+        vault,
+        vault_tokens,
+        mint,
+        proposer_real,
+    };
+    let instruction = create_vault_ix(&payer, &t, mint);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    fixture.mint_to(&mint, &vault_tokens, VAULT_BALANCE).await;
+    t
+}
+
+/// Propose a treasury action, vote it through with `choice` and end the voting period
+async fn decide(fixture: &mut TestFixture, t: &TreasuryFixture, action: TreasuryAction, choice: VoteChoice) -> Pubkey {
+    let voter = fixture.new_staker(&t.pool, QUORUM).await;
+    let proposal = fixture.next_proposal(&t.config).await;
+    let voting_ends_at = fixture.now().await + DAY + 60;
+    let instruction = create_treasury_proposal_ix(fixture, t, proposal, voting_ends_at, action);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = ix(
+        realstack::accounts::VoteOnProposal {
+            voter: voter.pubkey(),
+            proposal,
+            vote_record: pda(&[b"vote_record", proposal.as_ref(), voter.pubkey().as_ref()]),
+            governance_config: t.config,
+            real_token: fixture.real_token,
+            stake_pool: t.pool.stake_pool,
+            stake_account: None,
+            checkpoints: Some(pda(&[b"voting_power", t.pool.stake_pool.as_ref(), voter.pubkey().as_ref()])),
+            real_mint: None,
+            voter_real_account: None,
+            vote_escrow: None,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::VoteOnProposal {
+            choice,
+            vote_weight: QUORUM,
+            lock_duration: 0,
+        },
+    );
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    fixture.warp_forward(DAY + 60).await;
+    proposal
+}
+
+#[tokio::test]
+async fn treasury_setup_is_restricted_to_the_governance_authority() {
+    let mut fixture = TestFixture::new().await;
+    let t = setup_treasury(&mut fixture).await;
+    let intruder = fixture.new_actor().await;
+
+    let state: Treasury = fixture.get(&t.treasury).await;
+    assert_eq!(state.governance_config, t.config);
+    assert_eq!(state.spending_period, SPENDING_PERIOD);
+    let vault: TreasuryVault = fixture.get(&t.vault).await;
+    assert_eq!(vault.mint, t.mint);
+    assert_eq!(vault.spending_cap, SPENDING_CAP);
+
+    // Only the governance authority can add vaults
+    let mint = fixture.create_mint(6).await;
+    let instruction = create_vault_ix(&intruder.pubkey(), &t, mint);
+    let result = fixture.process(&[instruction], &[&intruder]).await;
+    assert_error(result, RealStackError::Unauthorized);
+
+    // Actions that could never execute are rejected up front
+    let proposal = fixture.next_proposal(&t.config).await;
+    let voting_ends_at = fixture.now().await + DAY + 60;
+    let invalid = [
+        TreasuryAction::Transfer {
+            mint: t.mint,
+            recipient: Pubkey::new_unique(),
+            amount: 0,
+        },
+        TreasuryAction::Stream {
+            mint: t.mint,
+            recipient: Pubkey::new_unique(),
+            amount: 100,
+            start_at: voting_ends_at,
+            end_at: voting_ends_at,
+        },
+    ];
+    for action in invalid {
+        let instruction = create_treasury_proposal_ix(&fixture, &t, proposal, voting_ends_at, action);
+        let result = fixture.process(&[instruction], &[]).await;
+        assert_error(result, RealStackError::InvalidParameters);
+    }
+}
+
+#[tokio::test]
+async fn approved_transfers_are_capped_per_period() {
+    let mut fixture = TestFixture::new().await;
+    let t = setup_treasury(&mut fixture).await;
+    let recipient = fixture.create_token_account(&t.mint, &Pubkey::new_unique()).await;
+    let transfer = TreasuryAction::Transfer {
+        mint: t.mint,
+        recipient,
+        amount: 600,
+    };
+
+    let first = decide(&mut fixture, &t, transfer, VoteChoice::Yes).await;
+    let state: Proposal = fixture.get(&first).await;
+    assert_eq!(state.proposal_type, ProposalType::TreasuryTransfer);
+
+    // Treasury proposals only execute through their own instruction
+    let instruction = ix(
+        realstack::accounts::ExecuteProposal {
+            executor: fixture.payer_key(),
+            proposal: first,
+            governance_config: t.config,
+            real_token: fixture.real_token,
+        },
+        realstack::instruction::ExecuteProposal {},
+    );
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::WrongExecutionInstruction);
+
+    let instruction = execute_transfer_ix(&fixture, &t, first, recipient);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&recipient).await, 600);
+
+    // A second transfer in the same period would exceed the cap
+    let second = decide(&mut fixture, &t, transfer, VoteChoice::Yes).await;
+    let instruction = execute_transfer_ix(&fixture, &t, second, recipient);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::SpendingCapExceeded);
+
+    fixture.warp_forward(SPENDING_PERIOD).await;
+    let instruction = execute_transfer_ix(&fixture, &t, second, recipient);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(fixture.token_balance(&recipient).await, 1_200);
+    assert_eq!(fixture.token_balance(&t.vault_tokens).await, VAULT_BALANCE - 1_200);
+
+    // A rejected transfer is marked executed without paying out
+    let rejected = decide(&mut fixture, &t, transfer, VoteChoice::No).await;
+    let instruction = execute_transfer_ix(&fixture, &t, rejected, recipient);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&rejected).await;
+    assert!(state.executed);
+    assert_eq!(fixture.token_balance(&recipient).await, 1_200);
+}
+
+#[tokio::test]
+async fn streams_accrue_linearly_until_cancelled() {
+    let mut fixture = TestFixture::new().await;
+    let t = setup_treasury(&mut fixture).await;
+    let grantee = fixture.new_actor().await;
+    let recipient = fixture.create_token_account(&t.mint, &grantee.pubkey()).await;
+    let start_at = fixture.now().await + DAY + 60;
+    let stream_action = TreasuryAction::Stream {
+        mint: t.mint,
+        recipient,
+        amount: 1_000,
+        start_at,
+        end_at: start_at + 10 * DAY,
+    };
+
+    let proposal = decide(&mut fixture, &t, stream_action, VoteChoice::Yes).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.proposal_type, ProposalType::CommunityFunding);
+
+    let instruction = execute_transfer_ix(&fixture, &t, proposal, recipient);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::WrongExecutionInstruction);
+
+    let instruction = execute_stream_ix(&fixture, &t, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let stream = stream_of(&proposal);
+    let vault: TreasuryVault = fixture.get(&t.vault).await;
+    assert_eq!(vault.reserved, 1_000);
+
+    // Streamed funds are set aside from other spending, even in a new period
+    let too_much = TreasuryAction::Transfer {
+        mint: t.mint,
+        recipient,
+        amount: VAULT_BALANCE - 999,
+    };
+    fixture.warp_forward(SPENDING_PERIOD).await;
+    let overdraw = decide(&mut fixture, &t, too_much, VoteChoice::Yes).await;
+    let instruction = execute_transfer_ix(&fixture, &t, overdraw, recipient);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InsufficientTreasuryFunds);
+
+    // Cancel the stream; past its end, the whole amount has accrued
+    let cancel = TreasuryAction::CancelStream { stream };
+    let cancellation = decide(&mut fixture, &t, cancel, VoteChoice::Yes).await;
+    let instruction = execute_cancellation_ix(&fixture, &t, cancellation, stream);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let instruction = claim_stream_ix(&grantee.pubkey(), &t, stream, recipient);
+    fixture.process(&[instruction], &[&grantee]).await.unwrap();
+    assert_eq!(fixture.token_balance(&recipient).await, 1_000);
+
+    let instruction = claim_stream_ix(&grantee.pubkey(), &t, stream, recipient);
+    let result = fixture.process(&[instruction], &[&grantee]).await;
+    assert_error(result, RealStackError::NothingToClaim);
+}
+
+#[tokio::test]
+async fn cancelled_stream_stops_accruing() {
+    let mut fixture = TestFixture::new().await;
+    let t = setup_treasury(&mut fixture).await;
+    let grantee = fixture.new_actor().await;
+    let recipient = fixture.create_token_account(&t.mint, &grantee.pubkey()).await;
+    let start_at = fixture.now().await + DAY + 60;
+    let stream_action = TreasuryAction::Stream {
+        mint: t.mint,
+        recipient,
+        amount: 1_000,
+        start_at,
+        end_at: start_at + 10 * DAY,
+    };
+    let proposal = decide(&mut fixture, &t, stream_action, VoteChoice::Yes).await;
+    let instruction = execute_stream_ix(&fixture, &t, proposal);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let stream = stream_of(&proposal);
+
+    // Three days in, 30% has accrued
+    fixture.warp_forward(3 * DAY).await;
+    let instruction = claim_stream_ix(&grantee.pubkey(), &t, stream, recipient);
+    fixture.process(&[instruction], &[&grantee]).await.unwrap();
+    assert_eq!(fixture.token_balance(&recipient).await, 300);
+
+    // The cancellation vote takes another day; accrual stops when it executes
+    let cancellation = decide(&mut fixture, &t, TreasuryAction::CancelStream { stream }, VoteChoice::Yes).await;
+    let instruction = execute_cancellation_ix(&fixture, &t, cancellation, stream);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: TreasuryStream = fixture.get(&stream).await;
+    let vested = state.vested_at(state.end_at);
+    assert!(state.cancelled_at.is_some());
+    assert!(vested > 300 && vested < 1_000);
+
+    fixture.warp_forward(10 * DAY).await;
+    let instruction = claim_stream_ix(&grantee.pubkey(), &t, stream, recipient);
+    fixture.process(&[instruction], &[&grantee]).await.unwrap();
+    assert_eq!(fixture.token_balance(&recipient).await, vested);
+    let vault: TreasuryVault = fixture.get(&t.vault).await;
+    assert_eq!(vault.reserved, 0);
+
+    // A stream can only be cancelled once
+    let again = decide(&mut fixture, &t, TreasuryAction::CancelStream { stream }, VoteChoice::Yes).await;
+    let instruction = execute_cancellation_ix(&fixture, &t, again, stream);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::StreamCancelled);
+}
@@ -0,0 +1,339 @@
+mod common;
+
+use common::*;
+use realstack::{Proposal, ProposalType, RealStackError, UpgradeAction, VoteChoice, VotingMode, UPGRADE_TIMELOCK};
+use solana_program_test::tokio;
+use solana_sdk::account::{Account, ReadableAccount};
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::hash::hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Signer;
+
+const DAY: i64 = 24 * 60 * 60;
+const QUORUM: u64 = 100;
+const PROGRAM_IMAGE: &[u8] = b"\x7fELF realstack v2 program image";
+/// Bytes of `UpgradeableLoaderState::ProgramData` metadata ahead of the code
+const PROGRAM_DATA_METADATA: usize = 45;
+
+/// Governance holding the program's upgrade authority, with a local buffer
+struct UpgradeFixture {
+    config: Pubkey,
+    pool: StakePoolFixture,
+    /// The upgrade authority PDA
+    authority: Pubkey,
+    /// Loader buffer holding `PROGRAM_IMAGE`
+    buffer: Pubkey,
+    /// The payer's REAL account, which funds proposal deposits
+    proposer_real: Pubkey,
+}
+
+fn create_upgrade_proposal_ix(
+    fixture: &TestFixture,
+    u: &UpgradeFixture,
+    proposal: Pubkey,
+    voting_ends_at: i64,
+    action: UpgradeAction,
+) -> Instruction {
+    let proposer = fixture.payer_key();
+    ix(
+        realstack::accounts::CreateProposal {
+            proposer,
+            proposal,
+            governance_config: u.config,
+            real_token: fixture.real_token,
+            real_mint: fixture.real_mint,
+            proposer_token_account: u.proposer_real,
+            deposit_escrow: pda(&[b"proposal_deposit", proposal.as_ref()]),
+            proposer_state: pda(&[b"proposer_state", u.config.as_ref(), proposer.as_ref()]),
+            stake_pool: None,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        },
+        realstack::instruction::CreateUpgradeProposal {
+            title: "Upgrade to v2".to_string(),
+            description: "Deploy the audited v2 build.".to_string(),
+            voting_ends_at,
+            action,
+        },
+    )
+}
+
+fn execute_upgrade_ix(fixture: &TestFixture, u: &UpgradeFixture, proposal: Pubkey, buffer: Pubkey) -> Instruction {
+    ix(
+        realstack::accounts::ExecuteProgramUpgrade {
+            executor: fixture.payer_key(),
+            proposal,
+            governance_config: u.config,
+            real_token: fixture.real_token,
+            upgrade_authority: u.authority,
+            program: realstack::ID,
+            program_data: program_data_address(),
+            buffer,
+            spill: fixture.payer_key(),
+            rent: solana_sdk::sysvar::rent::ID,
+            clock: solana_sdk::sysvar::clock::ID,
+            bpf_loader_upgradeable: bpf_loader_upgradeable::id(),
+        },
+        realstack::instruction::ExecuteProgramUpgrade {},
+    )
+}
+
+fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[realstack::ID.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Write a loader buffer holding `image`, owned by the upgrade authority
+fn write_buffer(fixture: &mut TestFixture, buffer: Pubkey, authority: &Pubkey, image: &[u8]) {
+    // Bincode layout of `UpgradeableLoaderState::Buffer { authority_address: Some(..) }`
+    let mut data = vec![1, 0, 0, 0, 1];
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(image);
+    let account = Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: bpf_loader_upgradeable::id(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    fixture.context.set_account(&buffer, &account.into());
+}
+
+/// Lay the program out as an upgradeable deployment with `code_len` bytes of code, owned by `authority`
+fn deploy_upgradeable(fixture: &mut TestFixture, authority: &Pubkey, code_len: usize) -> Pubkey {
+    let program_data = program_data_address();
+
+    // Bincode layout of `UpgradeableLoaderState::Program { programdata_address }`
+    let mut data = vec![2, 0, 0, 0];
+    data.extend_from_slice(program_data.as_ref());
+    let program = Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: bpf_loader_upgradeable::id(),
+        executable: true,
+        rent_epoch: 0,
+    };
+    fixture.context.set_account(&realstack::ID, &program.into());
+
+    // Bincode layout of `UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: Some(..) }`
+    let mut data = vec![3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+    data.extend_from_slice(authority.as_ref());
+    data.resize(PROGRAM_DATA_METADATA + code_len, 0);
+    let program_data_account = Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: bpf_loader_upgradeable::id(),
+        executable: false,
+        rent_epoch: 0,
+    };
+    fixture.context.set_account(&program_data, &program_data_account.into());
+    program_data
+}
+
+/// A program image the loader accepts: the SPL token program bundled with the test validator
+fn loadable_image() -> Vec<u8> {
+    solana_program_test::programs::spl_programs(&Rent::default())
+        .into_iter()
+        .find(|(program_id, _)| *program_id == anchor_spl::token::ID)
+        .map(|(_, account)| account.data().to_vec())
+        .unwrap()
+}
+
+/// Set up governance with the upgrade authority and a buffer holding `PROGRAM_IMAGE`
+async fn setup_upgrade(fixture: &mut TestFixture) -> UpgradeFixture {
+    let pool = fixture.create_stake_pool(5_000, DAY).await;
+    let payer = fixture.payer_key();
+    let real_mint = fixture.real_mint;
+    let slashed = fixture.create_token_account(&real_mint, &Pubkey::new_unique()).await;
+    let proposer_real = fixture.create_funded_token_account(&real_mint, &payer, 1_000).await;
+    let config = pda(&[b"governance_config", fixture.real_token.as_ref()]);
+    let instruction = ix(
+        realstack::accounts::InitializeGovernance {
+            authority: payer,
+            real_token: fixture.real_token,
+            roles: fixture.roles,
+            treasury: slashed,
+            governance_config: config,
+            system_program: anchor_lang::system_program::ID,
+        },
+        realstack::instruction::InitializeGovernance {
+            min_voting_period: DAY,
+            max_voting_period: 7 * DAY,
+            min_quorum_votes: QUORUM,
+            approval_threshold: 51,
+            min_proposal_balance: 200,
+            min_vote_balance: 10,
+            proposal_deposit: 50,
+            max_active_proposals: 5,
+            voting_mode: VotingMode::Linear,
+        },
+    );
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let authority = realstack::upgrade_authority(&config).0;
+    assert_eq!(authority, pda(&[b"upgrade_authority", config.as_ref()]));
+    let buffer = Pubkey::new_unique();
+    write_buffer(fixture, buffer, &authority, PROGRAM_IMAGE);
+    UpgradeFixture {
+        config,
+        pool,
+        authority,
+        buffer,
+        proposer_real,
+    }
+}
+
+/// Propose an upgrade, vote it through with `choice` and end the voting period
+async fn decide(fixture: &mut TestFixture, u: &UpgradeFixture, action: UpgradeAction, choice: VoteChoice) -> Pubkey {
+    let voter = fixture.new_staker(&u.pool, QUORUM).await;
+    let proposal = fixture.next_proposal(&u.config).await;
+    let voting_ends_at = fixture.now().await + DAY + 60;
+    let instruction = create_upgrade_proposal_ix(fixture, u, proposal, voting_ends_at, action);
+    fixture.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = ix(
+        realstack::accounts::VoteOnProposal {
+            voter: voter.pubkey(),
+            proposal,
+            vote_record: pda(&[b"vote_record", proposal.as_ref(), voter.pubkey().as_ref()]),
+            governance_config: u.config,
+            real_token: fixture.real_token,
+            stake_pool: u.pool.stake_pool,
+            stake_account: None,
+            checkpoints: Some(pda(&[b"voting_power", u.pool.stake_pool.as_ref(), voter.pubkey().as_ref()])),
+            real_mint: None,
+            voter_real_account: None,
+            vote_escrow: None,
+            system_program: anchor_lang::system_program::ID,
+            token_program: anchor_spl::token::ID,
+        },
+        realstack::instruction::VoteOnProposal {
+            choice,
+            vote_weight: QUORUM,
+            lock_duration: 0,
+        },
+    );
+    fixture.process(&[instruction], &[&voter]).await.unwrap();
+    fixture.warp_forward(DAY + 60).await;
+    proposal
+}
+
+fn upgrade_of(buffer: Pubkey) -> UpgradeAction {
+    UpgradeAction {
+        buffer,
+        buffer_hash: hash(PROGRAM_IMAGE).to_bytes(),
+    }
+}
+
+#[tokio::test]
+async fn upgrade_proposals_record_the_buffer_and_hash() {
+    let mut fixture = TestFixture::new().await;
+    let u = setup_upgrade(&mut fixture).await;
+
+    let action = upgrade_of(u.buffer);
+    let proposal = decide(&mut fixture, &u, action, VoteChoice::Yes).await;
+    let state: Proposal = fixture.get(&proposal).await;
+    assert_eq!(state.proposal_type, ProposalType::ProgramUpgrade);
+    assert_eq!(state.target_accounts, vec![u.buffer]);
+    assert_eq!(state.execution_data, anchor_lang::AnchorSerialize::try_to_vec(&action).unwrap());
+
+    // A proposal without a buffer could never execute
+    let proposal = fixture.next_proposal(&u.config).await;
+    let voting_ends_at = fixture.now().await + DAY + 60;
+    let instruction =
+        create_upgrade_proposal_ix(&fixture, &u, proposal, voting_ends_at, upgrade_of(Pubkey::default()));
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidParameters);
+}
+
+#[tokio::test]
+async fn approved_upgrades_wait_for_the_timelock_and_check_the_buffer() {
+    let mut fixture = TestFixture::new().await;
+    let u = setup_upgrade(&mut fixture).await;
+    let proposal = decide(&mut fixture, &u, upgrade_of(u.buffer), VoteChoice::Yes).await;
+
+    // Upgrade proposals only execute through their own instruction
+    let instruction = ix(
+        realstack::accounts::ExecuteProposal {
+            executor: fixture.payer_key(),
+            proposal,
+            governance_config: u.config,
+            real_token: fixture.real_token,
+        },
+        realstack::instruction::ExecuteProposal {},
+    );
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::WrongExecutionInstruction);
+
+    let instruction = execute_upgrade_ix(&fixture, &u, proposal, u.buffer);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::TimelockNotExpired);
+
+    fixture.warp_forward(UPGRADE_TIMELOCK).await;
+
+    // Only the buffer voted on can be deployed
+    let other = Pubkey::new_unique();
+    write_buffer(&mut fixture, other, &u.authority, PROGRAM_IMAGE);
+    let instruction = execute_upgrade_ix(&fixture, &u, proposal, other);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::InvalidUpgradeBuffer);
+
+    // A buffer rewritten after the vote no longer matches the approved hash
+    write_buffer(&mut fixture, u.buffer, &u.authority, b"\x7fELF backdoored program image");
+    let instruction = execute_upgrade_ix(&fixture, &u, proposal, u.buffer);
+    let result = fixture.process(&[instruction], &[]).await;
+    assert_error(result, RealStackError::BufferHashMismatch);
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(!state.executed);
+}
+
+#[tokio::test]
+async fn rejected_upgrade_closes_without_touching_the_buffer() {
+    let mut fixture = TestFixture::new().await;
+    let u = setup_upgrade(&mut fixture).await;
+    let proposal = decide(&mut fixture, &u, upgrade_of(u.buffer), VoteChoice::No).await;
+
+    // No timelock applies to a rejected upgrade
+    let instruction = execute_upgrade_ix(&fixture, &u, proposal, u.buffer);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+    assert!(!state.is_active);
+
+    let data = fixture.account_data(&u.buffer).await;
+    assert!(data.ends_with(PROGRAM_IMAGE));
+}
+
+#[tokio::test]
+async fn approved_upgrade_deploys_the_buffer_into_program_data() {
+    let mut fixture = TestFixture::new().await;
+    let u = setup_upgrade(&mut fixture).await;
+    let image = loadable_image();
+    let buffer = Pubkey::new_unique();
+    write_buffer(&mut fixture, buffer, &u.authority, &image);
+    let program_data = deploy_upgradeable(&mut fixture, &u.authority, image.len());
+
+    let action = UpgradeAction {
+        buffer,
+        buffer_hash: hash(&image).to_bytes(),
+    };
+    let proposal = decide(&mut fixture, &u, action, VoteChoice::Yes).await;
+    fixture.warp_forward(UPGRADE_TIMELOCK).await;
+
+    let instruction = execute_upgrade_ix(&fixture, &u, proposal, buffer);
+    fixture.process(&[instruction], &[]).await.unwrap();
+    let state: Proposal = fixture.get(&proposal).await;
+    assert!(state.executed);
+    assert!(!state.is_active);
+
+    // ProgramData now holds the voted bytes, still under the governance authority
+    let data = fixture.account_data(&program_data).await;
+    assert_eq!(&data[PROGRAM_DATA_METADATA..], &image[..]);
+    assert_eq!(data[12], 1);
+    assert_eq!(&data[13..PROGRAM_DATA_METADATA], u.authority.as_ref());
+
+    // The loader drains the buffer into the spill account
+    assert!(!fixture.account_exists(&buffer).await);
+}